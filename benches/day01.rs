@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use lib::gen::{calibration_document, Rng};
+use lib::y2023::day01::{part2, part2_regex};
+
+pub fn bench_part2(c: &mut Criterion) {
+    // A real puzzle input has 1000 lines.
+    let input = calibration_document(&mut Rng::new(1), 1000);
+    c.bench_function("part 2 scan", |b| b.iter(|| part2(&input)));
+    c.bench_function("part 2 regex", |b| b.iter(|| part2_regex(&input)));
+}
+
+criterion_group!(benches, bench_part2);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use lib::gen::Rng;
+use lib::y2023::day07::{parse_input, solve, ParsedLine, PART1_RULES, PART2_RULES};
+
+/// Returns as many random hands as there are in a real puzzle input.
+fn get_hands() -> Vec<ParsedLine> {
+    const CARDS: &[char] = &[
+        '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+    ];
+    let mut rng = Rng::new(7);
+    let input: String = (0..1000)
+        .map(|_| {
+            let hand: String = (0..5).map(|_| CARDS[rng.index(CARDS.len())]).collect();
+            format!("{hand} {}\n", rng.in_range(1..=1000))
+        })
+        .collect();
+    parse_input(&input).expect("generated hands should be valid")
+}
+
+pub fn bench_solve(c: &mut Criterion) {
+    let hands = get_hands();
+    c.bench_function("rank hands (part 1)", |b| {
+        b.iter(|| solve(&hands, &PART1_RULES))
+    });
+    c.bench_function("rank hands (part 2)", |b| {
+        b.iter(|| solve(&hands, &PART2_RULES))
+    });
+}
+
+criterion_group!(benches, bench_solve);
+criterion_main!(benches);
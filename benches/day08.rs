@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use lib::gen::{ghost_network, Rng};
+use lib::y2023::day08::{ghost_steps, parse_input};
+
+pub fn bench_ghosts(c: &mut Criterion) {
+    // Like a real puzzle input, the ghosts' periods are the length
+    // of the instructions times a prime, and the answer to part 2 is
+    // more than 10^12.  Unlike a real one, the ghosts visit each node
+    // only once per period, so there are far more nodes to compile
+    // for each step taken.
+    let periods: Vec<usize> = [43, 47, 53, 59, 61, 67].iter().map(|p| 31 * p).collect();
+    let input = ghost_network(&mut Rng::new(8), 31, &periods);
+    let (instructions, network) = parse_input(&input).expect("generated input should be valid");
+    let compiled = network.compile();
+    c.bench_function("ghost steps indexed", |b| {
+        b.iter(|| compiled.ghost_steps(&instructions))
+    });
+    c.bench_function("ghost steps by name", |b| {
+        b.iter(|| ghost_steps(&instructions, &network))
+    });
+    c.bench_function("compile", |b| b.iter(|| network.compile()));
+}
+
+criterion_group!(benches, bench_ghosts);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use lib::examples::example;
+use lib::gen::{reflected_pattern, Rng};
+#[cfg(feature = "parallel")]
+use lib::y2023::day13::{par_summarize, par_summarize_with_smudges};
+use lib::y2023::day13::{parse_input, summarize, summarize_with_smudges, Pattern};
+
+/// Returns about as many patterns as there are in a real puzzle
+/// input.
+fn get_patterns() -> Vec<Pattern> {
+    let example = example(13).expect("there should be an example");
+    let input = vec![example; 50].join("\n");
+    parse_input(&input).expect("example should be valid")
+}
+
+/// Returns a few patterns which are much larger than those in the
+/// real puzzle input.
+fn get_large_patterns() -> Vec<Pattern> {
+    let mut rng = Rng::new(13);
+    let input: Vec<String> = (0..10)
+        .map(|_| reflected_pattern(&mut rng, 150, 150, 0.5))
+        .collect();
+    parse_input(&input.join("\n")).expect("generated patterns should be valid")
+}
+
+pub fn bench_summarize(c: &mut Criterion) {
+    let patterns = get_patterns();
+    c.bench_function("summarize", |b| b.iter(|| summarize(&patterns)));
+    c.bench_function("summarize with smudges", |b| {
+        b.iter(|| summarize_with_smudges(&patterns))
+    });
+    let large = get_large_patterns();
+    c.bench_function("summarize large", |b| b.iter(|| summarize(&large)));
+}
+
+#[cfg(feature = "parallel")]
+pub fn bench_par_summarize(c: &mut Criterion) {
+    let patterns = get_patterns();
+    c.bench_function("par_summarize", |b| b.iter(|| par_summarize(&patterns)));
+    c.bench_function("par_summarize with smudges", |b| {
+        b.iter(|| par_summarize_with_smudges(&patterns))
+    });
+}
+
+#[cfg(not(feature = "parallel"))]
+criterion_group!(benches, bench_summarize);
+#[cfg(feature = "parallel")]
+criterion_group!(benches, bench_summarize, bench_par_summarize);
+criterion_main!(benches);
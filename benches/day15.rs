@@ -1,6 +1,11 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
 
-use lib::days::day15::hash_generic;
+use lib::examples::example;
+use lib::gen::{initialization_sequence, Rng};
+use lib::y2023::day15::{
+    hash_bytes, hash_generic, hash_many, parse_instructions, IndexedLensArray, Instruction,
+    LensArray, LensStore, SparseLensArray,
+};
 
 pub fn bench_hash_u16(c: &mut Criterion) {
     c.bench_function("hash u16", |b| {
@@ -20,5 +25,75 @@ pub fn bench_hash_u64(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_hash_u16, bench_hash_u32, bench_hash_u64);
+pub fn bench_hash_bytes(c: &mut Criterion) {
+    c.bench_function("hash bytes", |b| b.iter(|| hash_bytes(b"hello, world.")));
+}
+
+/// Compares hashing a large batch of labels one at a time with
+/// `hash_many`.
+pub fn bench_hash_batch(c: &mut Criterion) {
+    let input = initialization_sequence(&mut Rng::new(15), 100_000);
+    let labels: Vec<&str> = input.split(',').collect();
+    let mut group = c.benchmark_group("hash batch");
+    group.throughput(Throughput::Elements(labels.len() as u64));
+    group.bench_function("one at a time (u32)", |b| {
+        b.iter(|| -> Vec<u8> { labels.iter().map(|s| hash_generic::<u32>(s)).collect() })
+    });
+    group.bench_function("one at a time (bytes)", |b| {
+        b.iter(|| -> Vec<u8> { labels.iter().map(|s| hash_bytes(s.as_bytes())).collect() })
+    });
+    group.bench_function("hash_many", |b| b.iter(|| hash_many(&labels)));
+    group.finish();
+}
+
+pub fn bench_hashmap(c: &mut Criterion) {
+    let example = example(15).expect("there should be an example").trim_end();
+    let instructions = parse_instructions(example).expect("example should be valid");
+    c.bench_function("hashmap focusing power", |b| {
+        b.iter(|| {
+            let mut array = LensArray::new();
+            array.perform_sequence(&instructions);
+            array.focusing_power()
+        })
+    });
+}
+
+fn bench_store<S: LensStore>(c: &mut Criterion, name: &str, instructions: &[Instruction]) {
+    let mut group = c.benchmark_group(format!("lens store {name}"));
+    group.bench_function("perform and focusing power", |b| {
+        b.iter(|| {
+            let mut store = S::default();
+            store.perform_all(instructions);
+            store.focusing_power()
+        })
+    });
+    let mut store = S::default();
+    store.perform_all(instructions);
+    group.bench_function("focusing power", |b| b.iter(|| store.focusing_power()));
+    group.bench_function("display", |b| b.iter(|| store.to_string()));
+    group.finish();
+}
+
+/// Compares the ways of storing the lenses.  A short sequence leaves
+/// most of the boxes empty, which is where skipping them helps.
+pub fn bench_lens_stores(c: &mut Criterion) {
+    for (size, steps) in [("short", 20), ("long", 4000)] {
+        let input = initialization_sequence(&mut Rng::new(15), steps);
+        let instructions = parse_instructions(&input).expect("generated input should be valid");
+        bench_store::<LensArray>(c, &format!("array ({size})"), &instructions);
+        bench_store::<SparseLensArray>(c, &format!("sparse ({size})"), &instructions);
+        bench_store::<IndexedLensArray>(c, &format!("indexed ({size})"), &instructions);
+    }
+}
+
+criterion_group!(
+    benches,
+    bench_hash_u16,
+    bench_hash_u32,
+    bench_hash_u64,
+    bench_hash_bytes,
+    bench_hash_batch,
+    bench_hashmap,
+    bench_lens_stores
+);
 criterion_main!(benches);
@@ -0,0 +1,21 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use lib::gen::{mirror_grid, Rng};
+use lib::y2023::day16::{energised_from_top_left, max_energised, parse_grid, Grid};
+
+/// Returns a grid the size of a real puzzle input.
+fn get_grid() -> Grid {
+    let mut rng = Rng::new(16);
+    parse_grid(&mirror_grid(&mut rng, 110, 110, 0.1)).expect("generated grid should be valid")
+}
+
+pub fn bench_energise(c: &mut Criterion) {
+    let grid = get_grid();
+    c.bench_function("energised from top left", |b| {
+        b.iter(|| energised_from_top_left(&grid))
+    });
+    c.bench_function("max energised", |b| b.iter(|| max_energised(&grid)));
+}
+
+criterion_group!(benches, bench_energise);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use lib::examples::example;
+use lib::y2023::day19::{count_acceptable_combinations, parse_input, total_accepted_rating};
+
+/// Returns the example input with about as many items as there are
+/// in a real puzzle input.
+fn get_input() -> String {
+    let example = example(19).expect("there should be an example");
+    let (rules, items) = example
+        .split_once("\n\n")
+        .expect("example should have rules and items");
+    format!("{rules}\n\n{}", items.repeat(40))
+}
+
+pub fn bench_evaluation(c: &mut Criterion) {
+    let input = get_input();
+    let (rules, items) = parse_input(&input).expect("example should be valid");
+    c.bench_function("total accepted rating", |b| {
+        b.iter(|| total_accepted_rating(&rules, &items))
+    });
+    c.bench_function("count acceptable combinations", |b| {
+        b.iter(|| count_acceptable_combinations(&rules))
+    });
+    c.bench_function("parse", |b| b.iter(|| parse_input(&input)));
+}
+
+criterion_group!(benches, bench_evaluation);
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use lib::examples::example;
+use lib::gen::Rng;
+use lib::input::puzzle_input;
+use lib::y2023::day22::{
+    count_chain_reaction_falls_with, parse_input, random_bricks, Brick, BrickParams, FlatSurface,
+    TreeSurface,
+};
+
+/// Returns the bricks from the real puzzle input if we have it, since
+/// the example is too small to show much difference.
+fn get_bricks() -> Vec<Brick> {
+    let input = puzzle_input(22).unwrap_or_else(|e| {
+        eprintln!("{e}; using the example instead");
+        example(22).expect("there should be an example").to_string()
+    });
+    parse_input(&input).expect("input should be valid")
+}
+
+pub fn bench_chain_reaction(c: &mut Criterion) {
+    let bricks = get_bricks();
+    let mut group = c.benchmark_group("chain reaction");
+    group.sample_size(10);
+    group.bench_function("BTreeMap surface", |b| {
+        b.iter(|| count_chain_reaction_falls_with::<TreeSurface>(&bricks))
+    });
+    group.bench_function("flat surface", |b| {
+        b.iter(|| count_chain_reaction_falls_with::<FlatSurface>(&bricks))
+    });
+    group.finish();
+}
+
+/// Compares the surfaces on a snapshot much wider than the real
+/// input, where the flat surface has more cells to look after.
+pub fn bench_chain_reaction_wide(c: &mut Criterion) {
+    let params = BrickParams {
+        width: 100,
+        depth: 100,
+        height: 100,
+        count: 1000,
+        max_length: 5,
+    };
+    let bricks = random_bricks(&mut Rng::new(22), &params);
+    let mut group = c.benchmark_group("chain reaction (generated, wide)");
+    group.sample_size(10);
+    group.bench_function("BTreeMap surface", |b| {
+        b.iter(|| count_chain_reaction_falls_with::<TreeSurface>(&bricks))
+    });
+    group.bench_function("flat surface", |b| {
+        b.iter(|| count_chain_reaction_falls_with::<FlatSurface>(&bricks))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_chain_reaction, bench_chain_reaction_wide);
+criterion_main!(benches);
@@ -0,0 +1,139 @@
+//! Times some of the grid puzzles on generated inputs 1, 2, 4 and 8
+//! times the size (in cells) of a real puzzle input, and reports how
+//! the time grows with the size.  This shows whether a rewrite of one
+//! of these solutions changed its complexity, and not just its
+//! constant factor.
+//!
+//! ```sh
+//! cargo bench --bench scaling
+//! ```
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use lib::gen::{galaxy_image, mirror_grid, platform, reflected_pattern, Rng};
+use lib::numeric::power_law_exponent;
+use lib::y2023::{day11, day13, day14, day16};
+
+/// How many times larger than the real puzzle input each generated
+/// input is.
+const SCALES: [usize; 4] = [1, 2, 4, 8];
+
+/// Returns the side of a square with `scale` times the area of a
+/// square with the given side.
+fn scaled_side(side: usize, scale: usize) -> usize {
+    (side as f64 * (scale as f64).sqrt()).round() as usize
+}
+
+/// Benchmarks `run` on the input `make(scale)` for each of `SCALES`,
+/// then prints the exponent `k` of the best fit of `time = c·cells^k`.
+/// `make` returns the input and the number of cells in it.
+fn bench_scaling<T, M, R>(c: &mut Criterion, name: &str, mut make: M, mut run: R)
+where
+    M: FnMut(usize) -> (T, usize),
+    R: FnMut(&T),
+{
+    let mut group = c.benchmark_group(format!("scaling/{name}"));
+    group.sample_size(10);
+    let mut samples = Vec::new();
+    for scale in SCALES {
+        let (input, cells) = make(scale);
+        let mut total = Duration::ZERO;
+        let mut iterations = 0;
+        group.throughput(Throughput::Elements(cells as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(scale), &input, |b, input| {
+            b.iter_custom(|iters| {
+                let start = Instant::now();
+                for _ in 0..iters {
+                    run(input);
+                }
+                let elapsed = start.elapsed();
+                total += elapsed;
+                iterations += iters;
+                elapsed
+            })
+        });
+        if iterations > 0 {
+            samples.push((cells as f64, total.as_secs_f64() / iterations as f64));
+        }
+    }
+    group.finish();
+    match power_law_exponent(&samples) {
+        Some(k) => println!("{name}: time grows as n^{k:.2} (n = cells)"),
+        None => println!("{name}: too few timings to estimate the growth"),
+    }
+}
+
+pub fn bench_day11(c: &mut Criterion) {
+    let mut rng = Rng::new(11);
+    bench_scaling(
+        c,
+        "day 11 part 2",
+        |scale| {
+            let side = scaled_side(140, scale);
+            (galaxy_image(&mut rng, side, side, 0.02), side * side)
+        },
+        |input| {
+            day11::part2(input).expect("generated image should be valid");
+        },
+    );
+}
+
+pub fn bench_day13(c: &mut Criterion) {
+    let mut rng = Rng::new(13);
+    bench_scaling(
+        c,
+        "day 13 part 1",
+        |scale| {
+            let side = scaled_side(15, scale);
+            let input: Vec<String> = (0..100)
+                .map(|_| reflected_pattern(&mut rng, side, side, 0.5))
+                .collect();
+            let patterns =
+                day13::parse_input(&input.join("\n")).expect("generated patterns should be valid");
+            (patterns, 100 * side * side)
+        },
+        |patterns| {
+            day13::summarize_with_mismatches(patterns, 0);
+        },
+    );
+}
+
+pub fn bench_day14(c: &mut Criterion) {
+    // Part 2 is left out because the number of spin cycles before a
+    // random platform repeats itself varies too much with its size.
+    let mut rng = Rng::new(14);
+    bench_scaling(
+        c,
+        "day 14 part 1",
+        |scale| {
+            let side = scaled_side(100, scale);
+            let platform = day14::parse_input(&platform(&mut rng, side, side, 0.2, 0.1))
+                .expect("generated platform should be valid");
+            (platform, side * side)
+        },
+        |platform| {
+            day14::north_load_after_tilt(platform);
+        },
+    );
+}
+
+pub fn bench_day16(c: &mut Criterion) {
+    let mut rng = Rng::new(16);
+    bench_scaling(
+        c,
+        "day 16 part 2",
+        |scale| {
+            let side = scaled_side(110, scale);
+            let grid = day16::parse_grid(&mirror_grid(&mut rng, side, side, 0.1))
+                .expect("generated grid should be valid");
+            (grid, side * side)
+        },
+        |grid| {
+            day16::max_energised(grid);
+        },
+    );
+}
+
+criterion_group!(benches, bench_day11, bench_day13, bench_day14, bench_day16);
+criterion_main!(benches);
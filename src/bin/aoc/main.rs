@@ -0,0 +1,1287 @@
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::io::Read;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command as Subprocess, ExitCode, ExitStatus, Stdio};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::{Arg, ArgAction, Command};
+
+use lib::answer::Answer;
+use lib::answers::{self, Answers};
+use lib::debug;
+use lib::error::Fail;
+use lib::fail;
+use lib::options::{RunOptions, REPORT_VAR};
+use lib::report::{self, Record, ReportFormat};
+use lib::shrink::shrink_lines;
+use lib::solver::{Backend, Notes};
+use lib::{fetch, input, logging, solver, submit};
+
+/// The outcome of running the solver for a single day.
+#[derive(Debug)]
+struct DayResult {
+    day: u8,
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+    /// How long the solver says it took over each part, if it said.
+    timings: [Option<Duration>; 2],
+    elapsed: Duration,
+    outcome: Result<(), Fail>,
+}
+
+fn solver_path(day: u8) -> Result<PathBuf, Fail> {
+    // The day binaries are built into the same directory as this one.
+    let me = std::env::current_exe().map_err(|e| fail!("cannot locate the aoc executable: {e}"))?;
+    let dir = me
+        .parent()
+        .ok_or_else(|| fail!("{} has no parent directory", me.display()))?;
+    Ok(dir.join(format!("day{day:02}{}", std::env::consts::EXE_SUFFIX)))
+}
+
+/// Extracts the reports on parts 1 and 2 from the output of a day
+/// binary.  We ask the solvers to report in JSON, so the reports are
+/// the lines `report::parse_json` understands; anything else the
+/// solver prints (such as a picture of the puzzle) is ignored, as is
+/// a report on any other part.
+fn parse_answers(stdout: &str) -> [Option<Record>; 2] {
+    let mut reports = [None, None];
+    for record in stdout.lines().filter_map(report::parse_json) {
+        match record.part {
+            1 => reports[0] = Some(record),
+            2 => reports[1] = Some(record),
+            other => debug!("ignoring the report on part {other}"),
+        }
+    }
+    reports
+}
+
+#[test]
+fn test_parse_answers() {
+    let answers = |stdout: &str| parse_answers(stdout).map(|r| r.and_then(|r| r.result.ok()));
+    let output = concat!(
+        "{\"day\":7,\"part\":1,\"answer\":\"6440\",\"elapsed_ms\":1.5}\n",
+        "{\"day\":7,\"part\":2,\"answer\":\"5905\"}\n",
+    );
+    assert_eq!(
+        answers(output),
+        [Some(Answer::from(6440)), Some(Answer::from(5905))]
+    );
+    assert_eq!(
+        parse_answers(output).map(|r| r.and_then(|r| r.elapsed)),
+        [Some(Duration::from_micros(1500)), None]
+    );
+    assert_eq!(
+        answers("S-7\n|.|\nL-J\n{\"day\":10,\"part\":1,\"answer\":\"4\"}\n"),
+        [Some(Answer::from(4)), None]
+    );
+    assert_eq!(
+        answers(concat!(
+            "day 01 part 1: 142\n",
+            "{\"day\":1,\"part\":2,\"error\":\"no digits\"}\n",
+        )),
+        [None, None]
+    );
+    // There is no part 3.
+    assert_eq!(
+        answers("{\"day\":7,\"part\":3,\"answer\":\"1\"}\n"),
+        [None, None]
+    );
+}
+
+/// Waits for `child` to exit, killing it if it runs for longer than
+/// `timeout`.  Returns the exit status along with everything the
+/// child wrote to stdout and stderr.
+fn wait_with_timeout(
+    mut child: Child,
+    timeout: Option<Duration>,
+) -> Result<(ExitStatus, String, String), Fail> {
+    fn drain<R: Read + Send + 'static>(source: Option<R>) -> thread::JoinHandle<String> {
+        // Read the pipe on its own thread so that a child which
+        // produces a lot of output cannot block on a full pipe.
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut source) = source {
+                // A read error just truncates the captured output.
+                let _ = source.read_to_end(&mut buf);
+            }
+            String::from_utf8_lossy(&buf).into_owned()
+        })
+    }
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => match timeout {
+                Some(limit) if start.elapsed() > limit => {
+                    // Ignore errors: the child may have exited just now.
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(fail!("timed out after {}s", limit.as_secs_f64()));
+                }
+                _ => thread::sleep(Duration::from_millis(5)),
+            },
+            Err(e) => {
+                return Err(fail!("failed to wait for solver: {e}"));
+            }
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    Ok((status, stdout, stderr))
+}
+
+fn run_day(day: u8, path: &Path, options: &RunOptions, timeout: Option<Duration>) -> DayResult {
+    let _day = logging::day_span(day);
+    let mut result = DayResult {
+        day,
+        part1: None,
+        part2: None,
+        timings: [None; 2],
+        elapsed: Duration::ZERO,
+        outcome: Ok(()),
+    };
+    if !path.exists() {
+        result.outcome = Err(fail!(
+            "{} does not exist; build it with 'cargo build'",
+            path.display()
+        ));
+        return result;
+    }
+    debug!("running {}", path.display());
+    let start = Instant::now();
+    let child = Subprocess::new(path)
+        .env("RUST_BACKTRACE", "0")
+        .envs(options.env_vars())
+        .env(REPORT_VAR, ReportFormat::Json.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| fail!("failed to run {}: {e}", path.display()));
+    let output = child.and_then(|child| wait_with_timeout(child, timeout));
+    result.elapsed = start.elapsed();
+    result.outcome = match output {
+        Ok((status, stdout, stderr)) => {
+            // The solver's own diagnostics (if RUST_LOG asked for any).
+            for line in stderr.lines() {
+                debug!("{line}");
+            }
+            let reports = parse_answers(&stdout);
+            result.timings = reports
+                .each_ref()
+                .map(|report| report.as_ref().and_then(|r| r.elapsed));
+            [result.part1, result.part2] = reports.map(|report| report.and_then(|r| r.result.ok()));
+            if status.success() {
+                Ok(())
+            } else {
+                // Report the last line of diagnostic output, skipping
+                // the hint about RUST_BACKTRACE which follows a panic.
+                let last_line = stderr
+                    .lines()
+                    .rev()
+                    .find(|line| !line.starts_with("note: "))
+                    .unwrap_or("");
+                Err(fail!("{status}: {last_line}"))
+            }
+        }
+        Err(e) => Err(e),
+    };
+    result
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_day_timeout() {
+    // yes(1) never exits, and produces plenty of output.
+    let yes = Path::new("/usr/bin/yes");
+    if yes.exists() {
+        let result = run_day(
+            1,
+            yes,
+            &RunOptions::default(),
+            Some(Duration::from_millis(100)),
+        );
+        match result.outcome {
+            Err(Fail(msg)) => assert!(msg.starts_with("timed out"), "{msg}"),
+            Ok(()) => panic!("expected a timeout"),
+        }
+    }
+}
+
+/// Runs the solvers for `days` on up to `jobs` threads.  The results
+/// are returned in the same order as `days`.
+fn run_days(
+    days: &[u8],
+    jobs: usize,
+    options: &RunOptions,
+    timeout: Option<Duration>,
+) -> Result<Vec<DayResult>, Fail> {
+    let work: Vec<(u8, PathBuf)> = days
+        .iter()
+        .map(|day| solver_path(*day).map(|path| (*day, path)))
+        .collect::<Result<Vec<_>, Fail>>()?;
+    let queue = Mutex::new(work.into_iter().enumerate());
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            let sender = sender.clone();
+            let queue = &queue;
+            scope.spawn(move || loop {
+                let next = match queue.lock() {
+                    Ok(mut q) => q.next(),
+                    Err(_) => None,
+                };
+                let Some((index, (day, path))) = next else {
+                    break;
+                };
+                // A bug in the runner for one day should not take
+                // the other days down with it.
+                let result = panic::catch_unwind(|| run_day(day, &path, options, timeout))
+                    .unwrap_or_else(|_| DayResult {
+                        day,
+                        part1: None,
+                        part2: None,
+                        timings: [None; 2],
+                        elapsed: Duration::ZERO,
+                        outcome: Err(Fail("the runner panicked".to_string())),
+                    });
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            });
+        }
+    });
+    drop(sender);
+    let mut results: Vec<(usize, DayResult)> = receiver.into_iter().collect();
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+fn format_table(results: &[DayResult]) -> String {
+    let header = ["Day", "Part 1", "Part 2", "Time", "Status"];
+    let rows: Vec<[String; 5]> = results
+        .iter()
+        .map(|r| {
+            [
+                format!("{:02}", r.day),
+                r.part1.as_ref().map_or("-".to_string(), Answer::to_string),
+                r.part2.as_ref().map_or("-".to_string(), Answer::to_string),
+                format!("{:.1}ms", r.elapsed.as_secs_f64() * 1000.0),
+                match &r.outcome {
+                    Ok(()) => "ok".to_string(),
+                    Err(e) => format!("FAILED ({e})"),
+                },
+            ]
+        })
+        .collect();
+    let mut widths: [usize; 5] = header.map(|h| h.len());
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let format_row = |cells: &[&str]| -> String {
+        let mut line = cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<String>>()
+            .join("  ");
+        line.truncate(line.trim_end().len());
+        line.push('\n');
+        line
+    };
+    let mut table = format_row(&header);
+    for row in rows.iter() {
+        table.push_str(&format_row(&row.each_ref().map(|s| s.as_str())));
+    }
+    table
+}
+
+#[test]
+fn test_format_table() {
+    let results = vec![
+        DayResult {
+            day: 1,
+            part1: Some(Answer::from(142)),
+            part2: Some(Answer::from(281)),
+            timings: [None; 2],
+            elapsed: Duration::from_micros(1500),
+            outcome: Ok(()),
+        },
+        DayResult {
+            day: 10,
+            part1: Some(Answer::from(8)),
+            part2: None,
+            timings: [None; 2],
+            elapsed: Duration::from_millis(12),
+            outcome: Err(Fail("oops".to_string())),
+        },
+    ];
+    assert_eq!(
+        format_table(&results),
+        concat!(
+            "Day  Part 1  Part 2  Time    Status\n",
+            "01   142     281     1.5ms   ok\n",
+            "10   8       -       12.0ms  FAILED (oops)\n",
+        )
+    );
+}
+
+/// Describes the answers in `results` as records for a `Reporter`.
+/// A part with no answer is reported as a failure.
+fn records(results: &[DayResult]) -> Vec<Record> {
+    results
+        .iter()
+        .flat_map(|r| {
+            [(1, &r.part1), (2, &r.part2)].map(|(part, answer)| Record {
+                day: r.day,
+                part,
+                result: match (answer, &r.outcome) {
+                    (Some(answer), _) => Ok(answer.clone()),
+                    (None, Err(e)) => Err(Fail(e.0.clone())),
+                    (None, Ok(())) => Err(Fail("there is no answer".to_string())),
+                },
+                elapsed: r.timings[usize::from(part - 1)],
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_records() {
+    let results = vec![DayResult {
+        day: 10,
+        part1: Some(Answer::from(8)),
+        part2: None,
+        timings: [Some(Duration::from_millis(5)), None],
+        elapsed: Duration::from_millis(12),
+        outcome: Err(Fail("oops".to_string())),
+    }];
+    assert_eq!(
+        records(&results),
+        vec![
+            Record {
+                day: 10,
+                part: 1,
+                result: Ok(Answer::from(8)),
+                elapsed: Some(Duration::from_millis(5)),
+            },
+            Record {
+                day: 10,
+                part: 2,
+                result: Err(Fail("oops".to_string())),
+                elapsed: None,
+            },
+        ]
+    );
+}
+
+/// Compares the answers in `results` with the ones we know are
+/// correct, and returns a description of each difference.
+fn check_results(results: &[DayResult], known: &Answers) -> Vec<Fail> {
+    results
+        .iter()
+        .flat_map(|r| {
+            [
+                known.check(r.day, 1, r.part1.as_ref()),
+                known.check(r.day, 2, r.part2.as_ref()),
+            ]
+        })
+        .filter_map(Result::err)
+        .collect()
+}
+
+#[test]
+fn test_check_results() {
+    let mut known = Answers::default();
+    known.insert(1, 1, "142");
+    known.insert(1, 2, "281");
+    known.insert(10, 1, "8");
+    known.insert(10, 2, "4");
+    let results = vec![
+        DayResult {
+            day: 1,
+            part1: Some(Answer::from(142)),
+            part2: Some(Answer::from(281)),
+            timings: [None; 2],
+            elapsed: Duration::ZERO,
+            outcome: Ok(()),
+        },
+        DayResult {
+            day: 10,
+            part1: Some(Answer::from(9)),
+            part2: None,
+            timings: [None; 2],
+            elapsed: Duration::ZERO,
+            outcome: Err(Fail("oops".to_string())),
+        },
+    ];
+    assert_eq!(
+        check_results(&results, &known),
+        vec![
+            Fail("day 10 part 1: got 9, expected 8".to_string()),
+            Fail("day 10 part 2: got no answer, expected 4".to_string()),
+        ]
+    );
+}
+
+/// Formats the notes on the solution for `day` as markdown.  `result`
+/// is the outcome of running the solution, if we did.
+fn format_notes(year: u16, day: u8, notes: &Notes, result: Option<&DayResult>) -> String {
+    let runtime = match result {
+        Some(DayResult {
+            outcome: Ok(()),
+            elapsed,
+            ..
+        }) => format!("{:.1}ms", elapsed.as_secs_f64() * 1000.0),
+        Some(DayResult {
+            outcome: Err(e), ..
+        }) => format!("not measured (the solution failed: {e})"),
+        None => "not measured".to_string(),
+    };
+    format!(
+        concat!(
+            "# Day {day}: {title}\n",
+            "\n",
+            "<https://adventofcode.com/{year}/day/{day}>\n",
+            "\n",
+            "**Algorithm:** {algorithm}\n",
+            "\n",
+            "**Complexity:** {complexity}\n",
+            "\n",
+            "**Measured runtime:** {runtime}\n",
+        ),
+        day = day,
+        year = year,
+        title = notes.title,
+        algorithm = notes.algorithm,
+        complexity = notes.complexity,
+        runtime = runtime,
+    )
+}
+
+#[test]
+fn test_format_notes() {
+    let notes = Notes {
+        title: "Camel Cards",
+        algorithm: "Sort the hands.",
+        complexity: "O(h log h) for h hands",
+    };
+    let result = DayResult {
+        day: 7,
+        part1: Some(Answer::from(6440)),
+        part2: Some(Answer::from(5905)),
+        timings: [None; 2],
+        elapsed: Duration::from_micros(2500),
+        outcome: Ok(()),
+    };
+    assert_eq!(
+        format_notes(2023, 7, &notes, Some(&result)),
+        concat!(
+            "# Day 7: Camel Cards\n",
+            "\n",
+            "<https://adventofcode.com/2023/day/7>\n",
+            "\n",
+            "**Algorithm:** Sort the hands.\n",
+            "\n",
+            "**Complexity:** O(h log h) for h hands\n",
+            "\n",
+            "**Measured runtime:** 2.5ms\n",
+        )
+    );
+    assert!(format_notes(2023, 7, &notes, None).ends_with("**Measured runtime:** not measured\n"));
+    let failed = DayResult {
+        outcome: Err(Fail("oops".to_string())),
+        ..result
+    };
+    assert!(format_notes(2023, 7, &notes, Some(&failed))
+        .ends_with("**Measured runtime:** not measured (the solution failed: oops)\n"));
+}
+
+/// Writes the notes on the solutions for `days`, running each one
+/// (unless `results` is `None`) to find out how long it takes.  With
+/// an `output` directory, each day's notes go in their own file
+/// there; otherwise they are printed.
+fn write_notes(
+    year: u16,
+    days: &[u8],
+    results: Option<&[DayResult]>,
+    output: Option<&Path>,
+) -> Result<(), Fail> {
+    if let Some(dir) = output {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| fail!("failed to create {}: {e}", dir.display()))?;
+    }
+    for (i, day) in days.iter().enumerate() {
+        let notes = solver::notes(year, *day)
+            .ok_or_else(|| fail!("there is no solution for {year} day {day}"))?;
+        let result = results.and_then(|results| results.iter().find(|r| r.day == *day));
+        let text = format_notes(year, *day, notes, result);
+        match output {
+            Some(dir) => {
+                let path = dir.join(format!("day{day:02}.md"));
+                std::fs::write(&path, text)
+                    .map_err(|e| fail!("failed to write {}: {e}", path.display()))?;
+            }
+            None => {
+                if i > 0 {
+                    println!();
+                }
+                print!("{text}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Downloads the puzzle input for `day` into the input directory.
+fn fetch_day(day: u8) -> Result<PathBuf, Fail> {
+    let path = input::input_path(&input::input_dir(), day);
+    fetch::fetch_input(day, &path)?;
+    Ok(path)
+}
+
+/// Solves one part of the puzzle for `day` and submits the answer.
+fn submit_day(day: u8, part: u8, options: &RunOptions) -> Result<submit::Verdict, Fail> {
+    let result = run_day(day, &solver_path(day)?, options, None);
+    result.outcome?;
+    let answer = match part {
+        1 => result.part1,
+        _ => result.part2,
+    }
+    .ok_or_else(|| fail!("day {day} has no answer for part {part}"))?;
+    let dir = input::input_dir();
+    let record = submit::submissions_path(&dir, day);
+    let (verdict, submitted) = submit::submit(day, part, &answer, &record)?;
+    if verdict == submit::Verdict::Correct {
+        answers::record_answer(&answers::answers_path(&dir), day, part, &answer)?;
+    }
+    if !submitted {
+        println!("not submitting {answer}; earlier submissions show that it is {verdict}");
+    } else {
+        println!("submitted {answer}: {verdict}");
+    }
+    Ok(verdict)
+}
+
+/// What one of the algorithms for a day made of the input.
+#[derive(Debug)]
+struct Verification {
+    part: u8,
+    name: &'static str,
+    result: Result<Answer, Fail>,
+    elapsed: Duration,
+}
+
+/// Returns the algorithms for `day`, leaving out the slow ones unless
+/// `slow` is true.
+fn backends_to_verify(year: u16, day: u8, slow: bool) -> Vec<Backend> {
+    solver::backends(year, day)
+        .iter()
+        .filter(|backend| slow || !backend.slow)
+        .copied()
+        .collect()
+}
+
+#[test]
+fn test_backends_to_verify() {
+    let names = |slow| -> Vec<(u8, &str)> {
+        backends_to_verify(2023, 5, slow)
+            .iter()
+            .map(|backend| (backend.part, backend.name))
+            .collect()
+    };
+    assert!(!names(false).contains(&(2, "seeds")));
+    assert!(names(false).contains(&(2, "intervals")));
+    assert!(names(true).contains(&(2, "seeds")));
+    assert!(backends_to_verify(2023, 2, true).is_empty());
+}
+
+/// Solves the puzzle with each of `backends`, all on the same input.
+fn verify_day(backends: &[Backend], input: &str) -> Vec<Verification> {
+    backends
+        .iter()
+        .map(|backend| {
+            let start = Instant::now();
+            let result = logging::in_part(backend.part, || (backend.solve)(input));
+            Verification {
+                part: backend.part,
+                name: backend.name,
+                result,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// Describes each part of `day` for which the algorithms didn't all
+/// come up with the same answer.
+fn disagreements(day: u8, verifications: &[Verification]) -> Vec<Fail> {
+    let mut problems = Vec::new();
+    for part in [1, 2] {
+        let of_part: Vec<&Verification> = verifications.iter().filter(|v| v.part == part).collect();
+        let agree = match of_part.first() {
+            Some(first) => of_part
+                .iter()
+                .all(|v| first.result.is_ok() && v.result == first.result),
+            None => true,
+        };
+        if !agree {
+            let outcomes: Vec<String> = of_part
+                .iter()
+                .map(|v| match &v.result {
+                    Ok(answer) => format!("{} gives {answer}", v.name),
+                    Err(e) => format!("{} failed ({e})", v.name),
+                })
+                .collect();
+            problems.push(fail!(
+                "day {day} part {part}: the algorithms disagree: {}",
+                outcomes.join(", ")
+            ));
+        }
+    }
+    problems
+}
+
+fn format_verification(day: u8, verifications: &[Verification]) -> String {
+    let width = verifications
+        .iter()
+        .map(|v| v.name.len())
+        .max()
+        .unwrap_or(0);
+    verifications
+        .iter()
+        .map(|v| {
+            let outcome = match &v.result {
+                Ok(answer) => answer.to_string(),
+                Err(e) => format!("failed: {e}"),
+            };
+            format!(
+                "day {day:02} part {} {:<width$}  {outcome} ({:.1}ms)\n",
+                v.part,
+                v.name,
+                v.elapsed.as_secs_f64() * 1000.0
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn example_verifications() -> Vec<Verification> {
+    let verification = |part, name, result: Result<&str, &str>| Verification {
+        part,
+        name,
+        result: result.map(Answer::from).map_err(|e| Fail(e.to_string())),
+        elapsed: Duration::from_micros(1500),
+    };
+    vec![
+        verification(1, "flood", Ok("62")),
+        verification(1, "shoelace", Ok("62")),
+        verification(2, "flat", Ok("7")),
+        verification(2, "tree", Ok("8")),
+        verification(2, "broken", Err("oops")),
+    ]
+}
+
+#[test]
+fn test_verify_day() {
+    let backend = |part, name, solve| Backend {
+        part,
+        name,
+        description: "test",
+        slow: false,
+        solve,
+    };
+    let backends = [
+        backend(1, "length", |input: &str| Ok(input.len().into())),
+        backend(1, "chars", |input: &str| Ok(input.chars().count().into())),
+        backend(2, "fails", |_: &str| Err(Fail("oops".to_string()))),
+    ];
+    let verifications = verify_day(&backends, "héllo");
+    let outcomes: Vec<(u8, &str, Result<Answer, Fail>)> = verifications
+        .into_iter()
+        .map(|v| (v.part, v.name, v.result))
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            (1, "length", Ok(Answer::from(6))),
+            (1, "chars", Ok(Answer::from(5))),
+            (2, "fails", Err(Fail("oops".to_string()))),
+        ]
+    );
+}
+
+#[test]
+fn test_disagreements() {
+    let verifications = example_verifications();
+    assert_eq!(disagreements(18, &verifications[0..2]), vec![]);
+    assert_eq!(
+        disagreements(18, &verifications),
+        vec![Fail(
+            "day 18 part 2: the algorithms disagree: flat gives 7, tree gives 8, broken failed (oops)"
+                .to_string()
+        )]
+    );
+    // An algorithm which fails doesn't agree with anything, even
+    // when it is the only one.
+    assert_eq!(disagreements(18, &verifications[4..]).len(), 1);
+}
+
+#[test]
+fn test_format_verification() {
+    assert_eq!(
+        format_verification(18, &example_verifications()[1..]),
+        concat!(
+            "day 18 part 1 shoelace  62 (1.5ms)\n",
+            "day 18 part 2 flat      7 (1.5ms)\n",
+            "day 18 part 2 tree      8 (1.5ms)\n",
+            "day 18 part 2 broken    failed: oops (1.5ms)\n",
+        )
+    );
+}
+
+/// Runs every algorithm for each of `days` and reports any
+/// disagreement.  Slow algorithms are left out unless `slow` is true.
+/// Returns false if there was a disagreement (or if something else
+/// went wrong).
+fn verify_days(year: u16, days: &[u8], slow: bool) -> bool {
+    let mut ok = true;
+    for &day in days {
+        let backends = backends_to_verify(year, day, slow);
+        if backends.is_empty() {
+            println!("day {day:02} has only one algorithm for each part");
+            continue;
+        }
+        let input = match input::puzzle_input(day) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("aoc: day {day}: {e}");
+                ok = false;
+                continue;
+            }
+        };
+        let _day = logging::day_span(day);
+        let verifications = verify_day(&backends, &input);
+        print!("{}", format_verification(day, &verifications));
+        for problem in disagreements(day, &verifications) {
+            eprintln!("aoc: {problem}");
+            ok = false;
+        }
+    }
+    ok
+}
+
+/// How a solution went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Failure {
+    /// It panicked, with this message.
+    Panic(String),
+    /// It returned this error.
+    Error(String),
+    /// The day's algorithms didn't all give the same answer.
+    Disagreement(String),
+}
+
+impl Failure {
+    fn message(&self) -> &str {
+        match self {
+            Failure::Panic(message) | Failure::Error(message) | Failure::Disagreement(message) => {
+                message
+            }
+        }
+    }
+
+    /// Returns true if `other` is the same kind of failure as this
+    /// one, and its message contains `pattern` (if there is one).
+    fn matches(&self, other: &Failure, pattern: Option<&str>) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+            && pattern.is_none_or(|pattern| other.message().contains(pattern))
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Panic(message) => write!(f, "panicked: {message}"),
+            Failure::Error(message) => write!(f, "failed: {message}"),
+            Failure::Disagreement(message) => f.write_str(message),
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "(no message)".to_string(),
+        },
+    }
+}
+
+/// Solves one part of the puzzle for `day`, with each of its
+/// algorithms if it has more than one, and describes what went
+/// wrong, if anything did.  Slow algorithms are left out unless
+/// `slow` is true.
+fn diagnose(year: u16, day: u8, part: u8, input: &str, slow: bool) -> Option<Failure> {
+    let run = || -> Result<(), Failure> {
+        solver::solve(day, part, input).map_err(|e| Failure::Error(e.to_string()))?;
+        let backends: Vec<Backend> = backends_to_verify(year, day, slow)
+            .into_iter()
+            .filter(|b| b.part == part)
+            .collect();
+        if backends.len() > 1 {
+            if let Some(problem) = disagreements(day, &verify_day(&backends, input))
+                .into_iter()
+                .next()
+            {
+                return Err(Failure::Disagreement(problem.to_string()));
+            }
+        }
+        Ok(())
+    };
+    match panic::catch_unwind(run) {
+        Ok(result) => result.err(),
+        Err(payload) => Some(Failure::Panic(panic_message(payload))),
+    }
+}
+
+/// Deletes as many lines of `input` as possible while the solution
+/// for `day` still goes wrong on it in the same way.  This works on
+/// `part`, or on the first part which goes wrong.  If `pattern` is
+/// given, the failure's message must contain it.  Slow algorithms
+/// are left out unless `slow` is true.
+///
+/// Returns the part, how it goes wrong on `input`, and the shrunk
+/// input.
+fn shrink_input(
+    year: u16,
+    day: u8,
+    part: Option<u8>,
+    input: &str,
+    pattern: Option<&str>,
+    slow: bool,
+) -> Result<(u8, Failure, String), Fail> {
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    let Some((part, failure)) = parts
+        .into_iter()
+        .find_map(|part| diagnose(year, day, part, input, slow).map(|failure| (part, failure)))
+    else {
+        return Err(fail!("day {day} does not go wrong on this input"));
+    };
+    if !failure.matches(&failure, pattern) {
+        return Err(fail!(
+            "day {day} part {part} {failure}, which does not mention {}",
+            pattern.unwrap_or_default()
+        ));
+    }
+    let shrunk = shrink_lines(input, |candidate| {
+        diagnose(year, day, part, candidate, slow).is_some_and(|f| failure.matches(&f, pattern))
+    });
+    Ok((part, failure, shrunk))
+}
+
+#[test]
+fn test_diagnose() {
+    let example = lib::examples::examples(10)[0];
+    assert_eq!(diagnose(2023, 10, 1, example, false), None);
+    assert_eq!(
+        diagnose(2023, 10, 1, "", false),
+        Some(Failure::Error("no known start position".to_string()))
+    );
+}
+
+#[test]
+fn test_failure_matches() {
+    let error = Failure::Error("unrecognised character X".to_string());
+    let panic = Failure::Panic("unrecognised character X".to_string());
+    assert!(error.matches(&error, None));
+    assert!(error.matches(&error, Some("character")));
+    assert!(!error.matches(&error, Some("start")));
+    assert!(!error.matches(&panic, None));
+    assert_eq!(panic.to_string(), "panicked: unrecognised character X");
+}
+
+#[test]
+fn test_shrink_input() {
+    let input = concat!("7-F7-\n", ".FJ|7\n", "XJLL7\n", "|F--J\n", "LJ.LJ\n");
+    assert_eq!(
+        shrink_input(2023, 10, None, input, Some("unrecognised"), false),
+        Ok((
+            1,
+            Failure::Error("unrecognised character X".to_string()),
+            "XJLL7\n".to_string()
+        ))
+    );
+    // Without a pattern, any error will do.
+    assert_eq!(
+        shrink_input(2023, 10, Some(2), input, None, false).map(|(_, _, shrunk)| shrunk),
+        Ok(String::new())
+    );
+    assert!(shrink_input(2023, 10, None, input, Some("start"), false).is_err());
+    let example = lib::examples::examples(10)[0];
+    assert!(shrink_input(2023, 10, None, example, None, false).is_err());
+}
+
+fn main() -> ExitCode {
+    let matches = RunOptions::add_args(Command::new("aoc"))
+        .author("James Youngman, james@youngman.org")
+        .about("Runs the Advent of Code 2023 solutions")
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .action(ArgAction::SetTrue)
+                .help("Solve every implemented day and print a summary table"),
+        )
+        .arg(
+            Arg::new("year")
+                .long("year")
+                .value_parser(clap::value_parser!(u16))
+                .default_value("2023")
+                .help("Work on the puzzles from this year"),
+        )
+        .arg(
+            Arg::new("day")
+                .long("day")
+                .value_parser(clap::value_parser!(u8).range(1..=25))
+                .action(ArgAction::Append)
+                .help("Solve the puzzle for this day (may be repeated)"),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .help("Fail if any answer differs from a known correct one (in answers.toml)"),
+        )
+        .arg(
+            Arg::new("jobs")
+                .long("jobs")
+                .short('j')
+                .value_parser(clap::value_parser!(usize))
+                .help("Number of days to solve concurrently (default: one per CPU)"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_parser(clap::value_parser!(f64))
+                .help("Give up on a day after this many seconds"),
+        )
+        .subcommand(
+            Command::new("fetch")
+                .about("Download the puzzle input for a day (needs AOC_SESSION)")
+                .arg(
+                    Arg::new("day")
+                        .long("day")
+                        .required(true)
+                        .value_parser(clap::value_parser!(u8).range(1..=25))
+                        .help("Download the input for this day"),
+                ),
+        )
+        .subcommand(
+            Command::new("submit")
+                .about("Solve a puzzle and submit the answer (needs AOC_SESSION)")
+                .arg(
+                    Arg::new("day")
+                        .long("day")
+                        .required(true)
+                        .value_parser(clap::value_parser!(u8).range(1..=25))
+                        .help("Submit the answer for this day"),
+                )
+                .arg(
+                    Arg::new("part")
+                        .long("part")
+                        .required(true)
+                        .value_parser(clap::value_parser!(u8).range(1..=2))
+                        .help("Submit the answer for this part"),
+                ),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Solve a puzzle with each of its algorithms, and check that they agree")
+                .arg(
+                    Arg::new("day")
+                        .long("day")
+                        .value_parser(clap::value_parser!(u8).range(1..=25))
+                        .action(ArgAction::Append)
+                        .help(
+                            "Verify this day (may be repeated; default: every day with more than one algorithm)",
+                        ),
+                )
+                .arg(
+                    Arg::new("slow")
+                        .long("slow")
+                        .action(ArgAction::SetTrue)
+                        .help("Also run the algorithms which are too slow for a real input"),
+                ),
+        )
+        .subcommand(
+            Command::new("shrink")
+                .about("Delete as much as possible of an input on which a solution goes wrong, keeping it going wrong in the same way")
+                .arg(
+                    Arg::new("day")
+                        .long("day")
+                        .required(true)
+                        .value_parser(clap::value_parser!(u8).range(1..=25))
+                        .help("Shrink an input for this day"),
+                )
+                .arg(
+                    Arg::new("part")
+                        .long("part")
+                        .value_parser(clap::value_parser!(u8).range(1..=2))
+                        .help("Shrink the input for this part (default: the first part which goes wrong)"),
+                )
+                .arg(
+                    Arg::new("input")
+                        .long("input")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Start from the input in FILE (default: the puzzle input)"),
+                )
+                .arg(
+                    Arg::new("matching")
+                        .long("matching")
+                        .value_name("TEXT")
+                        .help("Only count it as going wrong when the error or panic message contains TEXT"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Write the shrunk input to FILE instead of printing it"),
+                )
+                .arg(
+                    Arg::new("slow")
+                        .long("slow")
+                        .action(ArgAction::SetTrue)
+                        .help("Also run the algorithms which are too slow for a real input"),
+                ),
+        )
+        .subcommand(
+            Command::new("notes")
+                .about("Write a markdown summary of how each day's solution works")
+                .arg(
+                    Arg::new("day")
+                        .long("day")
+                        .value_parser(clap::value_parser!(u8).range(1..=25))
+                        .action(ArgAction::Append)
+                        .help(
+                            "Describe this day (may be repeated; default: every implemented day)",
+                        ),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("DIR")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Write each day's notes to DIR/dayNN.md instead of printing them"),
+                )
+                .arg(
+                    Arg::new("no-timing")
+                        .long("no-timing")
+                        .action(ArgAction::SetTrue)
+                        .help("Don't run the solutions to measure how long they take"),
+                ),
+        )
+        .get_matches();
+    let options = RunOptions::from_matches(&matches);
+    options.init();
+    let year: u16 = *matches
+        .get_one::<u16>("year")
+        .expect("--year has a default value");
+    if !solver::YEARS.contains(&year) {
+        eprintln!("aoc: there are no solutions for {year}");
+        return ExitCode::FAILURE;
+    }
+
+    if let Some(("submit", submit_matches)) = matches.subcommand() {
+        let day: u8 = *submit_matches
+            .get_one::<u8>("day")
+            .expect("--day is a required argument");
+        let part: u8 = *submit_matches
+            .get_one::<u8>("part")
+            .expect("--part is a required argument");
+        return match submit_day(day, part, &options) {
+            Ok(submit::Verdict::Correct) => ExitCode::SUCCESS,
+            Ok(_) => ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("aoc: {e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if let Some(("fetch", fetch_matches)) = matches.subcommand() {
+        let day: u8 = *fetch_matches
+            .get_one::<u8>("day")
+            .expect("--day is a required argument");
+        return match fetch_day(day) {
+            Ok(path) => {
+                println!("saved day {day} input to {}", path.display());
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("aoc: {e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if let Some(("verify", verify_matches)) = matches.subcommand() {
+        let days: Vec<u8> = match verify_matches.get_many::<u8>("day") {
+            Some(days) => days.copied().collect(),
+            None => solver::implemented_days(year)
+                .iter()
+                .copied()
+                .filter(|day| !solver::backends(year, *day).is_empty())
+                .collect(),
+        };
+        return if verify_days(year, &days, verify_matches.get_flag("slow")) {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
+    if let Some(("shrink", shrink_matches)) = matches.subcommand() {
+        let day: u8 = *shrink_matches
+            .get_one::<u8>("day")
+            .expect("--day is a required argument");
+        let part: Option<u8> = shrink_matches.get_one::<u8>("part").copied();
+        let pattern = shrink_matches.get_one::<String>("matching");
+        let slow = shrink_matches.get_flag("slow");
+        let input = match shrink_matches.get_one::<PathBuf>("input") {
+            Some(path) => input::read_file_as_string(path).map(|input| input::normalize(&input)),
+            None => input::puzzle_input(day),
+        };
+        // The solution will go wrong many times while we shrink its
+        // input, and each panic would otherwise print a message.
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = input.map_err(Fail::from).and_then(|input| {
+            let (part, failure, shrunk) =
+                shrink_input(year, day, part, &input, pattern.map(String::as_str), slow)?;
+            eprintln!(
+                "day {day} part {part} {failure}; shrunk the input from {} to {} lines",
+                input.lines().count(),
+                shrunk.lines().count()
+            );
+            match shrink_matches.get_one::<PathBuf>("output") {
+                Some(path) => std::fs::write(path, &shrunk)
+                    .map_err(|e| fail!("failed to write {}: {e}", path.display())),
+                None => {
+                    print!("{shrunk}");
+                    Ok(())
+                }
+            }
+        });
+        panic::set_hook(hook);
+        return match result {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("aoc: {e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    let jobs: usize = matches
+        .get_one::<usize>("jobs")
+        .copied()
+        .unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        });
+    let timeout: Option<Duration> = matches
+        .get_one::<f64>("timeout")
+        .map(|secs| Duration::from_secs_f64(*secs));
+
+    if let Some(("notes", notes_matches)) = matches.subcommand() {
+        let days: Vec<u8> = match notes_matches.get_many::<u8>("day") {
+            Some(days) => days.copied().collect(),
+            None => solver::implemented_days(year).to_vec(),
+        };
+        // Run the solutions one at a time, so that they don't compete
+        // with each other and distort the timings.
+        let results = if notes_matches.get_flag("no-timing") {
+            Ok(None)
+        } else {
+            run_days(&days, 1, &options, timeout).map(Some)
+        };
+        let output = notes_matches.get_one::<PathBuf>("output");
+        return match results.and_then(|results| {
+            write_notes(year, &days, results.as_deref(), output.map(|p| p.as_path()))
+        }) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("aoc: {e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let known: Option<Answers> = if matches.get_flag("check") {
+        match answers::read_answers(&answers::answers_path(&input::input_dir())) {
+            Ok(known) => Some(known),
+            Err(e) => {
+                eprintln!("aoc: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        None
+    };
+
+    let days: Vec<u8> = if matches.get_flag("all") {
+        solver::implemented_days(year).to_vec()
+    } else {
+        match (matches.get_many::<u8>("day"), &known) {
+            (Some(days), _) => days.copied().collect(),
+            // By default, check the days for which we know an answer.
+            (None, Some(known)) if !known.is_empty() => known.days(),
+            (None, _) => {
+                eprintln!("aoc: please specify --all or --day N");
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+    match run_days(&days, jobs, &options, timeout) {
+        Ok(results) => {
+            match options.report {
+                ReportFormat::Text => print!("{}", format_table(&results)),
+                format => {
+                    let mut reporter = report::reporter(format);
+                    for record in records(&results) {
+                        if let Err(e) = reporter.report(&record) {
+                            eprintln!("aoc: cannot report the answers: {e}");
+                            return ExitCode::FAILURE;
+                        }
+                    }
+                }
+            }
+            let mut ok = results.iter().all(|r| r.outcome.is_ok());
+            if let Some(known) = known {
+                for problem in check_results(&results, &known) {
+                    eprintln!("aoc: {problem}");
+                    ok = false;
+                }
+            }
+            if ok {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("aoc: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
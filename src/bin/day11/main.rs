@@ -1,363 +1,108 @@
-use std::cmp::{max, Ordering};
-use std::collections::{BTreeMap, BTreeSet};
-use std::fmt::{Display, Formatter, Write};
-use std::str;
+use std::process::ExitCode;
 
-use bimap::BiMap;
+use clap::{Arg, ArgAction, Command};
 
 use lib::error::Fail;
-use lib::grid::{manhattan, BoundingBox, Position};
-
-#[derive(Debug)]
-struct Image {
-    occupied_rows: BTreeMap<i64, BTreeSet<i64>>,
-    occupied_cols: BTreeSet<i64>,
-    bounds: BoundingBox,
-}
-
-impl Display for Image {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
-        for y in (self.bounds.top_left.y)..=(self.bounds.bottom_right.y) {
-            match self.occupied_rows.get(&y) {
-                Some(row) => {
-                    for x in (self.bounds.top_left.x)..=(self.bounds.bottom_right.x) {
-                        if row.contains(&x) {
-                            f.write_char('#')?;
-                        } else {
-                            f.write_char('.')?;
-                        }
-                    }
-                }
-                None => {
-                    for _ in (self.bounds.top_left.x)..=(self.bounds.bottom_right.x) {
-                        f.write_char('.')?;
-                    }
-                }
-            }
-            f.write_char('\n')?;
-        }
-        Ok(())
-    }
-}
-
-impl Image {
-    fn popcount(&self) -> usize {
-        self.occupied_rows.values().map(|row| row.len()).sum()
-    }
-
-    #[cfg(test)]
-    fn unoccupied_cols(&self) -> Vec<i64> {
-        ((self.bounds.top_left.x)..=(self.bounds.bottom_right.x))
-            .filter(|col| !self.occupied_cols.contains(&col))
-            .collect()
-    }
-
-    #[cfg(test)]
-    fn unoccupied_rows(&self) -> Vec<i64> {
-        ((self.bounds.top_left.y)..=(self.bounds.bottom_right.y))
-            .filter(|y| !self.occupied_rows.contains_key(&y))
-            .collect()
-    }
-}
-
-fn parse_input(s: &str) -> Result<Image, Fail> {
-    let mut bbox: Option<BoundingBox> = None;
-    let mut occupied_rows: BTreeMap<i64, BTreeSet<i64>> = BTreeMap::new();
-    let mut occupied_cols: BTreeSet<i64> = BTreeSet::new();
-    for (y, line) in s.split_terminator('\n').enumerate() {
-        for (x, ch) in line.chars().enumerate() {
-            let pos = Position {
-                x: x as i64,
-                y: y as i64,
-            };
-            match bbox.as_mut() {
-                None => {
-                    bbox = Some(BoundingBox {
-                        top_left: pos,
-                        bottom_right: pos,
-                    });
-                }
-                Some(b) => {
-                    b.update(&pos);
-                }
-            }
-            fn just(z: i64) -> BTreeSet<i64> {
-                let mut result = BTreeSet::new();
-                result.insert(z);
-                result
-            }
-            if ch == '#' {
-                occupied_cols.insert(pos.x);
-                occupied_rows
-                    .entry(pos.y)
-                    .and_modify(|row| {
-                        row.insert(pos.x);
-                    })
-                    .or_insert_with(|| just(pos.x));
-            }
-        }
-    }
-    match bbox {
-        Some(bounds) => Ok(Image {
-            occupied_rows,
-            occupied_cols,
-            bounds,
-        }),
-        None => Err(Fail("empty input".to_string())),
-    }
-}
-
-#[cfg(test)]
-fn get_example() -> &'static str {
-    concat!(
-        "...#......\n",
-        ".......#..\n",
-        "#.........\n",
-        "..........\n",
-        "......#...\n",
-        ".#........\n",
-        ".........#\n",
-        "..........\n",
-        ".......#..\n",
-        "#...#.....\n",
-    )
-}
-
-#[cfg(test)]
-fn get_example_image() -> Image {
-    parse_input(get_example()).expect("example should be valid")
-}
-
-#[test]
-fn test_parse() {
-    let image =
-        parse_input(concat!("....\n", ".##.\n", "..#.\n")).expect("test input should be valid");
-    assert_eq!(image.popcount(), 3);
-    assert_eq!(
-        image.bounds,
-        BoundingBox {
-            top_left: Position { x: 0, y: 0 },
-            bottom_right: Position { x: 3, y: 2 },
-        }
+use lib::fail;
+use lib::grid::Metric;
+use lib::logging;
+use lib::options::RunOptions;
+use lib::solver::{self, DayArgs};
+use lib::y2023::day11::{expand, parse_input, sum_distances, PART1_EXPANSION, PART2_EXPANSION};
+
+fn main() -> ExitCode {
+    let matches = DayArgs::add_args(RunOptions::add_args(Command::new("day11")))
+        .arg(
+            Arg::new("dump-galaxies")
+                .long("dump-galaxies")
+                .value_name("FILE")
+                .help("Write the position of each galaxy after the expansion to FILE as CSV"),
+        )
+        .arg(
+            Arg::new("dump-pairs")
+                .long("dump-pairs")
+                .value_name("FILE")
+                .help("Write the distance between each pair of galaxies after the expansion to FILE as CSV"),
+        )
+        .arg(
+            Arg::new("metric")
+                .long("metric")
+                .value_name("METRIC")
+                .value_parser(|s: &str| s.parse::<Metric>())
+                .default_value("manhattan")
+                .help("Measure distances with METRIC (manhattan, chebyshev or euclidean-sq)"),
+        )
+        .arg(
+            Arg::new("stats")
+                .long("stats")
+                .action(ArgAction::SetTrue)
+                .help("Print statistics about the image and how much it expands"),
+        )
+        .get_matches();
+    RunOptions::from_matches(&matches).init();
+    let args = DayArgs::from_matches(&matches);
+    let _day = logging::day_span(11);
+    let metric: Metric = *matches
+        .get_one::<Metric>("metric")
+        .expect("--metric has a default value");
+    let solve = |input: &str, expansion| -> Result<i64, Fail> {
+        Ok(sum_distances(
+            &expand(&parse_input(input)?, expansion),
+            metric,
+        ))
+    };
+    let status = solver::run_day(
+        11,
+        &args,
+        |input| solve(input, PART1_EXPANSION),
+        |input| solve(input, PART2_EXPANSION),
     );
-}
-
-#[test]
-fn test_image_display() {
-    let representation = get_example();
-    let img = parse_input(representation).expect("example should be valid");
-    assert_eq!(&img.to_string(), representation);
-}
-
-#[test]
-fn test_col_occupation() {
-    let img = get_example_image();
-    assert_eq!(img.unoccupied_cols(), vec![2, 5, 8]);
-}
-
-#[test]
-fn test_row_occupation() {
-    let img = get_example_image();
-    assert_eq!(img.unoccupied_rows(), vec![3, 7]);
-}
-
-struct ExpandedImage<'a> {
-    original: &'a Image,
-    // x_map maps x values from the expanded coordinate system to the
-    // original coordinate system.
-    x_map: BiMap<i64, i64>,
-    // y_map maps y values from the expanded coordinate system to the
-    // original coordinate system.
-    y_map: BiMap<i64, i64>,
-    bounds: BoundingBox,
-}
-
-fn expand(img: &Image, expandby: i64) -> ExpandedImage<'_> {
-    let extra_rows_or_columns = expandby - 1;
-    let (x_map, max_x) = {
-        let mut empty_col_count: i64 = 0;
-        let mut x_map: BiMap<i64, i64> = Default::default();
-        let mut max_x = img.bounds.top_left.x;
-        for orig_x in (img.bounds.top_left.x)..=(img.bounds.bottom_right.x) {
-            let expanded_x = empty_col_count + orig_x;
-            max_x = max(max_x, expanded_x);
-            if img.occupied_cols.contains(&orig_x) {
-                x_map.insert(expanded_x, orig_x);
-            } else {
-                empty_col_count += extra_rows_or_columns;
-            }
-        }
-        (x_map, max_x)
+    let galaxies_path = matches.get_one::<String>("dump-galaxies");
+    let pairs_path = matches.get_one::<String>("dump-pairs");
+    let stats = matches.get_flag("stats");
+    if galaxies_path.is_none() && pairs_path.is_none() && !stats {
+        return status;
+    }
+    // Use the expansion for the part we were asked to solve, or the
+    // one for part 1 if we solved both.
+    let expansion = match args.part {
+        Some(2) => PART2_EXPANSION,
+        _ => PART1_EXPANSION,
     };
-
-    let (y_map, max_y) = {
-        let mut empty_row_count: i64 = 0;
-        let mut y_map: BiMap<i64, i64> = Default::default();
-        let mut max_y = img.bounds.top_left.y;
-
-        for orig_y in (img.bounds.top_left.y)..=(img.bounds.bottom_right.y) {
-            let expanded_y = empty_row_count + orig_y;
-            max_y = max(max_y, expanded_y);
-            if img.occupied_rows.contains_key(&orig_y) {
-                y_map.insert(expanded_y, orig_y);
-            } else {
-                empty_row_count += extra_rows_or_columns;
-            }
-        }
-        (y_map, max_y)
+    let write = |path: &String, csv: String| {
+        std::fs::write(path, csv).map_err(|e| fail!("failed to write {path}: {e}"))
     };
-
-    ExpandedImage {
-        original: img,
-        x_map,
-        y_map,
-        bounds: BoundingBox {
-            top_left: Position {
-                x: img.bounds.top_left.x,
-                y: img.bounds.top_left.y,
-            },
-            bottom_right: Position { x: max_x, y: max_y },
-        },
-    }
-}
-
-fn print_empty_row(f: &mut Formatter<'_>, len: i64) -> Result<(), std::fmt::Error> {
-    for _ in 0..len {
-        f.write_char('.')?;
-    }
-    Ok(())
-}
-
-impl<'a> Display for ExpandedImage<'a> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
-        for y in (self.bounds.top_left.y)..=(self.bounds.bottom_right.y) {
-            match self
-                .y_map
-                .get_by_left(&y)
-                .and_then(|orig_y| self.original.occupied_rows.get(orig_y))
-            {
-                Some(row) => {
-                    for x in (self.bounds.top_left.x)..=(self.bounds.bottom_right.x) {
-                        match self.x_map.get_by_left(&x) {
-                            Some(orig_x) => {
-                                if row.contains(orig_x) {
-                                    f.write_char('#')?;
-                                } else {
-                                    f.write_char('.')?;
-                                }
-                            }
-                            None => {
-                                f.write_char('.')?;
-                            }
-                        }
-                    }
-                }
-                None => {
-                    let len = 1 + self.bounds.bottom_right.x - self.bounds.top_left.x;
-                    print_empty_row(f, len)?;
+    let result = args
+        .input(11)
+        .map_err(Fail::from)
+        .and_then(|input| parse_input(&input))
+        .and_then(|image| {
+            if stats {
+                let stats = image.stats();
+                println!("{stats}");
+                println!("empty rows: {:?}", stats.empty_rows);
+                println!("empty columns: {:?}", stats.empty_cols);
+                for (part, expansion) in [(1, PART1_EXPANSION), (2, PART2_EXPANSION)] {
+                    let (dx, dy) = stats.expansion_delta(expansion);
+                    println!(
+                        "part {part}: expansion by {expansion} adds {dx} columns and {dy} rows"
+                    );
                 }
             }
-            f.write_char('\n')?;
-        }
-        Ok(())
-    }
-}
-
-impl<'a> ExpandedImage<'a> {
-    fn galaxies(&self) -> Vec<Position> {
-        let mut result = Vec::with_capacity(self.original.popcount());
-        for (y, xs) in self.original.occupied_rows.iter() {
-            let expanded_y = self
-                .y_map
-                .get_by_right(y)
-                .expect("y_map entry should exist");
-            for x in xs.iter() {
-                let expanded_x = self
-                    .x_map
-                    .get_by_right(x)
-                    .expect("x_map entry should exist");
-                result.push(Position {
-                    x: *expanded_x,
-                    y: *expanded_y,
-                });
+            let expanded = expand(&image, expansion);
+            if let Some(path) = galaxies_path {
+                write(path, expanded.galaxies_csv())?;
             }
-        }
-        result
-    }
-
-    fn galaxy_pairs(&self) -> Vec<(Position, Position)> {
-        let mut result: Vec<(Position, Position)> = Vec::new();
-        let v = self.galaxies();
-        fn galaxy_cmp(left: &Position, right: &Position) -> Ordering {
-            left.x.cmp(&right.x).then_with(|| left.y.cmp(&right.y))
-        }
-        for first in v.iter() {
-            for second in v.iter() {
-                if galaxy_cmp(first, second) == Ordering::Less {
-                    result.push((*first, *second));
-                }
+            if let Some(path) = pairs_path {
+                write(path, expanded.pairs_csv(metric))?;
             }
+            Ok(())
+        });
+    match result {
+        Ok(()) => status,
+        Err(e) => {
+            eprintln!("day 11: {e}");
+            ExitCode::FAILURE
         }
-        result
     }
 }
-
-#[test]
-fn test_expand() {
-    let img = get_example_image();
-    let expanded = expand(&img, 2);
-    let expected = concat!(
-        "....#........\n",
-        ".........#...\n",
-        "#............\n",
-        ".............\n",
-        ".............\n",
-        "........#....\n",
-        ".#...........\n",
-        "............#\n",
-        ".............\n",
-        ".............\n",
-        ".........#...\n",
-        "#....#.......\n",
-    );
-    let got = expanded.to_string();
-    println!("got:\n{got}");
-    println!("expected:\n{expected}");
-    assert_eq!(expanded.to_string(), expected);
-}
-
-fn sum_distances(expanded: &ExpandedImage<'_>) -> i64 {
-    expanded
-        .galaxy_pairs()
-        .iter()
-        .map(|(first, second)| manhattan(first, second))
-        .sum()
-}
-
-fn part1(img: &Image) -> i64 {
-    sum_distances(&expand(img, 2))
-}
-
-#[test]
-fn test_part1() {
-    let img = get_example_image();
-    assert_eq!(part1(&img), 374);
-}
-
-fn part2(img: &Image) -> i64 {
-    sum_distances(&expand(img, 1_000_000))
-}
-
-#[test]
-fn test_expand_10_100() {
-    let img = get_example_image();
-    assert_eq!(sum_distances(&expand(&img, 10)), 1030);
-    assert_eq!(sum_distances(&expand(&img, 100)), 8410);
-}
-
-fn main() {
-    let input = str::from_utf8(include_bytes!("input.txt")).unwrap();
-    let img = parse_input(input).expect("input should be valid");
-    println!("day 11 part 1: {}", part1(&img));
-    println!("day 11 part 2: {}", part2(&img));
-}
@@ -1,369 +1,70 @@
-use std::collections::BTreeMap;
-use std::collections::HashMap;
-use std::fmt::{Display, Write};
-use std::str;
+use std::process::ExitCode;
 
-use lib::error::Fail;
-
-use lib::grid::{BoundingBox, CompassDirection, Position};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Rock {
-    Round,
-    Cube,
-}
-
-impl Rock {
-    fn as_char(&self) -> char {
-        match self {
-            Rock::Round => 'O',
-            Rock::Cube => '#',
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Platform {
-    rocks: BTreeMap<Position, Rock>,
-    bbox: BoundingBox,
-}
-
-impl Display for Platform {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in self.bbox.top_left.y..=self.bbox.bottom_right.y {
-            for x in self.bbox.top_left.x..=self.bbox.bottom_right.x {
-                let here = Position { x, y };
-                let ch = self
-                    .rocks
-                    .get(&here)
-                    .map(|rock| rock.as_char())
-                    .unwrap_or('.');
-                f.write_char(ch)?;
-            }
-            f.write_char('\n')?;
-        }
-        Ok(())
-    }
-}
-
-fn parse_input(s: &str) -> Result<Platform, Fail> {
-    let mut rocks = BTreeMap::new();
-    let mut bbox: Option<BoundingBox> = None;
-    for (y, line) in s.split_terminator('\n').enumerate() {
-        for (x, ch) in line.chars().enumerate() {
-            let here = Position {
-                x: x as i64,
-                y: y as i64,
-            };
-            match bbox.as_mut() {
-                None => {
-                    bbox = Some(BoundingBox::new(&here));
-                }
-                Some(b) => {
-                    b.update(&here);
-                }
-            }
-            match ch {
-                '#' => {
-                    rocks.insert(here, Rock::Cube);
-                }
-                'O' => {
-                    rocks.insert(here, Rock::Round);
-                }
-                '.' => (),
-                other => {
-                    return Err(Fail(format!("unexpected input char {other}")));
-                }
-            }
-        }
-    }
-    if let Some(bbox) = bbox {
-        Ok(Platform { rocks, bbox })
-    } else {
-        Err(Fail("empty patterns are not allowed".to_string()))
-    }
-}
-
-#[cfg(test)]
-fn get_example() -> &'static str {
-    concat!(
-        "OOOO.#.O..\n",
-        "OO..#....#\n",
-        "OO..O##..O\n",
-        "O..#.OO...\n",
-        "........#.\n",
-        "..#....#.#\n",
-        "..O..#.O.O\n",
-        "..O.......\n",
-        "#....###..\n",
-        "#....#....\n",
-    )
-}
-
-#[cfg(test)]
-fn get_parsed_example() -> Platform {
-    parse_input(get_example()).expect("example should be valid")
-}
-
-#[test]
-fn test_parse() {
-    get_parsed_example();
-}
-
-#[test]
-fn test_tilt() {
-    let expected = tilted_north_example();
-    let got = get_parsed_example().tilt(CompassDirection::North);
-    println!("expected:\n{expected}\ngot:\n{got}");
-    assert_eq!(got, expected);
-}
-
-fn direction_vector(direction: &CompassDirection) -> (i64, i64) {
-    use CompassDirection::*;
-    match direction {
-        North => (0, -1),
-        East => (1, 0),
-        South => (0, 1),
-        West => (-1, 0),
-    }
-}
-
-fn next_pos(pos: &Position, (dx, dy): (i64, i64)) -> Position {
-    Position {
-        x: pos.x + dx,
-        y: pos.y + dy,
-    }
-}
-
-fn compute_final_position(
-    mut pos: Position,
-    direction: &CompassDirection,
-    occupied: &BTreeMap<Position, Rock>,
-    bounds: &BoundingBox,
-) -> Position {
-    let vector = direction_vector(direction);
-    loop {
-        let newpos = next_pos(&pos, vector);
-        if (!bounds.contains(&newpos)) || occupied.contains_key(&newpos) {
-            return pos;
-        } else {
-            pos = newpos;
-        }
-    }
-}
-
-impl Platform {
-    fn fingerprint(&self) -> String {
-        // We could make this a lot faster I'm sure.
-        self.to_string()
-    }
-
-    fn popcount(&self, rock: &Rock) -> usize {
-        self.rocks.values().filter(|r| *r == rock).count()
-    }
-
-    fn tilt(&self, direction: CompassDirection) -> Platform {
-        // We need to order the rocks such that those closest to the
-        // edge (in the direction of tilt) appear first.  This
-        // simplifies the process of moving them.  This lambda
-        // computes the order in which we should deal with the round
-        // rocks.
-        let rank = |pos: &Position| {
-            match direction {
-                // For tilting to the North or West, order the rocks
-                // in increasing (respectively) y or x value.  For
-                // tilting to the South or East, order the rocks in
-                // the opposite sense (so that we deal with high
-                // ordinate values dirst).
-                CompassDirection::North => pos.y,
-                CompassDirection::East => -pos.x,
-                CompassDirection::South => -pos.y,
-                CompassDirection::West => pos.x,
-            }
-        };
+use clap::{Arg, Command};
 
-        let round_rocks_by_original_pos: BTreeMap<i64, Vec<Position>> = self
-            .rocks
-            .iter()
-            .filter_map(|(pos, rock)| {
-                if *rock == Rock::Round {
-                    Some((rank(pos), *pos))
-                } else {
-                    None
-                }
-            })
-            .fold(BTreeMap::new(), |mut acc, (rank, pos)| {
-                acc.entry(rank)
-                    .and_modify(|v| v.push(pos))
-                    .or_insert_with(|| vec![pos]);
-                acc
-            });
-        assert_eq!(
-            self.popcount(&Rock::Round),
-            round_rocks_by_original_pos.values().map(|v| v.len()).sum(),
-            "We lost or gained some round rocks"
-        );
-
-        // Preserve the existing position of the cube rocks.
-        let mut new_positions: BTreeMap<Position, Rock> = self
-            .rocks
-            .iter()
-            .filter(|(_, rock)| **rock == Rock::Cube)
-            .map(|(p, r)| (*p, *r))
-            .collect();
-
-        // Move the rounded rocks in the correct direction.
-        for pos in round_rocks_by_original_pos
-            .iter()
-            .flat_map(|(_, pos)| pos.iter())
-        {
-            let newpos = compute_final_position(*pos, &direction, &new_positions, &self.bbox);
-            new_positions.insert(newpos, Rock::Round);
-        }
-
-        Platform {
-            rocks: new_positions,
-            bbox: self.bbox,
-        }
-    }
-
-    fn rock_load(&self, pos: &Position, rock: &Rock, tilt_direction: CompassDirection) -> i64 {
-        match rock {
-            Rock::Cube => 0,
-            Rock::Round => match tilt_direction {
-                CompassDirection::North => 1 + self.bbox.bottom_right.y - pos.y,
-                CompassDirection::South => 1 + pos.y - self.bbox.top_left.y,
-                CompassDirection::East => 1 + pos.x - self.bbox.top_left.x,
-                CompassDirection::West => 1 + self.bbox.bottom_right.x - pos.x,
-            },
-        }
-    }
-
-    fn loading(&self, direction: CompassDirection) -> i64 {
-        self.rocks
-            .iter()
-            .map(|(pos, rock)| self.rock_load(pos, rock, direction))
-            .sum()
-    }
-}
-
-#[cfg(test)]
-fn tilted_north_example() -> Platform {
-    parse_input(concat!(
-        "OOOO.#.O..\n", //  10
-        "OO..#....#\n", //   9
-        "OO..O##..O\n", //   8
-        "O..#.OO...\n", //   7
-        "........#.\n", //   6
-        "..#....#.#\n", //   5
-        "..O..#.O.O\n", //   4
-        "..O.......\n", //   3
-        "#....###..\n", //   2
-        "#....#....\n", //   1
-    ))
-    .expect("tilted example should be valid")
-}
-
-#[test]
-fn test_rock_load_cube() {
-    let tilted_platform = tilted_north_example();
-    for y in (tilted_platform.bbox.top_left.y)..=(tilted_platform.bbox.bottom_right.y) {
-        for x in (tilted_platform.bbox.top_left.x)..=(tilted_platform.bbox.bottom_right.x) {
-            assert_eq!(
-                tilted_platform.rock_load(&Position { x, y }, &Rock::Cube, CompassDirection::North),
-                0
-            );
-        }
-    }
-}
-
-#[test]
-fn test_rock_load_round() {
-    let tilted_platform = tilted_north_example();
-    assert_eq!(
-        tilted_platform.rock_load(
-            &Position { x: 0, y: 0 },
-            &Rock::Round,
-            CompassDirection::North
-        ),
-        10
-    );
-    assert_eq!(
-        tilted_platform.rock_load(
-            &Position { x: 0, y: 1 },
-            &Rock::Round,
-            CompassDirection::North
-        ),
-        9
-    );
-}
-
-#[test]
-fn test_loading() {
-    let tilted_platform = tilted_north_example();
-    assert_eq!(tilted_platform.loading(CompassDirection::North), 136);
-}
-
-fn part1(platform: &Platform) -> i64 {
-    platform
-        .tilt(CompassDirection::North)
-        .loading(CompassDirection::North)
-}
-
-#[test]
-fn test_part1() {
-    let platform = get_parsed_example();
-    assert_eq!(part1(&platform), 136);
-}
-
-fn part2(orig_platform: &Platform) -> i64 {
-    use CompassDirection::*;
-    const MAX_CYCLES: usize = 1000000000;
-
-    fn cycle(platform_in: Platform) -> Platform {
-        platform_in.tilt(North).tilt(West).tilt(South).tilt(East)
-    }
-
-    fn find_cycle_length(mut platform_in: Platform) -> Result<(Platform, usize), Platform> {
-        let mut states: HashMap<_, usize> = HashMap::new();
-        for cycle_number in 1..=MAX_CYCLES {
-            let platform_out = cycle(platform_in);
-            let fingerprint = platform_out.fingerprint();
-            if let Some(previous) = states.insert(fingerprint, cycle_number) {
-                let remaining = MAX_CYCLES - previous;
-                let cycle_length = cycle_number - previous;
-                let cycles_to_do = remaining % cycle_length;
-                return Ok((platform_out, cycles_to_do));
-            }
-            platform_in = platform_out;
+use lib::error::Fail;
+use lib::fail;
+use lib::logging;
+use lib::options::RunOptions;
+use lib::solver::{self, DayArgs};
+use lib::y2023::day14::{
+    parse_input, parse_sequence, part1, spin_history, SPIN_CYCLES, SPIN_ORDER,
+};
+
+fn main() -> ExitCode {
+    let matches = DayArgs::add_args(RunOptions::add_args(Command::new("day14")))
+        .arg(
+            Arg::new("dump-loads")
+                .long("dump-loads")
+                .value_name("FILE")
+                .help("Write the north load after each spin cycle (until the platform's position repeats) to FILE as CSV"),
+        )
+        .arg(
+            Arg::new("spin-order")
+                .long("spin-order")
+                .value_name("DIRECTIONS")
+                .value_parser(|s: &str| parse_sequence(s).map(|_| s.to_string()))
+                .default_value(SPIN_ORDER)
+                .help("Tilt the platform in each of DIRECTIONS (N, S, E or W) in each spin cycle"),
+        )
+        .arg(
+            Arg::new("spin-cycles")
+                .long("spin-cycles")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .help("Find the north load after N spin cycles in part 2 (default: a billion)"),
+        )
+        .get_matches();
+    RunOptions::from_matches(&matches).init();
+    let args = DayArgs::from_matches(&matches);
+    let _day = logging::day_span(14);
+    let order = matches
+        .get_one::<String>("spin-order")
+        .expect("--spin-order has a default value");
+    let cycles: usize = matches
+        .get_one::<usize>("spin-cycles")
+        .copied()
+        .unwrap_or(SPIN_CYCLES);
+    let Some(path) = matches.get_one::<String>("dump-loads") else {
+        return solver::run_day(14, &args, part1, |input| -> Result<i64, Fail> {
+            Ok(parse_input(input)?
+                .apply_sequence(order, cycles)?
+                .north_load())
+        });
+    };
+    let result = args
+        .input(14)
+        .map_err(Fail::from)
+        .and_then(|input| parse_input(&input))
+        .and_then(|platform| {
+            let csv = spin_history(&platform, order)?.to_csv();
+            std::fs::write(path, csv).map_err(|e| fail!("failed to write {path}: {e}"))
+        });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("day 14: {e}");
+            ExitCode::FAILURE
         }
-        Err(platform_in)
     }
-
-    let final_platform = match find_cycle_length(orig_platform.clone()) {
-        Ok((mut platform, cycles_still_to_do)) => {
-            for _ in 0..cycles_still_to_do {
-                platform = cycle(platform);
-            }
-            platform
-        }
-        Err(platform) => platform, // there was no cycle
-    };
-    final_platform.loading(CompassDirection::North)
-}
-
-#[test]
-fn test_part2() {
-    let platform = get_parsed_example();
-    assert_eq!(part2(&platform), 64);
-}
-
-fn get_input() -> &'static str {
-    str::from_utf8(include_bytes!("input.txt")).unwrap()
-}
-
-fn main() {
-    let input = parse_input(get_input()).expect("puzzle input should be valid");
-    println!("day 14 part 1: {}", part1(&input));
-    println!("day 14 part 2: {}", part2(&input));
 }
@@ -0,0 +1,76 @@
+use std::process::ExitCode;
+
+use clap::builder::RangedI64ValueParser;
+use clap::{value_parser, Arg, Command};
+
+use lib::gen::Rng;
+use lib::y2023::day22::{format_bricks, random_bricks, BrickParams};
+
+fn main() -> ExitCode {
+    let defaults = BrickParams::default();
+    let dimension = || RangedI64ValueParser::<i64>::new().range(1..);
+    let matches = Command::new("gen-bricks")
+        .about(
+            "Writes a random snapshot of falling bricks (day 22 puzzle input) to standard output",
+        )
+        .arg(
+            Arg::new("width")
+                .long("width")
+                .value_parser(dimension())
+                .help("Extent of the snapshot in x (default: 10)"),
+        )
+        .arg(
+            Arg::new("depth")
+                .long("depth")
+                .value_parser(dimension())
+                .help("Extent of the snapshot in y (default: 10)"),
+        )
+        .arg(
+            Arg::new("height")
+                .long("height")
+                .value_parser(dimension())
+                .help("Highest z at which a brick may start (default: 350)"),
+        )
+        .arg(
+            Arg::new("count")
+                .long("count")
+                .value_parser(value_parser!(usize))
+                .help("Number of bricks, or fewer if they don't fit (default: 1200)"),
+        )
+        .arg(
+            Arg::new("max-length")
+                .long("max-length")
+                .value_parser(dimension())
+                .help("Length of the longest brick (default: 5)"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_parser(value_parser!(u64))
+                .help("Seed for the random number generator (default: 0)"),
+        )
+        .get_matches();
+    let dimension =
+        |name: &str, default: i64| matches.get_one::<i64>(name).copied().unwrap_or(default);
+    let params = BrickParams {
+        width: dimension("width", defaults.width),
+        depth: dimension("depth", defaults.depth),
+        height: dimension("height", defaults.height),
+        count: matches
+            .get_one::<usize>("count")
+            .copied()
+            .unwrap_or(defaults.count),
+        max_length: dimension("max-length", defaults.max_length),
+    };
+    let seed = matches.get_one::<u64>("seed").copied().unwrap_or(0);
+    let bricks = random_bricks(&mut Rng::new(seed), &params);
+    if bricks.len() < params.count {
+        eprintln!(
+            "gen-bricks: only {} of {} bricks would fit",
+            bricks.len(),
+            params.count
+        );
+    }
+    print!("{}", format_bricks(&bricks));
+    ExitCode::SUCCESS
+}
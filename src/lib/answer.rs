@@ -0,0 +1,178 @@
+//! The answer to one part of a puzzle.
+//!
+//! The days' solutions return whatever integer type suits them, but
+//! everything which handles their answers afterwards (reporting them,
+//! checking them against the known answers, submitting them) uses an
+//! `Answer`.  This holds any of those integers exactly, so nothing
+//! has to be cast, and still allows for a puzzle whose answer is not
+//! a number.
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::Fail;
+
+/// The answer to one part of a puzzle.
+///
+/// An answer which is written as an integer is always an `Integer`,
+/// so the answer worked out by a solver compares equal to the same
+/// answer read back from a file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+}
+
+impl Answer {
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Answer::Integer(n) => Some(*n),
+            Answer::Text(_) => None,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{n}"),
+            Answer::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+macro_rules! integer_answers {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Answer {
+                    Answer::Integer(i128::from(n))
+                }
+            }
+        )*
+    };
+}
+
+integer_answers!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::Integer(i128::try_from(n).expect("a usize should fit in an i128"))
+    }
+}
+
+/// Text which is an integer in its usual form (such as "42", but not
+/// "042" or "+42") becomes an `Integer`.
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        match s.parse::<i128>() {
+            Ok(n) if n.to_string() == s => Answer::Integer(n),
+            _ => Answer::Text(s.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        match Answer::from(s.as_str()) {
+            Answer::Integer(n) => Answer::Integer(n),
+            Answer::Text(_) => Answer::Text(s),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Fail;
+
+    fn from_str(s: &str) -> Result<Answer, Fail> {
+        Ok(Answer::from(s))
+    }
+}
+
+#[test]
+fn test_answer() {
+    assert_eq!(Answer::from(6440_u32), Answer::Integer(6440));
+    assert_eq!(Answer::from(-3_i64), Answer::Integer(-3));
+    assert_eq!(Answer::from(7_usize), Answer::from("7"));
+    assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+    assert_eq!(
+        Answer::from(u64::MAX),
+        Answer::from("18446744073709551615".to_string())
+    );
+    assert_eq!(Answer::from("-12").as_integer(), Some(-12));
+    assert_eq!(Answer::from("042"), Answer::Text("042".to_string()));
+    assert_eq!(Answer::from("+42"), Answer::Text("+42".to_string()));
+    assert_eq!(Answer::from("EGH"), Answer::Text("EGH".to_string()));
+    assert_eq!(Answer::from("EGH").as_integer(), None);
+    assert_eq!("5905".parse::<Answer>(), Ok(Answer::Integer(5905)));
+}
+
+/// Integers are serialized as numbers (as long as they fit in an
+/// `i64` or a `u64`), and text as strings.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Answer {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(n) => match (i64::try_from(*n), u64::try_from(*n)) {
+                (Ok(n), _) => serializer.serialize_i64(n),
+                (_, Ok(n)) => serializer.serialize_u64(n),
+                _ => serializer.serialize_i128(*n),
+            },
+            Answer::Text(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Answer {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Answer, D::Error> {
+        struct AnswerVisitor;
+
+        impl serde::de::Visitor<'_> for AnswerVisitor {
+            type Value = Answer;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("an integer or a string")
+            }
+
+            fn visit_i64<E>(self, n: i64) -> Result<Answer, E> {
+                Ok(Answer::from(n))
+            }
+
+            fn visit_u64<E>(self, n: u64) -> Result<Answer, E> {
+                Ok(Answer::from(n))
+            }
+
+            fn visit_i128<E>(self, n: i128) -> Result<Answer, E> {
+                Ok(Answer::from(n))
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Answer, E> {
+                Ok(Answer::from(s))
+            }
+        }
+
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    for (answer, json) in [
+        (Answer::from(6440), "6440"),
+        (Answer::from(-1), "-1"),
+        (Answer::from(u64::MAX), "18446744073709551615"),
+        (Answer::from("EGH"), "\"EGH\""),
+    ] {
+        assert_eq!(
+            serde_json::to_string(&answer).expect("an answer should serialize"),
+            json
+        );
+        assert_eq!(serde_json::from_str::<Answer>(json).ok(), Some(answer));
+    }
+    // A number in a string is still a number.
+    assert_eq!(
+        serde_json::from_str::<Answer>("\"42\"").ok(),
+        Some(Answer::Integer(42))
+    );
+}
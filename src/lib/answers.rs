@@ -0,0 +1,233 @@
+//! Remembers the answers that adventofcode.com has confirmed are
+//! correct, so that we can notice when a change to a solution breaks
+//! it.
+//!
+//! The answers are kept in `answers.toml` in the input directory
+//! (since they depend on the puzzle input), one table per day:
+//!
+//! ```toml
+//! [day07]
+//! part1 = "6440"
+//! part2 = "5905"
+//! ```
+//!
+//! We only need this small subset of TOML, so we read and write it
+//! ourselves.
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::answer::Answer;
+use crate::error::Fail;
+use crate::fail;
+
+/// The known correct answers, by day and part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    known: BTreeMap<(u8, u8), Answer>,
+}
+
+impl Answers {
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.known.get(&(day, part))
+    }
+
+    /// Records `answer` as the correct answer.  Returns true if this
+    /// changed what we knew.
+    pub fn insert(&mut self, day: u8, part: u8, answer: impl Into<Answer>) -> bool {
+        let answer = answer.into();
+        self.known.insert((day, part), answer.clone()) != Some(answer)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.known.is_empty()
+    }
+
+    /// Returns the days for which we know at least one answer.
+    pub fn days(&self) -> Vec<u8> {
+        let mut days: Vec<u8> = self.known.keys().map(|(day, _)| *day).collect();
+        days.dedup();
+        days
+    }
+
+    /// Compares the answer we got for one part of a puzzle with the
+    /// one we know is correct.  Returns a description of the problem
+    /// if they differ.  When we don't know the correct answer, any
+    /// answer passes.
+    pub fn check(&self, day: u8, part: u8, got: Option<&Answer>) -> Result<(), Fail> {
+        match (self.get(day, part), got) {
+            (None, _) => Ok(()),
+            (Some(expected), Some(got)) if expected == got => Ok(()),
+            (Some(expected), Some(got)) => Err(fail!(
+                "day {day:02} part {part}: got {got}, expected {expected}"
+            )),
+            (Some(expected), None) => Err(fail!(
+                "day {day:02} part {part}: got no answer, expected {expected}"
+            )),
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next()? {
+                ch @ ('\\' | '"') => result.push(ch),
+                _ => return None,
+            },
+            '"' => return None,
+            ch => result.push(ch),
+        }
+    }
+    Some(result)
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut current_day = None;
+        for ((day, part), answer) in self.known.iter() {
+            if current_day != Some(*day) {
+                if current_day.is_some() {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[day{day:02}]")?;
+                current_day = Some(*day);
+            }
+            writeln!(f, "part{part} = {}", quote(&answer.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for Answers {
+    type Error = Fail;
+    fn try_from(s: &str) -> Result<Answers, Fail> {
+        let mut answers = Answers::default();
+        let mut day: Option<u8> = None;
+        for (n, line) in s.lines().enumerate() {
+            let bad_line = || fail!("line {}: cannot understand '{line}'", n + 1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(table) = line.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+                day = Some(
+                    table
+                        .trim()
+                        .strip_prefix("day")
+                        .and_then(|d| d.parse().ok())
+                        .ok_or_else(bad_line)?,
+                );
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(bad_line)?;
+            let part: u8 = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(bad_line()),
+            };
+            let day =
+                day.ok_or_else(|| fail!("line {}: answer is not in a [dayNN] table", n + 1))?;
+            let answer = unquote(value.trim()).ok_or_else(bad_line)?;
+            answers.insert(day, part, answer);
+        }
+        Ok(answers)
+    }
+}
+
+#[test]
+fn test_answers_round_trip() {
+    let mut answers = Answers::default();
+    assert!(answers.insert(7, 2, "5905"));
+    assert!(answers.insert(7, 1, "6440"));
+    assert!(answers.insert(15, 1, "a \"quoted\" \\ answer"));
+    assert!(!answers.insert(7, 1, "6440"));
+    let text = answers.to_string();
+    assert_eq!(
+        text,
+        concat!(
+            "[day07]\n",
+            "part1 = \"6440\"\n",
+            "part2 = \"5905\"\n",
+            "\n",
+            "[day15]\n",
+            "part1 = \"a \\\"quoted\\\" \\\\ answer\"\n",
+        )
+    );
+    assert_eq!(Answers::try_from(text.as_str()), Ok(answers.clone()));
+    assert_eq!(answers.get(7, 1), Some(&Answer::Integer(6440)));
+    assert_eq!(answers.days(), vec![7, 15]);
+}
+
+#[test]
+fn test_answers_parse_errors() {
+    assert_eq!(
+        Answers::try_from("# comment\n\n[ day01 ]\n part1 = \"142\" \n").map(|a| a.known.len()),
+        Ok(1)
+    );
+    assert!(Answers::try_from("part1 = \"142\"\n").is_err());
+    assert!(Answers::try_from("[day01]\npart3 = \"142\"\n").is_err());
+    assert!(Answers::try_from("[day01]\npart1 = 142\n").is_err());
+    assert!(Answers::try_from("[dayone]\n").is_err());
+}
+
+#[test]
+fn test_answers_check() {
+    let mut answers = Answers::default();
+    answers.insert(1, 1, "142");
+    assert_eq!(answers.check(1, 1, Some(&Answer::from(142))), Ok(()));
+    assert_eq!(answers.check(1, 2, Some(&Answer::from(281))), Ok(()));
+    assert_eq!(answers.check(1, 2, None), Ok(()));
+    assert_eq!(
+        answers.check(1, 1, Some(&Answer::from(143))),
+        Err(Fail("day 01 part 1: got 143, expected 142".to_string()))
+    );
+    assert_eq!(
+        answers.check(1, 1, None),
+        Err(Fail(
+            "day 01 part 1: got no answer, expected 142".to_string()
+        ))
+    );
+}
+
+pub fn answers_path(dir: &Path) -> PathBuf {
+    dir.join("answers.toml")
+}
+
+/// Reads the known answers.  A missing file just means that we don't
+/// know any yet.
+pub fn read_answers(path: &Path) -> Result<Answers, Fail> {
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            Answers::try_from(content.as_str()).map_err(|Fail(e)| fail!("{}: {e}", path.display()))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Answers::default()),
+        Err(e) => Err(fail!("failed to read {}: {e}", path.display())),
+    }
+}
+
+pub fn write_answers(path: &Path, answers: &Answers) -> Result<(), Fail> {
+    let io_error = |e: std::io::Error| fail!("failed to write {}: {e}", path.display());
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(path, answers.to_string()).map_err(io_error)
+}
+
+/// Records that `answer` is the correct answer for one part of the
+/// puzzle for `day`, in the file at `path`.
+pub fn record_answer(path: &Path, day: u8, part: u8, answer: &Answer) -> Result<(), Fail> {
+    let mut answers = read_answers(path)?;
+    if answers.insert(day, part, answer.clone()) {
+        write_answers(path, &answers)?;
+    }
+    Ok(())
+}
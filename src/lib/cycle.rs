@@ -0,0 +1,180 @@
+//! Finding the cycle in the sequence of states produced by repeatedly
+//! applying a function.
+//!
+//! A sequence `x0, f(x0), f(f(x0)), ...` over a finite set of states
+//! must eventually repeat.  It consists of a prefix of `prefix_len`
+//! states which never recur, followed by a cycle of `period` states
+//! which repeats forever.  Knowing these, we can find the state after
+//! an enormous number of steps without computing all of them.
+//!
+//! These functions loop forever if the sequence never repeats.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleInfo {
+    /// The number of states before the cycle starts.
+    pub prefix_len: usize,
+    /// The length of the cycle.
+    pub period: usize,
+}
+
+impl CycleInfo {
+    /// Returns the smallest `m` such that the state after `m` steps
+    /// is the same as the state after `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix_len {
+            n
+        } else {
+            self.prefix_len + (n - self.prefix_len) % self.period
+        }
+    }
+}
+
+/// The result of `detect`: the shape of the sequence, and the states
+/// it passes through.
+#[derive(Debug, Clone)]
+pub struct Cycle<T> {
+    pub info: CycleInfo,
+    /// The states in the prefix and the first repetition of the cycle.
+    states: Vec<T>,
+}
+
+impl<T> Cycle<T> {
+    /// Returns the state after `n` steps.
+    pub fn nth_state_after(&self, n: usize) -> &T {
+        &self.states[self.info.reduce(n)]
+    }
+
+    /// Returns the states in the prefix followed by one repetition of
+    /// the cycle, in order.
+    pub fn states(&self) -> &[T] {
+        &self.states
+    }
+}
+
+/// Finds the cycle by remembering every state seen so far.  This
+/// finds the cycle as soon as the first state repeats, and makes any
+/// state available afterwards, at the cost of keeping a copy of each
+/// state.
+pub fn detect<T, F>(initial: T, mut step: F) -> Cycle<T>
+where
+    T: Hash + Eq + Clone,
+    F: FnMut(&T) -> T,
+{
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    let mut states: Vec<T> = Vec::new();
+    let mut current = initial;
+    loop {
+        if let Some(first) = seen.get(&current) {
+            let info = CycleInfo {
+                prefix_len: *first,
+                period: states.len() - first,
+            };
+            return Cycle { info, states };
+        }
+        seen.insert(current.clone(), states.len());
+        let next = step(&current);
+        states.push(current);
+        current = next;
+    }
+}
+
+/// Finds the cycle using Brent's algorithm, which keeps only two
+/// states at a time but calls `step` more often than `detect` does.
+pub fn brent<T, F>(initial: &T, mut step: F) -> CycleInfo
+where
+    T: Eq + Clone,
+    F: FnMut(&T) -> T,
+{
+    // Find the period by looking for the hare meeting the tortoise,
+    // which is teleported to the hare's position at each power of two.
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+    // Then find the start of the cycle, using two pointers which are
+    // `period` steps apart.
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut prefix_len = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix_len += 1;
+    }
+    CycleInfo { prefix_len, period }
+}
+
+/// Returns the state after `n` steps, given the shape of the sequence
+/// (as found by `brent`).
+pub fn nth_state_after<T, F>(initial: T, mut step: F, info: &CycleInfo, n: usize) -> T
+where
+    F: FnMut(&T) -> T,
+{
+    (0..info.reduce(n)).fold(initial, |state, _| step(&state))
+}
+
+#[cfg(test)]
+fn example_step(x: &u32) -> u32 {
+    // 0, 3, 12, 24, 5, 28, 8, 26, 23, 40, 4, 19, 36, 28, ...
+    (x * x + 3) % 41
+}
+
+#[test]
+fn test_detect() {
+    let cycle = detect(0_u32, example_step);
+    assert_eq!(
+        cycle.info,
+        CycleInfo {
+            prefix_len: 5,
+            period: 8
+        }
+    );
+    assert_eq!(*cycle.nth_state_after(0), 0);
+    assert_eq!(*cycle.nth_state_after(4), 5);
+    assert_eq!(*cycle.nth_state_after(5), 28);
+    assert_eq!(*cycle.nth_state_after(13), 28);
+    let slow = (0..1000).fold(0, |x, _| example_step(&x));
+    assert_eq!(*cycle.nth_state_after(1000), slow);
+    assert_eq!(cycle.states().len(), 13);
+    assert_eq!(&cycle.states()[..4], &[0, 3, 12, 24]);
+}
+
+#[test]
+fn test_brent_agrees_with_detect() {
+    for initial in 0..41_u32 {
+        let expected = detect(initial, example_step);
+        let info = brent(&initial, example_step);
+        assert_eq!(info, expected.info, "initial state {initial}");
+        assert_eq!(
+            nth_state_after(initial, example_step, &info, 123_456_789),
+            *expected.nth_state_after(123_456_789)
+        );
+    }
+}
+
+#[test]
+fn test_pure_cycle() {
+    let info = brent(&0_u8, |x| (x + 1) % 3);
+    assert_eq!(
+        info,
+        CycleInfo {
+            prefix_len: 0,
+            period: 3
+        }
+    );
+    assert_eq!(info.reduce(7), 1);
+}
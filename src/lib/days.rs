@@ -1,3 +0,0 @@
-// Day-specific code.  This code is in the library so that it is
-// callable from benchmarks.
-pub mod day15;
@@ -15,3 +15,34 @@ impl std::error::Error for Fail {}
 pub fn fail_from_error(e: &dyn std::error::Error) -> Fail {
     Fail(e.to_string())
 }
+
+impl From<regex::Error> for Fail {
+    fn from(e: regex::Error) -> Fail {
+        Fail(e.to_string())
+    }
+}
+
+/// Makes a `Fail` with a message formatted as by `format!`.
+#[macro_export]
+macro_rules! fail {
+    ($($arg:tt)*) => {
+        $crate::error::Fail(format!($($arg)*))
+    };
+}
+
+#[test]
+fn test_fail_macro() {
+    let day = 7;
+    assert_eq!(
+        fail!("no answer for day {day} part {}", 2),
+        Fail("no answer for day 7 part 2".to_string())
+    );
+}
+
+#[test]
+fn test_from_regex_error() {
+    // Build the pattern at run time, so that clippy doesn't reject it.
+    let unclosed = ["(", "a"].concat();
+    let e = Fail::from(regex::Regex::new(&unclosed).expect_err("pattern is invalid"));
+    assert!(e.to_string().contains("unclosed group"), "{e}");
+}
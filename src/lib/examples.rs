@@ -0,0 +1,59 @@
+//! The example inputs given in the puzzle descriptions.
+//!
+//! These live in `testdata/dayNN/exampleK.txt` and are compiled in
+//! here, so that the unit tests, integration tests and benchmarks
+//! all use the same copy.
+
+/// Returns the examples for `day`, in the order in which they appear
+/// in the puzzle description.  Days we have no solution for have no
+/// examples.
+pub fn examples(day: u8) -> &'static [&'static str] {
+    match day {
+        1 => &[
+            include_str!("../../testdata/day01/example1.txt"),
+            include_str!("../../testdata/day01/example2.txt"),
+        ],
+        2 => &[include_str!("../../testdata/day02/example1.txt")],
+        3 => &[include_str!("../../testdata/day03/example1.txt")],
+        4 => &[include_str!("../../testdata/day04/example1.txt")],
+        5 => &[include_str!("../../testdata/day05/example1.txt")],
+        6 => &[include_str!("../../testdata/day06/example1.txt")],
+        7 => &[include_str!("../../testdata/day07/example1.txt")],
+        8 => &[
+            include_str!("../../testdata/day08/example1.txt"),
+            include_str!("../../testdata/day08/example2.txt"),
+            include_str!("../../testdata/day08/example3.txt"),
+        ],
+        9 => &[include_str!("../../testdata/day09/example1.txt")],
+        10 => &[
+            include_str!("../../testdata/day10/example1.txt"),
+            include_str!("../../testdata/day10/example2.txt"),
+            include_str!("../../testdata/day10/example3.txt"),
+        ],
+        11 => &[include_str!("../../testdata/day11/example1.txt")],
+        13 => &[include_str!("../../testdata/day13/example1.txt")],
+        14 => &[include_str!("../../testdata/day14/example1.txt")],
+        15 => &[include_str!("../../testdata/day15/example1.txt")],
+        16 => &[include_str!("../../testdata/day16/example1.txt")],
+        18 => &[include_str!("../../testdata/day18/example1.txt")],
+        19 => &[include_str!("../../testdata/day19/example1.txt")],
+        22 => &[include_str!("../../testdata/day22/example1.txt")],
+        _ => &[],
+    }
+}
+
+/// Returns the first example for `day`, if there is one.
+pub fn example(day: u8) -> Option<&'static str> {
+    examples(day).first().copied()
+}
+
+#[test]
+fn test_examples() {
+    assert_eq!(
+        example(15),
+        Some("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n")
+    );
+    assert_eq!(examples(8).len(), 3);
+    assert_eq!(example(12), None);
+    assert!((1..=25).flat_map(examples).all(|text| text.ends_with('\n')));
+}
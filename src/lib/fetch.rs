@@ -0,0 +1,160 @@
+//! Downloads puzzle inputs from adventofcode.com.
+//!
+//! Puzzle inputs differ for each user, so the site requires the
+//! session cookie of a logged-in user.  We take this from the
+//! `AOC_SESSION` environment variable.
+//!
+//! Talking to the site needs the `fetch` feature.  Without it, every
+//! request fails with `FetchError::Disabled`, so inputs must already
+//! have been downloaded.
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Fail;
+
+/// The environment variable holding the adventofcode.com session cookie.
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+
+pub const YEAR: u16 = crate::y2023::YEAR;
+
+#[cfg(feature = "fetch")]
+const USER_AGENT: &str = "github.com/jamesyoungman/advent-of-rust-2023 by james@youngman.org";
+
+#[derive(Debug)]
+pub enum FetchError {
+    NoSession,
+    Disabled,
+    Http { url: String, status: u16 },
+    Transport { url: String, message: String },
+    Io { path: PathBuf, err: std::io::Error },
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::NoSession => write!(
+                f,
+                "cannot download puzzle input: {SESSION_VARIABLE} is not set"
+            ),
+            FetchError::Disabled => f.write_str(
+                "cannot reach adventofcode.com: this program was built without the fetch feature",
+            ),
+            FetchError::Http { url, status } => {
+                write!(f, "request for {url} failed with HTTP status {status}")
+            }
+            FetchError::Transport { url, message } => {
+                write!(f, "request for {url} failed: {message}")
+            }
+            FetchError::Io { path, err } => {
+                write!(f, "failed to write {}: {err}", path.display())
+            }
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+impl From<FetchError> for Fail {
+    fn from(e: FetchError) -> Fail {
+        Fail(e.to_string())
+    }
+}
+
+/// Returns the session cookie from the environment.
+pub fn session_token() -> Result<String, FetchError> {
+    match std::env::var(SESSION_VARIABLE) {
+        Ok(token) if !token.trim().is_empty() => Ok(token.trim().to_string()),
+        _ => Err(FetchError::NoSession),
+    }
+}
+
+pub fn input_url(day: u8) -> String {
+    format!("https://adventofcode.com/{YEAR}/day/{day}/input")
+}
+
+#[test]
+fn test_input_url() {
+    assert_eq!(input_url(7), "https://adventofcode.com/2023/day/7/input");
+}
+
+/// Sends a request to the site, authenticated with `session`, and
+/// returns the body of the response.  If `form` is not empty, it is
+/// sent as the body of the request.
+#[cfg(feature = "fetch")]
+pub(crate) fn send(
+    method: &str,
+    url: &str,
+    session: &str,
+    form: &[(&str, &str)],
+) -> Result<String, FetchError> {
+    let transport_error = |message: String| FetchError::Transport {
+        url: url.to_string(),
+        message,
+    };
+    let request = ureq::request(method, url)
+        .set("Cookie", &format!("session={session}"))
+        .set("User-Agent", USER_AGENT);
+    let outcome = if form.is_empty() {
+        request.call()
+    } else {
+        request.send_form(form)
+    };
+    match outcome {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| transport_error(e.to_string())),
+        Err(ureq::Error::Status(status, _)) => Err(FetchError::Http {
+            url: url.to_string(),
+            status,
+        }),
+        Err(ureq::Error::Transport(t)) => Err(transport_error(t.to_string())),
+    }
+}
+
+#[cfg(not(feature = "fetch"))]
+pub(crate) fn send(
+    _method: &str,
+    _url: &str,
+    _session: &str,
+    _form: &[(&str, &str)],
+) -> Result<String, FetchError> {
+    Err(FetchError::Disabled)
+}
+
+#[cfg(not(feature = "fetch"))]
+#[test]
+fn test_send_disabled() {
+    assert!(matches!(
+        download_input(1, "session"),
+        Err(FetchError::Disabled)
+    ));
+}
+
+/// Downloads the puzzle input for `day`.
+pub fn download_input(day: u8, session: &str) -> Result<String, FetchError> {
+    send("GET", &input_url(day), session, &[])
+}
+
+/// Stores `content` at `path`, creating the parent directory if
+/// necessary.
+pub fn save(path: &Path, content: &str) -> Result<(), FetchError> {
+    let io_error = |err| FetchError::Io {
+        path: path.to_path_buf(),
+        err,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(path, content).map_err(io_error)
+}
+
+/// Downloads the puzzle input for `day` into `path`, returning the
+/// input.
+pub fn fetch_input(day: u8, path: &Path) -> Result<String, FetchError> {
+    let session = session_token()?;
+    let input = download_input(day, &session)?;
+    save(path, &input)?;
+    Ok(input)
+}
@@ -0,0 +1,524 @@
+//! Random puzzle inputs, for stress tests and benchmarks at sizes
+//! much larger than the real puzzle inputs.
+//!
+//! The generators take a seed rather than using a source of true
+//! randomness, so that an input which shows up a problem can be
+//! generated again.
+use std::ops::RangeInclusive;
+
+/// A small, fast pseudo-random number generator (SplitMix64).  It is
+/// not suitable for anything which needs to be unpredictable, but it
+/// is plenty good enough for making up puzzle inputs.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `range`, which must not be empty.
+    pub fn in_range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (low, high) = range.into_inner();
+        assert!(
+            low <= high,
+            "cannot choose from the empty range {low}..={high}"
+        );
+        let width = high.abs_diff(low).wrapping_add(1);
+        let offset = match width {
+            // The range covers every i64.
+            0 => self.next_u64(),
+            width => self.next_u64() % width,
+        };
+        low.wrapping_add(offset as i64)
+    }
+
+    /// Returns an index into a collection of `len` items, which must
+    /// not be zero.
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "cannot choose from an empty collection");
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// Returns a number in [0, 1).
+    pub fn fraction(&mut self) -> f64 {
+        // The top 53 bits give an evenly distributed f64.
+        ((self.next_u64() >> 11) as f64) / ((1u64 << 53) as f64)
+    }
+
+    /// Returns true with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.fraction() < p
+    }
+}
+
+#[test]
+fn test_rng_is_repeatable() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    let mut c = Rng::new(43);
+    let from_a: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+    let from_b: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+    let from_c: Vec<u64> = (0..10).map(|_| c.next_u64()).collect();
+    assert_eq!(from_a, from_b);
+    assert_ne!(from_a, from_c);
+}
+
+#[test]
+fn test_rng_ranges() {
+    let mut rng = Rng::new(7);
+    for _ in 0..1000 {
+        assert!((-3..=5).contains(&rng.in_range(-3..=5)));
+        assert!(rng.index(4) < 4);
+    }
+    assert_eq!(rng.in_range(9..=9), 9);
+    // The full range should not overflow.
+    rng.in_range(i64::MIN..=i64::MAX);
+    assert!(!rng.chance(0.0));
+    assert!(rng.chance(1.0));
+}
+
+/// Joins rows of characters up into puzzle input.
+fn to_text(rows: &[Vec<char>]) -> String {
+    rows.iter()
+        .map(|row| {
+            let mut line: String = row.iter().collect();
+            line.push('\n');
+            line
+        })
+        .collect()
+}
+
+/// Returns a `width` by `height` grid in which each cell is chosen
+/// from `tiles` with the given probability, or is '.'.  The
+/// probabilities should add up to at most 1.
+fn scatter(rng: &mut Rng, width: usize, height: usize, tiles: &[(char, f64)]) -> Vec<Vec<char>> {
+    (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| {
+                    let mut p = 0.0;
+                    let roll = rng.fraction();
+                    for (tile, chance) in tiles {
+                        p += chance;
+                        if roll < p {
+                            return *tile;
+                        }
+                    }
+                    '.'
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns a calibration document for day 1, with `lines` lines of
+/// lower-case letters.  Digits, and their names, are scattered
+/// through each line; every line has at least one digit.
+pub fn calibration_document(rng: &mut Rng, lines: usize) -> String {
+    const NAMES: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut result = String::new();
+    for _ in 0..lines {
+        let mut line = String::new();
+        let length = rng.in_range(5..=40) as usize;
+        while line.len() < length {
+            if rng.chance(0.05) {
+                line.push_str(NAMES[rng.index(NAMES.len())]);
+            } else if rng.chance(0.05) {
+                line.push(char::from(b'0' + rng.index(10) as u8));
+            } else {
+                line.push(char::from(b'a' + rng.index(26) as u8));
+            }
+        }
+        let at = rng.index(line.len() + 1);
+        line.insert(at, char::from(b'1' + rng.index(9) as u8));
+        result.push_str(&line);
+        result.push('\n');
+    }
+    result
+}
+
+/// Returns an initialization sequence for day 15, with `steps` steps
+/// separated by commas.  The labels are made of two to eight
+/// lower-case letters.
+pub fn initialization_sequence(rng: &mut Rng, steps: usize) -> String {
+    let steps: Vec<String> = (0..steps)
+        .map(|_| {
+            let length = rng.in_range(2..=8) as usize;
+            let mut step: String = (0..length)
+                .map(|_| char::from(b'a' + rng.index(26) as u8))
+                .collect();
+            if rng.chance(0.5) {
+                step.push('-');
+            } else {
+                step.push('=');
+                step.push(char::from(b'1' + rng.index(9) as u8));
+            }
+            step
+        })
+        .collect();
+    steps.join(",")
+}
+
+/// Returns a map of the haunted wasteland for day 8, with
+/// `instructions` random left and right turns.  There is a ghost for
+/// each of `periods`, the first starting at AAA and the others at
+/// BBA, CCA and so on.  Whichever way it turns, ghost `k` reaches a
+/// node ending in Z after `periods[k]` steps, and every `periods[k]`
+/// steps after that, so the answer to part 2 is the least common
+/// multiple of `periods`.
+pub fn ghost_network(rng: &mut Rng, instructions: usize, periods: &[usize]) -> String {
+    assert!(periods.len() <= 25, "there are names for only 25 ghosts");
+    assert!(
+        periods.iter().all(|p| *p >= 2),
+        "each period must be at least 2"
+    );
+    let mut result: String = (0..instructions)
+        .map(|_| if rng.chance(0.5) { 'L' } else { 'R' })
+        .collect();
+    result.push_str("\n\n");
+    // Names for the nodes in between, which end in neither A nor Z.
+    const LAST: &[u8] = b"0123456789BCDEFGHIJKLMNOPQRSTUVWXY";
+    const OTHER: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut count = 0;
+    let mut next_name = || {
+        let n = count;
+        count += 1;
+        let last = LAST[n % LAST.len()];
+        let middle = OTHER[(n / LAST.len()) % OTHER.len()];
+        let first = OTHER[n / (LAST.len() * OTHER.len())];
+        String::from_utf8(vec![first, middle, last]).expect("names are ASCII")
+    };
+    let node = |result: &mut String, name: &str, left: &str, right: &str| {
+        result.push_str(&format!("{name} = ({left}, {right})\n"));
+    };
+    for (k, period) in periods.iter().enumerate() {
+        let (start, target) = if k == 0 {
+            ("AAA".to_string(), "ZZZ".to_string())
+        } else {
+            let c = char::from(b'A' + k as u8);
+            (format!("{c}{c}A"), format!("{c}{c}Z"))
+        };
+        // Each step between the target and the next visit to it
+        // can go through either of two nodes.
+        let pairs: Vec<[String; 2]> = (1..*period).map(|_| [next_name(), next_name()]).collect();
+        let mut pick = |i: usize| pairs[i][rng.index(2)].clone();
+        let (left, right) = (pick(0), pick(0));
+        node(&mut result, &start, &left, &right);
+        node(&mut result, &target, &left, &right);
+        for i in 0..pairs.len() {
+            for name in pairs[i].iter() {
+                let (left, right) = match pairs.get(i + 1) {
+                    Some(_) => (pick(i + 1), pick(i + 1)),
+                    None => (target.clone(), target.clone()),
+                };
+                node(&mut result, name, &left, &right);
+            }
+        }
+    }
+    result
+}
+
+/// Returns a pattern of ash ('.') and rocks ('#') for day 13.  The
+/// pattern is symmetrical about a randomly chosen line between two
+/// rows or two columns, and `density` is the fraction of cells which
+/// are rocks.  A small pattern may by chance have other lines of
+/// reflection too.
+pub fn reflected_pattern(rng: &mut Rng, width: usize, height: usize, density: f64) -> String {
+    assert!(
+        width >= 2 || height >= 2,
+        "a pattern needs two rows or two columns to have a line of reflection"
+    );
+    let mut rows = scatter(rng, width, height, &[('#', density)]);
+    let between_columns = height < 2 || (width >= 2 && rng.chance(0.5));
+    if between_columns {
+        // The line lies between columns `axis` and `axis + 1`.
+        let axis = rng.index(width - 1);
+        for row in rows.iter_mut() {
+            for x in (axis + 1)..width.min(2 * axis + 2) {
+                row[x] = row[2 * axis + 1 - x];
+            }
+        }
+    } else {
+        let axis = rng.index(height - 1);
+        for y in (axis + 1)..height.min(2 * axis + 2) {
+            rows[y] = rows[2 * axis + 1 - y].clone();
+        }
+    }
+    to_text(&rows)
+}
+
+/// Returns a platform for day 14, in which the given fractions of the
+/// cells are round rocks ('O') and cube-shaped rocks ('#').
+pub fn platform(rng: &mut Rng, width: usize, height: usize, round: f64, cube: f64) -> String {
+    to_text(&scatter(rng, width, height, &[('O', round), ('#', cube)]))
+}
+
+/// Returns a contraption for day 16, in which `density` is the
+/// fraction of the cells which hold a mirror or a splitter (all four
+/// kinds being equally likely).
+pub fn mirror_grid(rng: &mut Rng, width: usize, height: usize, density: f64) -> String {
+    let each = density / 4.0;
+    to_text(&scatter(
+        rng,
+        width,
+        height,
+        &[('/', each), ('\\', each), ('-', each), ('|', each)],
+    ))
+}
+
+/// Returns an image of the sky for day 11, in which `density` is the
+/// fraction of the cells which hold a galaxy.  A low density leaves
+/// some rows and columns empty, which is what makes the universe
+/// expand.
+pub fn galaxy_image(rng: &mut Rng, width: usize, height: usize, density: f64) -> String {
+    to_text(&scatter(rng, width, height, &[('#', density)]))
+}
+
+/// Returns a field of pipes for day 10, containing a single loop
+/// which passes through the start ('S').  The other cells hold junk
+/// pipes (which may form loops of their own, but are not connected
+/// to the start) or ground.
+///
+/// The loop is the outline of a random tree grown over a grid of
+/// 2x2 blocks; `fill` is the fraction of the blocks which the tree
+/// should cover.  The loop passes through every cell of those
+/// blocks, and any blocks the tree grows around are enclosed by it.
+/// If `width` or `height` is odd, the last column or row is all junk.
+pub fn pipe_loop(rng: &mut Rng, width: usize, height: usize, fill: f64) -> String {
+    let (columns, rows) = (width / 2, height / 2);
+    assert!(columns > 0 && rows > 0, "the field must be at least 2x2");
+    let block_count = columns * rows;
+    let target = ((block_count as f64 * fill).round() as usize).clamp(1, block_count);
+
+    // Grow the tree by repeatedly adding a random edge from a block
+    // in the tree to a neighbouring block which is not.
+    const NORTH: usize = 0;
+    const EAST: usize = 1;
+    const SOUTH: usize = 2;
+    const WEST: usize = 3;
+    let step = |(c, r): (usize, usize), dir: usize| -> Option<(usize, usize)> {
+        match dir {
+            NORTH => r.checked_sub(1).map(|r| (c, r)),
+            EAST => (c + 1 < columns).then_some((c + 1, r)),
+            SOUTH => (r + 1 < rows).then_some((c, r + 1)),
+            WEST => c.checked_sub(1).map(|c| (c, r)),
+            _ => unreachable!("there are only four directions"),
+        }
+    };
+    // For each block, whether it is in the tree and which of its
+    // sides have a tree edge.
+    let mut in_tree = vec![vec![false; columns]; rows];
+    let mut edges = vec![vec![[false; 4]; columns]; rows];
+    let first = (rng.index(columns), rng.index(rows));
+    in_tree[first.1][first.0] = true;
+    let mut size = 1;
+    let all_directions = [NORTH, EAST, SOUTH, WEST];
+    let mut frontier: Vec<((usize, usize), usize)> =
+        all_directions.map(|dir| (first, dir)).to_vec();
+    while size < target && !frontier.is_empty() {
+        let (from, dir) = frontier.swap_remove(rng.index(frontier.len()));
+        let Some(to) = step(from, dir) else {
+            continue;
+        };
+        if in_tree[to.1][to.0] {
+            continue;
+        }
+        in_tree[to.1][to.0] = true;
+        size += 1;
+        edges[from.1][from.0][dir] = true;
+        edges[to.1][to.0][(dir + 2) % 4] = true;
+        frontier.extend(all_directions.map(|dir| (to, dir)));
+    }
+
+    // Each cell of a block in the tree is joined to its two
+    // neighbours around the outline of the tree.
+    let mut joins = vec![vec![[false; 4]; width]; height];
+    let mut join = |(x, y): (usize, usize), dir: usize| {
+        joins[y][x][dir] = true;
+        let (x2, y2) = match dir {
+            NORTH => (x, y - 1),
+            EAST => (x + 1, y),
+            SOUTH => (x, y + 1),
+            WEST => (x - 1, y),
+            _ => unreachable!("there are only four directions"),
+        };
+        joins[y2][x2][(dir + 2) % 4] = true;
+    };
+    for r in 0..rows {
+        for c in 0..columns {
+            if !in_tree[r][c] {
+                continue;
+            }
+            let (x, y) = (2 * c, 2 * r);
+            let [north, east, south, west] = edges[r][c];
+            if north {
+                join((x, y), NORTH);
+                join((x + 1, y), NORTH);
+            } else {
+                join((x, y), EAST);
+            }
+            if east {
+                join((x + 1, y), EAST);
+                join((x + 1, y + 1), EAST);
+            } else {
+                join((x + 1, y), SOUTH);
+            }
+            // The joins to the south and west are made from the
+            // neighbouring block, if the tree has an edge there.
+            if !south {
+                join((x, y + 1), EAST);
+            }
+            if !west {
+                join((x, y), SOUTH);
+            }
+        }
+    }
+
+    let mut field = scatter(
+        rng,
+        width,
+        height,
+        &[
+            ('|', 0.1),
+            ('-', 0.1),
+            ('L', 0.1),
+            ('J', 0.1),
+            ('7', 0.1),
+            ('F', 0.1),
+        ],
+    );
+    let mut on_loop: Vec<(usize, usize)> = Vec::new();
+    for (y, row) in joins.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            field[y][x] = match cell {
+                [true, false, true, false] => '|',
+                [false, true, false, true] => '-',
+                [true, true, false, false] => 'L',
+                [true, false, false, true] => 'J',
+                [false, false, true, true] => '7',
+                [false, true, true, false] => 'F',
+                _ => continue,
+            };
+            on_loop.push((x, y));
+        }
+    }
+    let (sx, sy) = on_loop[rng.index(on_loop.len())];
+    field[sy][sx] = 'S';
+    // Junk next to the start might look as if it connects to it.
+    for (x, y) in [(sx, sy + 1), (sx + 1, sy)]
+        .into_iter()
+        .chain(sx.checked_sub(1).map(|x| (x, sy)))
+        .chain(sy.checked_sub(1).map(|y| (sx, y)))
+    {
+        if y < height && x < width && !on_loop.contains(&(x, y)) {
+            field[y][x] = '.';
+        }
+    }
+    to_text(&field)
+}
+
+#[test]
+fn test_reflected_pattern() {
+    use crate::y2023::day13;
+    let mut rng = Rng::new(13);
+    for _ in 0..20 {
+        let input = reflected_pattern(&mut rng, 15, 11, 0.5);
+        let patterns = day13::parse_input(&input).expect("pattern should be valid");
+        assert!(!patterns[0].all_symmetries().is_empty(), "{input}");
+    }
+    // Narrow patterns can only be reflected one way.
+    let input = reflected_pattern(&mut rng, 1, 4, 0.5);
+    assert_eq!(input.lines().count(), 4);
+}
+
+#[test]
+fn test_platform() {
+    let input = platform(&mut Rng::new(14), 30, 20, 0.2, 0.1);
+    assert_eq!(input.lines().count(), 20);
+    assert!(input.lines().all(|line| line.len() == 30));
+    assert!(input.contains('O') && input.contains('#'));
+    assert!(crate::y2023::day14::part1(&input).is_ok());
+}
+
+#[test]
+fn test_mirror_grid() {
+    let input = mirror_grid(&mut Rng::new(16), 40, 30, 0.2);
+    assert_eq!(input.lines().count(), 30);
+    assert!(crate::y2023::day16::part1(&input).is_ok());
+    assert!(!mirror_grid(&mut Rng::new(16), 5, 5, 0.0).contains(['/', '\\', '-', '|']));
+}
+
+#[test]
+fn test_galaxy_image() {
+    use crate::y2023::day11;
+    let input = galaxy_image(&mut Rng::new(11), 140, 140, 0.02);
+    assert_eq!(input.lines().count(), 140);
+    let image = day11::parse_input(&input).expect("generated image should be valid");
+    assert!(image.popcount() > 0);
+    assert!(!image.unoccupied_rows().is_empty() || !image.unoccupied_cols().is_empty());
+    assert!(day11::part2(&input).is_ok());
+}
+
+#[test]
+fn test_pipe_loop() {
+    use crate::y2023::day10;
+    // When the tree covers every block, the loop passes through every
+    // cell, so the farthest point is half way round and nothing is
+    // enclosed.
+    let input = pipe_loop(&mut Rng::new(10), 20, 12, 1.0);
+    assert!(!input.contains('.'), "{input}");
+    assert_eq!(day10::part1(&input), Ok(20 * 12 / 2));
+    assert_eq!(day10::part2(&input), Ok(0));
+
+    for seed in 0..20 {
+        let input = pipe_loop(&mut Rng::new(seed), 31, 25, 0.5);
+        assert_eq!(input.matches('S').count(), 1);
+        let farthest = day10::part1(&input).expect("loop should be valid");
+        // The tree covers 0.5 * 15 * 12 blocks (rounded), and the
+        // loop has four cells per block.
+        assert_eq!(farthest, 4 * 90 / 2, "{input}");
+        assert!(day10::part2(&input).is_ok());
+    }
+}
+
+#[test]
+fn test_calibration_document() {
+    let input = calibration_document(&mut Rng::new(1), 100);
+    assert_eq!(input.lines().count(), 100);
+    assert!(crate::y2023::day01::part1(&input).is_ok());
+    assert!(crate::y2023::day01::part2(&input).is_ok());
+}
+
+#[test]
+fn test_ghost_network() {
+    use crate::y2023::day08;
+    let input = ghost_network(&mut Rng::new(8), 13, &[6, 10, 7]);
+    assert_eq!(day08::part1(&input), Ok(6));
+    assert_eq!(day08::part2(&input), Ok(210));
+    // Names in between are unique and end in neither A nor Z.
+    let input = ghost_network(&mut Rng::new(8), 5, &[800]);
+    assert_eq!(day08::part1(&input), Ok(800));
+    let names: std::collections::HashSet<&str> = input.lines().skip(2).map(|l| &l[..3]).collect();
+    assert_eq!(names.len(), 2 * 800);
+}
+
+#[test]
+fn test_initialization_sequence() {
+    let input = initialization_sequence(&mut Rng::new(15), 200);
+    assert_eq!(input.split(',').count(), 200);
+    assert!(crate::y2023::day15::part1(&input).is_ok());
+    assert!(crate::y2023::day15::part2(&input).is_ok());
+}
@@ -0,0 +1,526 @@
+//! Searches over the cells of a grid, and over graphs in general.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::error::Fail;
+use crate::fail;
+#[cfg(test)]
+use crate::grid::{CompassDirection, WrapMode, ALL_MOVE_OPTIONS};
+use crate::grid::{Grid2D, Position};
+
+/// Finds the region of `grid` which can be reached from `start` by
+/// moving north, south, east or west through cells for which
+/// `passable` returns true.  The result has `true` in each cell of
+/// the region.
+///
+/// This is a scanline fill: it fills a whole run of cells along a
+/// row at a time, and keeps an explicit stack of the runs still to
+/// be filled, so it needs little memory beyond the result even for
+/// very large regions.
+pub fn flood_fill<T, P>(
+    grid: &Grid2D<T>,
+    start: &Position,
+    passable: P,
+) -> Result<Grid2D<bool>, Fail>
+where
+    T: Clone,
+    P: Fn(&T) -> bool,
+{
+    match grid.get(start) {
+        None => {
+            return Err(fail!(
+                "flood fill cannot start at {start}, which is outside the grid"
+            ));
+        }
+        Some(cell) if !passable(cell) => {
+            return Err(fail!(
+                "flood fill cannot start at {start}, which is not passable"
+            ));
+        }
+        Some(_) => (),
+    }
+    let mut filled = Grid2D::new(grid.width(), grid.height(), false);
+    let can_fill = |filled: &Grid2D<bool>, x: i64, y: i64| -> bool {
+        let pos = Position { x, y };
+        filled.get(&pos) == Some(&false) && grid.get(&pos).is_some_and(&passable)
+    };
+    let mut seeds: Vec<Position> = vec![*start];
+    while let Some(Position { x, y }) = seeds.pop() {
+        if !can_fill(&filled, x, y) {
+            // We reached this run some other way since we pushed the
+            // seed.
+            continue;
+        }
+        let mut left = x;
+        while can_fill(&filled, left - 1, y) {
+            left -= 1;
+        }
+        let mut right = x;
+        while can_fill(&filled, right + 1, y) {
+            right += 1;
+        }
+        for x in left..=right {
+            if let Some(cell) = filled.get_mut(&Position { x, y }) {
+                *cell = true;
+            }
+        }
+        // Push one seed for each run of fillable cells in the rows
+        // above and below.
+        for y in [y - 1, y + 1] {
+            let mut in_run = false;
+            for x in left..=right {
+                let fillable = can_fill(&filled, x, y);
+                if fillable && !in_run {
+                    seeds.push(Position { x, y });
+                }
+                in_run = fillable;
+            }
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+fn show(filled: &Grid2D<bool>) -> String {
+    filled
+        .rows()
+        .map(|row| {
+            let mut line: String = row.iter().map(|f| if *f { 'O' } else { '.' }).collect();
+            line.push('\n');
+            line
+        })
+        .collect()
+}
+
+#[test]
+fn test_flood_fill() {
+    let grid = Grid2D::try_from(concat!(
+        "..#.....\n",
+        "..#.###.\n",
+        "..#.#.#.\n",
+        "....###.\n",
+        "###.....\n",
+        "..#.....\n",
+    ))
+    .expect("grid should be valid");
+    let filled =
+        flood_fill(&grid, &Position { x: 0, y: 0 }, |ch| *ch == '.').expect("start is valid");
+    assert_eq!(
+        show(&filled),
+        concat!(
+            "OO.OOOOO\n",
+            "OO.O...O\n",
+            "OO.O...O\n",
+            "OOOO...O\n",
+            "...OOOOO\n",
+            "...OOOOO\n",
+        )
+    );
+    let filled =
+        flood_fill(&grid, &Position { x: 5, y: 2 }, |ch| *ch == '.').expect("start is valid");
+    assert_eq!(filled.iter().filter(|(_, f)| **f).count(), 1);
+}
+
+#[test]
+fn test_flood_fill_spiral() {
+    // A region which the fill has to enter from above and from below
+    // at different times.
+    let grid = Grid2D::try_from(concat!(
+        ".......\n",
+        ".#####.\n",
+        ".#...#.\n",
+        ".#.#.#.\n",
+        ".#.#...\n",
+        ".#.####\n",
+        ".......\n",
+    ))
+    .expect("grid should be valid");
+    let filled =
+        flood_fill(&grid, &Position { x: 4, y: 3 }, |ch| *ch == '.').expect("start is valid");
+    let expected = grid.iter().filter(|(_, ch)| **ch == '.').count();
+    assert_eq!(filled.iter().filter(|(_, f)| **f).count(), expected);
+}
+
+#[test]
+fn test_flood_fill_bad_start() {
+    let grid = Grid2D::try_from(".#\n").expect("grid should be valid");
+    assert!(flood_fill(&grid, &Position { x: 1, y: 0 }, |ch| *ch == '.').is_err());
+    assert!(flood_fill(&grid, &Position { x: 2, y: 0 }, |ch| *ch == '.').is_err());
+    assert!(flood_fill(&grid, &Position { x: 0, y: -1 }, |ch| *ch == '.').is_err());
+}
+
+/// Finds the length of the shortest path from `start` to each node
+/// which can be reached from it, by a breadth-first search.
+/// `neighbours` returns the nodes which are one step away from a
+/// node.  The result includes `start`, at distance 0.
+pub fn bfs_with_distances<N, F, I>(start: N, neighbours: F) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    bfs_within(start, usize::MAX, neighbours)
+}
+
+/// Like `bfs_with_distances`, but searches only as far as
+/// `max_steps` from `start`.
+pub fn bfs_within<N, F, I>(start: N, max_steps: usize, mut neighbours: F) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances: HashMap<N, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut frontier: VecDeque<(N, usize)> = VecDeque::from([(start, 0)]);
+    while let Some((node, steps)) = frontier.pop_front() {
+        if steps >= max_steps {
+            continue;
+        }
+        for next in neighbours(&node) {
+            distances.entry(next.clone()).or_insert_with(|| {
+                frontier.push_back((next, steps + 1));
+                steps + 1
+            });
+        }
+    }
+    distances
+}
+
+/// Whether a number of steps is even or odd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    pub fn of(steps: usize) -> Parity {
+        if steps.is_multiple_of(2) {
+            Parity::Even
+        } else {
+            Parity::Odd
+        }
+    }
+}
+
+/// Counts the nodes in `distances` (as returned by
+/// `bfs_with_distances`) which are at most `max_steps` away and whose
+/// distance has the given parity.
+pub fn count_with_parity<N>(
+    distances: &HashMap<N, usize>,
+    parity: Parity,
+    max_steps: usize,
+) -> usize {
+    distances
+        .values()
+        .filter(|d| **d <= max_steps && Parity::of(**d) == parity)
+        .count()
+}
+
+/// Counts the nodes at which a walk of exactly `steps` steps from the
+/// start could end, given the `distances` to each node.
+///
+/// A walk can waste steps by going back and forth, two at a time, so
+/// it can end at any node at most `steps` away whose distance has the
+/// same parity as `steps`.  In a grid (or any bipartite graph), these
+/// are the only nodes at which it can end.
+pub fn count_reachable_in_exactly<N>(distances: &HashMap<N, usize>, steps: usize) -> usize {
+    count_with_parity(distances, Parity::of(steps), steps)
+}
+
+#[cfg(test)]
+fn garden_neighbours(grid: &Grid2D<char>) -> impl FnMut(&Position) -> Vec<Position> + '_ {
+    move |pos: &Position| {
+        ALL_MOVE_OPTIONS
+            .iter()
+            .map(|d| pos.move_direction(d))
+            .filter(|n| grid.get(n).is_some_and(|ch| *ch != '#'))
+            .collect()
+    }
+}
+
+#[test]
+fn test_bfs_with_distances() {
+    let grid = Grid2D::try_from(concat!("...#\n", ".#.#\n", "...#\n", "####\n"))
+        .expect("grid should be valid");
+    let distances = bfs_with_distances(Position { x: 0, y: 0 }, garden_neighbours(&grid));
+    assert_eq!(distances.len(), 8);
+    assert_eq!(distances.get(&Position { x: 0, y: 0 }), Some(&0));
+    assert_eq!(distances.get(&Position { x: 2, y: 0 }), Some(&2));
+    assert_eq!(distances.get(&Position { x: 2, y: 2 }), Some(&4));
+    assert_eq!(distances.get(&Position { x: 1, y: 1 }), None);
+    assert_eq!(distances.get(&Position { x: 3, y: 0 }), None);
+
+    let near = bfs_within(Position { x: 0, y: 0 }, 2, garden_neighbours(&grid));
+    assert_eq!(near.len(), 5);
+    assert!(near.values().all(|d| *d <= 2));
+}
+
+#[cfg(test)]
+fn day21_example() -> Grid2D<char> {
+    Grid2D::try_from(concat!(
+        "...........\n",
+        ".....###.#.\n",
+        ".###.##..#.\n",
+        "..#.#...#..\n",
+        "....#.#....\n",
+        ".##..S####.\n",
+        ".##..#...#.\n",
+        ".......##..\n",
+        ".##.#.####.\n",
+        ".##..##.##.\n",
+        "...........\n",
+    ))
+    .expect("grid should be valid")
+}
+
+#[test]
+fn test_count_reachable() {
+    // The example from day 21 of 2023.
+    let grid = day21_example();
+    let distances = bfs_with_distances(Position { x: 5, y: 5 }, garden_neighbours(&grid));
+    assert_eq!(count_reachable_in_exactly(&distances, 1), 2);
+    assert_eq!(count_reachable_in_exactly(&distances, 2), 4);
+    assert_eq!(count_reachable_in_exactly(&distances, 3), 6);
+    assert_eq!(count_reachable_in_exactly(&distances, 6), 16);
+    assert_eq!(count_with_parity(&distances, Parity::Even, 0), 1);
+    assert_eq!(count_with_parity(&distances, Parity::Odd, 1), 2);
+    assert_eq!(Parity::of(7), Parity::Odd);
+}
+
+#[test]
+fn test_count_reachable_wrapping() {
+    // In part 2 of day 21, the garden repeats forever.
+    let grid = day21_example();
+    let neighbours = |pos: &Position| -> Vec<Position> {
+        ALL_MOVE_OPTIONS
+            .iter()
+            .map(|d| pos.move_direction(d))
+            .filter(|n| {
+                grid.get_with(n, WrapMode::Wrap)
+                    .is_some_and(|ch| *ch != '#')
+            })
+            .collect()
+    };
+    let distances = bfs_within(Position { x: 5, y: 5 }, 50, neighbours);
+    assert_eq!(count_reachable_in_exactly(&distances, 6), 16);
+    assert_eq!(count_reachable_in_exactly(&distances, 10), 50);
+    assert_eq!(count_reachable_in_exactly(&distances, 50), 1594);
+}
+
+/// A cheapest path, as found by `dijkstra` or `astar`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    /// The total cost of the steps along the path.
+    pub cost: usize,
+    /// The nodes along the path, from the start to the goal.
+    pub nodes: Vec<N>,
+}
+
+/// Finds a cheapest path from `start` to a node for which `is_goal`
+/// returns true.  `neighbours` returns the nodes which are one step
+/// away from a node, each with the cost of that step.
+///
+/// `heuristic` estimates the cost of getting from a node to the
+/// nearest goal.  If it never overestimates (that is, it is
+/// admissible), the path found is a cheapest one.  A better estimate
+/// means fewer nodes are searched; an estimate of zero makes this
+/// Dijkstra's algorithm.
+pub fn astar<N, F, I, H, G>(
+    start: N,
+    mut neighbours: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+    H: FnMut(&N) -> usize,
+    G: FnMut(&N) -> bool,
+{
+    // The nodes we have seen, by index, with the cheapest known cost
+    // of reaching each one and the node before it on that path.
+    let mut nodes: Vec<N> = vec![start.clone()];
+    let mut index: HashMap<N, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut cost: Vec<usize> = vec![0];
+    let mut previous: Vec<Option<usize>> = vec![None];
+    // Ordered by the estimated total cost, then the cost so far.
+    let mut frontier = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    while let Some(Reverse((_, steps_cost, current))) = frontier.pop() {
+        if steps_cost > cost[current] {
+            // We have since found a cheaper way here.
+            continue;
+        }
+        if is_goal(&nodes[current]) {
+            let mut path = vec![current];
+            while let Some(before) = previous[*path.last().expect("path is not empty")] {
+                path.push(before);
+            }
+            return Some(Path {
+                cost: steps_cost,
+                nodes: path.into_iter().rev().map(|i| nodes[i].clone()).collect(),
+            });
+        }
+        for (next, step) in neighbours(&nodes[current]) {
+            let next_cost = steps_cost + step;
+            let next_index = match index.get(&next) {
+                Some(i) if cost[*i] <= next_cost => continue,
+                Some(i) => *i,
+                None => {
+                    nodes.push(next.clone());
+                    cost.push(usize::MAX);
+                    previous.push(None);
+                    index.insert(next.clone(), nodes.len() - 1);
+                    nodes.len() - 1
+                }
+            };
+            cost[next_index] = next_cost;
+            previous[next_index] = Some(current);
+            frontier.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                next_index,
+            )));
+        }
+    }
+    None
+}
+
+/// Finds a cheapest path from `start` to a goal, as `astar` does but
+/// without a heuristic.
+pub fn dijkstra<N, F, I, G>(start: N, neighbours: F, is_goal: G) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+    G: FnMut(&N) -> bool,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+#[cfg(test)]
+fn day17_example() -> Grid2D<usize> {
+    let grid = Grid2D::try_from(concat!(
+        "2413432311323\n",
+        "3215453535623\n",
+        "3255245654254\n",
+        "3446585845452\n",
+        "4546657867536\n",
+        "1438598798454\n",
+        "4457876987766\n",
+        "3637877979653\n",
+        "4654967986887\n",
+        "4564679986453\n",
+        "1224686865563\n",
+        "2546548887735\n",
+        "4322674655533\n",
+    ))
+    .expect("grid should be valid");
+    let mut heat_loss = Grid2D::new(grid.width(), grid.height(), 0);
+    for (pos, ch) in grid.iter() {
+        if let (Some(cell), Some(digit)) = (heat_loss.get_mut(&pos), ch.to_digit(10)) {
+            *cell = digit as usize;
+        }
+    }
+    heat_loss
+}
+
+#[test]
+fn test_astar_grid() {
+    let grid = day17_example();
+    let goal = Position {
+        x: grid.width() as i64 - 1,
+        y: grid.height() as i64 - 1,
+    };
+    let expanded = std::cell::Cell::new(0);
+    let mut neighbours = |pos: &Position| -> Vec<(Position, usize)> {
+        expanded.set(expanded.get() + 1);
+        ALL_MOVE_OPTIONS
+            .iter()
+            .map(|d| pos.move_direction(d))
+            .filter_map(|n| grid.get(&n).map(|loss| (n, *loss)))
+            .collect()
+    };
+    let slow = dijkstra(Position { x: 0, y: 0 }, &mut neighbours, |pos| *pos == goal)
+        .expect("the goal is reachable");
+    let dijkstra_expanded = expanded.replace(0);
+    // Each step costs at least 1, so the Manhattan distance never
+    // overestimates.
+    let fast = astar(
+        Position { x: 0, y: 0 },
+        &mut neighbours,
+        |pos| pos.manhattan(&goal) as usize,
+        |pos| *pos == goal,
+    )
+    .expect("the goal is reachable");
+    assert_eq!(fast.cost, slow.cost);
+    assert!(expanded.get() < dijkstra_expanded);
+    assert_eq!(fast.nodes.first(), Some(&Position { x: 0, y: 0 }));
+    assert_eq!(fast.nodes.last(), Some(&goal));
+    let path_cost: usize = fast.nodes[1..].iter().filter_map(|pos| grid.get(pos)).sum();
+    assert_eq!(path_cost, fast.cost);
+}
+
+#[test]
+fn test_astar_crucible() {
+    // Day 17 of 2023: a crucible must turn after at most `max_run`
+    // blocks in a straight line, and (in part 2) may not turn until it
+    // has gone `min_run`.  The state includes the direction and how
+    // far the crucible has gone in it.
+    let grid = day17_example();
+    let goal = Position {
+        x: grid.width() as i64 - 1,
+        y: grid.height() as i64 - 1,
+    };
+    let least_heat_loss = |min_run: usize, max_run: usize| {
+        let neighbours = |(pos, direction, run): &(Position, Option<CompassDirection>, usize)| {
+            let mut result = Vec::new();
+            for d in ALL_MOVE_OPTIONS {
+                let straight = Some(d) == *direction;
+                let allowed = match direction {
+                    None => true,
+                    Some(current) if d == current.reversed() => false,
+                    Some(_) if straight => *run < max_run,
+                    Some(_) => *run >= min_run,
+                };
+                let next = pos.move_direction(&d);
+                if let (true, Some(loss)) = (allowed, grid.get(&next)) {
+                    let next_run = if straight { run + 1 } else { 1 };
+                    result.push(((next, Some(d), next_run), *loss));
+                }
+            }
+            result
+        };
+        astar(
+            (Position { x: 0, y: 0 }, None, 0),
+            neighbours,
+            |(pos, _, _)| pos.manhattan(&goal) as usize,
+            |(pos, _, run)| *pos == goal && *run >= min_run,
+        )
+        .map(|path| path.cost)
+    };
+    assert_eq!(least_heat_loss(1, 3), Some(102));
+    assert_eq!(least_heat_loss(4, 10), Some(94));
+}
+
+#[test]
+fn test_astar_unreachable() {
+    let grid = Grid2D::try_from(concat!("..#.\n", "..#.\n")).expect("grid should be valid");
+    let neighbours = |pos: &Position| -> Vec<(Position, usize)> {
+        garden_neighbours(&grid)(pos)
+            .into_iter()
+            .map(|n| (n, 1))
+            .collect()
+    };
+    let goal = Position { x: 3, y: 0 };
+    assert_eq!(
+        dijkstra(Position { x: 0, y: 0 }, neighbours, |pos| *pos == goal),
+        None
+    );
+    let path = dijkstra(Position { x: 0, y: 0 }, neighbours, |pos| pos.x == 1)
+        .expect("the goal is reachable");
+    assert_eq!(path.cost, 1);
+    assert_eq!(path.nodes.len(), 2);
+}
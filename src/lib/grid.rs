@@ -1,9 +1,15 @@
 use std::cmp::{max, min};
 use std::fmt::{self, Debug, Display, Formatter, Write};
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
 
 use itertools::Itertools;
 
+use crate::error::Fail;
+use crate::fail;
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CompassDirection {
     North,
     South,
@@ -21,6 +27,43 @@ impl CompassDirection {
             West => East,
         }
     }
+
+    /// Returns the direction faced after turning 90 degrees
+    /// anticlockwise.
+    pub fn turn_left(&self) -> CompassDirection {
+        use CompassDirection::*;
+        match self {
+            North => West,
+            West => South,
+            South => East,
+            East => North,
+        }
+    }
+
+    /// Returns the direction faced after turning 90 degrees
+    /// clockwise.
+    pub fn turn_right(&self) -> CompassDirection {
+        self.turn_left().reversed()
+    }
+
+    /// Returns the change in x and y for one step in this direction.
+    /// The y axis points south.
+    pub fn as_delta(&self) -> (i64, i64) {
+        let v = self.unit_vector();
+        (v.x, v.y)
+    }
+
+    /// Returns the vector for one step in this direction.  The y axis
+    /// points south.
+    pub fn unit_vector(&self) -> Vector {
+        use CompassDirection::*;
+        match self {
+            North => Position { x: 0, y: -1 },
+            East => Position { x: 1, y: 0 },
+            South => Position { x: 0, y: 1 },
+            West => Position { x: -1, y: 0 },
+        }
+    }
 }
 
 impl Display for CompassDirection {
@@ -41,6 +84,24 @@ impl From<CompassDirection> for char {
     }
 }
 
+/// Accepts compass points (N, E, S, W) and, as used in some puzzle
+/// inputs, U, D, L and R (for up, down, left and right, with up being
+/// north).
+impl FromStr for CompassDirection {
+    type Err = Fail;
+
+    fn from_str(s: &str) -> Result<CompassDirection, Fail> {
+        use CompassDirection::*;
+        match s {
+            "N" | "U" => Ok(North),
+            "S" | "D" => Ok(South),
+            "W" | "L" => Ok(West),
+            "E" | "R" => Ok(East),
+            _ => Err(fail!("unknown direction {s}")),
+        }
+    }
+}
+
 pub const ALL_MOVE_OPTIONS: [CompassDirection; 4] = [
     CompassDirection::North,
     CompassDirection::East,
@@ -48,12 +109,79 @@ pub const ALL_MOVE_OPTIONS: [CompassDirection; 4] = [
     CompassDirection::West,
 ];
 
+#[test]
+fn test_compass_direction() {
+    use CompassDirection::*;
+    for d in ALL_MOVE_OPTIONS {
+        assert_eq!(d.to_string().parse::<CompassDirection>(), Ok(d));
+        assert_eq!(d.turn_left().turn_right(), d);
+        assert_eq!(d.turn_right().turn_right(), d.reversed());
+        let (dx, dy) = d.as_delta();
+        assert_eq!(Position { x: dx, y: dy }, d.unit_vector());
+    }
+    let names: Vec<String> = ALL_MOVE_OPTIONS.iter().map(|d| d.to_string()).collect();
+    assert_eq!(names, vec!["N", "E", "S", "W"]);
+    assert_eq!(North.turn_right(), East);
+    assert_eq!(North.turn_left(), West);
+    assert_eq!(North.as_delta(), (0, -1));
+    assert_eq!("U".parse::<CompassDirection>(), Ok(North));
+    assert_eq!("D".parse::<CompassDirection>(), Ok(South));
+    assert_eq!("L".parse::<CompassDirection>(), Ok(West));
+    assert_eq!("R".parse::<CompassDirection>(), Ok(East));
+    assert!("X".parse::<CompassDirection>().is_err());
+    assert!("".parse::<CompassDirection>().is_err());
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub x: i64,
     pub y: i64,
 }
 
+/// A displacement from one position to another.
+pub type Vector = Position;
+
+impl Add for Position {
+    type Output = Position;
+    fn add(self, other: Vector) -> Position {
+        Position {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Sub for Position {
+    type Output = Vector;
+    fn sub(self, other: Position) -> Vector {
+        Position {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl Mul<i64> for Position {
+    type Output = Vector;
+    fn mul(self, factor: i64) -> Vector {
+        Position {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+#[test]
+fn test_position_arithmetic() {
+    let a = Position { x: 3, y: -2 };
+    let b = Position { x: -1, y: 5 };
+    assert_eq!(a + b, Position { x: 2, y: 3 });
+    assert_eq!(a - b, Position { x: 4, y: -7 });
+    assert_eq!((a - b) + b, a);
+    assert_eq!(b * 3, Position { x: -3, y: 15 });
+}
+
 impl Display for Position {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{},{}", self.x, self.y)
@@ -88,6 +216,57 @@ impl Position {
         }
     }
 
+    /// Returns the position `n` steps away in direction `d`.
+    pub fn step_n(&self, d: &CompassDirection, n: i64) -> Position {
+        *self + d.unit_vector() * n
+    }
+
+    /// Returns the positions passed through on the way from here in a
+    /// straight line to `other`, which must be in the same row or
+    /// column as this position, or on a diagonal through it.  The
+    /// positions begin with the one next to this position and end with
+    /// `other`, so the line from a position to itself is empty.
+    pub fn line_to(&self, other: &Position) -> Result<impl Iterator<Item = Position>, Fail> {
+        let offset = *other - *self;
+        if offset.x != 0 && offset.y != 0 && offset.x.abs() != offset.y.abs() {
+            return Err(fail!("there is no straight line from {self} to {other}"));
+        }
+        let step = Position {
+            x: offset.x.signum(),
+            y: offset.y.signum(),
+        };
+        let start = *self;
+        Ok((1..=max(offset.x.abs(), offset.y.abs())).map(move |n| start + step * n))
+    }
+
+    /// Returns the positions reached by moving repeatedly in direction
+    /// `d`, beginning with the one next to this position.  The
+    /// iterator never ends.
+    pub fn ray(&self, d: &CompassDirection) -> impl Iterator<Item = Position> {
+        let start = *self;
+        let step = d.unit_vector();
+        (1..).map(move |n| start + step * n)
+    }
+
+    /// Returns the number of steps north, south, east or west from
+    /// here to `other`.
+    pub fn manhattan(&self, other: &Position) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// Returns the number of steps from here to `other` when diagonal
+    /// steps are allowed.
+    pub fn chebyshev(&self, other: &Position) -> i64 {
+        max((self.x - other.x).abs(), (self.y - other.y).abs())
+    }
+
+    /// Returns the square of the straight-line distance from here to
+    /// `other`.
+    pub fn euclidean_sq(&self, other: &Position) -> i64 {
+        let (dx, dy) = (self.x - other.x, self.y - other.y);
+        dx * dx + dy * dy
+    }
+
     pub fn neighbour_xbearing(&self, to: &Position) -> Result<Option<CompassDirection>, String> {
         match self.x - to.x {
             -1 => Ok(Some(CompassDirection::West)),
@@ -113,6 +292,52 @@ impl Position {
     }
 }
 
+#[test]
+fn test_step_n() {
+    let origin = Position { x: 0, y: 0 };
+    for d in ALL_MOVE_OPTIONS.iter() {
+        assert_eq!(origin.step_n(d, 1), origin.move_direction(d));
+        assert_eq!(origin.step_n(d, 0), origin);
+    }
+    assert_eq!(
+        Position { x: 2, y: 2 }.step_n(&CompassDirection::North, 5),
+        Position { x: 2, y: -3 }
+    );
+}
+
+#[test]
+fn test_line_to() {
+    let origin = Position { x: 0, y: 0 };
+    let line = |to: Position| -> Vec<(i64, i64)> {
+        origin
+            .line_to(&to)
+            .expect("line should be straight")
+            .map(|p| (p.x, p.y))
+            .collect()
+    };
+    assert_eq!(line(Position { x: 3, y: 0 }), vec![(1, 0), (2, 0), (3, 0)]);
+    assert_eq!(line(Position { x: 0, y: -2 }), vec![(0, -1), (0, -2)]);
+    assert_eq!(line(Position { x: -2, y: 2 }), vec![(-1, 1), (-2, 2)]);
+    assert_eq!(line(origin), vec![]);
+    assert!(origin.line_to(&Position { x: 1, y: 2 }).is_err());
+}
+
+#[test]
+fn test_ray() {
+    let start = Position { x: 2, y: 2 };
+    for d in ALL_MOVE_OPTIONS.iter() {
+        let ray: Vec<Position> = start.ray(d).take(4).collect();
+        let expected: Vec<Position> = (1..=4).map(|n| start.step_n(d, n)).collect();
+        assert_eq!(ray, expected);
+        assert_eq!(
+            start
+                .line_to(&start.step_n(d, 4))
+                .map(|line| line.collect::<Vec<_>>()),
+            Ok(expected)
+        );
+    }
+}
+
 pub fn maybe_update_min(min: &mut Option<i64>, val: i64) {
     match min {
         None => {
@@ -146,6 +371,7 @@ pub fn update_max(max: &mut i64, val: i64) {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoundingBox {
     pub top_left: Position,
     pub bottom_right: Position,
@@ -205,6 +431,33 @@ impl BoundingBox {
             .map(|(y, x)| Position { x, y })
     }
 
+    /// Returns each position on the edge of the box, with the
+    /// direction which leads into the box from there.  The ranges are
+    /// inclusive, so each corner appears twice (once for each edge it
+    /// is on, with a different direction each time).
+    pub fn edge_positions_with_inward_direction(
+        &self,
+    ) -> impl Iterator<Item = (Position, CompassDirection)> + '_ {
+        use CompassDirection::*;
+        let left = self.rows().map(|y| {
+            let x = self.top_left.x;
+            (Position { x, y }, East)
+        });
+        let right = self.rows().map(|y| {
+            let x = self.bottom_right.x;
+            (Position { x, y }, West)
+        });
+        let top = self.columns().map(|x| {
+            let y = self.top_left.y;
+            (Position { x, y }, South)
+        });
+        let bottom = self.columns().map(|x| {
+            let y = self.bottom_right.y;
+            (Position { x, y }, North)
+        });
+        left.chain(right).chain(top).chain(bottom)
+    }
+
     pub fn update(&mut self, pos: &Position) {
         self.top_left = Position {
             x: min(self.top_left.x, pos.x),
@@ -222,6 +475,86 @@ impl BoundingBox {
             && self.bottom_right.x >= pos.x
             && self.bottom_right.y >= pos.y
     }
+
+    /// Returns the position inside the box which corresponds to `pos`
+    /// if the box repeats forever in every direction, as the garden
+    /// does in part 2 of day 21.
+    pub fn wrap(&self, pos: &Position) -> Position {
+        Position {
+            x: self.top_left.x + (pos.x - self.top_left.x).rem_euclid(self.width()),
+            y: self.top_left.y + (pos.y - self.top_left.y).rem_euclid(self.height()),
+        }
+    }
+
+    /// Returns which copy of the box `pos` lies in, when the box
+    /// repeats forever in every direction.  The box itself is copy
+    /// (0, 0), the copy to its left is (-1, 0) and so on.
+    pub fn tile(&self, pos: &Position) -> (i64, i64) {
+        (
+            (pos.x - self.top_left.x).div_euclid(self.width()),
+            (pos.y - self.top_left.y).div_euclid(self.height()),
+        )
+    }
+
+    /// Returns the position inside the box which is nearest to `pos`.
+    pub fn clamp(&self, pos: &Position) -> Position {
+        Position {
+            x: pos.x.clamp(self.top_left.x, self.bottom_right.x),
+            y: pos.y.clamp(self.top_left.y, self.bottom_right.y),
+        }
+    }
+
+    /// Returns the position inside the box which `mode` gives for
+    /// `pos`, if there is one.
+    pub fn place(&self, pos: &Position, mode: WrapMode) -> Option<Position> {
+        match mode {
+            WrapMode::Clamp => Some(self.clamp(pos)),
+            WrapMode::Wrap => Some(self.wrap(pos)),
+            WrapMode::None => self.contains(pos).then_some(*pos),
+        }
+    }
+}
+
+/// What to do with a position which lies outside a box or grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    /// Use the nearest position inside instead.
+    Clamp,
+    /// Treat the box as repeating forever in every direction, so that
+    /// moving off one edge brings you back on at the opposite edge.
+    Wrap,
+    /// Positions outside are simply outside.
+    None,
+}
+
+#[test]
+fn test_bbox_wrap() {
+    let b = BoundingBox {
+        top_left: Position { x: 1, y: -2 },
+        bottom_right: Position { x: 3, y: 1 },
+    };
+    let inside = Position { x: 2, y: 0 };
+    assert_eq!(b.wrap(&inside), inside);
+    assert_eq!(b.tile(&inside), (0, 0));
+    // Just off each edge.
+    assert_eq!(b.wrap(&Position { x: 0, y: 0 }), Position { x: 3, y: 0 });
+    assert_eq!(b.wrap(&Position { x: 4, y: 0 }), Position { x: 1, y: 0 });
+    assert_eq!(b.wrap(&Position { x: 2, y: -3 }), Position { x: 2, y: 1 });
+    assert_eq!(b.wrap(&Position { x: 2, y: 2 }), Position { x: 2, y: -2 });
+    assert_eq!(b.tile(&Position { x: 0, y: 0 }), (-1, 0));
+    assert_eq!(b.tile(&Position { x: 2, y: 2 }), (0, 1));
+    // Far away, with negative coordinates.
+    let far = Position { x: -10, y: -15 };
+    assert_eq!(b.wrap(&far), Position { x: 2, y: 1 });
+    assert_eq!(b.tile(&far), (-4, -4));
+    assert!(b.contains(&b.wrap(&far)));
+
+    assert_eq!(b.clamp(&far), Position { x: 1, y: -2 });
+    assert_eq!(b.clamp(&Position { x: 9, y: 0 }), Position { x: 3, y: 0 });
+    assert_eq!(b.place(&far, WrapMode::None), None);
+    assert_eq!(b.place(&inside, WrapMode::None), Some(inside));
+    assert_eq!(b.place(&far, WrapMode::Wrap), Some(b.wrap(&far)));
+    assert_eq!(b.place(&far, WrapMode::Clamp), Some(b.clamp(&far)));
 }
 
 #[test]
@@ -237,6 +570,35 @@ fn test_bbox_contains() {
     assert!(!b.contains(&Position { x: 1, y: 3 })); // y too high
 }
 
+#[test]
+fn test_bbox_edge_positions_with_inward_direction() {
+    use CompassDirection::*;
+    let b = BoundingBox {
+        top_left: Position { x: 1, y: 0 },
+        bottom_right: Position { x: 4, y: 2 },
+    };
+    let edges: Vec<(Position, CompassDirection)> =
+        b.edge_positions_with_inward_direction().collect();
+    assert_eq!(edges.len() as i64, 2 * b.width() + 2 * b.height());
+    // Every tile on the edge is covered, and nothing else.
+    for pos in b.surface() {
+        let on_edge = pos.x == 1 || pos.x == 4 || pos.y == 0 || pos.y == 2;
+        assert_eq!(edges.iter().any(|(p, _)| *p == pos), on_edge, "{pos}");
+    }
+    // Each direction leads into the box.
+    for (pos, direction) in edges.iter() {
+        assert!(b.contains(&pos.move_direction(direction)));
+    }
+    // The corners appear once for each of their edges.
+    let bottom_right = Position { x: 4, y: 2 };
+    let corner: Vec<CompassDirection> = edges
+        .iter()
+        .filter(|(p, _)| *p == bottom_right)
+        .map(|(_, d)| *d)
+        .collect();
+    assert_eq!(corner, vec![West, North]);
+}
+
 #[test]
 fn test_bbox_update() {
     let mut b = BoundingBox {
@@ -304,16 +666,395 @@ where
     }
 }
 
-pub fn manhattan(a: &Position, b: &Position) -> i64 {
-    let dx = (a.x - b.x).abs();
-    let dy = (a.y - b.y).abs();
-    dx + dy
+/// A way of measuring the distance between two positions.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub enum Metric {
+    /// The number of steps north, south, east or west.
+    #[default]
+    Manhattan,
+    /// The number of steps when diagonal steps are allowed too.
+    Chebyshev,
+    /// The square of the straight-line distance.
+    EuclideanSq,
+}
+
+pub const ALL_METRICS: [Metric; 3] = [Metric::Manhattan, Metric::Chebyshev, Metric::EuclideanSq];
+
+impl Metric {
+    pub fn distance(&self, a: &Position, b: &Position) -> i64 {
+        match self {
+            Metric::Manhattan => a.manhattan(b),
+            Metric::Chebyshev => a.chebyshev(b),
+            Metric::EuclideanSq => a.euclidean_sq(b),
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Metric::Manhattan => "manhattan",
+            Metric::Chebyshev => "chebyshev",
+            Metric::EuclideanSq => "euclidean-sq",
+        })
+    }
+}
+
+impl FromStr for Metric {
+    type Err = Fail;
+
+    fn from_str(s: &str) -> Result<Metric, Fail> {
+        ALL_METRICS
+            .into_iter()
+            .find(|metric| metric.to_string() == s)
+            .ok_or_else(|| fail!("unknown metric {s}"))
+    }
 }
 
 #[test]
-fn test_manhattan() {
+fn test_distances() {
+    let a = Position { x: 1, y: -2 };
+    let b = Position { x: 12, y: 7 };
+    assert_eq!(a.manhattan(&b), 11 + 9);
+    assert_eq!(a.chebyshev(&b), 11);
+    assert_eq!(a.euclidean_sq(&b), 121 + 81);
+    for metric in ALL_METRICS {
+        assert_eq!(metric.distance(&a, &b), metric.distance(&b, &a));
+        assert_eq!(metric.distance(&a, &a), 0);
+        assert_eq!(metric.to_string().parse::<Metric>(), Ok(metric));
+    }
+    assert_eq!(Metric::default().distance(&a, &b), 20);
+    assert!("euclidean".parse::<Metric>().is_err());
+}
+
+/// A rotation or reflection of a rectangular grid.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Transform {
+    Identity,
+    /// Rotate a quarter turn clockwise (so that west becomes north).
+    RotateCw,
+    /// Rotate a quarter turn anticlockwise (so that east becomes north).
+    RotateCcw,
+    Rotate180,
+    /// Mirror left-to-right.
+    FlipHorizontal,
+    /// Mirror top-to-bottom.
+    FlipVertical,
+    /// Swap rows and columns.
+    Transpose,
+}
+
+pub const ALL_TRANSFORMS: [Transform; 7] = [
+    Transform::Identity,
+    Transform::RotateCw,
+    Transform::RotateCcw,
+    Transform::Rotate180,
+    Transform::FlipHorizontal,
+    Transform::FlipVertical,
+    Transform::Transpose,
+];
+
+impl Transform {
+    pub fn inverse(&self) -> Transform {
+        match self {
+            Transform::RotateCw => Transform::RotateCcw,
+            Transform::RotateCcw => Transform::RotateCw,
+            other => *other,
+        }
+    }
+
+    /// Returns true if the transform exchanges the width and height
+    /// of a grid.
+    pub fn swaps_axes(&self) -> bool {
+        matches!(
+            self,
+            Transform::RotateCw | Transform::RotateCcw | Transform::Transpose
+        )
+    }
+
+    /// Returns the transform which turns `direction` into north.
+    pub fn to_north(direction: CompassDirection) -> Transform {
+        match direction {
+            CompassDirection::North => Transform::Identity,
+            CompassDirection::West => Transform::RotateCw,
+            CompassDirection::East => Transform::RotateCcw,
+            CompassDirection::South => Transform::Rotate180,
+        }
+    }
+
+    /// Maps `pos` in a grid of the given size (whose top-left corner
+    /// is the origin) to its position in the transformed grid.
+    pub fn apply(&self, pos: &Position, width: i64, height: i64) -> Position {
+        let (x, y) = (pos.x, pos.y);
+        let (x, y) = match self {
+            Transform::Identity => (x, y),
+            Transform::RotateCw => (height - 1 - y, x),
+            Transform::RotateCcw => (y, width - 1 - x),
+            Transform::Rotate180 => (width - 1 - x, height - 1 - y),
+            Transform::FlipHorizontal => (width - 1 - x, y),
+            Transform::FlipVertical => (x, height - 1 - y),
+            Transform::Transpose => (y, x),
+        };
+        Position { x, y }
+    }
+}
+
+#[test]
+fn test_transform_point_round_trip() {
+    let (width, height) = (5, 3);
+    for t in ALL_TRANSFORMS.iter() {
+        let (w, h) = if t.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        for y in 0..height {
+            for x in 0..width {
+                let pos = Position { x, y };
+                let moved = t.apply(&pos, width, height);
+                assert!((0..w).contains(&moved.x) && (0..h).contains(&moved.y));
+                assert_eq!(t.inverse().apply(&moved, w, h), pos, "{t:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_transform_to_north() {
+    let (width, height) = (4, 4);
+    let centre = Position { x: 1, y: 1 };
+    for d in ALL_MOVE_OPTIONS.iter() {
+        let t = Transform::to_north(*d);
+        let moved = t.apply(&centre.move_direction(d), width, height);
+        assert_eq!(
+            moved,
+            t.apply(&centre, width, height)
+                .move_direction(&CompassDirection::North),
+            "{d:?}"
+        );
+    }
+}
+
+/// A rectangular grid of cells, stored row by row.  The top-left cell
+/// is at the origin.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid2D<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid2D<T> {
+        Grid2D {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid2D<T>, Fail> {
+        let height = rows.len();
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        if let Some(y) = rows.iter().position(|row| row.len() != width) {
+            return Err(fail!(
+                "row {y} has {} cells but row 0 has {width}",
+                rows[y].len()
+            ));
+        }
+        Ok(Grid2D {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bbox(&self) -> Option<BoundingBox> {
+        if self.cells.is_empty() {
+            None
+        } else {
+            Some(BoundingBox {
+                top_left: Position { x: 0, y: 0 },
+                bottom_right: Position {
+                    x: self.width as i64 - 1,
+                    y: self.height as i64 - 1,
+                },
+            })
+        }
+    }
+
+    fn index(&self, pos: &Position) -> Option<usize> {
+        let x = usize::try_from(pos.x).ok().filter(|x| *x < self.width)?;
+        let y = usize::try_from(pos.y).ok().filter(|y| *y < self.height)?;
+        Some(y * self.width + x)
+    }
+
+    pub fn get(&self, pos: &Position) -> Option<&T> {
+        self.index(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: &Position) -> Option<&mut T> {
+        self.index(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Like `get`, but first deals with positions outside the grid as
+    /// `mode` says.
+    pub fn get_with(&self, pos: &Position, mode: WrapMode) -> Option<&T> {
+        let pos = self.bbox()?.place(pos, mode)?;
+        self.get(&pos)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks() rejects a chunk size of zero.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Iterates over the cells, row by row, with their positions.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        let width = self.width.max(1);
+        self.cells.iter().enumerate().map(move |(i, cell)| {
+            let pos = Position {
+                x: (i % width) as i64,
+                y: (i / width) as i64,
+            };
+            (pos, cell)
+        })
+    }
+
+    pub fn transform(&self, t: Transform) -> Grid2D<T> {
+        let (width, height) = if t.swaps_axes() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+        let mut result = Grid2D {
+            width,
+            height,
+            cells: self.cells.clone(),
+        };
+        for (pos, cell) in self.iter() {
+            let target = t.apply(&pos, self.width as i64, self.height as i64);
+            if let Some(slot) = result.get_mut(&target) {
+                *slot = cell.clone();
+            }
+        }
+        result
+    }
+
+    pub fn rotate_cw(&self) -> Grid2D<T> {
+        self.transform(Transform::RotateCw)
+    }
+
+    pub fn rotate_ccw(&self) -> Grid2D<T> {
+        self.transform(Transform::RotateCcw)
+    }
+
+    pub fn flip_horizontal(&self) -> Grid2D<T> {
+        self.transform(Transform::FlipHorizontal)
+    }
+
+    pub fn flip_vertical(&self) -> Grid2D<T> {
+        self.transform(Transform::FlipVertical)
+    }
+
+    pub fn transpose(&self) -> Grid2D<T> {
+        self.transform(Transform::Transpose)
+    }
+}
+
+impl TryFrom<&str> for Grid2D<char> {
+    type Error = Fail;
+    fn try_from(s: &str) -> Result<Grid2D<char>, Fail> {
+        Grid2D::from_rows(
+            s.split_terminator('\n')
+                .map(|line| line.chars().collect())
+                .collect(),
+        )
+    }
+}
+
+impl<T: Display + Clone> Display for Grid2D<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_grid2d_parse() {
+    let g = Grid2D::try_from("ab\ncd\nef\n").expect("grid should be valid");
+    assert_eq!((g.width(), g.height()), (2, 3));
+    assert_eq!(g.get(&Position { x: 1, y: 2 }), Some(&'f'));
+    assert_eq!(g.get(&Position { x: 2, y: 0 }), None);
+    assert_eq!(g.get(&Position { x: 0, y: -1 }), None);
+    assert!(Grid2D::try_from("ab\nc\n").is_err());
+}
+
+#[test]
+fn test_grid2d_get_with() {
+    let g = Grid2D::try_from("ab\ncd\nef\n").expect("grid should be valid");
+    let outside = Position { x: -1, y: 4 };
+    assert_eq!(g.get_with(&outside, WrapMode::Wrap), Some(&'d'));
+    assert_eq!(g.get_with(&outside, WrapMode::Clamp), Some(&'e'));
+    assert_eq!(g.get_with(&outside, WrapMode::None), None);
+    let empty: Grid2D<char> = Grid2D::new(0, 0, '.');
+    assert_eq!(empty.get_with(&outside, WrapMode::Wrap), None);
+}
+
+#[test]
+fn test_grid2d_transforms() {
+    let g = Grid2D::try_from("ab\ncd\nef\n").expect("grid should be valid");
+    assert_eq!(g.rotate_cw().to_string(), "eca\nfdb\n");
+    assert_eq!(g.rotate_ccw().to_string(), "bdf\nace\n");
+    assert_eq!(g.flip_horizontal().to_string(), "ba\ndc\nfe\n");
+    assert_eq!(g.flip_vertical().to_string(), "ef\ncd\nab\n");
+    assert_eq!(g.transpose().to_string(), "ace\nbdf\n");
+    assert_eq!(
+        g.transform(Transform::Rotate180).to_string(),
+        "fe\ndc\nba\n"
+    );
+    assert_eq!(g.rotate_cw().rotate_cw(), g.transform(Transform::Rotate180));
+}
+
+#[test]
+fn test_grid2d_round_trip() {
+    let g = Grid2D::try_from("abc\ndef\n").expect("grid should be valid");
+    for t in ALL_TRANSFORMS.iter() {
+        assert_eq!(g.transform(*t).transform(t.inverse()), g, "{t:?}");
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let bbox = BoundingBox {
+        top_left: Position { x: -1, y: 2 },
+        bottom_right: Position { x: 3, y: 4 },
+    };
+    let json = serde_json::to_string(&bbox).expect("a bounding box should serialize");
+    assert_eq!(
+        json,
+        r#"{"top_left":{"x":-1,"y":2},"bottom_right":{"x":3,"y":4}}"#
+    );
+    assert_eq!(serde_json::from_str::<BoundingBox>(&json).ok(), Some(bbox));
+    let json =
+        serde_json::to_string(&CompassDirection::West).expect("a direction should serialize");
+    assert_eq!(json, r#""West""#);
     assert_eq!(
-        manhattan(&Position { x: 1, y: -2 }, &Position { x: 12, y: 7 }),
-        11 + 9
+        serde_json::from_str::<CompassDirection>(&json).ok(),
+        Some(CompassDirection::West)
     );
 }
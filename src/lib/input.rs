@@ -1,10 +1,11 @@
 use std::error::Error;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Read};
+use std::io::{self, BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
 
 use crate::error::Fail;
+use crate::fetch::{self, FetchError};
 
 use clap::{Arg, Command};
 
@@ -15,6 +16,10 @@ pub enum InputError {
         filename: Option<PathBuf>,
         err: std::io::Error,
     },
+    FetchError {
+        filename: PathBuf,
+        err: FetchError,
+    },
 }
 
 impl Display for InputError {
@@ -29,6 +34,12 @@ impl Display for InputError {
                 filename: None,
                 err,
             } => write!(f, "read error on input: {err}"),
+            InputError::FetchError { filename, err } => write!(
+                f,
+                "input file '{}' does not exist and could not be downloaded: {}",
+                filename.display(),
+                err
+            ),
         }
     }
 }
@@ -84,6 +95,207 @@ pub fn read_file_as_lines(input_file_name: &Path) -> Result<Vec<String>, InputEr
     }
 }
 
+/// The environment variable naming the directory in which puzzle
+/// inputs are kept.
+pub const INPUT_DIR_VARIABLE: &str = "AOC_INPUT_DIR";
+
+/// Returns the directory in which puzzle inputs are kept.  The input
+/// for each day is in `dayNN/input.txt` beneath this directory.
+pub fn input_dir() -> PathBuf {
+    match std::env::var_os(INPUT_DIR_VARIABLE) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("bin"),
+    }
+}
+
+pub fn input_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{day:02}")).join("input.txt")
+}
+
+fn read_or_fetch<F>(path: &Path, day: u8, fetcher: F) -> Result<String, InputError>
+where
+    F: FnOnce(u8, &Path) -> Result<String, FetchError>,
+{
+    if path.exists() {
+        read_file_as_string(path)
+    } else {
+        fetcher(day, path).map_err(|err| InputError::FetchError {
+            filename: path.to_path_buf(),
+            err,
+        })
+    }
+}
+
+/// Tidies up puzzle input which has been through an editor or
+/// another operating system, so that the parsers only have to deal
+/// with one form of it.  This removes any UTF-8 byte order mark,
+/// converts CRLF line endings to LF, removes whitespace from the ends
+/// of lines and removes blank lines from the end.  Unless the input is
+/// empty, the result ends with a single newline.
+pub fn normalize(s: &str) -> String {
+    let s = s.strip_prefix('\u{feff}').unwrap_or(s);
+    let mut result = String::with_capacity(s.len() + 1);
+    // `lines` accepts both LF and CRLF line endings.
+    for line in s.lines() {
+        result.push_str(line.trim_end());
+        result.push('\n');
+    }
+    result.truncate(result.trim_end_matches('\n').len());
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    result
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("a\nb\n"), "a\nb\n");
+    assert_eq!(normalize("a\r\nb\r\n"), "a\nb\n");
+    assert_eq!(normalize("\u{feff}a\nb"), "a\nb\n");
+    assert_eq!(normalize("a  \nb\t\n\n\n \n"), "a\nb\n");
+    // Blank lines within the input separate its sections, so they
+    // stay.
+    assert_eq!(normalize("a\r\n\r\nb\r\n"), "a\n\nb\n");
+    assert_eq!(normalize(""), "");
+    assert_eq!(normalize("\n\n"), "");
+}
+
+/// Reads input a line at a time, tidying each line up as `normalize`
+/// does, so that inputs too large to hold in memory can be
+/// processed.  Blank lines are held back until we know that another
+/// line follows them.
+pub struct NormalizedLines<R> {
+    lines: io::Lines<R>,
+    filename: Option<PathBuf>,
+    first: bool,
+    /// Blank lines we have read but not yet returned.
+    blank: usize,
+    /// The line which follows them.
+    pending: Option<String>,
+}
+
+impl<R: BufRead> Iterator for NormalizedLines<R> {
+    type Item = Result<String, InputError>;
+
+    fn next(&mut self) -> Option<Result<String, InputError>> {
+        if self.pending.is_some() {
+            if self.blank > 0 {
+                self.blank -= 1;
+                return Some(Ok(String::new()));
+            }
+            return self.pending.take().map(Ok);
+        }
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(err) => {
+                    return Some(Err(InputError::IoError {
+                        filename: self.filename.clone(),
+                        err,
+                    }))
+                }
+            };
+            let line = if self.first {
+                self.first = false;
+                line.strip_prefix('\u{feff}').unwrap_or(&line).trim_end()
+            } else {
+                line.trim_end()
+            };
+            if line.is_empty() {
+                self.blank += 1;
+            } else if self.blank > 0 {
+                self.blank -= 1;
+                self.pending = Some(line.to_string());
+                return Some(Ok(String::new()));
+            } else {
+                return Some(Ok(line.to_string()));
+            }
+        }
+    }
+}
+
+/// Reads the lines of `reader` (which came from `filename`, if it
+/// has a name) as `NormalizedLines`.
+pub fn stream_lines<R: BufRead>(reader: R, filename: Option<&Path>) -> NormalizedLines<R> {
+    NormalizedLines {
+        lines: reader.lines(),
+        filename: filename.map(Path::to_path_buf),
+        first: true,
+        blank: 0,
+        pending: None,
+    }
+}
+
+/// Reads the file at `path` a line at a time, or the standard input
+/// if `path` is `None` or "-".
+pub fn open_lines(path: Option<&Path>) -> Result<NormalizedLines<Box<dyn BufRead>>, InputError> {
+    match path {
+        Some(path) if path != Path::new("-") => {
+            let reader: Box<dyn BufRead> = Box::new(open_input_file(path)?);
+            Ok(stream_lines(reader, Some(path)))
+        }
+        _ => Ok(stream_lines(Box::new(io::stdin().lock()), None)),
+    }
+}
+
+#[test]
+fn test_stream_lines() {
+    for input in [
+        "a\nb\n",
+        "a\r\nb\r\n",
+        "\u{feff}a\nb",
+        "a  \nb\t\n\n\n \n",
+        "a\r\n\r\n\r\nb\r\n",
+        "\n\na\n",
+        "",
+        "\n\n",
+    ] {
+        let lines: Vec<String> = stream_lines(input.as_bytes(), None)
+            .collect::<Result<_, _>>()
+            .expect("reading from a slice should work");
+        let expected: Vec<String> = normalize(input).lines().map(str::to_string).collect();
+        assert_eq!(lines, expected, "input {input:?}");
+    }
+}
+
+/// Reads the puzzle input for `day`, downloading (and keeping) a
+/// copy if we don't already have one.  The input is normalized (see
+/// `normalize`) before it is returned.
+pub fn puzzle_input(day: u8) -> Result<String, InputError> {
+    read_or_fetch(&input_path(&input_dir(), day), day, fetch::fetch_input)
+        .map(|input| normalize(&input))
+}
+
+#[cfg(test)]
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc-{}-{name}", std::process::id()));
+    // The directory may not exist yet.
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_read_or_fetch_prefers_local_copy() {
+    let dir = scratch_dir("local-input");
+    let path = input_path(&dir, 3);
+    fetch::save(&path, "local\n").expect("should be able to write scratch file");
+    let got = read_or_fetch(&path, 3, |_, _| panic!("should not download"));
+    assert_eq!(got.expect("local copy should be readable"), "local\n");
+    std::fs::remove_dir_all(&dir).expect("should be able to clean up");
+}
+
+#[test]
+fn test_read_or_fetch_downloads_missing_input() {
+    let dir = scratch_dir("missing-input");
+    let path = input_path(&dir, 4);
+    let got = read_or_fetch(&path, 4, |day, _| Ok(format!("day {day}\n")));
+    assert_eq!(got.expect("fetch should succeed"), "day 4\n");
+    let got = read_or_fetch(&path, 4, |_, _| Err(FetchError::NoSession));
+    assert!(matches!(got, Err(InputError::FetchError { .. })));
+}
+
 pub fn run_with_input<ErrorType, InputErrorType, InputReader, F, T, InputType>(
     program_name: &'static str,
     day: i8,
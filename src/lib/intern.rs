@@ -0,0 +1,67 @@
+//! Replacing names with small integers.
+//!
+//! Puzzle inputs often name things (rules, valves, modules) and then
+//! refer to them by name.  Interning the names while parsing means
+//! that the solution can keep the things in a `Vec` indexed by number,
+//! rather than looking them up by string and cloning names around.
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interner {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// Returns the number for `name`, giving it the next unused
+    /// number if it doesn't have one yet.  The numbers start at 0.
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    /// Returns the number for `name`, if it has one.
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the name which was given the number `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was not returned by this interner.
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    /// Returns the number of names interned so far.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[test]
+fn test_interner() {
+    let mut names = Interner::new();
+    assert!(names.is_empty());
+    assert_eq!(names.intern("in"), 0);
+    assert_eq!(names.intern("px"), 1);
+    assert_eq!(names.intern("in"), 0);
+    assert_eq!(names.len(), 2);
+    assert_eq!(names.get("px"), Some(1));
+    assert_eq!(names.get("qq"), None);
+    assert_eq!(names.name(1), "px");
+}
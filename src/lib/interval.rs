@@ -0,0 +1,323 @@
+//! Sets of integers represented as ranges.
+use std::fmt::{self, Display, Formatter};
+
+/// The integers `lo..hi` (that is, including `lo` but not `hi`).  An
+/// interval with `hi <= lo` is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub lo: i64,
+    pub hi: i64,
+}
+
+impl Interval {
+    pub fn new(lo: i64, hi: i64) -> Interval {
+        Interval { lo, hi }
+    }
+
+    /// Returns the interval of `len` integers starting at `start`.
+    pub fn with_len(start: i64, len: i64) -> Interval {
+        Interval {
+            lo: start,
+            hi: start + len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hi <= self.lo
+    }
+
+    pub fn len(&self) -> i64 {
+        (self.hi - self.lo).max(0)
+    }
+
+    pub fn contains(&self, n: i64) -> bool {
+        self.lo <= n && n < self.hi
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let result = Interval {
+            lo: self.lo.max(other.lo),
+            hi: self.hi.min(other.hi),
+        };
+        (!result.is_empty()).then_some(result)
+    }
+
+    /// Returns the parts of this interval which are not in `other`.
+    pub fn subtract(&self, other: &Interval) -> Vec<Interval> {
+        match self.intersection(other) {
+            None => {
+                if self.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![*self]
+                }
+            }
+            Some(common) => [
+                Interval::new(self.lo, common.lo),
+                Interval::new(common.hi, self.hi),
+            ]
+            .into_iter()
+            .filter(|i| !i.is_empty())
+            .collect(),
+        }
+    }
+
+    /// Splits the interval into the part below `n` and the part at or
+    /// above `n`.
+    pub fn split_at(&self, n: i64) -> (Option<Interval>, Option<Interval>) {
+        let below = Interval::new(self.lo, self.hi.min(n));
+        let above = Interval::new(self.lo.max(n), self.hi);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    /// Returns the interval moved up by `offset`.
+    pub fn shift(&self, offset: i64) -> Interval {
+        Interval::new(self.lo + offset, self.hi + offset)
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[{},{})", self.lo, self.hi)
+    }
+}
+
+#[test]
+fn test_interval_basics() {
+    let i = Interval::with_len(10, 5);
+    assert_eq!(i, Interval::new(10, 15));
+    assert_eq!(i.len(), 5);
+    assert!(i.contains(10));
+    assert!(i.contains(14));
+    assert!(!i.contains(15));
+    assert!(!i.is_empty());
+    assert!(Interval::new(3, 3).is_empty());
+    assert_eq!(Interval::new(5, 2).len(), 0);
+    assert_eq!(i.shift(-10), Interval::new(0, 5));
+    assert_eq!(i.to_string(), "[10,15)");
+}
+
+#[test]
+fn test_interval_intersection() {
+    let a = Interval::new(0, 10);
+    assert_eq!(
+        a.intersection(&Interval::new(5, 20)),
+        Some(Interval::new(5, 10))
+    );
+    assert_eq!(
+        a.intersection(&Interval::new(2, 3)),
+        Some(Interval::new(2, 3))
+    );
+    assert_eq!(a.intersection(&Interval::new(10, 20)), None);
+    assert_eq!(a.intersection(&Interval::new(-5, 0)), None);
+}
+
+#[test]
+fn test_interval_subtract() {
+    let a = Interval::new(0, 10);
+    assert_eq!(
+        a.subtract(&Interval::new(3, 5)),
+        vec![Interval::new(0, 3), Interval::new(5, 10)]
+    );
+    assert_eq!(
+        a.subtract(&Interval::new(-3, 5)),
+        vec![Interval::new(5, 10)]
+    );
+    assert_eq!(a.subtract(&Interval::new(5, 50)), vec![Interval::new(0, 5)]);
+    assert_eq!(a.subtract(&Interval::new(-1, 11)), vec![]);
+    assert_eq!(a.subtract(&Interval::new(20, 30)), vec![a]);
+}
+
+#[test]
+fn test_interval_split_at() {
+    let a = Interval::new(0, 10);
+    assert_eq!(
+        a.split_at(4),
+        (Some(Interval::new(0, 4)), Some(Interval::new(4, 10)))
+    );
+    assert_eq!(a.split_at(0), (None, Some(a)));
+    assert_eq!(a.split_at(10), (Some(a), None));
+    assert_eq!(a.split_at(-7), (None, Some(a)));
+}
+
+/// A set of integers, held as a sorted list of disjoint intervals.
+/// Adjacent intervals are merged, so each set has a unique
+/// representation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns the number of integers in the set.
+    pub fn len(&self) -> i64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn contains(&self, n: i64) -> bool {
+        self.intervals.iter().any(|i| i.contains(n))
+    }
+
+    /// Returns the smallest member of the set.
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|i| i.lo)
+    }
+
+    /// Adds the members of `interval` to the set.
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        let mut merged = interval;
+        let mut result = Vec::with_capacity(self.intervals.len() + 1);
+        for existing in self.intervals.drain(..) {
+            if existing.hi < merged.lo || merged.hi < existing.lo {
+                result.push(existing);
+            } else {
+                // They overlap or touch.
+                merged = Interval::new(existing.lo.min(merged.lo), existing.hi.max(merged.hi));
+            }
+        }
+        let pos = result.partition_point(|i| i.lo < merged.lo);
+        result.insert(pos, merged);
+        self.intervals = result;
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.insert(*interval);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals
+            .iter()
+            .flat_map(|a| other.iter().filter_map(move |b| a.intersection(b)))
+            .collect()
+    }
+
+    /// Returns the members of this set which are not in `other`.
+    pub fn subtract(&self, other: &IntervalSet) -> IntervalSet {
+        let mut remaining = self.intervals.clone();
+        for b in other.iter() {
+            remaining = remaining.iter().flat_map(|a| a.subtract(b)).collect();
+        }
+        remaining.into_iter().collect()
+    }
+
+    /// Splits the set into the members below `n` and those at or above
+    /// `n`.
+    pub fn split_at(&self, n: i64) -> (IntervalSet, IntervalSet) {
+        let mut below = IntervalSet::new();
+        let mut above = IntervalSet::new();
+        for interval in self.iter() {
+            let (b, a) = interval.split_at(n);
+            below.extend(b);
+            above.extend(a);
+        }
+        (below, above)
+    }
+
+    pub fn shift(&self, offset: i64) -> IntervalSet {
+        IntervalSet {
+            intervals: self.intervals.iter().map(|i| i.shift(offset)).collect(),
+        }
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> IntervalSet {
+        let mut result = IntervalSet::new();
+        result.insert(interval);
+        result
+    }
+}
+
+impl Extend<Interval> for IntervalSet {
+    fn extend<I: IntoIterator<Item = Interval>>(&mut self, intervals: I) {
+        for interval in intervals {
+            self.insert(interval);
+        }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(intervals: I) -> IntervalSet {
+        let mut result = IntervalSet::new();
+        result.extend(intervals);
+        result
+    }
+}
+
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        for (i, interval) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{interval}")?;
+        }
+        f.write_str("}")
+    }
+}
+
+#[cfg(test)]
+fn set_of(intervals: &[(i64, i64)]) -> IntervalSet {
+    intervals
+        .iter()
+        .map(|(lo, hi)| Interval::new(*lo, *hi))
+        .collect()
+}
+
+#[test]
+fn test_interval_set_insert_merges() {
+    let s = set_of(&[(10, 20), (0, 5), (30, 40)]);
+    assert_eq!(s.to_string(), "{[0,5), [10,20), [30,40)}");
+    assert_eq!(s.len(), 25);
+    // Touching intervals merge.
+    assert_eq!(set_of(&[(0, 5), (5, 10)]).to_string(), "{[0,10)}");
+    // An interval spanning several others swallows them.
+    let mut s = s;
+    s.insert(Interval::new(3, 35));
+    assert_eq!(s.to_string(), "{[0,40)}");
+    // Empty intervals are ignored.
+    s.insert(Interval::new(50, 50));
+    assert_eq!(s.to_string(), "{[0,40)}");
+    assert_eq!(s.min(), Some(0));
+    assert!(IntervalSet::new().is_empty());
+}
+
+#[test]
+fn test_interval_set_operations() {
+    let a = set_of(&[(0, 10), (20, 30)]);
+    let b = set_of(&[(5, 25)]);
+    assert_eq!(a.union(&b), set_of(&[(0, 30)]));
+    assert_eq!(a.intersection(&b), set_of(&[(5, 10), (20, 25)]));
+    assert_eq!(a.subtract(&b), set_of(&[(0, 5), (25, 30)]));
+    assert_eq!(b.subtract(&a), set_of(&[(10, 20)]));
+    assert_eq!(
+        a.split_at(25),
+        (set_of(&[(0, 10), (20, 25)]), set_of(&[(25, 30)]))
+    );
+    assert_eq!(a.shift(1), set_of(&[(1, 11), (21, 31)]));
+    assert!(a.contains(29));
+    assert!(!a.contains(15));
+}
@@ -1,3 +1,17 @@
+//! Small helpers for iterators which several days' solutions need.
+//!
+//! Most of these are folds and groupings which are a line or two to
+//! write out by hand, but easy to get subtly wrong (forgetting the
+//! empty case, say).
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Mul;
+
+use num::{CheckedAdd, One, Zero};
+
+/// Adds `current` to `total`, for use with `try_fold` to sum an
+/// iterator of `Result`s.  The first error stops the sum.  The items
+/// may be of a narrower type than the total.
 pub fn sum_result<T, Q, E>(total: T, current: Result<Q, E>) -> Result<T, E>
 where
     T: std::ops::Add<Output = T>,
@@ -6,6 +20,8 @@ where
     current.map(|x: Q| total + x.into())
 }
 
+/// Like `sum_result`, but for iterators over references to
+/// `Result`s.
 pub fn sum_result_refs<T, Q, E>(total: T, current: &Result<Q, E>) -> Result<T, E>
 where
     T: std::ops::Add<Output = T>,
@@ -67,5 +83,146 @@ fn test_sum_result_propagate_error() {
         .iter()
         .map(|s| s.parse::<i64>())
         .try_fold(0_i64, sum_result);
-    assert!(matches!(total, Err(_)), "{total:?}");
+    assert!(total.is_err(), "{total:?}");
+}
+
+/// Multiplies together the values in `items`, stopping at the first
+/// error.  The product of no items is 1.
+pub fn try_product<I, T, Q, E>(items: I) -> Result<T, E>
+where
+    I: IntoIterator<Item = Result<Q, E>>,
+    T: Mul<Output = T> + One,
+    Q: Into<T>,
+{
+    items
+        .into_iter()
+        .try_fold(T::one(), |total, item| item.map(|x| total * x.into()))
+}
+
+#[test]
+fn test_try_product() {
+    let product: Result<i64, ()> = try_product(vec![Ok(2_i32), Ok(3), Ok(7)]);
+    assert_eq!(product, Ok(42));
+    let product: Result<i64, &str> = try_product(Vec::<Result<i32, &str>>::new());
+    assert_eq!(product, Ok(1));
+    let product: Result<i64, &str> = try_product(vec![Ok(2), Err("bad"), Err("worse")]);
+    assert_eq!(product, Err("bad"));
+}
+
+/// Returns the smallest and largest items, or `None` if there are no
+/// items.
+pub fn min_max<I, T>(items: I) -> Option<(T, T)>
+where
+    I: IntoIterator<Item = T>,
+    T: Ord + Clone,
+{
+    items.into_iter().fold(None, |acc, item| match acc {
+        None => Some((item.clone(), item)),
+        Some((lo, hi)) => {
+            if item < lo {
+                Some((item, hi))
+            } else if item > hi {
+                Some((lo, item))
+            } else {
+                Some((lo, hi))
+            }
+        }
+    })
+}
+
+#[test]
+fn test_min_max() {
+    assert_eq!(min_max(vec![3, -1, 4, 1, 5]), Some((-1, 5)));
+    assert_eq!(min_max(vec![7]), Some((7, 7)));
+    assert_eq!(min_max(Vec::<i32>::new()), None);
+}
+
+/// Splits `s` into the groups of lines separated by blank lines.  Runs
+/// of several blank lines, and blank lines at the start and end, do
+/// not produce empty groups.  Each group keeps the newlines between
+/// its lines, but not the one at its end.
+pub fn chunk_by_blank_lines(s: &str) -> impl Iterator<Item = &str> {
+    s.split("\n\n")
+        .map(|chunk| chunk.trim_matches('\n'))
+        .filter(|chunk| !chunk.is_empty())
+}
+
+#[test]
+fn test_chunk_by_blank_lines() {
+    let chunks: Vec<&str> = chunk_by_blank_lines("\na\nb\n\nc\n\n\n\nd\n\n").collect();
+    assert_eq!(chunks, vec!["a\nb", "c", "d"]);
+    assert_eq!(chunk_by_blank_lines("").count(), 0);
+    assert_eq!(chunk_by_blank_lines("\n\n\n").count(), 0);
+}
+
+/// Like `slice::windows`, but yields arrays, so that the caller can
+/// destructure each window: `windows_exact(v).map(|[a, b]| b - a)`.
+/// There are no windows if `v` has fewer than `N` items.
+///
+/// # Panics
+///
+/// Panics if `N` is 0.
+pub fn windows_exact<T, const N: usize>(v: &[T]) -> impl Iterator<Item = &[T; N]> {
+    v.windows(N)
+        .map(|w| <&[T; N]>::try_from(w).expect("every window should have exactly N items"))
+}
+
+#[test]
+fn test_windows_exact() {
+    let v = [1, 4, 9, 16];
+    let diffs: Vec<i32> = windows_exact(&v).map(|[a, b]| b - a).collect();
+    assert_eq!(diffs, vec![3, 5, 7]);
+    assert_eq!(windows_exact::<_, 3>(&v).count(), 2);
+    assert_eq!(windows_exact::<_, 5>(&v).count(), 0);
+}
+
+/// Counts how many times each distinct item occurs.
+pub fn counts<I, T>(items: I) -> HashMap<T, usize>
+where
+    I: IntoIterator<Item = T>,
+    T: Eq + Hash,
+{
+    let mut result = HashMap::new();
+    for item in items {
+        *result.entry(item).or_insert(0) += 1;
+    }
+    result
+}
+
+#[test]
+fn test_counts() {
+    let freq = counts("hello".chars());
+    assert_eq!(freq.len(), 4);
+    assert_eq!(freq[&'l'], 2);
+    assert_eq!(freq[&'h'], 1);
+    assert!(counts(Vec::<u8>::new()).is_empty());
+}
+
+/// Like `counts`, but for items which are small indexes: counts how
+/// many times each of `0..K` occurs, in an array rather than a map.
+/// That needs no hashing and no memory allocation.  Returns `None` if
+/// an index is `K` or more, or if a count overflows `T`.
+pub fn counts_by_index<T, I, const K: usize>(indices: I) -> Option<[T; K]>
+where
+    I: IntoIterator<Item = usize>,
+    T: Copy + Zero + One + CheckedAdd,
+{
+    let mut result = [T::zero(); K];
+    for index in indices {
+        let count = result.get_mut(index)?;
+        *count = count.checked_add(&T::one())?;
+    }
+    Some(result)
+}
+
+#[test]
+fn test_counts_by_index() {
+    assert_eq!(
+        counts_by_index::<u8, _, 4>([2, 0, 2, 3]),
+        Some([1, 0, 2, 1])
+    );
+    assert_eq!(counts_by_index::<u8, _, 2>([]), Some([0, 0]));
+    assert_eq!(counts_by_index::<u8, _, 2>([0, 2]), None);
+    assert_eq!(counts_by_index::<u8, _, 1>(vec![0; 255]), Some([255]));
+    assert_eq!(counts_by_index::<u8, _, 1>(vec![0; 256]), None);
 }
@@ -1,5 +1,27 @@
-pub mod days;
+pub mod answer;
+pub mod answers;
+pub mod cycle;
 pub mod error;
+pub mod examples;
+pub mod fetch;
+pub mod gen;
+pub mod graph;
 pub mod grid;
 pub mod input;
+pub mod intern;
+pub mod interval;
 pub mod iterplus;
+pub mod logging;
+pub mod memo;
+pub mod numeric;
+pub mod options;
+pub mod parse;
+pub mod report;
+pub mod shrink;
+pub mod sim;
+pub mod solver;
+pub mod submit;
+pub mod testing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod y2023;
@@ -0,0 +1,140 @@
+//! Diagnostic output for the solvers, using `tracing` when the `viz`
+//! feature is enabled.
+//!
+//! Nothing is shown by default apart from warnings.  Use `-v` (see
+//! `options::RunOptions`) or set `RUST_LOG` to see more, for example
+//! `RUST_LOG=debug` or `RUST_LOG=lib::y2023::day15=trace`.
+//!
+//! Without the `viz` feature, warnings are written to stderr and
+//! everything else is discarded (though the messages are still
+//! checked by the compiler).  Solvers log with the `trace!`,
+//! `debug!`, `info!` and `warn!` macros of this crate, rather than
+//! those of `tracing`, so that they build either way.
+#[cfg(feature = "viz")]
+use std::io::IsTerminal;
+
+#[cfg(feature = "viz")]
+use tracing::span::EnteredSpan;
+#[cfg(feature = "viz")]
+use tracing_subscriber::EnvFilter;
+
+#[cfg(feature = "viz")]
+#[doc(hidden)]
+pub use tracing;
+
+/// Logs a message (in the syntax of `format!`) at the given level.
+#[cfg(feature = "viz")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! log_event {
+    ($level:ident, $($arg:tt)*) => {
+        $crate::logging::tracing::event!($crate::logging::tracing::Level::$level, $($arg)*)
+    };
+}
+
+/// Logs a message (in the syntax of `format!`) at the given level.
+#[cfg(not(feature = "viz"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! log_event {
+    (WARN, $($arg:tt)*) => {
+        ::std::eprintln!("warning: {}", ::std::format_args!($($arg)*))
+    };
+    ($level:ident, $($arg:tt)*) => {
+        if false {
+            let _ = ::std::format!($($arg)*);
+        }
+    };
+}
+
+/// Logs a message of interest only when following an algorithm step
+/// by step.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log_event!(TRACE, $($arg)*) };
+}
+
+/// Logs a message about an intermediate result.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log_event!(DEBUG, $($arg)*) };
+}
+
+/// Logs a message summarising what a solver found.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log_event!(INFO, $($arg)*) };
+}
+
+/// Logs a message about something suspicious, which is shown by
+/// default.
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => { $crate::log_event!(WARN, $($arg)*) };
+}
+
+/// Installs a subscriber which writes to stderr.  Calling this more
+/// than once has no further effect.
+pub fn init() {
+    init_at("warn")
+}
+
+/// Like `init`, but shows messages at `level` (such as "debug") and
+/// above, unless `RUST_LOG` says otherwise.
+#[cfg(feature = "viz")]
+pub fn init_at(level: &str) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    // This fails only if a subscriber is already installed.
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .try_init();
+}
+
+/// Like `init`, but shows messages at `level` (such as "debug") and
+/// above, unless `RUST_LOG` says otherwise.  Without the `viz`
+/// feature, there is nothing to set up.
+#[cfg(not(feature = "viz"))]
+pub fn init_at(_level: &str) {}
+
+/// The span covering the work for one day's puzzle, which lasts until
+/// this is dropped.
+pub struct DaySpan {
+    #[cfg(feature = "viz")]
+    _span: EnteredSpan,
+}
+
+/// Enters a span covering the work for one day's puzzle.
+pub fn day_span(day: u8) -> DaySpan {
+    #[cfg(not(feature = "viz"))]
+    let _ = day;
+    DaySpan {
+        #[cfg(feature = "viz")]
+        _span: tracing::info_span!("day", day).entered(),
+    }
+}
+
+/// Runs `f` (the solution to one part of the puzzle) within its own
+/// span.
+#[cfg(feature = "viz")]
+pub fn in_part<T, F>(part: u8, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _part = tracing::info_span!("part", part).entered();
+    let start = std::time::Instant::now();
+    let result = f();
+    tracing::debug!(elapsed = ?start.elapsed(), "solved");
+    result
+}
+
+/// Runs `f` (the solution to one part of the puzzle) within its own
+/// span.  Without the `viz` feature, there are no spans.
+#[cfg(not(feature = "viz"))]
+pub fn in_part<T, F>(_part: u8, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    f()
+}
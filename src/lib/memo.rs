@@ -0,0 +1,178 @@
+//! Memoisation: remembering the results of a computation so that we
+//! don't have to repeat it when the same question comes up again.
+//!
+//! A `Cache` keeps count of how often it already knew the answer,
+//! which is useful for deciding whether it is earning its keep.
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+
+/// How well a `Cache` has been doing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of lookups which found a remembered value.
+    pub hits: u64,
+    /// The number of lookups which had to compute the value.
+    pub misses: u64,
+    /// The number of values currently remembered.
+    pub entries: usize,
+}
+
+impl CacheStats {
+    /// Returns the fraction of lookups which found a remembered
+    /// value, or zero if there have been none.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+impl Display for CacheStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} entries",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.entries
+        )
+    }
+}
+
+/// Remembers the value computed for each key.
+///
+/// A cache may have a capacity bound, to limit the memory it uses.
+/// When a new value would take a full cache over the bound, the cache
+/// forgets everything it knew first.  This is crude, but it is cheap
+/// and works well enough for the searches in these puzzles, where
+/// recently computed values are the ones most likely to be needed
+/// again.
+#[derive(Debug, Clone)]
+pub struct Cache<K, V> {
+    entries: HashMap<K, V>,
+    capacity: Option<usize>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K, V> Default for Cache<K, V> {
+    fn default() -> Cache<K, V> {
+        Cache {
+            entries: HashMap::new(),
+            capacity: None,
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> Cache<K, V> {
+    /// Returns an empty cache with no capacity bound.
+    pub fn new() -> Cache<K, V> {
+        Cache::default()
+    }
+
+    /// Returns an empty cache which holds at most `max_entries`
+    /// values (but always at least one).
+    pub fn with_capacity_bound(max_entries: usize) -> Cache<K, V> {
+        Cache {
+            capacity: Some(max_entries.max(1)),
+            ..Cache::default()
+        }
+    }
+
+    /// Returns the value remembered for `key`, if there is one.  This
+    /// does not count as a hit or a miss.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key)
+    }
+
+    /// Returns the value for `key`, calling `compute` to work it out
+    /// if we don't already know it.
+    pub fn get_or_insert_with<F>(&mut self, key: K, compute: F) -> &V
+    where
+        F: FnOnce(&K) -> V,
+    {
+        if let Some(capacity) = self.capacity {
+            if self.entries.len() >= capacity && !self.entries.contains_key(&key) {
+                self.entries.clear();
+            }
+        }
+        match self.entries.entry(key) {
+            Entry::Occupied(entry) => {
+                self.hits += 1;
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => {
+                self.misses += 1;
+                let value = compute(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+        }
+    }
+}
+
+#[test]
+fn test_cache() {
+    let mut cache: Cache<u32, u32> = Cache::new();
+    let mut calls = 0;
+    let mut square = |n: &u32| {
+        calls += 1;
+        n * n
+    };
+    assert_eq!(*cache.get_or_insert_with(3, &mut square), 9);
+    assert_eq!(*cache.get_or_insert_with(4, &mut square), 16);
+    assert_eq!(*cache.get_or_insert_with(3, &mut square), 9);
+    assert_eq!(calls, 2);
+    assert_eq!(cache.get(&4), Some(&16));
+    assert_eq!(cache.get(&5), None);
+    assert_eq!(
+        cache.stats(),
+        CacheStats {
+            hits: 1,
+            misses: 2,
+            entries: 2
+        }
+    );
+    assert_eq!(
+        cache.stats().to_string(),
+        "1 hits, 2 misses (33.3% hit rate), 2 entries"
+    );
+}
+
+#[test]
+fn test_cache_capacity_bound() {
+    let mut cache: Cache<u32, u32> = Cache::with_capacity_bound(2);
+    cache.get_or_insert_with(1, |n| n + 1);
+    cache.get_or_insert_with(2, |n| n + 1);
+    assert_eq!(cache.len(), 2);
+    // Looking up a key we know doesn't need any more room.
+    assert_eq!(*cache.get_or_insert_with(2, |_| unreachable!()), 3);
+    assert_eq!(cache.len(), 2);
+    // A new key takes the cache over its bound, so it starts again.
+    assert_eq!(*cache.get_or_insert_with(3, |n| n + 1), 4);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.stats().misses, 3);
+    assert_eq!(CacheStats::default().hit_rate(), 0.0);
+}
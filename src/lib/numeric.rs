@@ -0,0 +1,281 @@
+//! Number theory helpers, and exact arithmetic with fractions.
+use num::{BigInt, BigRational, Integer, Zero};
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    a.gcd(&b)
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    a.lcm(&b)
+}
+
+/// Returns the lowest common multiple of all the `values`, or `None`
+/// if there are none.
+pub fn lcm_all<T, I>(values: I) -> Option<T>
+where
+    T: Integer,
+    I: IntoIterator<Item = T>,
+{
+    values.into_iter().reduce(lcm)
+}
+
+#[test]
+fn test_gcd_lcm() {
+    assert_eq!(gcd(12, 18), 6);
+    assert_eq!(gcd(17_u64, 5), 1);
+    assert_eq!(lcm(4, 6), 12);
+    assert_eq!(lcm_all([2_usize, 3, 4]), Some(12));
+    assert_eq!(lcm_all(Vec::<i32>::new()), None);
+}
+
+/// Fits `y = c·x^k` to `samples` of `(x, y)` by least squares on
+/// their logarithms, and returns the exponent `k`.  This tells us how
+/// the cost of an algorithm grows with the size of its input.
+/// Returns `None` unless there are at least two different values of
+/// `x`, and all the values are positive.
+pub fn power_law_exponent(samples: &[(f64, f64)]) -> Option<f64> {
+    if samples.iter().any(|&(x, y)| x <= 0.0 || y <= 0.0) {
+        return None;
+    }
+    let logs: Vec<(f64, f64)> = samples.iter().map(|(x, y)| (x.ln(), y.ln())).collect();
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    if sxx > 0.0 {
+        Some(sxy / sxx)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_power_law_exponent() {
+    let close = |a: Option<f64>, b: f64| a.is_some_and(|a| (a - b).abs() < 1e-9);
+    let quadratic: Vec<(f64, f64)> = [1.0, 2.0, 4.0, 8.0]
+        .iter()
+        .map(|&x| (x, 3.0 * x * x))
+        .collect();
+    assert!(close(power_law_exponent(&quadratic), 2.0));
+    let constant = [(1.0, 5.0), (10.0, 5.0)];
+    assert!(close(power_law_exponent(&constant), 0.0));
+    let square_root = [(4.0, 2.0), (16.0, 4.0), (64.0, 8.0)];
+    assert!(close(power_law_exponent(&square_root), 0.5));
+    assert_eq!(power_law_exponent(&[(2.0, 1.0)]), None);
+    assert_eq!(power_law_exponent(&[(2.0, 1.0), (2.0, 3.0)]), None);
+    assert_eq!(power_law_exponent(&[(0.0, 1.0), (2.0, 3.0)]), None);
+    assert_eq!(power_law_exponent(&[]), None);
+}
+
+/// Returns `(g, p, q)` such that `a*p + b*q = g = gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, p, q) = extended_gcd(b, a % b);
+        (g, q, p - (a / b) * q)
+    }
+}
+
+/// Solves a system of congruences `x = residue (mod modulus)` using
+/// the Chinese Remainder Theorem.  The moduli must be positive but
+/// need not be coprime.  Returns the solution as `(residue, modulus)`,
+/// with `0 <= residue < modulus`, or `None` if the congruences are
+/// inconsistent (or the combined modulus doesn't fit in an `i64`).
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut result: (i128, i128) = (0, 1);
+    for (residue, modulus) in congruences.iter() {
+        if *modulus <= 0 {
+            return None;
+        }
+        let (a1, m1) = result;
+        let (a2, m2) = (i128::from(*residue), i128::from(*modulus));
+        let (g, p, _) = extended_gcd(m1, m2);
+        let diff = a2 - a1;
+        if diff % g != 0 {
+            return None;
+        }
+        let m2_reduced = m2 / g;
+        let k = ((diff / g) % m2_reduced * p).rem_euclid(m2_reduced);
+        let modulus = m1 * m2_reduced;
+        result = ((a1 + m1 * k).rem_euclid(modulus), modulus);
+    }
+    Some((i64::try_from(result.0).ok()?, i64::try_from(result.1).ok()?))
+}
+
+#[test]
+fn test_crt_coprime() {
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    assert_eq!(crt(&[(-1, 4)]), Some((3, 4)));
+    assert_eq!(crt(&[]), Some((0, 1)));
+}
+
+#[test]
+fn test_crt_not_coprime() {
+    assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+    assert_eq!(crt(&[(0, 4), (1, 6)]), None);
+    assert_eq!(crt(&[(5, 0)]), None);
+}
+
+/// An exact fraction, with numerator and denominator as large as they
+/// need to be.
+pub type Rational = BigRational;
+
+/// Returns `n` as a `Rational`.
+pub fn rational(n: i64) -> Rational {
+    Rational::from_integer(BigInt::from(n))
+}
+
+/// Where two lines in a plane cross.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Intersection {
+    pub x: Rational,
+    pub y: Rational,
+    /// How far along the first line the crossing is, as a multiple of
+    /// its direction.
+    pub t: Rational,
+    /// How far along the second line the crossing is.
+    pub s: Rational,
+}
+
+/// Finds where the line through `p` in direction `d` crosses the line
+/// through `q` in direction `e`; that is, the point `p + t*d = q +
+/// s*e`.  Returns `None` if the lines are parallel (even if they are
+/// the same line).
+pub fn line_intersection_2d(
+    p: [i64; 2],
+    d: [i64; 2],
+    q: [i64; 2],
+    e: [i64; 2],
+) -> Option<Intersection> {
+    let [p, d, q, e] = [p, d, q, e].map(|v| v.map(rational));
+    // Solve t*d - s*e = q - p by Cramer's rule.
+    let det = &e[0] * &d[1] - &d[0] * &e[1];
+    if det.is_zero() {
+        return None;
+    }
+    let dx = &q[0] - &p[0];
+    let dy = &q[1] - &p[1];
+    let t = (&e[0] * &dy - &dx * &e[1]) / &det;
+    let s = (&d[0] * &dy - &dx * &d[1]) / &det;
+    Some(Intersection {
+        x: &p[0] + &t * &d[0],
+        y: &p[1] + &t * &d[1],
+        t,
+        s,
+    })
+}
+
+#[test]
+fn test_line_intersection_2d() {
+    // Two of the hailstones from the example for day 24 of 2023.
+    let crossing = line_intersection_2d([19, 13], [-2, 1], [18, 19], [-1, -1])
+        .expect("the paths are not parallel");
+    let third = |n: i64| rational(n) / rational(3);
+    assert_eq!(crossing.x, third(43));
+    assert_eq!(crossing.y, third(46));
+    assert_eq!(crossing.t, third(7));
+    assert_eq!(crossing.s, third(11));
+    assert_eq!(
+        line_intersection_2d([18, 19], [-1, -1], [20, 25], [-2, -2]),
+        None
+    );
+}
+
+/// Returns the cross product of two vectors in three dimensions.
+pub fn cross(a: &[Rational; 3], b: &[Rational; 3]) -> [Rational; 3] {
+    [
+        &a[1] * &b[2] - &a[2] * &b[1],
+        &a[2] * &b[0] - &a[0] * &b[2],
+        &a[0] * &b[1] - &a[1] * &b[0],
+    ]
+}
+
+/// Solves the linear equations `a x = b` exactly, by Gaussian
+/// elimination.  `a` must be square, with as many rows as `b`.
+/// Returns `None` if the equations don't have a unique solution.
+pub fn solve_linear(mut a: Vec<Vec<Rational>>, mut b: Vec<Rational>) -> Option<Vec<Rational>> {
+    let n = b.len();
+    if a.len() != n || a.iter().any(|row| row.len() != n) {
+        return None;
+    }
+    for col in 0..n {
+        let pivot = (col..n).find(|row| !a[*row][col].is_zero())?;
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        let pivot_b = b[col].clone();
+        for row in 0..n {
+            if row == col || a[row][col].is_zero() {
+                continue;
+            }
+            let factor = &a[row][col] / &pivot_row[col];
+            for (x, p) in a[row].iter_mut().zip(pivot_row.iter()).skip(col) {
+                *x -= &factor * p;
+            }
+            b[row] -= &factor * &pivot_b;
+        }
+    }
+    Some((0..n).map(|i| &b[i] / &a[i][i]).collect())
+}
+
+#[test]
+fn test_solve_linear() {
+    let r = |row: [i64; 3]| row.map(rational).to_vec();
+    let a = vec![r([2, 1, -1]), r([-3, -1, 2]), r([-2, 1, 2])];
+    let b = r([8, -11, -3]);
+    assert_eq!(solve_linear(a, b), Some(r([2, 3, -1])));
+    // The second row is a multiple of the first.
+    let singular = vec![r([1, 2, 3]), r([2, 4, 6]), r([0, 1, 1])];
+    assert_eq!(solve_linear(singular, r([1, 2, 3])), None);
+    assert_eq!(solve_linear(vec![r([1, 2, 3])], r([1, 2, 3])), None);
+}
+
+#[test]
+fn test_solve_linear_day24() {
+    // Part 2 of day 24 of 2023: find the rock which, thrown from P
+    // with velocity V, hits every hailstone.  For each hailstone
+    // (p, v), P - p is parallel to V - v, so (P - p) x (V - v) = 0.
+    // The term P x V is the same for every hailstone, so subtracting
+    // the equations for two hailstones leaves equations linear in P
+    // and V.
+    let hail: Vec<([Rational; 3], [Rational; 3])> = [
+        ([19, 13, 30], [-2, 1, -2]),
+        ([18, 19, 22], [-1, -1, -2]),
+        ([20, 25, 34], [-2, -2, -4]),
+    ]
+    .into_iter()
+    .map(|(p, v)| (p.map(rational), v.map(rational)))
+    .collect();
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    let (p0, v0) = &hail[0];
+    for (p, v) in &hail[1..] {
+        // (P - p0) x (V - v0) - (P - p) x (V - v) = 0 rearranges to
+        // P x (v - v0) + (p - p0) x V = p x v - p0 x v0.
+        let dv: [Rational; 3] = std::array::from_fn(|i| &v[i] - &v0[i]);
+        let dp: [Rational; 3] = std::array::from_fn(|i| &p[i] - &p0[i]);
+        let rhs: [Rational; 3] = {
+            let (c, c0) = (cross(p, v), cross(p0, v0));
+            std::array::from_fn(|i| &c[i] - &c0[i])
+        };
+        // Each component of the cross products, as coefficients of
+        // (Px, Py, Pz, Vx, Vy, Vz).
+        let zero = rational(0);
+        for (i, rhs) in rhs.into_iter().enumerate() {
+            let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+            let mut row = vec![zero.clone(); 6];
+            // (P x dv)[i] = P[j]*dv[k] - P[k]*dv[j]
+            row[j] += &dv[k];
+            row[k] -= &dv[j];
+            // (dp x V)[i] = dp[j]*V[k] - dp[k]*V[j]
+            row[3 + k] += &dp[j];
+            row[3 + j] -= &dp[k];
+            a.push(row);
+            b.push(rhs);
+        }
+    }
+    let solution = solve_linear(a, b).expect("the rock's path is unique");
+    assert_eq!(solution, [24, 13, 10, -3, 1, 2].map(rational).to_vec());
+}
@@ -0,0 +1,227 @@
+//! Options which affect how any day's solution runs, as opposed to
+//! what it computes.
+//!
+//! Each option can be given on the command line or, so that the `aoc`
+//! runner can pass them on to the solvers it starts, in an
+//! environment variable.  The command line takes precedence.
+use clap::builder::RangedU64ValueParser;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+use crate::logging;
+use crate::report::{self, ReportFormat};
+
+pub const VERBOSITY_VAR: &str = "AOC_VERBOSITY";
+pub const VISUALISE_VAR: &str = "AOC_VISUALISE";
+pub const THREADS_VAR: &str = "AOC_THREADS";
+pub const REPORT_VAR: &str = "AOC_REPORT";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    /// How much diagnostic output to show: 0 shows only warnings, and
+    /// 3 or more shows everything.  `RUST_LOG` overrides this.
+    pub verbosity: u8,
+    /// Whether to draw pictures of the puzzle, for the days which
+    /// know how.
+    pub visualise: bool,
+    /// The number of threads to use, for the solutions which can use
+    /// more than one.  `None` means one per CPU.
+    pub threads: Option<usize>,
+    /// How to report the answers.
+    pub report: ReportFormat,
+}
+
+impl RunOptions {
+    /// Reads the options from the environment variables `lookup`
+    /// gives values for.  Values which don't make sense are ignored.
+    fn from_lookup<F>(lookup: F) -> RunOptions
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        RunOptions {
+            verbosity: lookup(VERBOSITY_VAR)
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(0),
+            visualise: lookup(VISUALISE_VAR)
+                .is_some_and(|v| !matches!(v.trim(), "" | "0" | "false" | "no")),
+            threads: lookup(THREADS_VAR)
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|n| *n > 0),
+            report: lookup(REPORT_VAR)
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn from_env() -> RunOptions {
+        RunOptions::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Adds the command-line flags for these options to `command`.
+    pub fn add_args(command: Command) -> Command {
+        command
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .short('v')
+                    .action(ArgAction::Count)
+                    .help("Show more diagnostic output (may be repeated)"),
+            )
+            .arg(
+                Arg::new("visualise")
+                    .long("visualise")
+                    .alias("show")
+                    .action(ArgAction::SetTrue)
+                    .help("Draw pictures of the puzzle, where the solution knows how"),
+            )
+            .arg(
+                Arg::new("threads")
+                    .long("threads")
+                    .value_parser(RangedU64ValueParser::<usize>::new().range(1..))
+                    .help("Number of threads a solution may use (default: one per CPU)"),
+            )
+            .arg(
+                Arg::new("report")
+                    .long("report")
+                    .value_name("FORMAT")
+                    .value_parser(|s: &str| s.parse::<ReportFormat>())
+                    .help("How to report the answers: text, json or quiet (default: text)"),
+            )
+    }
+
+    /// Works out the options from the flags added by `add_args`,
+    /// falling back on the environment.
+    pub fn from_matches(matches: &ArgMatches) -> RunOptions {
+        let mut options = RunOptions::from_env();
+        let verbosity = matches.get_count("verbose");
+        if verbosity > 0 {
+            options.verbosity = verbosity;
+        }
+        if matches.get_flag("visualise") {
+            options.visualise = true;
+        }
+        if let Some(threads) = matches.get_one::<usize>("threads") {
+            options.threads = Some(*threads);
+        }
+        if let Some(format) = matches.get_one::<ReportFormat>("report") {
+            options.report = *format;
+        }
+        options
+    }
+
+    /// Returns the `tracing` level selected by `verbosity`.
+    pub fn log_level(&self) -> &'static str {
+        match self.verbosity {
+            0 => "warn",
+            1 => "info",
+            2 => "debug",
+            _ => "trace",
+        }
+    }
+
+    /// Returns the environment variables which pass these options on
+    /// to another program.
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = vec![
+            (VERBOSITY_VAR, self.verbosity.to_string()),
+            (VISUALISE_VAR, u8::from(self.visualise).to_string()),
+            (REPORT_VAR, self.report.to_string()),
+        ];
+        if let Some(threads) = self.threads {
+            vars.push((THREADS_VAR, threads.to_string()));
+        }
+        vars
+    }
+
+    /// Sets up diagnostic output, the reporting of answers and the
+    /// thread pool to match the options.
+    pub fn init(&self) {
+        logging::init_at(self.log_level());
+        report::select(self.report);
+        #[cfg(feature = "parallel")]
+        if let Some(threads) = self.threads {
+            // This fails only if the pool has already been set up.
+            let _ = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build_global();
+        }
+    }
+}
+
+#[test]
+fn test_options_from_env() {
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+    assert_eq!(RunOptions::from_lookup(env(&[])), RunOptions::default());
+    assert_eq!(
+        RunOptions::from_lookup(env(&[
+            (VERBOSITY_VAR, "2"),
+            (VISUALISE_VAR, "1"),
+            (THREADS_VAR, "4"),
+            (REPORT_VAR, "json"),
+        ])),
+        RunOptions {
+            verbosity: 2,
+            visualise: true,
+            threads: Some(4),
+            report: ReportFormat::Json,
+        }
+    );
+    assert_eq!(
+        RunOptions::from_lookup(env(&[
+            (VERBOSITY_VAR, "lots"),
+            (VISUALISE_VAR, "0"),
+            (THREADS_VAR, "0"),
+            (REPORT_VAR, "xml"),
+        ])),
+        RunOptions::default()
+    );
+}
+
+#[test]
+fn test_options_env_round_trip() {
+    let options = RunOptions {
+        verbosity: 3,
+        visualise: true,
+        threads: Some(2),
+        report: ReportFormat::Quiet,
+    };
+    let vars = options.env_vars();
+    let lookup = |name: &str| {
+        vars.iter()
+            .find(|(var, _)| *var == name)
+            .map(|(_, value)| value.clone())
+    };
+    assert_eq!(RunOptions::from_lookup(lookup), options);
+    assert_eq!(options.log_level(), "trace");
+}
+
+#[test]
+fn test_options_from_matches() {
+    let command = RunOptions::add_args(Command::new("test"));
+    let matches = command
+        .clone()
+        .try_get_matches_from([
+            "test",
+            "-vv",
+            "--show",
+            "--threads",
+            "3",
+            "--report",
+            "quiet",
+        ])
+        .expect("arguments should be valid");
+    let options = RunOptions::from_matches(&matches);
+    assert_eq!(options.verbosity, 2);
+    assert!(options.visualise);
+    assert_eq!(options.threads, Some(3));
+    assert_eq!(options.report, ReportFormat::Quiet);
+    assert_eq!(options.log_level(), "debug");
+    assert!(command
+        .try_get_matches_from(["test", "--report", "xml"])
+        .is_err());
+}
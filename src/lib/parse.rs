@@ -0,0 +1,162 @@
+//! Reporting where in the puzzle input a parse error occurred.
+//!
+//! Parsers for parts of the input (a line, a field) return `BadInput`,
+//! which refers to the offending text by slicing it out of the input.
+//! The parser for the whole input then calls `BadInput::locate` to
+//! find the line and column, so the lower-level parsers don't need to
+//! keep track of where they are.
+use std::fmt::{self, Display, Formatter};
+
+use crate::error::Fail;
+
+/// A problem with `fragment`, which is a slice of the input being
+/// parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadInput<'a> {
+    pub fragment: &'a str,
+    pub message: String,
+}
+
+impl<'a> BadInput<'a> {
+    pub fn new<M: Display>(fragment: &'a str, message: M) -> BadInput<'a> {
+        BadInput {
+            fragment,
+            message: message.to_string(),
+        }
+    }
+
+    /// Finds the position of the problem within `input`, of which the
+    /// fragment must be a slice.
+    pub fn locate(self, input: &str) -> ParseError {
+        ParseError::at(input, self.fragment, self.message)
+    }
+}
+
+impl Display for BadInput<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in {:?}", self.message, self.fragment)
+    }
+}
+
+impl From<BadInput<'_>> for Fail {
+    fn from(e: BadInput<'_>) -> Fail {
+        Fail(e.to_string())
+    }
+}
+
+/// A problem at a known line and column of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line number, counting from 1.
+    pub line: usize,
+    /// The column number (in characters), counting from 1.
+    pub column: usize,
+    /// The text of the line, without its newline.
+    pub text: String,
+    pub message: String,
+}
+
+/// Returns the byte offset of `fragment` within `input`, if it is a
+/// slice of it.
+fn offset_of(input: &str, fragment: &str) -> Option<usize> {
+    let start = input.as_ptr() as usize;
+    let here = fragment.as_ptr() as usize;
+    (start..=start + input.len())
+        .contains(&here)
+        .then(|| here - start)
+}
+
+impl ParseError {
+    /// Describes a problem with `fragment`.  This should be a slice of
+    /// `input`; if it is not, the first place in `input` where the
+    /// same text occurs is used instead (or failing that, the start of
+    /// the input).
+    pub fn at<M: Display>(input: &str, fragment: &str, message: M) -> ParseError {
+        let offset = offset_of(input, fragment)
+            .or_else(|| input.find(fragment))
+            .unwrap_or(0);
+        let line_start = input[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = input[offset..]
+            .find('\n')
+            .map(|i| offset + i)
+            .unwrap_or(input.len());
+        ParseError {
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            text: input[line_start..line_end].to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Shows the message, then the line with a caret under the problem.
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        writeln!(f, "  {}", self.text)?;
+        write!(f, "  {:>width$}", "^", width = self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Fail {
+    fn from(e: ParseError) -> Fail {
+        Fail(e.to_string())
+    }
+}
+
+#[test]
+fn test_locate_fragment() {
+    let input = "first line\nsecond line\nthird\n";
+    let second = input.lines().nth(1).unwrap();
+    let word = &second[7..];
+    let e = BadInput::new(word, "bad word").locate(input);
+    assert_eq!(
+        e,
+        ParseError {
+            line: 2,
+            column: 8,
+            text: "second line".to_string(),
+            message: "bad word".to_string(),
+        }
+    );
+    assert_eq!(
+        e.to_string(),
+        "line 2, column 8: bad word\n  second line\n         ^"
+    );
+}
+
+#[test]
+fn test_locate_at_ends() {
+    let input = "abc\ndef";
+    let e = ParseError::at(input, &input[0..0], "start");
+    assert_eq!((e.line, e.column, e.text.as_str()), (1, 1, "abc"));
+    let e = ParseError::at(input, &input[7..], "end");
+    assert_eq!((e.line, e.column, e.text.as_str()), (2, 4, "def"));
+    let e = ParseError::at(input, &input[3..], "newline");
+    assert_eq!((e.line, e.column, e.text.as_str()), (1, 4, "abc"));
+}
+
+#[test]
+fn test_locate_copied_fragment() {
+    // A fragment which isn't a slice of the input is found by
+    // searching for it.
+    let e = ParseError::at("abc\ndef\n", "ef", "copy");
+    assert_eq!((e.line, e.column), (2, 2));
+    let e = ParseError::at("abc\n", "xyz", "missing");
+    assert_eq!((e.line, e.column), (1, 1));
+}
+
+#[test]
+fn test_bad_input_without_location() {
+    let e = BadInput::new("1,2", "expected 3 coordinates");
+    assert_eq!(
+        Fail::from(e),
+        Fail("expected 3 coordinates in \"1,2\"".to_string())
+    );
+}
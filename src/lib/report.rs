@@ -0,0 +1,363 @@
+//! Reporting the answers to the puzzles.
+//!
+//! People want labelled answers and timings, but scripts (including
+//! the `aoc` runner) want something they can read without guessing
+//! at the layout.  So there are several `Reporter`s, and which one is
+//! used is a `RunOptions` setting.
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use crate::answer::Answer;
+use crate::error::Fail;
+use crate::fail;
+
+/// How answers are reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Labelled lines such as "day 07 part 1: 6440 (2.1ms)".
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// Just the answers, one per line.  Failures are reported on
+    /// stderr.
+    Quiet,
+}
+
+pub const ALL_FORMATS: [ReportFormat; 3] =
+    [ReportFormat::Text, ReportFormat::Json, ReportFormat::Quiet];
+
+impl Display for ReportFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReportFormat::Text => "text",
+            ReportFormat::Json => "json",
+            ReportFormat::Quiet => "quiet",
+        })
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = Fail;
+
+    fn from_str(s: &str) -> Result<ReportFormat, Fail> {
+        ALL_FORMATS
+            .into_iter()
+            .find(|format| format.to_string() == s)
+            .ok_or_else(|| fail!("unknown report format {s}"))
+    }
+}
+
+/// The result of solving one part of a day's puzzle.
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    /// The answer, or the reason there isn't one.
+    pub result: Result<Answer, Fail>,
+    /// How long it took to work out, if we know.
+    pub elapsed: Option<Duration>,
+}
+
+pub trait Reporter {
+    fn report(&mut self, record: &Record) -> io::Result<()>;
+}
+
+fn milliseconds(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1000.0
+}
+
+/// Reports answers in the form "day 07 part 1: 6440 (2.1ms)".
+/// Failures go to `errors`.
+pub struct TextReporter<W: Write, E: Write> {
+    out: W,
+    errors: E,
+}
+
+impl<W: Write, E: Write> TextReporter<W, E> {
+    pub fn new(out: W, errors: E) -> TextReporter<W, E> {
+        TextReporter { out, errors }
+    }
+}
+
+impl<W: Write, E: Write> Reporter for TextReporter<W, E> {
+    fn report(&mut self, record: &Record) -> io::Result<()> {
+        let Record { day, part, .. } = record;
+        match (&record.result, record.elapsed) {
+            (Ok(answer), Some(elapsed)) => writeln!(
+                self.out,
+                "day {day:02} part {part}: {answer} ({:.1}ms)",
+                milliseconds(elapsed)
+            ),
+            (Ok(answer), None) => writeln!(self.out, "day {day:02} part {part}: {answer}"),
+            (Err(e), _) => writeln!(self.errors, "day {day:02} part {part} failed: {e}"),
+        }
+    }
+}
+
+/// Reports each answer as a JSON object on a line of its own, for
+/// example `{"day":7,"part":1,"answer":6440,"elapsed_ms":2.125}`.
+/// Answers which are not integers are strings.  Failures have an
+/// "error" instead of an "answer", and are reported the same way.
+pub struct JsonReporter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonReporter<W> {
+    pub fn new(out: W) -> JsonReporter<W> {
+        JsonReporter { out }
+    }
+}
+
+impl<W: Write> Reporter for JsonReporter<W> {
+    fn report(&mut self, record: &Record) -> io::Result<()> {
+        writeln!(self.out, "{}", to_json(record))
+    }
+}
+
+/// Reports just the answers, so that a script can use the output of
+/// a program which solves a single part as it is.  Failures go to
+/// `errors`.
+pub struct QuietReporter<W: Write, E: Write> {
+    out: W,
+    errors: E,
+}
+
+impl<W: Write, E: Write> QuietReporter<W, E> {
+    pub fn new(out: W, errors: E) -> QuietReporter<W, E> {
+        QuietReporter { out, errors }
+    }
+}
+
+impl<W: Write, E: Write> Reporter for QuietReporter<W, E> {
+    fn report(&mut self, record: &Record) -> io::Result<()> {
+        match &record.result {
+            Ok(answer) => writeln!(self.out, "{answer}"),
+            Err(e) => writeln!(
+                self.errors,
+                "day {:02} part {} failed: {e}",
+                record.day, record.part
+            ),
+        }
+    }
+}
+
+/// Returns a reporter which writes to stdout (and stderr).
+pub fn reporter(format: ReportFormat) -> Box<dyn Reporter> {
+    match format {
+        ReportFormat::Text => Box::new(TextReporter::new(io::stdout(), io::stderr())),
+        ReportFormat::Json => Box::new(JsonReporter::new(io::stdout())),
+        ReportFormat::Quiet => Box::new(QuietReporter::new(io::stdout(), io::stderr())),
+    }
+}
+
+/// The format `RunOptions::init` selected, as an index into
+/// `ALL_FORMATS`.
+static SELECTED: AtomicU8 = AtomicU8::new(0);
+
+/// Selects the format in which `solver::report` reports answers.
+pub fn select(format: ReportFormat) {
+    let index = ALL_FORMATS
+        .iter()
+        .position(|f| *f == format)
+        .expect("every format is in ALL_FORMATS");
+    SELECTED.store(index as u8, Ordering::Relaxed);
+}
+
+pub fn selected() -> ReportFormat {
+    ALL_FORMATS[usize::from(SELECTED.load(Ordering::Relaxed))]
+}
+
+/// Returns `s` as a JSON string.
+pub fn json_quote(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            ch if ch.is_control() => result.push_str(&format!("\\u{:04x}", u32::from(ch))),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[test]
+fn test_json_quote() {
+    assert_eq!(json_quote("a\"b\\c\n"), r#""a\"b\\c\u000a""#);
+}
+
+fn to_json(record: &Record) -> String {
+    let mut result = format!("{{\"day\":{},\"part\":{}", record.day, record.part);
+    match &record.result {
+        Ok(Answer::Integer(n)) => result.push_str(&format!(",\"answer\":{n}")),
+        Ok(Answer::Text(answer)) => result.push_str(&format!(",\"answer\":{}", json_quote(answer))),
+        Err(Fail(e)) => result.push_str(&format!(",\"error\":{}", json_quote(e))),
+    }
+    if let Some(elapsed) = record.elapsed {
+        result.push_str(&format!(",\"elapsed_ms\":{:.3}", milliseconds(elapsed)));
+    }
+    result.push('}');
+    result
+}
+
+/// Reads a JSON string from the start of `s`, returning it and the
+/// rest of `s`.  Only the escapes `json_quote` uses are understood.
+fn parse_json_string(s: &str) -> Option<(String, &str)> {
+    let mut chars = s.strip_prefix('"')?.char_indices();
+    let mut result = String::new();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '"' => return Some((result, &s[i + 2..])),
+            '\\' => match chars.next()?.1 {
+                'u' => {
+                    let hex: String = (0..4)
+                        .filter_map(|_| chars.next())
+                        .map(|(_, c)| c)
+                        .collect();
+                    result.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                escaped => result.push(escaped),
+            },
+            ch => result.push(ch),
+        }
+    }
+    None
+}
+
+/// Reads a line written by a `JsonReporter`.  Returns `None` if the
+/// line isn't one.
+pub fn parse_json(line: &str) -> Option<Record> {
+    let mut rest = line.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let (mut day, mut part, mut result, mut elapsed) = (None, None, None, None);
+    while !rest.is_empty() {
+        let (key, after_key) = parse_json_string(rest)?;
+        let after_colon = after_key.strip_prefix(':')?;
+        let (value, after_value) = if after_colon.starts_with('"') {
+            let (value, after) = parse_json_string(after_colon)?;
+            (Ok(value), after)
+        } else {
+            let end = after_colon.find(',').unwrap_or(after_colon.len());
+            (Err(&after_colon[..end]), &after_colon[end..])
+        };
+        match (key.as_str(), value) {
+            ("day", Err(n)) => day = Some(n.parse().ok()?),
+            ("part", Err(n)) => part = Some(n.parse().ok()?),
+            ("answer", Ok(answer)) => result = Some(Ok(Answer::from(answer))),
+            ("answer", Err(n)) => result = Some(Ok(Answer::Integer(n.parse().ok()?))),
+            ("error", Ok(e)) => result = Some(Err(Fail(e))),
+            ("elapsed_ms", Err(n)) => {
+                elapsed = Some(Duration::try_from_secs_f64(n.parse::<f64>().ok()? / 1000.0).ok()?)
+            }
+            _ => return None,
+        }
+        rest = after_value.strip_prefix(',').unwrap_or(after_value);
+    }
+    Some(Record {
+        day: day?,
+        part: part?,
+        result: result?,
+        elapsed,
+    })
+}
+
+#[cfg(test)]
+fn example_records() -> Vec<Record> {
+    vec![
+        Record {
+            day: 7,
+            part: 1,
+            result: Ok(Answer::from(6440)),
+            elapsed: Some(Duration::from_micros(2500)),
+        },
+        Record {
+            day: 7,
+            part: 2,
+            result: Err(Fail("no \"jokers\"".to_string())),
+            elapsed: None,
+        },
+    ]
+}
+
+#[cfg(test)]
+fn report_all(reporter: &mut dyn Reporter) {
+    for record in example_records() {
+        reporter
+            .report(&record)
+            .expect("writing to a Vec should work");
+    }
+}
+
+#[test]
+fn test_text_reporter() {
+    let (mut out, mut errors) = (Vec::new(), Vec::new());
+    report_all(&mut TextReporter::new(&mut out, &mut errors));
+    assert_eq!(
+        String::from_utf8_lossy(&out),
+        "day 07 part 1: 6440 (2.5ms)\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&errors),
+        "day 07 part 2 failed: no \"jokers\"\n"
+    );
+}
+
+#[test]
+fn test_quiet_reporter() {
+    let (mut out, mut errors) = (Vec::new(), Vec::new());
+    report_all(&mut QuietReporter::new(&mut out, &mut errors));
+    assert_eq!(String::from_utf8_lossy(&out), "6440\n");
+    assert_eq!(
+        String::from_utf8_lossy(&errors),
+        "day 07 part 2 failed: no \"jokers\"\n"
+    );
+}
+
+#[test]
+fn test_json_reporter() {
+    let mut out = Vec::new();
+    report_all(&mut JsonReporter::new(&mut out));
+    let text = String::from_utf8_lossy(&out);
+    assert_eq!(
+        text,
+        concat!(
+            "{\"day\":7,\"part\":1,\"answer\":6440,\"elapsed_ms\":2.500}\n",
+            "{\"day\":7,\"part\":2,\"error\":\"no \\\"jokers\\\"\"}\n",
+        )
+    );
+    let records: Vec<Option<Record>> = text.lines().map(parse_json).collect();
+    assert_eq!(
+        records,
+        example_records().into_iter().map(Some).collect::<Vec<_>>()
+    );
+    // Answers which are integers may also be given as strings, and
+    // other answers are strings.
+    assert_eq!(
+        parse_json("{\"day\":7,\"part\":1,\"answer\":\"6440\"}").map(|r| r.result),
+        Some(Ok(Answer::from(6440)))
+    );
+    assert_eq!(
+        parse_json("{\"day\":7,\"part\":1,\"answer\":\"EGH\"}").map(|r| r.result),
+        Some(Ok(Answer::Text("EGH".to_string())))
+    );
+    assert_eq!(parse_json("{\"day\":7,\"part\":1,\"answer\":6e3}"), None);
+    assert_eq!(parse_json("day 07 part 1: 6440"), None);
+    assert_eq!(parse_json("{\"day\":7}"), None);
+    assert_eq!(
+        parse_json("{\"day\":7,\"part\":1,\"answer\":\"x\",\"colour\":1}"),
+        None
+    );
+}
+
+#[test]
+fn test_report_format() {
+    for format in ALL_FORMATS {
+        assert_eq!(format.to_string().parse::<ReportFormat>(), Ok(format));
+    }
+    assert!("xml".parse::<ReportFormat>().is_err());
+}
@@ -0,0 +1,88 @@
+//! Shrinking an input which makes a solution go wrong, so that there
+//! is less of it to read when working out why.
+//!
+//! This is a simple form of delta debugging: we try deleting blocks
+//! of lines, keeping each deletion after which the input still goes
+//! wrong, and try smaller blocks once no block of the current size
+//! can be deleted.  The result is not the smallest failing input, but
+//! no single line of it can be deleted without losing the failure.
+use crate::debug;
+
+/// Returns the lines of `input` which are needed for `fails` to
+/// return true, in their original order.  `fails(input)` itself
+/// should be true; if it is not, the result is meaningless.
+///
+/// If `input` ends with a newline, so does the result (unless it is
+/// empty).
+///
+/// ```
+/// use lib::shrink::shrink_lines;
+/// let input = "1\n2\n3\n4\n5\n6\n";
+/// let shrunk = shrink_lines(input, |s| s.contains('2') && s.contains('5'));
+/// assert_eq!(shrunk, "2\n5\n");
+/// ```
+pub fn shrink_lines<F>(input: &str, mut fails: F) -> String
+where
+    F: FnMut(&str) -> bool,
+{
+    let terminated = input.ends_with('\n');
+    let join = |lines: &[&str]| -> String {
+        let mut s = lines.join("\n");
+        if terminated && !lines.is_empty() {
+            s.push('\n');
+        }
+        s
+    };
+    let mut lines: Vec<&str> = input.split_terminator('\n').collect();
+    let mut block = (lines.len() / 2).max(1);
+    loop {
+        let mut deleted = false;
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + block).min(lines.len());
+            let candidate: Vec<&str> = lines[..start]
+                .iter()
+                .chain(lines[end..].iter())
+                .copied()
+                .collect();
+            if fails(&join(&candidate)) {
+                debug!("deleted lines {start}..{end}, leaving {}", candidate.len());
+                lines = candidate;
+                deleted = true;
+            } else {
+                start = end;
+            }
+        }
+        if !deleted {
+            if block == 1 {
+                return join(&lines);
+            }
+            block /= 2;
+        }
+    }
+}
+
+#[test]
+fn test_shrink_lines() {
+    let input: String = (0..100).map(|n| format!("{n}\n")).collect();
+    let needs = |s: &str, wanted: &[&str]| wanted.iter().all(|w| s.lines().any(|l| l == *w));
+    assert_eq!(
+        shrink_lines(&input, |s| needs(s, &["3", "50", "99"])),
+        "3\n50\n99\n"
+    );
+    // Without a final newline.
+    assert_eq!(shrink_lines("a\nb\nc", |s| needs(s, &["b", "c"])), "b\nc");
+    // Everything can go.
+    assert_eq!(shrink_lines(&input, |_| true), "");
+    assert_eq!(shrink_lines("", |_| true), "");
+    // Nothing can go.
+    assert_eq!(shrink_lines("x\ny\n", |s| s == "x\ny\n"), "x\ny\n");
+    // The failure depends on the order of two lines.
+    assert_eq!(
+        shrink_lines("b\na\nb\nc\n", |s| s
+            .find('a')
+            .zip(s.rfind('b'))
+            .is_some_and(|(a, b)| a < b)),
+        "a\nb\n"
+    );
+}
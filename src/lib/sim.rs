@@ -0,0 +1,316 @@
+//! Simulating a network of components which send each other
+//! messages.
+//!
+//! Messages are delivered in the order in which they were sent, one
+//! at a time, and a component can send further messages while it
+//! handles one.  A `Simulation` runs until there are no messages left
+//! to deliver.  Hooks can watch each message as it is sent and as it
+//! is received, which is the easiest way to see what the network is
+//! doing.
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+
+/// A message on its way from one component to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope<A, M> {
+    pub from: A,
+    pub to: A,
+    pub message: M,
+}
+
+/// Collects the messages a component sends while it handles one.
+#[derive(Debug)]
+pub struct Outbox<A, M> {
+    messages: Vec<Envelope<A, M>>,
+}
+
+impl<A, M> Outbox<A, M> {
+    pub fn send(&mut self, from: A, to: A, message: M) {
+        self.messages.push(Envelope { from, to, message });
+    }
+}
+
+/// Something which can receive messages.
+pub trait Component<A, M> {
+    /// Handles a message addressed to this component, sending any
+    /// messages which result through `outbox`.
+    fn receive(&mut self, envelope: &Envelope<A, M>, outbox: &mut Outbox<A, M>);
+}
+
+/// How much a `Simulation` has done.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimStats {
+    /// The number of messages sent.
+    pub sent: u64,
+    /// The number of messages delivered (including those addressed to
+    /// no component).
+    pub delivered: u64,
+    /// The number of times the simulation has run until there were
+    /// no messages left.
+    pub runs: u64,
+}
+
+impl Display for SimStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} messages sent, {} delivered, in {} runs",
+            self.sent, self.delivered, self.runs
+        )
+    }
+}
+
+type Hook<'h, A, M> = Box<dyn FnMut(&Envelope<A, M>) + 'h>;
+
+/// A queue of messages waiting to be delivered, in the order they
+/// were sent.
+pub struct Simulation<'h, A, M> {
+    queue: VecDeque<Envelope<A, M>>,
+    stats: SimStats,
+    on_send: Option<Hook<'h, A, M>>,
+    on_receive: Option<Hook<'h, A, M>>,
+}
+
+impl<A, M> Default for Simulation<'_, A, M> {
+    fn default() -> Self {
+        Simulation {
+            queue: VecDeque::new(),
+            stats: SimStats::default(),
+            on_send: None,
+            on_receive: None,
+        }
+    }
+}
+
+impl<'h, A, M> Simulation<'h, A, M> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `hook` for each message as it is sent.
+    pub fn on_send(&mut self, hook: impl FnMut(&Envelope<A, M>) + 'h) {
+        self.on_send = Some(Box::new(hook));
+    }
+
+    /// Calls `hook` for each message as it is delivered.
+    pub fn on_receive(&mut self, hook: impl FnMut(&Envelope<A, M>) + 'h) {
+        self.on_receive = Some(Box::new(hook));
+    }
+
+    pub fn stats(&self) -> SimStats {
+        self.stats
+    }
+
+    /// Returns true if no messages are waiting to be delivered.
+    pub fn is_idle(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    fn post(&mut self, envelope: Envelope<A, M>) {
+        if let Some(hook) = self.on_send.as_mut() {
+            hook(&envelope);
+        }
+        self.stats.sent += 1;
+        self.queue.push_back(envelope);
+    }
+
+    /// Queues a message for delivery.
+    pub fn send(&mut self, from: A, to: A, message: M) {
+        self.post(Envelope { from, to, message });
+    }
+
+    /// Delivers the next message by calling `deliver`, and queues the
+    /// messages that sends.  Returns false if there was no message to
+    /// deliver.
+    pub fn step<F>(&mut self, mut deliver: F) -> bool
+    where
+        F: FnMut(&Envelope<A, M>, &mut Outbox<A, M>),
+    {
+        let Some(envelope) = self.queue.pop_front() else {
+            return false;
+        };
+        if let Some(hook) = self.on_receive.as_mut() {
+            hook(&envelope);
+        }
+        self.stats.delivered += 1;
+        let mut outbox = Outbox {
+            messages: Vec::new(),
+        };
+        deliver(&envelope, &mut outbox);
+        for reply in outbox.messages {
+            self.post(reply);
+        }
+        true
+    }
+
+    /// Delivers messages by calling `deliver` until there are none
+    /// left.  Returns the number of messages delivered.
+    pub fn run_with<F>(&mut self, mut deliver: F) -> u64
+    where
+        F: FnMut(&Envelope<A, M>, &mut Outbox<A, M>),
+    {
+        let before = self.stats.delivered;
+        while self.step(&mut deliver) {}
+        self.stats.runs += 1;
+        self.stats.delivered - before
+    }
+
+    /// Delivers messages to `components` until there are none left.
+    /// Messages addressed to a component which doesn't exist are
+    /// delivered to nobody.  Returns the number of messages
+    /// delivered.
+    pub fn run<C>(&mut self, components: &mut HashMap<A, C>) -> u64
+    where
+        A: Eq + Hash,
+        C: Component<A, M>,
+    {
+        self.run_with(|envelope, outbox| {
+            if let Some(component) = components.get_mut(&envelope.to) {
+                component.receive(envelope, outbox);
+            }
+        })
+    }
+}
+
+/// The modules of the first example from day 20 of 2023, which is
+/// the sort of puzzle this module is for.
+#[cfg(test)]
+mod pulses {
+    use super::*;
+
+    pub enum Kind {
+        Broadcast,
+        FlipFlop(bool),
+        Conjunction(HashMap<&'static str, bool>),
+    }
+
+    pub struct Module {
+        pub kind: Kind,
+        pub outputs: Vec<&'static str>,
+    }
+
+    impl Component<&'static str, bool> for Module {
+        fn receive(
+            &mut self,
+            envelope: &Envelope<&'static str, bool>,
+            outbox: &mut Outbox<&'static str, bool>,
+        ) {
+            let high = match &mut self.kind {
+                Kind::Broadcast => envelope.message,
+                Kind::FlipFlop(_) if envelope.message => return,
+                Kind::FlipFlop(on) => {
+                    *on = !*on;
+                    *on
+                }
+                Kind::Conjunction(memory) => {
+                    memory.insert(envelope.from, envelope.message);
+                    !memory.values().all(|high| *high)
+                }
+            };
+            for output in self.outputs.iter() {
+                outbox.send(envelope.to, output, high);
+            }
+        }
+    }
+
+    pub fn example() -> HashMap<&'static str, Module> {
+        let module = |kind, outputs: &[&'static str]| Module {
+            kind,
+            outputs: outputs.to_vec(),
+        };
+        HashMap::from([
+            ("broadcaster", module(Kind::Broadcast, &["a", "b", "c"])),
+            ("a", module(Kind::FlipFlop(false), &["b"])),
+            ("b", module(Kind::FlipFlop(false), &["c"])),
+            ("c", module(Kind::FlipFlop(false), &["inv"])),
+            (
+                "inv",
+                module(Kind::Conjunction(HashMap::from([("c", false)])), &["a"]),
+            ),
+        ])
+    }
+}
+
+#[test]
+fn test_simulation_order() {
+    use std::cell::RefCell;
+
+    let received: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    let mut modules = pulses::example();
+    let mut sim = Simulation::new();
+    sim.on_receive(|envelope: &Envelope<&str, bool>| {
+        let pulse = if envelope.message { "high" } else { "low" };
+        received
+            .borrow_mut()
+            .push(format!("{} -{pulse}-> {}", envelope.from, envelope.to));
+    });
+    sim.send("button", "broadcaster", false);
+    assert_eq!(sim.run(&mut modules), 12);
+    assert!(sim.is_idle());
+    assert_eq!(
+        sim.stats(),
+        SimStats {
+            sent: 12,
+            delivered: 12,
+            runs: 1
+        }
+    );
+    drop(sim);
+    assert_eq!(
+        received.into_inner(),
+        vec![
+            "button -low-> broadcaster",
+            "broadcaster -low-> a",
+            "broadcaster -low-> b",
+            "broadcaster -low-> c",
+            "a -high-> b",
+            "b -high-> c",
+            "c -high-> inv",
+            "inv -low-> a",
+            "a -low-> b",
+            "b -low-> c",
+            "c -low-> inv",
+            "inv -high-> a",
+        ]
+    );
+}
+
+#[test]
+fn test_simulation_counts() {
+    // In the example, 1000 presses of the button send 8000 low pulses
+    // and 4000 high ones.
+    let mut modules = pulses::example();
+    let mut counts = [0_u64; 2];
+    let mut sim = Simulation::new();
+    sim.on_send(|envelope: &Envelope<&str, bool>| counts[usize::from(envelope.message)] += 1);
+    for _ in 0..1000 {
+        sim.send("button", "broadcaster", false);
+        sim.run(&mut modules);
+    }
+    let stats = sim.stats();
+    drop(sim);
+    assert_eq!(counts, [8000, 4000]);
+    assert_eq!(stats.runs, 1000);
+    assert_eq!(
+        stats.to_string(),
+        "12000 messages sent, 12000 delivered, in 1000 runs"
+    );
+}
+
+#[test]
+fn test_simulation_step() {
+    // Messages to nobody are still delivered.
+    let mut sim: Simulation<u8, ()> = Simulation::new();
+    assert!(!sim.step(|_, _| ()));
+    sim.send(1, 2, ());
+    let mut seen = Vec::new();
+    let delivered = sim.run_with(|envelope, outbox| {
+        seen.push(envelope.to);
+        if envelope.to < 5 {
+            outbox.send(envelope.to, envelope.to + 1, ());
+        }
+    });
+    assert_eq!(delivered, 4);
+    assert_eq!(seen, vec![2, 3, 4, 5]);
+}
@@ -0,0 +1,459 @@
+//! Running the solution for a day and reporting the answers.
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use clap::{Arg, ArgMatches, Command};
+
+use crate::answer::Answer;
+use crate::error::Fail;
+use crate::fail;
+use crate::info;
+use crate::input::{normalize, puzzle_input, read_file_as_string, InputError};
+use crate::logging;
+use crate::options::RunOptions;
+use crate::report::{self, Record};
+use crate::y2023::{self, *};
+
+/// Reports the answer to one part of a day's puzzle, or the reason
+/// there isn't one, in the format `RunOptions::init` selected.
+/// Returns true if there was an answer.
+pub fn report<T: Into<Answer>>(day: u8, part: u8, result: Result<T, Fail>) -> bool {
+    report_timed(day, part, result, None)
+}
+
+/// Like `report`, but also says how long working out the answer
+/// took.
+pub fn report_timed<T: Into<Answer>>(
+    day: u8,
+    part: u8,
+    result: Result<T, Fail>,
+    elapsed: Option<Duration>,
+) -> bool {
+    let record = Record {
+        day,
+        part,
+        result: result.map(Into::into),
+        elapsed,
+    };
+    if let Err(e) = report::reporter(report::selected()).report(&record) {
+        eprintln!("day {day:02} part {part}: cannot report the answer: {e}");
+        return false;
+    }
+    record.result.is_ok()
+}
+
+/// Solves one part of a puzzle, timing it, and reports the answer.
+fn solve_and_report<T, P>(day: u8, part: u8, solve: P) -> bool
+where
+    T: Into<Answer>,
+    P: FnOnce() -> Result<T, Fail>,
+{
+    let start = Instant::now();
+    let result = logging::in_part(part, solve);
+    report_timed(day, part, result, Some(start.elapsed()))
+}
+
+/// The command-line arguments which every day's program accepts, in
+/// addition to those for the `RunOptions`: which part of the puzzle
+/// to solve, how to solve it, and a file to read the puzzle input
+/// from instead of the usual one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DayArgs {
+    /// The part to solve; `None` means both.
+    pub part: Option<u8>,
+    /// The name of the `Backend` to solve it with, or "all" to try
+    /// each of them and check that they agree.  `None` means the
+    /// usual one.
+    pub algo: Option<String>,
+    pub input_file: Option<PathBuf>,
+}
+
+impl DayArgs {
+    /// Adds the arguments to `command`.
+    pub fn add_args(command: Command) -> Command {
+        command
+            .arg(
+                Arg::new("part")
+                    .long("part")
+                    .value_parser(clap::value_parser!(u8).range(1..=2))
+                    .help("Solve only this part of the puzzle (default: both)"),
+            )
+            .arg(
+                Arg::new("algo")
+                    .long("algo")
+                    .value_name("NAME")
+                    .help("Solve the puzzle with this algorithm, for the days which have more than one, or with each of them (\"all\") to check that they agree"),
+            )
+            .arg(
+                Arg::new("input-file")
+                    .value_name("INPUT")
+                    .value_parser(clap::value_parser!(PathBuf))
+                    .help("Read the puzzle input from this file instead of the usual place"),
+            )
+    }
+
+    pub fn from_matches(matches: &ArgMatches) -> DayArgs {
+        DayArgs {
+            part: matches.get_one::<u8>("part").copied(),
+            algo: matches.get_one::<String>("algo").cloned(),
+            input_file: matches.get_one::<PathBuf>("input-file").cloned(),
+        }
+    }
+
+    /// Parses the command line of a day's program which has no flags
+    /// of its own.
+    pub fn parse(name: &'static str) -> (RunOptions, DayArgs) {
+        let matches = DayArgs::add_args(RunOptions::add_args(Command::new(name))).get_matches();
+        (
+            RunOptions::from_matches(&matches),
+            DayArgs::from_matches(&matches),
+        )
+    }
+
+    /// Returns true if we should solve `part` of the puzzle.
+    pub fn wants_part(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+
+    /// Reads the puzzle input for `day`, from the file given on the
+    /// command line if there was one.  Either way, the input is
+    /// normalized.
+    pub fn input(&self, day: u8) -> Result<String, InputError> {
+        match &self.input_file {
+            Some(path) => read_file_as_string(path).map(|input| normalize(&input)),
+            None => puzzle_input(day),
+        }
+    }
+}
+
+#[test]
+fn test_day_args() {
+    let command = DayArgs::add_args(RunOptions::add_args(Command::new("test")));
+    let parse = |args: &[&str]| {
+        command
+            .clone()
+            .try_get_matches_from(args)
+            .map(|matches| DayArgs::from_matches(&matches))
+    };
+    assert_eq!(parse(&["test"]).ok(), Some(DayArgs::default()));
+    let args = parse(&["test", "-v", "--part", "2", "--algo", "all", "my-input.txt"])
+        .expect("arguments are valid");
+    assert_eq!(
+        args,
+        DayArgs {
+            part: Some(2),
+            algo: Some("all".to_string()),
+            input_file: Some(PathBuf::from("my-input.txt")),
+        }
+    );
+    assert!(args.wants_part(2));
+    assert!(!args.wants_part(1));
+    assert!(DayArgs::default().wants_part(1));
+    assert!(parse(&["test", "--part", "3"]).is_err());
+}
+
+#[test]
+fn test_day_args_input_file() {
+    let path = std::env::temp_dir().join(format!("aoc-{}-day-args.txt", std::process::id()));
+    std::fs::write(&path, "1 2\r\n3 4\r\n\r\n").expect("should be able to write scratch file");
+    let args = DayArgs {
+        part: None,
+        algo: None,
+        input_file: Some(path.clone()),
+    };
+    assert_eq!(
+        args.input(1).expect("input should be readable"),
+        "1 2\n3 4\n"
+    );
+    std::fs::remove_file(&path).expect("should be able to clean up");
+    assert!(args.input(1).is_err());
+}
+
+/// One of several ways of solving a part of a day's puzzle.  A day
+/// which has more than one declares them as `BACKENDS`, and the first
+/// for each part is the one its `part1` or `part2` uses.
+#[derive(Debug, Clone, Copy)]
+pub struct Backend {
+    pub part: u8,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// True if this is too slow to run on a real puzzle input as a
+    /// matter of course (a brute-force check of a faster method, say).
+    /// `aoc verify` and `aoc shrink` leave it out unless asked not to.
+    pub slow: bool,
+    pub solve: fn(&str) -> Result<Answer, Fail>,
+}
+
+/// Returns the alternative ways of solving the puzzle for `day` of
+/// `year`.  This is empty for days with only one.
+pub fn backends(year: u16, day: u8) -> &'static [Backend] {
+    match year {
+        y2023::YEAR => y2023::backends(day),
+        _ => &[],
+    }
+}
+
+/// Solves the puzzle with each of `backends`, and checks that they
+/// agree.
+fn solve_with_all(part: u8, backends: &[&Backend], input: &str) -> Result<Answer, Fail> {
+    let mut answers: Vec<(&str, Answer)> = Vec::new();
+    for backend in backends {
+        let start = Instant::now();
+        let answer = logging::in_part(part, || (backend.solve)(input))
+            .map_err(|e| fail!("{} failed: {e}", backend.name))?;
+        info!(
+            "{} gives {answer} in {:.1}ms",
+            backend.name,
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        answers.push((backend.name, answer));
+    }
+    match answers.first() {
+        Some((_, first)) if answers.iter().all(|(_, answer)| answer == first) => Ok(first.clone()),
+        Some(_) => Err(fail!(
+            "the algorithms disagree: {}",
+            answers
+                .iter()
+                .map(|(name, answer)| format!("{name} gives {answer}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        None => Err(fail!("there are no algorithms to compare")),
+    }
+}
+
+/// Solves one part of the puzzle, with the algorithm `args` asks for,
+/// and reports the answer.  `default` is the usual way of solving
+/// this part.
+pub fn solve_part<T, P>(day: u8, part: u8, args: &DayArgs, input: &str, default: P) -> bool
+where
+    T: Into<Answer>,
+    P: FnOnce(&str) -> Result<T, Fail>,
+{
+    let Some(algo) = args.algo.as_deref() else {
+        return solve_and_report(day, part, || default(input));
+    };
+    let all_backends = backends(y2023::YEAR, day);
+    let backends: Vec<&Backend> = all_backends.iter().filter(|b| b.part == part).collect();
+    if algo == "all" {
+        if backends.is_empty() {
+            return solve_and_report(day, part, || default(input));
+        }
+        let start = Instant::now();
+        let result = solve_with_all(part, &backends, input);
+        return report_timed(day, part, result, Some(start.elapsed()));
+    }
+    match backends.iter().find(|b| b.name == algo) {
+        Some(backend) => solve_and_report(day, part, || (backend.solve)(input)),
+        // The algorithm is for the other part; this one has only one.
+        None if backends.is_empty() && all_backends.iter().any(|b| b.name == algo) => {
+            solve_and_report(day, part, || default(input))
+        }
+        None => {
+            let names: Vec<&str> = backends.iter().map(|b| b.name).collect();
+            let choices = if names.is_empty() {
+                "it has only one".to_string()
+            } else {
+                format!("choose from {} or all", names.join(", "))
+            };
+            report(
+                day,
+                part,
+                Err::<Answer, _>(fail!(
+                    "there is no algorithm called {algo} for day {day} part {part} ({choices})"
+                )),
+            )
+        }
+    }
+}
+
+#[test]
+fn test_backends() {
+    // Every way of solving a part should get the same answer for the
+    // example.
+    for year in YEARS {
+        for day in implemented_days(*year) {
+            let Some(example) = crate::examples::example(*day) else {
+                continue;
+            };
+            for backend in backends(*year, *day) {
+                assert_eq!(
+                    (backend.solve)(example),
+                    solve(*day, backend.part, example),
+                    "day {day} part {} with {}",
+                    backend.part,
+                    backend.name
+                );
+                assert!(!backend.description.is_empty());
+            }
+        }
+    }
+    assert!(backends(2023, 18).iter().any(|b| b.name == "shoelace"));
+    assert!(backends(2023, 2).is_empty());
+}
+
+#[test]
+fn test_solve_with_all() {
+    let backend = |name, solve| Backend {
+        part: 1,
+        name,
+        description: "test",
+        slow: false,
+        solve,
+    };
+    let one = backend("one", |_| Ok(Answer::from(1)));
+    let also_one = backend("also-one", |input| Ok(Answer::from(input.trim())));
+    let two = backend("two", |_| Ok(Answer::from(2)));
+    assert_eq!(
+        solve_with_all(1, &[&one, &also_one], "1\n"),
+        Ok(Answer::from(1))
+    );
+    assert_eq!(
+        solve_with_all(1, &[&one, &two], "1\n"),
+        Err(Fail(
+            "the algorithms disagree: one gives 1, two gives 2".to_string()
+        ))
+    );
+}
+
+/// Solves the puzzle for `day` (both parts, unless `args` selects
+/// just one), and reports the answers.  A failure in one part doesn't
+/// stop us trying the other.
+pub fn run_day<A, B, P1, P2>(day: u8, args: &DayArgs, part1: P1, part2: P2) -> ExitCode
+where
+    A: Into<Answer>,
+    B: Into<Answer>,
+    P1: FnOnce(&str) -> Result<A, Fail>,
+    P2: FnOnce(&str) -> Result<B, Fail>,
+{
+    let input = match args.input(day) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("day {day:02}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let ok1 = !args.wants_part(1) || solve_part(day, 1, args, &input, part1);
+    let ok2 = !args.wants_part(2) || solve_part(day, 2, args, &input, part2);
+    if ok1 && ok2 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// The years for which we have solutions.
+pub const YEARS: &[u16] = &[y2023::YEAR];
+
+/// Returns the days of `year` for which we have a solution.
+pub fn implemented_days(year: u16) -> &'static [u8] {
+    match year {
+        y2023::YEAR => y2023::DAYS,
+        _ => &[],
+    }
+}
+
+/// A description of how the solution for a day works, for people
+/// reading about it rather than running it.  Each day's module
+/// declares its own as `NOTES`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Notes {
+    /// The title of the puzzle.
+    pub title: &'static str,
+    /// How the solution works, in a sentence or two.
+    pub algorithm: &'static str,
+    /// The asymptotic running time of the solution.
+    pub complexity: &'static str,
+}
+
+/// Returns the notes for the solution for `day` of `year`, if we have
+/// one.
+pub fn notes(year: u16, day: u8) -> Option<&'static Notes> {
+    match year {
+        y2023::YEAR => y2023::notes(day),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_implemented_days() {
+    assert!(implemented_days(2023).contains(&1));
+    assert!(implemented_days(2022).is_empty());
+    for year in YEARS {
+        assert!(!implemented_days(*year).is_empty());
+    }
+}
+
+#[test]
+fn test_notes() {
+    for year in YEARS {
+        for day in implemented_days(*year) {
+            let notes = notes(*year, *day).expect("every solution should have notes");
+            assert!(!notes.title.is_empty());
+            assert!(!notes.algorithm.is_empty());
+            assert!(notes.complexity.starts_with("O("));
+        }
+    }
+    assert_eq!(notes(2023, 12), None);
+    assert_eq!(notes(2022, 1), None);
+}
+
+fn answer<T: Into<Answer>>(result: Result<T, Fail>) -> Result<Answer, Fail> {
+    result.map(Into::into)
+}
+
+/// Solves one part of the puzzle for `day`, given the puzzle input.
+/// This doesn't need access to the filesystem, so it can be used
+/// where the input comes from elsewhere (such as a web page).
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, Fail> {
+    match (day, part) {
+        (1, 1) => answer(day01::part1(input)),
+        (1, 2) => answer(day01::part2(input)),
+        (2, 1) => answer(day02::part1(input)),
+        (2, 2) => answer(day02::part2(input)),
+        (3, 1) => answer(day03::part1(input)),
+        (3, 2) => answer(day03::part2(input)),
+        (4, 1) => answer(day04::part1(input)),
+        (4, 2) => answer(day04::part2(input)),
+        (5, 1) => answer(day05::part1(input)),
+        (5, 2) => answer(day05::part2(input)),
+        (6, 1) => answer(day06::part1(input)),
+        (6, 2) => answer(day06::part2(input)),
+        (7, 1) => answer(day07::part1(input)),
+        (7, 2) => answer(day07::part2(input)),
+        (8, 1) => answer(day08::part1(input)),
+        (8, 2) => answer(day08::part2(input)),
+        (9, 1) => answer(day09::part1(input)),
+        (9, 2) => answer(day09::part2(input)),
+        (10, 1) => answer(day10::part1(input)),
+        (10, 2) => answer(day10::part2(input)),
+        (11, 1) => answer(day11::part1(input)),
+        (11, 2) => answer(day11::part2(input)),
+        (13, 1) => answer(day13::part1(input)),
+        (13, 2) => answer(day13::part2(input)),
+        (14, 1) => answer(day14::part1(input)),
+        (14, 2) => answer(day14::part2(input)),
+        (15, 1) => answer(day15::part1(input)),
+        (15, 2) => answer(day15::part2(input)),
+        (16, 1) => answer(day16::part1(input)),
+        (16, 2) => answer(day16::part2(input)),
+        (18, 1) => answer(day18::part1(input)),
+        (19, 1) => answer(day19::part1(input)),
+        (19, 2) => answer(day19::part2(input)),
+        (22, 1) => answer(day22::part1(input)),
+        (22, 2) => answer(day22::part2(input)),
+        (_, 1 | 2) => Err(fail!("there is no solution for day {day} part {part}")),
+        _ => Err(fail!("there is no part {part}; each day has parts 1 and 2")),
+    }
+}
+
+#[test]
+fn test_solve() {
+    assert_eq!(solve(15, 1, "HASH\n"), Ok(Answer::from(52)));
+    assert_eq!(solve(9, 2, "10 13 16 21 30 45\n"), Ok(Answer::from(5)));
+    assert!(solve(1, 1, "no digits here\n").is_err());
+    assert!(solve(12, 1, "").is_err());
+    assert!(solve(18, 2, "").is_err());
+    assert!(solve(1, 3, "").is_err());
+}
@@ -0,0 +1,299 @@
+//! Submits answers to adventofcode.com.
+//!
+//! The site rate-limits wrong answers, so we keep a record of each
+//! submission and its verdict (in `dayNN/submissions.txt` in the input
+//! directory) and don't submit an answer whose verdict we can already
+//! work out.
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::answer::Answer;
+use crate::error::Fail;
+use crate::fail;
+use crate::fetch::{self, YEAR};
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    /// We submitted an answer too recently.  The site tells us how
+    /// long to wait, e.g. "35s".
+    RateLimited {
+        wait: Option<String>,
+    },
+    /// The part has already been solved, or part 1 has not been
+    /// solved yet.
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Works out the verdict from the HTML page the site sends back.
+    pub fn from_response(body: &str) -> Option<Verdict> {
+        lazy_static! {
+            static ref WAIT: Regex = Regex::new(r"You have (.*?) left to wait").unwrap();
+        }
+        if body.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if body.contains("your answer is too high") {
+            Some(Verdict::TooHigh)
+        } else if body.contains("your answer is too low") {
+            Some(Verdict::TooLow)
+        } else if body.contains("That's not the right answer") {
+            Some(Verdict::Incorrect)
+        } else if body.contains("You gave an answer too recently") {
+            Some(Verdict::RateLimited {
+                wait: WAIT.captures(body).map(|caps| caps[1].to_string()),
+            })
+        } else if body.contains("You don't seem to be solving the right level") {
+            Some(Verdict::WrongLevel)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the verdict is about the answer itself (and so
+    /// is worth remembering).
+    pub fn is_about_answer(&self) -> bool {
+        matches!(
+            self,
+            Verdict::Correct | Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect
+        )
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("correct"),
+            Verdict::TooHigh => f.write_str("too high"),
+            Verdict::TooLow => f.write_str("too low"),
+            Verdict::Incorrect => f.write_str("incorrect"),
+            Verdict::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited (wait {wait})")
+            }
+            Verdict::RateLimited { wait: None } => f.write_str("rate limited"),
+            Verdict::WrongLevel => f.write_str("wrong level (already solved?)"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Verdict {
+    type Error = Fail;
+    fn try_from(s: &str) -> Result<Verdict, Fail> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "too high" => Ok(Verdict::TooHigh),
+            "too low" => Ok(Verdict::TooLow),
+            "incorrect" => Ok(Verdict::Incorrect),
+            _ => Err(fail!("unknown verdict '{s}'")),
+        }
+    }
+}
+
+#[test]
+fn test_verdict_from_response() {
+    assert_eq!(
+        Verdict::from_response("<p>That's the right answer!  You are one gold star"),
+        Some(Verdict::Correct)
+    );
+    assert_eq!(
+        Verdict::from_response("<p>That's not the right answer; your answer is too low."),
+        Some(Verdict::TooLow)
+    );
+    assert_eq!(
+        Verdict::from_response("<p>That's not the right answer.  If you're stuck"),
+        Some(Verdict::Incorrect)
+    );
+    assert_eq!(
+        Verdict::from_response(
+            "<p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 35s left to wait."
+        ),
+        Some(Verdict::RateLimited {
+            wait: Some("35s".to_string())
+        })
+    );
+    assert_eq!(
+        Verdict::from_response("<p>You don't seem to be solving the right level."),
+        Some(Verdict::WrongLevel)
+    );
+    assert_eq!(Verdict::from_response("<html></html>"), None);
+}
+
+/// A previously submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub part: u8,
+    pub answer: Answer,
+    pub verdict: Verdict,
+}
+
+impl Display for Submission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}\t{}", self.part, self.verdict, self.answer)
+    }
+}
+
+impl TryFrom<&str> for Submission {
+    type Error = Fail;
+    fn try_from(s: &str) -> Result<Submission, Fail> {
+        match s.splitn(3, '\t').collect::<Vec<&str>>().as_slice() {
+            [part, verdict, answer] => Ok(Submission {
+                part: part
+                    .parse()
+                    .map_err(|e| fail!("invalid part '{part}': {e}"))?,
+                verdict: Verdict::try_from(*verdict)?,
+                answer: Answer::from(*answer),
+            }),
+            _ => Err(fail!("invalid submission record '{s}'")),
+        }
+    }
+}
+
+#[test]
+fn test_submission_round_trip() {
+    let sub = Submission {
+        part: 2,
+        answer: Answer::from(12345),
+        verdict: Verdict::TooHigh,
+    };
+    assert_eq!(sub.to_string(), "2\ttoo high\t12345");
+    assert_eq!(Submission::try_from(sub.to_string().as_str()), Ok(sub));
+    assert!(Submission::try_from("2\tcorrect").is_err());
+}
+
+/// Returns the verdict the site would give for `answer`, if we can
+/// tell from earlier submissions.
+pub fn known_verdict(previous: &[Submission], part: u8, answer: &Answer) -> Option<Verdict> {
+    let previous = previous.iter().filter(|sub| sub.part == part);
+    let mut verdict = None;
+    for sub in previous {
+        if sub.answer == *answer {
+            return Some(sub.verdict.clone());
+        }
+        if let (Some(ours), Some(theirs)) = (answer.as_integer(), sub.answer.as_integer()) {
+            match sub.verdict {
+                Verdict::TooHigh if ours >= theirs => verdict = Some(Verdict::TooHigh),
+                Verdict::TooLow if ours <= theirs => verdict = Some(Verdict::TooLow),
+                _ => (),
+            }
+        }
+        if sub.verdict == Verdict::Correct {
+            verdict = Some(Verdict::Incorrect);
+        }
+    }
+    verdict
+}
+
+#[test]
+fn test_known_verdict() {
+    let previous = vec![
+        Submission {
+            part: 1,
+            answer: Answer::from(100),
+            verdict: Verdict::TooHigh,
+        },
+        Submission {
+            part: 1,
+            answer: Answer::from(20),
+            verdict: Verdict::TooLow,
+        },
+        Submission {
+            part: 2,
+            answer: Answer::from("abc"),
+            verdict: Verdict::Correct,
+        },
+    ];
+    let verdict = |part, answer: &str| known_verdict(&previous, part, &Answer::from(answer));
+    assert_eq!(verdict(1, "100"), Some(Verdict::TooHigh));
+    assert_eq!(verdict(1, "150"), Some(Verdict::TooHigh));
+    assert_eq!(verdict(1, "3"), Some(Verdict::TooLow));
+    assert_eq!(verdict(1, "50"), None);
+    assert_eq!(verdict(2, "abc"), Some(Verdict::Correct));
+    assert_eq!(verdict(2, "xyz"), Some(Verdict::Incorrect));
+}
+
+pub fn submissions_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{day:02}")).join("submissions.txt")
+}
+
+/// Reads the record of earlier submissions.  A missing file just means
+/// that we haven't submitted anything yet.
+pub fn read_submissions(path: &Path) -> Result<Vec<Submission>, Fail> {
+    match fs::read_to_string(path) {
+        Ok(content) => content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(Submission::try_from)
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(fail!("failed to read {}: {e}", path.display())),
+    }
+}
+
+pub fn record_submission(path: &Path, sub: &Submission) -> Result<(), Fail> {
+    let io_error = |e: std::io::Error| fail!("failed to write {}: {e}", path.display());
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let mut f = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(io_error)?;
+    writeln!(f, "{sub}").map_err(io_error)
+}
+
+pub fn answer_url(day: u8) -> String {
+    format!("https://adventofcode.com/{YEAR}/day/{day}/answer")
+}
+
+/// Posts `answer` for the given part of the puzzle for `day`, and
+/// returns the verdict.
+pub fn post_answer(day: u8, part: u8, answer: &Answer, session: &str) -> Result<Verdict, Fail> {
+    let url = answer_url(day);
+    let level = part.to_string();
+    let answer = answer.to_string();
+    let body = fetch::send(
+        "POST",
+        &url,
+        session,
+        &[("level", level.as_str()), ("answer", answer.as_str())],
+    )?;
+    Verdict::from_response(&body).ok_or_else(|| fail!("did not understand the response from {url}"))
+}
+
+/// Submits `answer` unless the record of earlier submissions kept at
+/// `record` already tells us the verdict.  Returns the verdict and
+/// whether we actually submitted the answer.
+pub fn submit(day: u8, part: u8, answer: &Answer, record: &Path) -> Result<(Verdict, bool), Fail> {
+    let text = answer.to_string();
+    let answer = Answer::from(text.trim());
+    if text.trim().is_empty() || text.contains(['\t', '\n']) {
+        return Err(fail!("'{answer}' is not a plausible answer"));
+    }
+    let previous = read_submissions(record)?;
+    if let Some(verdict) = known_verdict(&previous, part, &answer) {
+        return Ok((verdict, false));
+    }
+    let session = fetch::session_token()?;
+    let verdict = post_answer(day, part, &answer, &session)?;
+    if verdict.is_about_answer() {
+        record_submission(
+            record,
+            &Submission {
+                part,
+                answer,
+                verdict: verdict.clone(),
+            },
+        )?;
+    }
+    Ok((verdict, true))
+}
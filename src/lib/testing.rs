@@ -0,0 +1,100 @@
+//! Snapshot tests: checking text (usually a grid) against a copy of
+//! it kept in a file, so that a test doesn't need a large literal.
+//!
+//! When the output changes on purpose, update the snapshots by running
+//! the tests with `AOC_UPDATE_SNAPSHOTS` set, and review the changes
+//! to them before committing:
+//!
+//! ```sh
+//! AOC_UPDATE_SNAPSHOTS=1 cargo test
+//! ```
+use std::fs;
+use std::path::Path;
+
+/// When this environment variable is set (to anything but an empty
+/// string), snapshots are written instead of checked.
+pub const UPDATE_VAR: &str = "AOC_UPDATE_SNAPSHOTS";
+
+/// Asserts that `value` is displayed as the text in the snapshot file
+/// at `path` (relative to the top of the package).
+///
+/// ```
+/// use lib::assert_grid_snapshot;
+/// use lib::y2023::day18::{dig_trenches, parse_plan};
+/// let plan = parse_plan(lib::examples::examples(18)[0]).expect("example should be valid");
+/// assert_grid_snapshot!(dig_trenches(&plan), "tests/snapshots/day18_dig.txt");
+/// ```
+#[macro_export]
+macro_rules! assert_grid_snapshot {
+    ($value:expr, $path:expr) => {
+        $crate::testing::assert_snapshot(
+            &$value.to_string(),
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
+
+/// Panics unless `actual` is the text in the snapshot file at `path`,
+/// or writes `actual` to that file if `AOC_UPDATE_SNAPSHOTS` is set.
+/// This is what `assert_grid_snapshot!` calls.
+pub fn assert_snapshot<P: AsRef<Path>>(actual: &str, path: P) {
+    let update = std::env::var_os(UPDATE_VAR).is_some_and(|v| !v.is_empty());
+    if let Err(e) = check_snapshot(actual, path.as_ref(), update) {
+        panic!("{e}");
+    }
+}
+
+/// Compares `actual` with the snapshot at `path` (or, if `update` is
+/// true, replaces the snapshot with it).  The error describes the
+/// difference.
+fn check_snapshot(actual: &str, path: &Path, update: bool) -> Result<(), String> {
+    let shown = path.display();
+    if update {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+        }
+        return fs::write(path, actual).map_err(|e| format!("failed to write {shown}: {e}"));
+    }
+    let expected = fs::read_to_string(path).map_err(|e| {
+        format!("failed to read snapshot {shown}: {e} (set {UPDATE_VAR} to create it)")
+    })?;
+    if actual == expected {
+        return Ok(());
+    }
+    let mismatch = match actual
+        .lines()
+        .zip(expected.lines())
+        .position(|(a, e)| a != e)
+    {
+        Some(n) => format!("line {} differs", n + 1),
+        None => match (actual.lines().count(), expected.lines().count()) {
+            (got, wanted) if got == wanted => "the final newline differs".to_string(),
+            (got, wanted) => format!("got {got} lines, expected {wanted}"),
+        },
+    };
+    Err(format!(
+        "snapshot {shown} does not match ({mismatch}; set {UPDATE_VAR} to update it)\n\
+         got:\n{actual}\nexpected:\n{expected}"
+    ))
+}
+
+#[test]
+fn test_check_snapshot() {
+    let path = std::env::temp_dir()
+        .join(format!("aoc-snapshot-test-{}", std::process::id()))
+        .join("grid.txt");
+    assert!(check_snapshot("#.\n.#\n", &path, false)
+        .is_err_and(|e| e.contains("failed to read") && e.contains(UPDATE_VAR)));
+    assert_eq!(check_snapshot("#.\n.#\n", &path, true), Ok(()));
+    assert_eq!(check_snapshot("#.\n.#\n", &path, false), Ok(()));
+    assert!(check_snapshot("#.\n##\n", &path, false).is_err_and(|e| e.contains("line 2 differs")));
+    assert!(check_snapshot("#.\n.#\n..\n", &path, false)
+        .is_err_and(|e| e.contains("got 3 lines, expected 2")));
+    // A missing final newline matters too.
+    assert!(check_snapshot("#.\n.#", &path, false)
+        .is_err_and(|e| e.contains("the final newline differs")));
+    if let Some(dir) = path.parent() {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
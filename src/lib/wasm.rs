@@ -0,0 +1,18 @@
+//! Entry points for running the solutions from JavaScript, for
+//! example in a web page where the user pastes in their puzzle input.
+//! Nothing here reads the filesystem, and the puzzle inputs are not
+//! built in, so the input always has to come from the caller.
+use wasm_bindgen::prelude::*;
+
+use crate::input::normalize;
+use crate::solver;
+
+/// Solves one part of the puzzle for `day`.  On failure, the error is
+/// a message suitable for showing to the user.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, String> {
+    // Pasted input may have Windows line endings or extra blank lines.
+    solver::solve(day, part, &normalize(input))
+        .map(|answer| answer.to_string())
+        .map_err(|e| e.to_string())
+}
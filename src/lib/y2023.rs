@@ -0,0 +1,72 @@
+// The solutions for Advent of Code 2023.  This code is in the
+// library so that it is callable from benchmarks, examples and tests
+// as well as from the binaries.  Solutions for other years would go
+// in sibling modules (y2024 and so on).
+
+pub const YEAR: u16 = 2023;
+
+/// The days for which we have a solution.
+pub const DAYS: &[u8] = &[
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 14, 15, 16, 18, 19, 22,
+];
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day18;
+pub mod day19;
+pub mod day22;
+
+use crate::solver::{Backend, Notes};
+
+/// Returns the notes for the solution for `day`, if we have one.
+pub fn notes(day: u8) -> Option<&'static Notes> {
+    match day {
+        1 => Some(&day01::NOTES),
+        2 => Some(&day02::NOTES),
+        3 => Some(&day03::NOTES),
+        4 => Some(&day04::NOTES),
+        5 => Some(&day05::NOTES),
+        6 => Some(&day06::NOTES),
+        7 => Some(&day07::NOTES),
+        8 => Some(&day08::NOTES),
+        9 => Some(&day09::NOTES),
+        10 => Some(&day10::NOTES),
+        11 => Some(&day11::NOTES),
+        13 => Some(&day13::NOTES),
+        14 => Some(&day14::NOTES),
+        15 => Some(&day15::NOTES),
+        16 => Some(&day16::NOTES),
+        18 => Some(&day18::NOTES),
+        19 => Some(&day19::NOTES),
+        22 => Some(&day22::NOTES),
+        _ => None,
+    }
+}
+
+/// Returns the alternative ways of solving the puzzle for `day`, if
+/// it has more than one.
+pub fn backends(day: u8) -> &'static [Backend] {
+    match day {
+        1 => &day01::BACKENDS,
+        5 => &day05::BACKENDS,
+        8 => &day08::BACKENDS,
+        11 => &day11::BACKENDS,
+        15 => &day15::BACKENDS,
+        18 => &day18::BACKENDS,
+        22 => &day22::BACKENDS,
+        _ => &[],
+    }
+}
@@ -0,0 +1,342 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::str;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::input::InputError;
+use crate::iterplus::sum_result;
+use crate::solver::{Backend, Notes};
+
+pub const NOTES: Notes = Notes {
+    title: "Trebuchet?!",
+    algorithm: "The first and last digit on each line are found by scanning it from each end; in part 2 the digits may also be spelled out.",
+    complexity: "O(n) in the size of the input",
+};
+
+fn first_and_last(v: &[u32]) -> Result<(u32, u32), Fail> {
+    match v {
+        [] => Err(Fail("there are no digits".to_string())),
+        [d] => Ok((*d, *d)),
+        [d1, .., d2] => Ok((*d1, *d2)),
+    }
+}
+
+fn first_and_last_p1(s: &str) -> Result<(u32, u32), Fail> {
+    let digits: Vec<u32> = s.chars().filter_map(|ch| ch.to_digit(10)).collect();
+    first_and_last(digits.as_slice())
+}
+
+#[test]
+fn test_digit_extraction() {
+    assert!(first_and_last_p1("").is_err());
+    assert!(first_and_last_p1("foo").is_err());
+    assert_eq!(first_and_last_p1("12"), Ok((1, 2)));
+    assert_eq!(first_and_last_p1("3"), Ok((3, 3)));
+    assert_eq!(first_and_last_p1("f6o9o"), Ok((6, 9)));
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(crate::examples::examples(1)[0]), Ok(142));
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day01::part1;
+/// let example = lib::examples::examples(1)[0];
+/// assert_eq!(part1(example), Ok(142));
+/// ```
+pub fn part1(s: &str) -> Result<u32, Fail> {
+    s.lines()
+        .map(|line| first_and_last_p1(line).map(|(left, right)| 10 * left + right))
+        .try_fold(0, sum_result)
+}
+
+fn get_part2_digit(cap: &str) -> Result<u32, Fail> {
+    match cap {
+        "0" | "zero" => Ok(0),
+        "1" | "one" => Ok(1),
+        "2" | "two" => Ok(2),
+        "3" | "three" => Ok(3),
+        "4" | "four" => Ok(4),
+        "5" | "five" => Ok(5),
+        "6" | "six" => Ok(6),
+        "7" | "seven" => Ok(7),
+        "8" | "eight" => Ok(8),
+        "9" | "nine" => Ok(9),
+        _ => Err(fail!("{cap} is not a digit")),
+    }
+}
+
+fn make_regexes() -> (Regex, Regex) {
+    (
+        // first digit
+        Regex::new("^.*?([0123456789]|one|two|three|four|five|six|seven|eight|nine).*$").unwrap(),
+        //  last figit
+        Regex::new("^.*([0123456789]|one|two|three|four|five|six|seven|eight|nine).*?$").unwrap(),
+    )
+}
+
+#[test]
+fn test_p2_matchers() {
+    let (first_matcher, last_matcher) = make_regexes();
+
+    assert_eq!(
+        first_matcher
+            .captures("1")
+            .unwrap()
+            .get(1)
+            .unwrap()
+            .as_str(),
+        "1"
+    );
+    assert_eq!(
+        first_matcher
+            .captures("21")
+            .unwrap()
+            .get(1)
+            .unwrap()
+            .as_str(),
+        "2"
+    );
+    assert_eq!(
+        last_matcher
+            .captures("21")
+            .unwrap()
+            .get(1)
+            .unwrap()
+            .as_str(),
+        "1"
+    );
+}
+
+fn extract_match_str(m: Option<Captures<'_>>) -> &str {
+    match m {
+        Some(captures) => match captures.get(1) {
+            Some(m) => m.as_str(),
+            None => "",
+        },
+        None => "",
+    }
+}
+
+/// The ways a digit can be written in part 2.  (Zero is never
+/// spelled out.)
+const DIGIT_NAMES: [(&str, u32); 19] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// Returns the digit (written either way) which starts at byte `i` of
+/// `line`, if there is one.
+fn digit_at(line: &[u8], i: usize) -> Option<u32> {
+    let rest = &line[i..];
+    DIGIT_NAMES
+        .iter()
+        .find(|(name, _)| rest.starts_with(name.as_bytes()))
+        .map(|(_, value)| *value)
+}
+
+/// Finds the first and last digits for part 2 by looking for a digit
+/// at each position in turn, from the start of the line and then
+/// from the end.  The two may overlap, as in "twone".
+fn scan_first_and_last(line: &str) -> Result<(u32, u32), Fail> {
+    let bytes = line.as_bytes();
+    let first = (0..bytes.len()).find_map(|i| digit_at(bytes, i));
+    let last = (0..bytes.len()).rev().find_map(|i| digit_at(bytes, i));
+    match (first, last) {
+        (Some(first), Some(last)) => Ok((first, last)),
+        _ => Err(Fail("there are no digits".to_string())),
+    }
+}
+
+fn first_and_last_p2(
+    line: &str,
+    first_matcher: &Regex,
+    last_matcher: &Regex,
+) -> Result<(u32, u32), Fail> {
+    // The wrinkle here is that the first and last digit can overlap.
+    let s = line.trim_end();
+    let d1: u32 = get_part2_digit(extract_match_str(first_matcher.captures(s)))?;
+    let d2: u32 = get_part2_digit(extract_match_str(last_matcher.captures(s)))?;
+    first_and_last(&[d1, d2])
+}
+
+lazy_static! {
+    /// The matchers for part 2, which are compiled only once.
+    static ref MATCHERS: (Regex, Regex) = make_regexes();
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day01::part2;
+/// let example = lib::examples::examples(1)[1];
+/// assert_eq!(part2(example), Ok(281));
+/// ```
+pub fn part2(s: &str) -> Result<u32, Fail> {
+    s.lines()
+        .map(|line| scan_first_and_last(line).map(|(a, b)| 10 * a + b))
+        .try_fold(0, sum_result)
+}
+
+/// Solves part 2 with regular expressions, which is how `part2` used
+/// to work.
+pub fn part2_regex(s: &str) -> Result<u32, Fail> {
+    let (first_matcher, last_matcher) = &*MATCHERS;
+    s.lines()
+        .map(|line| first_and_last_p2(line, first_matcher, last_matcher).map(|(a, b)| 10 * a + b))
+        .try_fold(0, sum_result)
+}
+
+pub const BACKENDS: [Backend; 2] = [
+    Backend {
+        part: 2,
+        name: "scan",
+        description: "Look for a digit, or the name of one, at each position in the line",
+        slow: false,
+        solve: |input| Ok(part2(input)?.into()),
+    },
+    Backend {
+        part: 2,
+        name: "regex",
+        description: "Find the first and last digits with regular expressions",
+        slow: false,
+        solve: |input| Ok(part2_regex(input)?.into()),
+    },
+];
+
+#[test]
+fn test_first_and_last_p2() {
+    let (first_matcher, last_matcher) = make_regexes();
+
+    let first_and_last = |s| first_and_last_p2(s, &first_matcher, &last_matcher);
+    assert!(first_and_last("").is_err());
+    assert!(first_and_last("foo").is_err());
+    assert_eq!(first_and_last("one"), Ok((1, 1)));
+    assert_eq!(first_and_last("two"), Ok((2, 2)));
+    assert_eq!(first_and_last("twotwo"), Ok((2, 2)));
+    assert_eq!(first_and_last("twotwo\n"), Ok((2, 2)));
+    assert_eq!(first_and_last("12"), Ok((1, 2)));
+
+    assert_eq!(first_and_last("two1nine\n"), Ok((2, 9)));
+    assert_eq!(first_and_last("eightwothree\n"), Ok((8, 3)));
+    assert_eq!(first_and_last("abcone2threexyz\n"), Ok((1, 3)));
+    assert_eq!(first_and_last("xtwone3four\n"), Ok((2, 4)));
+    assert_eq!(first_and_last("4nineeightseven2\n"), Ok((4, 2)));
+    assert_eq!(first_and_last("zoneight234\n"), Ok((1, 4)));
+    assert_eq!(first_and_last("7pqrstsixteen\n"), Ok((7, 6)));
+
+    // I made this example up
+    assert_eq!(first_and_last("twoone\n"), Ok((2, 1)));
+    // I made this example up; note the numbers overlap.
+    assert_eq!(first_and_last("twone\n"), Ok((2, 1)));
+}
+
+#[test]
+fn test_part2() {
+    assert_eq!(part2(crate::examples::examples(1)[1]), Ok(281));
+    assert_eq!(part2("eighttwo\nfotwooneg\n"), Ok(82 + 21));
+    assert_eq!(part2_regex(crate::examples::examples(1)[1]), Ok(281));
+}
+
+#[test]
+fn test_scan_first_and_last() {
+    assert!(scan_first_and_last("").is_err());
+    assert!(scan_first_and_last("zero").is_err());
+    assert_eq!(scan_first_and_last("twone"), Ok((2, 1)));
+    assert_eq!(scan_first_and_last("7pqrstsixteen"), Ok((7, 6)));
+    assert_eq!(scan_first_and_last("é3"), Ok((3, 3)));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_scan_matches_regex(line in "[0-9a-z]{0,30}") {
+        let (first_matcher, last_matcher) = &*MATCHERS;
+        let scanned = scan_first_and_last(&line);
+        let matched = first_and_last_p2(&line, first_matcher, last_matcher);
+        proptest::prop_assert_eq!(scanned.ok(), matched.ok());
+    }
+
+    #[test]
+    fn test_scan_matches_regex_on_digit_names(line in "(one|two|thre|three|four|five|six|seven|eigh|eight|nine|[0-9x]){0,8}") {
+        let (first_matcher, last_matcher) = &*MATCHERS;
+        let scanned = scan_first_and_last(&line);
+        let matched = first_and_last_p2(&line, first_matcher, last_matcher);
+        proptest::prop_assert_eq!(scanned.ok(), matched.ok());
+    }
+}
+
+/// The answers to both parts of the puzzle, or the reason there isn't
+/// one.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StreamTotals {
+    pub part1: Result<u64, Fail>,
+    pub part2: Result<u64, Fail>,
+}
+
+/// Solves both parts of the puzzle in a single pass over `lines`, so
+/// that the input can be arbitrarily large (and can be read from a
+/// pipe).  Errors in the input are reported with their line number;
+/// an error reading the input stops the whole thing.
+pub fn sum_streaming<I>(lines: I) -> Result<StreamTotals, InputError>
+where
+    I: IntoIterator<Item = Result<String, InputError>>,
+{
+    let mut totals = StreamTotals {
+        part1: Ok(0),
+        part2: Ok(0),
+    };
+    let add = |total: &mut Result<u64, Fail>, number: usize, value: Result<(u32, u32), Fail>| {
+        if let Ok(sum) = total {
+            *total = match value {
+                Ok((left, right)) => Ok(*sum + u64::from(10 * left + right)),
+                Err(Fail(e)) => Err(fail!("line {number}: {e}")),
+            };
+        }
+    };
+    for (i, line) in lines.into_iter().enumerate() {
+        let line = line?;
+        add(&mut totals.part1, i + 1, first_and_last_p1(&line));
+        add(&mut totals.part2, i + 1, scan_first_and_last(&line));
+    }
+    Ok(totals)
+}
+
+#[test]
+fn test_sum_streaming() {
+    let stream = |s: &'static str| {
+        sum_streaming(crate::input::stream_lines(s.as_bytes(), None))
+            .expect("reading from a slice should work")
+    };
+    let examples = crate::examples::examples(1);
+    assert_eq!(stream(examples[0]).part1, Ok(142));
+    assert_eq!(stream(examples[1]).part2, Ok(281));
+    assert_eq!(
+        stream("1abc2\nnine\n"),
+        StreamTotals {
+            part1: Err(Fail("line 2: there are no digits".to_string())),
+            part2: Ok(12 + 99),
+        }
+    );
+}
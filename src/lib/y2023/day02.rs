@@ -0,0 +1,328 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::str;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::report::json_quote;
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Cube Conundrum",
+    algorithm: "Each game is reduced to the largest number of cubes of each colour shown at once, which is compared with the stock (part 1) or multiplied out (part 2).",
+    complexity: "O(n) in the size of the input",
+};
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(2).expect("there should be an example")
+}
+
+#[cfg(test)]
+fn part1_example() -> Vec<Game> {
+    parse_input(get_example()).expect("example should be valid")
+}
+
+#[derive(Debug, Default)]
+pub struct Stock {
+    pub counts: BTreeMap<String, u32>,
+}
+
+impl Stock {
+    /// Returns the contents of the bag in part 1.
+    pub fn part1() -> Stock {
+        Stock {
+            counts: [
+                ("red".to_string(), 12),
+                ("green".to_string(), 13),
+                ("blue".to_string(), 14),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    fn power(&self) -> u32 {
+        self.counts.values().product()
+    }
+
+    fn get(&self, colour: &str) -> &u32 {
+        self.counts.get(colour).unwrap_or(&0)
+    }
+
+    fn update_requirement(&mut self, colour: &str, count: u32) {
+        self.counts
+            .entry(colour.to_string())
+            .and_modify(|needed| {
+                if *needed < count {
+                    *needed = count
+                }
+            })
+            .or_insert(count);
+    }
+
+    fn suffices_for(&self, required: &Stock) -> bool {
+        required
+            .counts
+            .iter()
+            .all(|(colour, needed)| self.get(colour) >= needed)
+    }
+}
+
+#[derive(Debug)]
+pub struct Turn {
+    pub counts: BTreeMap<String, u32>,
+}
+
+impl Turn {
+    fn update_requirement(&self, req: &mut Stock) {
+        self.counts.iter().for_each(|(colour, count)| {
+            req.update_requirement(colour, *count);
+        });
+    }
+}
+
+fn str_to_num(s: &str) -> Result<u32, Fail> {
+    match s.parse() {
+        Ok(n) => Ok(n),
+        Err(e) => Err(fail!("{s} is not a valid number: {e}")),
+    }
+}
+
+impl TryFrom<&str> for Turn {
+    type Error = Fail;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Ok(Turn {
+            counts: s
+                .split(", ")
+                .map(|pair| match pair.split_once(' ') {
+                    Some((ns, colour)) => match str_to_num(ns) {
+                        Ok(n) => Ok((colour.to_string(), n)),
+                        Err(e) => Err(e),
+                    },
+                    None => Err(fail!("invalid pair: {pair}")),
+                })
+                .collect::<Result<BTreeMap<String, u32>, Fail>>()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Game {
+    pub id: u32,
+    pub turns: Vec<Turn>,
+}
+
+impl Game {
+    fn min_requirement(&self) -> Stock {
+        self.turns.iter().fold(Stock::default(), |mut acc, turn| {
+            turn.update_requirement(&mut acc);
+            acc
+        })
+    }
+}
+
+impl TryFrom<&str> for Game {
+    type Error = Fail;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        match line.split_once(": ") {
+            Some((prefix, counts_str)) => match prefix.strip_prefix("Game ") {
+                None => Err(fail!("prefix should start with 'Game ': {prefix}")),
+                Some(id_str) => Ok(Game {
+                    id: str_to_num(id_str)?,
+                    turns: counts_str
+                        .split("; ")
+                        .map(Turn::try_from)
+                        .collect::<Result<Vec<Turn>, Fail>>()?,
+                }),
+            },
+            None => Err(fail!("invalid line contains no id: {line}")),
+        }
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Game>, Fail> {
+    input.lines().map(Game::try_from).collect()
+}
+
+pub fn sum_possible_game_ids(games: &[Game], stock: &Stock) -> u32 {
+    games
+        .iter()
+        .filter_map(|game| {
+            if stock.suffices_for(&game.min_requirement()) {
+                Some(game.id)
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+#[test]
+fn test_debug_output_is_deterministic() {
+    // The counts are kept in order of colour, so printing the same
+    // game always gives the same output.
+    let game = Game::try_from("Game 7: 3 blue, 4 red, 1 green; 2 green, 6 blue")
+        .expect("game should be valid");
+    let expected = format!("{game:?}");
+    assert!(expected.find("blue").unwrap() < expected.find("green").unwrap());
+    for _ in 0..10 {
+        let game = Game::try_from("Game 7: 3 blue, 4 red, 1 green; 2 green, 6 blue")
+            .expect("game should be valid");
+        assert_eq!(format!("{game:?}"), expected);
+    }
+}
+
+#[test]
+fn test_sum_possible_game_ids() {
+    let got = sum_possible_game_ids(&part1_example(), &Stock::part1());
+    assert_eq!(got, 8);
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day02::part1;
+/// let example = lib::examples::examples(2)[0];
+/// assert_eq!(part1(example), Ok(8));
+/// ```
+pub fn part1(input: &str) -> Result<u32, Fail> {
+    Ok(sum_possible_game_ids(&parse_input(input)?, &Stock::part1()))
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(get_example()), Ok(8));
+}
+
+pub fn sum_of_powers(games: &[Game]) -> u32 {
+    games
+        .iter()
+        .map(|game| game.min_requirement().power())
+        .sum()
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day02::part2;
+/// let example = lib::examples::examples(2)[0];
+/// assert_eq!(part2(example), Ok(2286));
+/// ```
+pub fn part2(input: &str) -> Result<u32, Fail> {
+    Ok(sum_of_powers(&parse_input(input)?))
+}
+
+#[test]
+fn test_part2() {
+    assert_eq!(part2(get_example()), Ok(2286));
+}
+
+/// The smallest stock with which a game is possible.
+#[derive(Debug)]
+pub struct Requirement {
+    pub game: u32,
+    pub stock: Stock,
+    /// The product of the counts in `stock`.
+    pub power: u32,
+}
+
+/// Returns the smallest stock each game needs, most powerful first
+/// (and then in order of game number).
+pub fn requirements(games: &[Game]) -> Vec<Requirement> {
+    let mut result: Vec<Requirement> = games
+        .iter()
+        .map(|game| {
+            let stock = game.min_requirement();
+            Requirement {
+                game: game.id,
+                power: stock.power(),
+                stock,
+            }
+        })
+        .collect();
+    result.sort_by(|a, b| b.power.cmp(&a.power).then(a.game.cmp(&b.game)));
+    result
+}
+
+#[test]
+fn test_requirements() {
+    let found: Vec<(u32, u32)> = requirements(&part1_example())
+        .iter()
+        .map(|r| (r.game, r.power))
+        .collect();
+    assert_eq!(found, vec![(3, 1560), (4, 630), (1, 48), (5, 36), (2, 12)]);
+}
+
+/// Formats `requirements` as a table, with a column for each colour
+/// any game needs.
+pub fn requirements_table(requirements: &[Requirement]) -> String {
+    let colours: BTreeSet<&str> = requirements
+        .iter()
+        .flat_map(|r| r.stock.counts.keys().map(String::as_str))
+        .collect();
+    let mut result = String::from("game");
+    for colour in colours.iter() {
+        result.push_str(&format!("  {colour:>5}"));
+    }
+    result.push_str("  power\n");
+    for r in requirements {
+        result.push_str(&format!("{:>4}", r.game));
+        for colour in colours.iter() {
+            result.push_str(&format!(
+                "  {:>width$}",
+                r.stock.get(colour),
+                width = colour.len().max(5)
+            ));
+        }
+        result.push_str(&format!("  {:>5}\n", r.power));
+    }
+    result
+}
+
+#[test]
+fn test_requirements_table() {
+    let table = requirements_table(&requirements(&part1_example()));
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines[0], "game   blue  green    red  power");
+    assert_eq!(lines[1], "   3      6     13     20   1560");
+    assert_eq!(lines.len(), 6);
+}
+
+/// Formats `requirements` as a JSON array.
+pub fn requirements_json(requirements: &[Requirement]) -> String {
+    let objects: Vec<String> = requirements
+        .iter()
+        .map(|r| {
+            let counts: Vec<String> = r
+                .stock
+                .counts
+                .iter()
+                .map(|(colour, count)| format!("{}:{count}", json_quote(colour)))
+                .collect();
+            format!(
+                "{{\"game\":{},\"stock\":{{{}}},\"power\":{}}}",
+                r.game,
+                counts.join(","),
+                r.power
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+#[test]
+fn test_requirements_json() {
+    let games = parse_input("Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red\nGame 9: 2 red\n")
+        .expect("example should be valid");
+    assert_eq!(
+        requirements_json(&requirements(&games)),
+        concat!(
+            "[\n",
+            "{\"game\":4,\"stock\":{\"blue\":6,\"green\":3,\"red\":6},\"power\":108},\n",
+            "{\"game\":9,\"stock\":{\"red\":2},\"power\":2}\n",
+            "]\n",
+        )
+    );
+}
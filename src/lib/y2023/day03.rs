@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::str;
+
+use crate::error::Fail;
+use crate::grid::Position;
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Gear Ratios",
+    algorithm: "Numbers are found by scanning each row, and a number is a part number if a symbol is next to any of its digits; gears collect the numbers next to them.  A streaming version keeps only three rows in memory.",
+    complexity: "O(n) in the size of the input",
+};
+
+#[derive(Eq, PartialEq, Clone, Copy)]
+enum Symbol {
+    Gear(Position),
+    Other,
+}
+
+impl Symbol {
+    fn is_gear(&self) -> bool {
+        matches!(self, Symbol::Gear(_))
+    }
+}
+
+fn symbol_type(ch: char, pos: Position) -> Option<Symbol> {
+    if ch == '*' {
+        Some(Symbol::Gear(pos))
+    } else if ch == '.' || ch.is_ascii_digit() {
+        None
+    } else {
+        Some(Symbol::Other)
+    }
+}
+
+pub fn parse_input(input: &str) -> HashMap<Position, char> {
+    let mut result = HashMap::new();
+    for (y, line) in input.split_terminator('\n').enumerate() {
+        for (x, ch) in line.chars().enumerate() {
+            result.insert(
+                Position {
+                    x: x as i64,
+                    y: y as i64,
+                },
+                ch,
+            );
+        }
+    }
+    result
+}
+
+fn symbol_neighbour(p: &Position, schematic: &HashMap<Position, char>) -> Option<Symbol> {
+    for dy in [-1, 0, 1] {
+        for dx in [-1, 0, 1] {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let neighbour = Position {
+                x: p.x + dx,
+                y: p.y + dy,
+            };
+            if let Some(ch) = schematic.get(&neighbour) {
+                let symtype = symbol_type(*ch, neighbour);
+                if symtype.is_some() {
+                    return symtype;
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Works out which symbol a number is associated with, given the
+/// symbol found so far for its earlier digits and the one next to its
+/// latest digit.  A gear takes precedence over any other symbol.
+fn associate(associated_part: Option<Symbol>, neighbour: Option<Symbol>) -> Option<Symbol> {
+    match neighbour {
+        Some(Symbol::Gear(_)) => neighbour,
+        Some(Symbol::Other) if !associated_part.is_some_and(|sym| sym.is_gear()) => neighbour,
+        _ => associated_part,
+    }
+}
+
+/// Records the number `n`, which has just ended, if it is a part
+/// number.
+fn finish_number(
+    n: i64,
+    associated_part: Option<Symbol>,
+    result: &mut Vec<i64>,
+    gears: &mut HashMap<Position, HashSet<usize>>,
+) {
+    match associated_part {
+        Some(Symbol::Other) => {
+            result.push(n);
+        }
+        Some(Symbol::Gear(gear_location)) => {
+            gears.entry(gear_location).or_default().insert(result.len());
+            result.push(n);
+        }
+        None => (),
+    }
+}
+
+/// Returns the part numbers in `schematic`, and for each gear, the
+/// indexes (into the part numbers) of the numbers next to it.
+///
+/// A number ends at the end of its line.  (Before the streaming
+/// scanner was added, a number at the end of one line ran on into
+/// any digits at the start of the next, so "12" followed by a line
+/// starting "3" was read as 123; the scanner can't do that, and it
+/// was wrong anyway, so this was changed to match.)
+fn extract_part_numbers(
+    schematic: &HashMap<Position, char>,
+) -> (Vec<i64>, HashMap<Position, HashSet<usize>>) {
+    let mut result = Vec::new();
+    let mut gears: HashMap<Position, HashSet<usize>> = HashMap::new();
+    let mut current_num: Option<i64> = None;
+    let mut associated_part: Option<Symbol> = None;
+
+    for y in 0.. {
+        for x in 0.. {
+            let p = Position { x, y };
+            let ch = schematic.get(&p);
+            if let Some(digit_value) = ch.and_then(|ch| ch.to_digit(10)) {
+                current_num = Some(current_num.unwrap_or(0) * 10 + i64::from(digit_value));
+                associated_part = associate(associated_part, symbol_neighbour(&p, schematic));
+                continue;
+            }
+            // A number ends at a non-digit or at the end of the line.
+            if let Some(n) = current_num.take() {
+                finish_number(n, associated_part.take(), &mut result, &mut gears);
+            }
+            if ch.is_none() {
+                if x == 0 {
+                    return (result, gears);
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+    unreachable!("the schematic has a finite number of lines")
+}
+
+pub fn sum_part_numbers(schematic: &HashMap<Position, char>) -> i64 {
+    let (part_numbers, _) = extract_part_numbers(schematic);
+    part_numbers.iter().sum()
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(3).expect("there should be an example")
+}
+
+#[test]
+fn test_sum_part_numbers() {
+    let example = get_example();
+    assert_eq!(sum_part_numbers(&parse_input(example)), 4361);
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day03::part1;
+/// let example = lib::examples::examples(3)[0];
+/// assert_eq!(part1(example), Ok(4361));
+/// ```
+pub fn part1(input: &str) -> Result<i64, Fail> {
+    Ok(sum_part_numbers(&parse_input(input)))
+}
+
+/// A `*` which is next to more than one part number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub position: Position,
+    /// The part numbers next to the gear, in the order they appear in
+    /// the schematic.
+    pub part_numbers: Vec<i64>,
+    /// The product of `part_numbers`.
+    pub ratio: i64,
+}
+
+impl Display for Gear {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let factors: Vec<String> = self.part_numbers.iter().map(i64::to_string).collect();
+        write!(
+            f,
+            "gear at {}: {} = {}",
+            self.position,
+            factors.join(" * "),
+            self.ratio
+        )
+    }
+}
+
+/// Returns the gears in `schematic`, in reading order.
+pub fn gears(schematic: &HashMap<Position, char>) -> Vec<Gear> {
+    let (part_numbers, gear_locations) = extract_part_numbers(schematic);
+    let mut result: Vec<Gear> = gear_locations
+        .into_iter()
+        .filter(|(_, partnum_indices)| partnum_indices.len() > 1)
+        .map(|(position, partnum_indices)| {
+            let mut indices: Vec<usize> = partnum_indices.into_iter().collect();
+            indices.sort_unstable();
+            let part_numbers: Vec<i64> = indices.iter().map(|i| part_numbers[*i]).collect();
+            Gear {
+                position,
+                ratio: part_numbers.iter().product(),
+                part_numbers,
+            }
+        })
+        .collect();
+    result.sort_by_key(|gear| (gear.position.y, gear.position.x));
+    result
+}
+
+#[test]
+fn test_gears() {
+    let found = gears(&parse_input(get_example()));
+    assert_eq!(
+        found,
+        vec![
+            Gear {
+                position: Position { x: 3, y: 1 },
+                part_numbers: vec![467, 35],
+                ratio: 16345,
+            },
+            Gear {
+                position: Position { x: 5, y: 8 },
+                part_numbers: vec![755, 598],
+                ratio: 451490,
+            },
+        ]
+    );
+    assert_eq!(found[0].to_string(), "gear at 3,1: 467 * 35 = 16345");
+}
+
+pub fn sum_gear_ratios(schematic: &HashMap<Position, char>) -> i64 {
+    gears(schematic).iter().map(|gear| gear.ratio).sum()
+}
+
+#[test]
+fn test_sum_gear_ratios() {
+    let example = get_example();
+    assert_eq!(sum_gear_ratios(&parse_input(example)), 467835);
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day03::part2;
+/// let example = lib::examples::examples(3)[0];
+/// assert_eq!(part2(example), Ok(467835));
+/// ```
+pub fn part2(input: &str) -> Result<i64, Fail> {
+    Ok(sum_gear_ratios(&parse_input(input)))
+}
+
+#[test]
+fn test_number_at_end_of_line() {
+    // Numbers end at the end of the line, even if the next line
+    // starts with a digit.
+    let input = "..12\n3*..\n..45\n";
+    let schematic = parse_input(input);
+    assert_eq!(sum_part_numbers(&schematic), 60);
+    assert_eq!(sum_gear_ratios(&schematic), 12 * 3 * 45);
+    assert_eq!(part1_streaming(input), Ok(60));
+    assert_eq!(part2_streaming(input), Ok(12 * 3 * 45));
+    // A number which fills the line still ends there.
+    assert_eq!(part1("123\n*45\n"), Ok(168));
+}
+
+/// The answers to both parts of the puzzle.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub part_numbers: i64,
+    pub gear_ratios: i64,
+}
+
+/// Like `symbol_neighbour`, but looks only at `rows`, which are the
+/// line above the digit at `x`, the digit's own line, and the line
+/// below.
+fn window_symbol_neighbour(x: usize, y: i64, rows: [Option<&[char]>; 3]) -> Option<Symbol> {
+    for (row, dy) in rows.iter().zip([-1, 0, 1]) {
+        for dx in [-1, 0, 1] {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let Some(nx) = x.checked_add_signed(dx) else {
+                continue;
+            };
+            if let Some(ch) = row.and_then(|row| row.get(nx)) {
+                let neighbour = Position {
+                    x: nx as i64,
+                    y: y + dy,
+                };
+                let symtype = symbol_type(*ch, neighbour);
+                if symtype.is_some() {
+                    return symtype;
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Works through the schematic a line at a time, keeping only the
+/// lines on either side of the current one, so it needs memory in
+/// proportion to the width of the schematic rather than its area.
+#[derive(Debug, Default)]
+struct StreamingScanner {
+    above: Option<Vec<char>>,
+    current: Option<Vec<char>>,
+    /// The number of the line in `current`.
+    y: i64,
+    /// The part numbers next to each gear which may yet be next to
+    /// more.
+    gears: HashMap<Position, Vec<i64>>,
+    totals: Totals,
+}
+
+impl StreamingScanner {
+    fn push_line(&mut self, below: Option<Vec<char>>) {
+        if let Some(current) = self.current.as_deref() {
+            let rows = [self.above.as_deref(), Some(current), below.as_deref()];
+            let mut current_num: Option<i64> = None;
+            let mut associated_part: Option<Symbol> = None;
+            // Visit one position past the end, so that a number which
+            // ends the line is finished.
+            for x in 0..=current.len() {
+                if let Some(digit_value) = current.get(x).and_then(|ch| ch.to_digit(10)) {
+                    current_num = Some(current_num.unwrap_or(0) * 10 + i64::from(digit_value));
+                    associated_part =
+                        associate(associated_part, window_symbol_neighbour(x, self.y, rows));
+                } else if let Some(n) = current_num.take() {
+                    match associated_part.take() {
+                        Some(Symbol::Other) => {
+                            self.totals.part_numbers += n;
+                        }
+                        Some(Symbol::Gear(gear_location)) => {
+                            self.totals.part_numbers += n;
+                            self.gears.entry(gear_location).or_default().push(n);
+                        }
+                        None => (),
+                    }
+                }
+            }
+            // Numbers on later lines can't be next to gears on the line
+            // above this one.
+            let y = self.y;
+            self.finish_gears(|gear| gear.y < y);
+            self.y += 1;
+        }
+        self.above = self.current.take();
+        self.current = below;
+    }
+
+    fn finish_gears<P: Fn(&Position) -> bool>(&mut self, finished: P) {
+        let done: Vec<Position> = self.gears.keys().filter(|g| finished(g)).copied().collect();
+        for gear in done {
+            if let Some(parts) = self.gears.remove(&gear) {
+                if parts.len() > 1 {
+                    self.totals.gear_ratios += parts.iter().product::<i64>();
+                }
+            }
+        }
+    }
+
+    fn finish(mut self) -> Totals {
+        self.push_line(None);
+        self.finish_gears(|_| true);
+        self.totals
+    }
+}
+
+/// Solves both parts of the puzzle, reading the schematic a line at a
+/// time.
+pub fn scan_streaming<I, S>(lines: I) -> Totals
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut scanner = StreamingScanner::default();
+    for line in lines {
+        scanner.push_line(Some(line.as_ref().chars().collect()));
+    }
+    scanner.finish()
+}
+
+pub fn part1_streaming(input: &str) -> Result<i64, Fail> {
+    Ok(scan_streaming(input.split_terminator('\n')).part_numbers)
+}
+
+pub fn part2_streaming(input: &str) -> Result<i64, Fail> {
+    Ok(scan_streaming(input.split_terminator('\n')).gear_ratios)
+}
+
+#[test]
+fn test_streaming_example() {
+    let example = get_example();
+    assert_eq!(part1_streaming(example), Ok(4361));
+    assert_eq!(part2_streaming(example), Ok(467835));
+    assert_eq!(scan_streaming(Vec::<String>::new()), Totals::default());
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    // A schematic has no blank lines; `parse_input` takes one to be the
+    // end of the schematic.
+    #[test]
+    fn test_streaming_matches_whole_schematic(s in "([.0-9*#]{1,12}\n){0,8}") {
+        proptest::prop_assert_eq!(part1_streaming(&s), part1(&s));
+        proptest::prop_assert_eq!(part2_streaming(&s), part2(&s));
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::HashSet;
+use std::num::ParseIntError;
+use std::str;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Scratchcards",
+    algorithm: "The winning numbers on each card are found by set intersection; in part 2, one pass over the cards adds the copies each card wins to the cards which follow it.",
+    complexity: "O(n + c·w) for c cards with at most w wins each",
+};
+
+/// Represents a single card.
+#[derive(Debug)]
+pub struct Card {
+    have: HashSet<i32>,
+    winners: HashSet<i32>,
+}
+
+impl Card {
+    /// Counts how many wins a card has.
+    fn count_wins(&self) -> usize {
+        self.have
+            .iter()
+            .filter(|have| self.winners.contains(have))
+            .count()
+    }
+
+    /// Computes the score we use for part 1 (only).
+    fn part1_score(&self) -> u32 {
+        (1..=self.count_wins()).fold(0, |acc, _| if acc == 0 { 1 } else { acc * 2 })
+    }
+}
+
+#[test]
+fn test_part1_score_0() {
+    let c0 = Card {
+        have: vec![1].into_iter().collect(),
+        winners: vec![2].into_iter().collect(),
+    };
+    assert_eq!(c0.count_wins(), 0);
+    assert_eq!(c0.part1_score(), 0);
+}
+
+#[test]
+fn test_part1_score_1() {
+    let c1 = Card {
+        have: vec![1].into_iter().collect(),
+        winners: vec![1].into_iter().collect(),
+    };
+    assert_eq!(c1.count_wins(), 1);
+    assert_eq!(c1.part1_score(), 1);
+}
+
+#[test]
+fn test_part1_score_2() {
+    let c2 = Card {
+        have: vec![6, 7, 9].into_iter().collect(),
+        winners: vec![6, 7, 10].into_iter().collect(),
+    };
+    assert_eq!(c2.count_wins(), 2);
+    assert_eq!(c2.part1_score(), 2);
+}
+
+#[test]
+fn test_part1_score_3() {
+    let c3 = Card {
+        have: vec![6, 7, 9].into_iter().collect(),
+        winners: vec![6, 7, 9].into_iter().collect(),
+    };
+    assert_eq!(c3.count_wins(), 3);
+    assert_eq!(c3.part1_score(), 4);
+}
+
+fn parse_number_list(s: &str) -> Result<HashSet<i32>, Fail> {
+    s.split_whitespace()
+        .map(|numstr| {
+            numstr
+                .parse()
+                .map_err(|e: ParseIntError| fail!("{numstr} is invalid: {e}"))
+        })
+        .collect()
+}
+
+/// Parses a card from an input string.
+impl TryFrom<&str> for Card {
+    type Error = Fail;
+
+    fn try_from(s: &str) -> Result<Card, Self::Error> {
+        match s.split_once(": ") {
+            Some((_prefix, tail)) => match tail.split_once(" | ") {
+                Some((have, winners)) => Ok(Card {
+                    have: parse_number_list(have)?,
+                    winners: parse_number_list(winners)?,
+                }),
+                None => Err(fail!("expected but did not find '|' in {tail}")),
+            },
+            None => Err(fail!("expected card id prefix: {s}")),
+        }
+    }
+}
+
+/// Parse a sequence of cards from an input string.
+pub fn parse_input(s: &str) -> Result<Vec<Card>, Fail> {
+    s.split_terminator('\n')
+        .map(Card::try_from)
+        .collect::<Result<Vec<Card>, Fail>>()
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(4).expect("there should be an example")
+}
+
+#[cfg(test)]
+fn get_example_cards() -> Vec<Card> {
+    parse_input(get_example()).expect("example should be valid")
+}
+
+/// Computes the total number of points the cards are worth.
+pub fn total_points(cards: &[Card]) -> u32 {
+    cards.iter().map(|card| card.part1_score()).sum()
+}
+
+#[test]
+fn test_total_points() {
+    assert_eq!(total_points(&get_example_cards()), 13);
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day04::part1;
+/// let example = lib::examples::examples(4)[0];
+/// assert_eq!(part1(example), Ok(13));
+/// ```
+pub fn part1(input: &str) -> Result<u32, Fail> {
+    Ok(total_points(&parse_input(input)?))
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(get_example()), Ok(13));
+}
+
+/// Determines the updated counts of cards we hold following a win.
+///
+/// Arguments
+/// * `holding` - number of each card we have.  Cards appear in the same
+///   order they appear in the input (IDs are ignored).
+/// * `card_num` - the index of the card that won
+/// * `wins` - the number of wins on card `card_num`.
+fn won(mut holding: Vec<usize>, (card_num, wins): (usize, usize)) -> Vec<usize> {
+    // Card `card_num` is a winner, with `wins` wins on it.  But we
+    // hold `holding[card_num]` of that card.  For each one we hold,
+    // we win a copy of each of the next `wins` cards.
+
+    // Each winning card will win us an extra copy of one of more
+    // following cards.
+    let number_held_of_winning_card = holding[card_num];
+
+    // We use `card_num+1` here because the first card of which we win
+    // a copy is the one which immediately follows the winning card.
+    for count in holding.iter_mut().skip(card_num + 1).take(wins) {
+        *count += number_held_of_winning_card;
+    }
+    holding
+}
+
+/// What we hold of one of the original cards, once all the copies
+/// won by the cards before it have been counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Holding {
+    /// The position of the card in the input, counting from 1.
+    pub card: usize,
+    /// The number of wins on the card.
+    pub wins: usize,
+    /// The number of copies of the card we end up with (including
+    /// the original).
+    pub copies: usize,
+    /// The number of cards we hold, counting only this one and those
+    /// before it.
+    pub total: usize,
+}
+
+/// Iterates over the cards, yielding the number of each card we
+/// end up holding.  Copies are only ever won of later cards, so the
+/// count for each card is final by the time we reach it.
+pub struct Holdings<'a> {
+    cards: &'a [Card],
+    holding: Vec<usize>,
+    next: usize,
+    total: usize,
+}
+
+impl Iterator for Holdings<'_> {
+    type Item = Holding;
+
+    fn next(&mut self) -> Option<Holding> {
+        let card = self.cards.get(self.next)?;
+        let wins = card.count_wins();
+        let copies = self.holding[self.next];
+        self.holding = won(std::mem::take(&mut self.holding), (self.next, wins));
+        self.next += 1;
+        self.total += copies;
+        Some(Holding {
+            card: self.next,
+            wins,
+            copies,
+            total: self.total,
+        })
+    }
+}
+
+/// Works out how many of each card we will hold after taking into
+/// account all the wins.
+pub fn holdings(cards: &[Card]) -> Holdings<'_> {
+    Holdings {
+        cards,
+        // Initially we have 1 of each card.
+        holding: vec![1; cards.len()],
+        next: 0,
+        total: 0,
+    }
+}
+
+#[test]
+fn test_holdings() {
+    let cards = get_example_cards();
+    let found: Vec<(usize, usize, usize)> = holdings(&cards)
+        .map(|h| (h.wins, h.copies, h.total))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (4, 1, 1),
+            (2, 2, 3),
+            (2, 4, 7),
+            (1, 8, 15),
+            (0, 14, 29),
+            (0, 1, 30)
+        ]
+    );
+    assert_eq!(holdings(&[]).next(), None);
+}
+
+/// Formats what we hold of each card as a table.
+pub fn holdings_table(cards: &[Card]) -> String {
+    let mut result = String::from("card  wins  copies  total\n");
+    for h in holdings(cards) {
+        result.push_str(&format!(
+            "{:>4}  {:>4}  {:>6}  {:>5}\n",
+            h.card, h.wins, h.copies, h.total
+        ));
+    }
+    result
+}
+
+#[test]
+fn test_holdings_table() {
+    let table = holdings_table(&get_example_cards());
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], "card  wins  copies  total");
+    assert_eq!(lines[5], "   5     0      14     29");
+}
+
+/// Computes the total number of cards we end up with.
+pub fn total_cards(cards: &[Card]) -> usize {
+    holdings(cards).last().map_or(0, |h| h.total)
+}
+
+#[test]
+fn test_total_cards() {
+    assert_eq!(total_cards(&get_example_cards()), 30);
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day04::part2;
+/// let example = lib::examples::examples(4)[0];
+/// assert_eq!(part2(example), Ok(30));
+/// ```
+pub fn part2(input: &str) -> Result<usize, Fail> {
+    Ok(total_cards(&parse_input(input)?))
+}
+
+#[test]
+fn test_part2() {
+    assert_eq!(part2(get_example()), Ok(30));
+}
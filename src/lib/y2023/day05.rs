@@ -0,0 +1,825 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str;
+
+use regex::Regex;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::interval::{Interval, IntervalSet};
+use crate::parse::{BadInput, ParseError};
+use crate::solver::{Backend, Notes};
+use crate::warn;
+
+pub const NOTES: Notes = Notes {
+    title: "If You Give A Seed A Fertilizer",
+    algorithm: "The maps are chained together from seed to location.  Part 1 follows each seed through the chain; part 2 pushes whole sets of intervals through it, splitting them where the map ranges begin and end.",
+    complexity: "O(s·m) for s seeds (or seed ranges) and m map ranges",
+};
+
+pub type Id = u32;
+
+#[derive(Debug)]
+struct MappingRange {
+    dest_start: Id,
+    source_start: Id,
+    len: Id,
+}
+
+impl MappingRange {
+    fn get(&self, id: Id) -> Option<Id> {
+        if id < self.source_start {
+            None
+        } else {
+            let offset = id - self.source_start;
+            if offset >= self.len {
+                None
+            } else {
+                Some(self.dest_start + offset)
+            }
+        }
+    }
+}
+
+impl MappingRange {
+    fn source(&self) -> Interval {
+        Interval::with_len(i64::from(self.source_start), i64::from(self.len))
+    }
+
+    fn offset(&self) -> i64 {
+        i64::from(self.dest_start) - i64::from(self.source_start)
+    }
+}
+
+#[test]
+fn test_mapping_range_lookup() {
+    let example = MappingRange {
+        dest_start: 50,
+        source_start: 98,
+        len: 2,
+    };
+    assert_eq!(example.get(97), None);
+    assert_eq!(example.get(98), Some(50));
+    assert_eq!(example.get(99), Some(51));
+    assert_eq!(example.get(100), None);
+}
+
+fn parse_id(s: &str) -> Result<Id, BadInput<'_>> {
+    s.parse()
+        .map_err(|e| BadInput::new(s, format!("not a valid number: {e}")))
+}
+
+impl<'a> TryFrom<&'a str> for MappingRange {
+    type Error = BadInput<'a>;
+
+    fn try_from(s: &'a str) -> Result<MappingRange, Self::Error> {
+        let fields: Vec<Id> = s
+            .split_whitespace()
+            .map(parse_id)
+            .collect::<Result<Vec<Id>, Self::Error>>()?;
+        match fields.as_slice() {
+            [dest_start, source_start, len] => Ok(MappingRange {
+                dest_start: *dest_start,
+                source_start: *source_start,
+                len: *len,
+            }),
+            _ => Err(BadInput::new(
+                s,
+                format!("expected 3 fields, got {}", fields.len()),
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Mapping {
+    entries: Vec<MappingRange>,
+}
+
+impl Mapping {
+    fn get(&self, id: Id) -> Id {
+        for maprange in self.entries.iter() {
+            if let Some(result) = maprange.get(id) {
+                return result;
+            }
+        }
+        id
+    }
+}
+
+impl Mapping {
+    /// Maps every ID in `ids`.
+    fn get_set(&self, ids: &IntervalSet) -> IntervalSet {
+        let mut unmapped = ids.clone();
+        let mut result = IntervalSet::new();
+        for maprange in self.entries.iter() {
+            let source = IntervalSet::from(maprange.source());
+            result = result.union(&unmapped.intersection(&source).shift(maprange.offset()));
+            unmapped = unmapped.subtract(&source);
+        }
+        // IDs not covered by any range map to themselves.
+        result.union(&unmapped)
+    }
+}
+
+/// Something questionable about the source ranges of a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeIssue {
+    /// The source ranges `first` and `second` (in the order in which
+    /// they appear in the map) share the IDs in `overlap`.  Only
+    /// `first` is used to map those IDs.
+    Overlap {
+        first: Interval,
+        second: Interval,
+        overlap: Interval,
+    },
+    /// The IDs in this interval lie between the map's source ranges
+    /// but are in none of them, so they map to themselves.
+    Gap(Interval),
+}
+
+impl Mapping {
+    /// Looks for source ranges which overlap, and gaps between them.
+    fn issues(&self) -> Vec<RangeIssue> {
+        let mut issues = Vec::new();
+        for (i, first) in self.entries.iter().enumerate() {
+            for second in self.entries[i + 1..].iter() {
+                if let Some(overlap) = first.source().intersection(&second.source()) {
+                    issues.push(RangeIssue::Overlap {
+                        first: first.source(),
+                        second: second.source(),
+                        overlap,
+                    });
+                }
+            }
+        }
+        let mut sources: Vec<Interval> = self
+            .entries
+            .iter()
+            .map(MappingRange::source)
+            .filter(|source| !source.is_empty())
+            .collect();
+        sources.sort();
+        if let Some((first, rest)) = sources.split_first() {
+            let mut covered_to = first.hi;
+            for source in rest {
+                if source.lo > covered_to {
+                    issues.push(RangeIssue::Gap(Interval::new(covered_to, source.lo)));
+                }
+                covered_to = covered_to.max(source.hi);
+            }
+        }
+        issues
+    }
+}
+
+#[test]
+fn test_mapping_issues() {
+    let issues = |s| Mapping::try_from(s).expect("test input is valid").issues();
+    assert_eq!(issues(concat!("50 98 2\n", "52 50 48\n")), vec![]);
+    assert_eq!(
+        issues(concat!("0 10 5\n", "100 20 5\n", "200 12 2\n")),
+        vec![
+            RangeIssue::Overlap {
+                first: Interval::new(10, 15),
+                second: Interval::new(12, 14),
+                overlap: Interval::new(12, 14),
+            },
+            RangeIssue::Gap(Interval::new(15, 20)),
+        ]
+    );
+    // Empty ranges neither overlap nor fill gaps.
+    assert_eq!(
+        issues(concat!("0 0 5\n", "0 2 0\n", "0 7 0\n", "0 10 5\n")),
+        vec![RangeIssue::Gap(Interval::new(5, 10))]
+    );
+}
+
+impl<'a> TryFrom<&'a str> for Mapping {
+    type Error = BadInput<'a>;
+
+    fn try_from(s: &'a str) -> Result<Mapping, Self::Error> {
+        let entries: Vec<MappingRange> = s
+            .split_terminator('\n')
+            .map(MappingRange::try_from)
+            .collect::<Result<Vec<MappingRange>, Self::Error>>()?;
+        Ok(Mapping { entries })
+    }
+}
+
+#[test]
+fn test_mapping_lookup() {
+    let mapping =
+        Mapping::try_from(concat!("50 98 2\n", "52 50 48\n")).expect("example should be valid");
+    assert_eq!(mapping.get(0), 0);
+    assert_eq!(mapping.get(1), 1);
+    assert_eq!(mapping.get(48), 48);
+    assert_eq!(mapping.get(49), 49);
+    assert_eq!(mapping.get(50), 52);
+    assert_eq!(mapping.get(51), 53);
+    assert_eq!(mapping.get(96), 98);
+    assert_eq!(mapping.get(97), 99);
+    assert_eq!(mapping.get(98), 50);
+    assert_eq!(mapping.get(99), 51);
+}
+
+#[test]
+fn test_mapping_set_lookup() {
+    let mapping =
+        Mapping::try_from(concat!("50 98 2\n", "52 50 48\n")).expect("example should be valid");
+    let ids: IntervalSet = [Interval::new(45, 55), Interval::new(97, 101)]
+        .into_iter()
+        .collect();
+    let expected: IntervalSet = [
+        Interval::new(45, 50),   // unmapped
+        Interval::new(52, 57),   // 50..55
+        Interval::new(99, 100),  // 97
+        Interval::new(50, 52),   // 98..100
+        Interval::new(100, 101), // 100, unmapped
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(mapping.get_set(&ids), expected);
+}
+
+/// The category of the things we start with, and the one we want to
+/// end up with.
+const FIRST_CATEGORY: &str = "seed";
+const LAST_CATEGORY: &str = "location";
+
+#[derive(Debug)]
+pub struct Almanac {
+    seeds: Vec<Id>,
+    /// The categories from seed to location, in order.
+    categories: Vec<String>,
+    /// The mappings between successive categories.
+    chain: Vec<Mapping>,
+}
+
+/// Splits a map name such as "seed-to-soil" into its source and
+/// destination categories.
+fn parse_map_name(name: &str) -> Result<(&str, &str), BadInput<'_>> {
+    match name.split_once("-to-") {
+        Some((from, to)) if !from.is_empty() && !to.is_empty() => Ok((from, to)),
+        _ => Err(BadInput::new(
+            name,
+            "a map name should have the form X-to-Y",
+        )),
+    }
+}
+
+/// Puts the mappings in the order in which they are applied to get
+/// from a seed to a location.  Mappings which aren't on that path are
+/// ignored.
+fn build_chain(
+    mut mappings: HashMap<String, (String, Mapping)>,
+) -> Result<(Vec<String>, Vec<Mapping>), Fail> {
+    let mut categories = vec![FIRST_CATEGORY.to_string()];
+    let mut chain = Vec::new();
+    let mut current = FIRST_CATEGORY.to_string();
+    while current != LAST_CATEGORY {
+        let (next, mapping) = mappings.remove(&current).ok_or_else(|| {
+            fail!(
+                "the almanac has no map from {current}, so there is no way to get from {FIRST_CATEGORY} to {LAST_CATEGORY}"
+            )
+        })?;
+        if categories.contains(&next) {
+            return Err(fail!(
+                "the map from {current} to {next} leads back to an earlier category"
+            ));
+        }
+        categories.push(next.clone());
+        chain.push(mapping);
+        current = next;
+    }
+    Ok((categories, chain))
+}
+
+impl TryFrom<&str> for Almanac {
+    type Error = Fail;
+
+    fn try_from(s: &str) -> Result<Almanac, Self::Error> {
+        let map_re = Regex::new("^(.*) map:\n(?s)(.*)$")?;
+        let seeds_re = Regex::new("^seeds: (.*)$")?;
+        let chunks = s.split("\n\n");
+        let mut seeds: Vec<Id> = Vec::new();
+        // Maps each source category to its destination category and
+        // the mapping between them.
+        let mut mappings: HashMap<String, (String, Mapping)> = HashMap::new();
+        for chunk in chunks {
+            match seeds_re.captures(chunk) {
+                Some(caps) => {
+                    seeds = caps
+                        .get(1)
+                        .unwrap()
+                        .as_str()
+                        .split_whitespace()
+                        .map(parse_id)
+                        .collect::<Result<Vec<Id>, _>>()
+                        .map_err(|e| e.locate(s))?;
+                }
+                None => match map_re.captures(chunk) {
+                    Some(caps) => {
+                        let name = caps.get(1).unwrap().as_str();
+                        let (from, to) = parse_map_name(name).map_err(|e| e.locate(s))?;
+                        let mapping = Mapping::try_from(caps.get(2).unwrap().as_str())
+                            .map_err(|e| e.locate(s))?;
+                        if mappings
+                            .insert(from.to_string(), (to.to_string(), mapping))
+                            .is_some()
+                        {
+                            return Err(ParseError::at(
+                                s,
+                                name,
+                                format!("there is more than one map from {from}"),
+                            )
+                            .into());
+                        }
+                    }
+                    None => {
+                        return Err(
+                            ParseError::at(s, chunk, "expected a list of seeds or a map").into(),
+                        );
+                    }
+                },
+            }
+        }
+        let (categories, chain) = build_chain(mappings)?;
+        Ok(Almanac {
+            seeds,
+            categories,
+            chain,
+        })
+    }
+}
+
+/// A `RangeIssue` in the map from one category to the next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapDiagnostic {
+    pub from: String,
+    pub to: String,
+    pub issue: RangeIssue,
+}
+
+impl MapDiagnostic {
+    /// Returns true if the issue makes the map ambiguous.  A gap is
+    /// not an error, because the puzzle says what happens to IDs in
+    /// it.
+    pub fn is_error(&self) -> bool {
+        matches!(self.issue, RangeIssue::Overlap { .. })
+    }
+}
+
+impl Display for MapDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-to-{} map: ", self.from, self.to)?;
+        match &self.issue {
+            RangeIssue::Overlap {
+                first,
+                second,
+                overlap,
+            } => write!(f, "source ranges {first} and {second} overlap in {overlap}"),
+            RangeIssue::Gap(gap) => write!(f, "no source range covers {gap}"),
+        }
+    }
+}
+
+fn follow_chain(chain: &[Mapping], seed: Id) -> Id {
+    chain.iter().fold(seed, |id, mapping| mapping.get(id))
+}
+
+impl Almanac {
+    /// Returns the categories that a seed passes through on its way to
+    /// becoming a location, starting with "seed" and ending with
+    /// "location".
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    /// Checks the source ranges of each map from seed to location,
+    /// returning anything questionable about them.
+    pub fn diagnostics(&self) -> Vec<MapDiagnostic> {
+        self.categories
+            .windows(2)
+            .zip(self.chain.iter())
+            .flat_map(|(names, mapping)| {
+                mapping.issues().into_iter().map(|issue| MapDiagnostic {
+                    from: names[0].clone(),
+                    to: names[1].clone(),
+                    issue,
+                })
+            })
+            .collect()
+    }
+
+    /// Parses an almanac like `try_from`, but rejects one which has a
+    /// map whose source ranges overlap.  Gaps between source ranges
+    /// are only logged.
+    pub fn parse_strict(s: &str) -> Result<Almanac, Fail> {
+        let almanac = Almanac::try_from(s)?;
+        let (errors, warnings): (Vec<MapDiagnostic>, Vec<MapDiagnostic>) = almanac
+            .diagnostics()
+            .into_iter()
+            .partition(MapDiagnostic::is_error);
+        for warning in warnings {
+            warn!("{warning}");
+        }
+        if errors.is_empty() {
+            Ok(almanac)
+        } else {
+            Err(fail!(
+                "{}",
+                errors
+                    .iter()
+                    .map(MapDiagnostic::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ))
+        }
+    }
+
+    /// Returns the location for each seed in turn.  The locations are
+    /// worked out as they are needed, so this takes no more memory
+    /// for a long list of seeds than for a short one.
+    pub fn location_iter(&self) -> impl Iterator<Item = Id> + '_ {
+        self.seeds
+            .iter()
+            .map(|seed| follow_chain(&self.chain, *seed))
+    }
+
+    pub fn get_lowest_location(&self) -> Option<Id> {
+        self.location_iter().min()
+    }
+
+    /// Like `get_lowest_location`, but examines the seeds in
+    /// parallel.
+    #[cfg(feature = "parallel")]
+    pub fn par_get_lowest_location(&self) -> Option<Id> {
+        use rayon::prelude::*;
+        self.seeds
+            .par_iter()
+            .map(|seed| follow_chain(&self.chain, *seed))
+            .min()
+    }
+
+    /// In part 2, the seeds line is a list of (start, length) pairs.
+    fn seed_ranges(&self) -> IntervalSet {
+        self.seeds
+            .chunks(2)
+            .filter_map(|pair| match pair {
+                [start, len] => Some(Interval::with_len(i64::from(*start), i64::from(*len))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn get_lowest_location_for_ranges(&self) -> Result<Option<Id>, Fail> {
+        self.lowest_location_in(self.seed_ranges())
+    }
+
+    /// Like `get_lowest_location`, but pushes the seeds through the
+    /// maps as a set of intervals (each seed being an interval of
+    /// length one), as we do in part 2.
+    pub fn get_lowest_location_via_intervals(&self) -> Result<Option<Id>, Fail> {
+        self.lowest_location_in(
+            self.seeds
+                .iter()
+                .map(|seed| Interval::with_len(i64::from(*seed), 1))
+                .collect(),
+        )
+    }
+
+    /// Like `get_lowest_location_for_ranges`, but follows each seed
+    /// in each range through the maps separately.  A real puzzle input
+    /// has billions of seeds, so this is very slow; it is here to check
+    /// the answer of the faster method.
+    pub fn get_lowest_location_for_ranges_by_seed(&self) -> Result<Option<Id>, Fail> {
+        let mut lowest: Option<Id> = None;
+        for range in self.seed_ranges().iter() {
+            for n in range.lo..range.hi {
+                let seed = Id::try_from(n).map_err(|_| fail!("seed {n} is out of range"))?;
+                let location = follow_chain(&self.chain, seed);
+                lowest = Some(lowest.map_or(location, |l| l.min(location)));
+            }
+        }
+        Ok(lowest)
+    }
+
+    /// Returns the lowest location of any of the seeds in `seeds`.
+    fn lowest_location_in(&self, seeds: IntervalSet) -> Result<Option<Id>, Fail> {
+        let locations = self
+            .chain
+            .iter()
+            .fold(seeds, |ids, mapping| mapping.get_set(&ids));
+        locations
+            .min()
+            .map(|loc| Id::try_from(loc).map_err(|_| fail!("location {loc} is out of range")))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(5).expect("there should be an example")
+}
+
+#[test]
+fn test_parse_example() {
+    let almanac = Almanac::try_from(get_example()).expect("example should be valid");
+    assert_eq!(almanac.seeds.len(), 4);
+    assert_eq!(
+        almanac.categories(),
+        [
+            "seed",
+            "soil",
+            "fertilizer",
+            "water",
+            "light",
+            "temperature",
+            "humidity",
+            "location"
+        ]
+    );
+    assert_eq!(almanac.chain.len(), 7);
+}
+
+#[test]
+fn test_parse_error_location() {
+    let e = Almanac::try_from("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 x\n")
+        .expect_err("input is not valid");
+    assert_eq!(
+        e.to_string(),
+        "line 5, column 7: not a valid number: invalid digit found in string\n  52 50 x\n        ^"
+    );
+    let e = Almanac::try_from("seeds: 79 14\n\nseed-to-soil map:\n50 98\n")
+        .expect_err("input is not valid");
+    assert!(e
+        .to_string()
+        .starts_with("line 4, column 1: expected 3 fields, got 2\n"));
+    let e = Almanac::try_from("seeds: 79 14\n\nnonsense\n").expect_err("input is not valid");
+    assert!(e
+        .to_string()
+        .starts_with("line 3, column 1: expected a list of seeds or a map\n"));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_parse_arbitrary_bytes_does_not_panic(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..200)) {
+        let _ = Almanac::try_from(String::from_utf8_lossy(&bytes).as_ref());
+    }
+
+    #[test]
+    fn test_parse_almost_valid_does_not_panic(s in "seeds:( [0-9]{1,11}){0,4}\n\n([a-z]{1,5}-to-[a-z]{1,5} map:\n([0-9]{1,11} ){0,3}[0-9]{0,11}\n?){0,3}") {
+        let _ = Almanac::try_from(s.as_str());
+    }
+}
+
+#[test]
+fn test_example_mappings() {
+    let almanac = Almanac::try_from(get_example()).expect("example should be valid");
+    assert_eq!(follow_chain(&almanac.chain, 79), 82);
+    assert_eq!(follow_chain(&almanac.chain, 14), 43);
+    assert_eq!(follow_chain(&almanac.chain, 55), 86);
+    assert_eq!(follow_chain(&almanac.chain, 13), 35);
+}
+
+#[test]
+fn test_location_iter() {
+    let almanac = Almanac::try_from(get_example()).expect("example should be valid");
+    assert_eq!(
+        almanac.location_iter().collect::<Vec<Id>>(),
+        vec![82, 43, 86, 35]
+    );
+}
+
+#[test]
+fn test_get_lowest_location() {
+    let almanac = Almanac::try_from(get_example()).expect("example should be valid");
+    assert_eq!(almanac.get_lowest_location(), Some(35));
+    assert_eq!(almanac.get_lowest_location_via_intervals(), Ok(Some(35)));
+    #[cfg(feature = "parallel")]
+    assert_eq!(almanac.par_get_lowest_location(), Some(35));
+}
+
+#[test]
+fn test_get_lowest_location_for_ranges() {
+    let almanac = Almanac::try_from(get_example()).expect("example should be valid");
+    assert_eq!(almanac.get_lowest_location_for_ranges(), Ok(Some(46)));
+    assert_eq!(
+        almanac.get_lowest_location_for_ranges_by_seed(),
+        Ok(Some(46))
+    );
+}
+
+#[test]
+fn test_missing_mapping() {
+    assert_eq!(
+        Almanac::try_from(concat!(
+            "seeds: 79 14\n",
+            "\n",
+            "seed-to-soil map:\n",
+            "50 98 2\n",
+        ))
+        .map(|_| ()),
+        Err(Fail(
+            "the almanac has no map from soil, so there is no way to get from seed to location"
+                .to_string()
+        ))
+    );
+    assert!(part2("seeds: 1 2\n").is_err());
+}
+
+#[test]
+fn test_nonstandard_chain() {
+    // The maps can be in any order, and can use any categories, so
+    // long as they lead from seed to location.  Maps which aren't on
+    // the way are ignored.
+    let almanac = Almanac::try_from(concat!(
+        "seeds: 1 5 10\n",
+        "\n",
+        "plot-to-location map:\n",
+        "100 0 20\n",
+        "\n",
+        "water-to-light map:\n",
+        "0 0 1\n",
+        "\n",
+        "seed-to-plot map:\n",
+        "0 5 5\n",
+    ))
+    .expect("almanac should be valid");
+    assert_eq!(almanac.categories(), ["seed", "plot", "location"]);
+    assert_eq!(
+        almanac.location_iter().collect::<Vec<Id>>(),
+        vec![101, 100, 110]
+    );
+}
+
+#[test]
+fn test_bad_chain() {
+    let looping = concat!(
+        "seeds: 1\n",
+        "\n",
+        "seed-to-soil map:\n",
+        "0 0 1\n",
+        "\n",
+        "soil-to-seed map:\n",
+        "0 0 1\n",
+    );
+    assert_eq!(
+        Almanac::try_from(looping).map(|_| ()),
+        Err(Fail(
+            "the map from soil to seed leads back to an earlier category".to_string()
+        ))
+    );
+    let duplicate = concat!(
+        "seeds: 1\n",
+        "\n",
+        "seed-to-soil map:\n",
+        "0 0 1\n",
+        "\n",
+        "seed-to-location map:\n",
+        "0 0 1\n",
+    );
+    assert!(Almanac::try_from(duplicate).is_err());
+    let badly_named = "seeds: 1\n\nseed-location map:\n0 0 1\n";
+    match Almanac::try_from(badly_named) {
+        Err(Fail(msg)) => assert!(msg.contains("X-to-Y"), "unexpected message {msg}"),
+        Ok(_) => panic!("badly named map should be rejected"),
+    }
+}
+
+#[test]
+fn test_diagnostics() {
+    let almanac = Almanac::try_from(get_example()).expect("example should be valid");
+    assert_eq!(almanac.diagnostics(), vec![]);
+    let input = concat!(
+        "seeds: 79 14\n",
+        "\n",
+        "seed-to-soil map:\n",
+        "50 98 2\n",
+        "0 99 10\n",
+        "\n",
+        "soil-to-location map:\n",
+        "0 0 5\n",
+        "10 10 5\n",
+    );
+    let almanac = Almanac::try_from(input).expect("test input is valid");
+    let diagnostics: Vec<String> = almanac
+        .diagnostics()
+        .iter()
+        .map(MapDiagnostic::to_string)
+        .collect();
+    assert_eq!(
+        diagnostics,
+        vec![
+            "seed-to-soil map: source ranges [98,100) and [99,109) overlap in [99,100)",
+            "soil-to-location map: no source range covers [5,10)",
+        ]
+    );
+    assert_eq!(
+        Almanac::parse_strict(input).map(|_| ()),
+        Err(Fail(diagnostics[0].clone()))
+    );
+    assert_eq!(part1_with(input, true), Err(Fail(diagnostics[0].clone())));
+    assert_eq!(part1_with(input, false), part1(input));
+    // A gap alone is not an error.
+    assert!(Almanac::parse_strict(&input.replace("0 99 10", "0 100 10")).is_ok());
+    assert!(Almanac::parse_strict(get_example()).is_ok());
+}
+
+/// Parses the almanac, checking its maps if `strict` is true (see
+/// `Almanac::parse_strict`).
+fn parse(input: &str, strict: bool) -> Result<Almanac, Fail> {
+    if strict {
+        Almanac::parse_strict(input)
+    } else {
+        Almanac::try_from(input)
+    }
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day05::part1;
+/// let example = lib::examples::examples(5)[0];
+/// assert_eq!(part1(example), Ok(35));
+/// ```
+pub fn part1(input: &str) -> Result<Id, Fail> {
+    part1_with(input, false)
+}
+
+/// Computes the answer to part 1, rejecting an ambiguous almanac if
+/// `strict` is true.
+pub fn part1_with(input: &str, strict: bool) -> Result<Id, Fail> {
+    let almanac = parse(input, strict)?;
+    #[cfg(feature = "parallel")]
+    let lowest = almanac.par_get_lowest_location();
+    #[cfg(not(feature = "parallel"))]
+    let lowest = almanac.get_lowest_location();
+    lowest.ok_or_else(no_seeds)
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day05::part2;
+/// let example = lib::examples::examples(5)[0];
+/// assert_eq!(part2(example), Ok(46));
+/// ```
+pub fn part2(input: &str) -> Result<Id, Fail> {
+    part2_with(input, false)
+}
+
+/// Computes the answer to part 2, rejecting an ambiguous almanac if
+/// `strict` is true.
+pub fn part2_with(input: &str, strict: bool) -> Result<Id, Fail> {
+    parse(input, strict)?
+        .get_lowest_location_for_ranges()?
+        .ok_or_else(no_seeds)
+}
+
+fn no_seeds() -> Fail {
+    Fail("almanac has no seeds".to_string())
+}
+
+pub const BACKENDS: [Backend; 4] = [
+    Backend {
+        part: 1,
+        name: "seeds",
+        description: "Follow each seed through the maps",
+        slow: false,
+        solve: |input| Ok(part1(input)?.into()),
+    },
+    Backend {
+        part: 1,
+        name: "intervals",
+        description: "Push the seeds through the maps as a set of intervals",
+        slow: false,
+        solve: |input| {
+            Ok(parse(input, false)?
+                .get_lowest_location_via_intervals()?
+                .ok_or_else(no_seeds)?
+                .into())
+        },
+    },
+    Backend {
+        part: 2,
+        name: "intervals",
+        description: "Push the seed ranges through the maps as a set of intervals",
+        slow: false,
+        solve: |input| Ok(part2(input)?.into()),
+    },
+    Backend {
+        part: 2,
+        name: "seeds",
+        description: "Follow every seed in every range through the maps",
+        slow: true,
+        solve: |input| {
+            Ok(parse(input, false)?
+                .get_lowest_location_for_ranges_by_seed()?
+                .ok_or_else(no_seeds)?
+                .into())
+        },
+    },
+];
@@ -0,0 +1,256 @@
+use std::str;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::parse::ParseError;
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Wait For It",
+    algorithm: "The distance travelled is a quadratic in the charge time, so the winning charge times are found by solving a quadratic equation and then adjusting the ends of the region to integers.",
+    complexity: "O(r) for r races",
+};
+
+/// The two parts of the puzzle read the numbers differently: part 1
+/// reads a list of races, and part 2 ignores the spaces and reads a
+/// single race.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+fn win(charge_time: i64, race_time: i64, record: i64) -> bool {
+    // The charge_time is also equal to the speed we get.
+    // The vehicle moves for (race_time - charge_time).
+    //
+    // Hence the distance moved is charge_time * (race_time - charge_time).
+    //
+    // We win if this is greater than `record`.  So the win condition is
+    //
+    // charge_time * (race_time - charge_time) > record
+    //
+    // We widen to avoid overflow.
+    i128::from(charge_time) * i128::from(race_time - charge_time) > i128::from(record)
+}
+
+fn charge_time_for_max_distance(race_time: i64) -> f64 {
+    // The maximum distance we can travel in the race time is simply
+    // the maximum of the distance-travelled function, and so we
+    // differentiate it to find the maximum.  The maximum is at T/2.
+    (race_time as f64) / 2.0
+}
+
+fn approx_win_region_width(race_time: i64, record: i64) -> f64 {
+    let r = record as f64;
+    let t = race_time as f64;
+    // We use (and then simplify) the quadratic formula togive us the
+    // distance between the roots of the quadratic equation
+    // determining the win condition.
+    (t * t - 4.0 * r).sqrt()
+}
+
+/// Returns the lowest and highest winning charge times, or `None` if
+/// there is no way to beat the record.
+fn exact_win_region(race_time: i64, record: i64) -> Option<(i64, i64)> {
+    // We use approx_win_region_width to determine the width of the win region,
+    // rounding down on the left and up on the right.  If the
+    // quadratic has no real roots, the width is NaN and the casts
+    // below saturate to zero, which is still a valid place to start
+    // searching.
+    let halfwidth = approx_win_region_width(race_time, record) / 2.0;
+    let lower = ((charge_time_for_max_distance(race_time) - halfwidth).floor()) as i64;
+    let upper = (charge_time_for_max_distance(race_time) + halfwidth).ceil() as i64;
+    let is_win = |x: &i64| win(*x, race_time, record);
+
+    // The values for `lower` and `upper` are approximations, so we
+    // check the nearby points to find the lowest and the highest
+    // winning charge time.  The best charge time is half the race
+    // time, so if that doesn't win, nothing does.  A race with a
+    // negative time can't be won at all.
+    if race_time < 0 {
+        return None;
+    }
+    let best = race_time / 2;
+    let lower = (lower.clamp(0, best)..=best).find(is_win)?;
+    let upper = (best..=upper.clamp(best, race_time)).rev().find(is_win)?;
+    Some((lower, upper))
+}
+
+#[test]
+fn test_exact_win_region() {
+    assert_eq!(exact_win_region(7, 9), Some((2, 5)));
+    assert_eq!(exact_win_region(15, 40), Some((4, 11)));
+    assert_eq!(exact_win_region(30, 200), Some((11, 19)));
+    assert_eq!(exact_win_region(7, 12), None);
+    assert_eq!(exact_win_region(0, 0), None);
+    assert_eq!(exact_win_region(-7, -20), None);
+}
+
+/// Returns the number of whole-millisecond charge times which beat
+/// `record` in a race lasting `race_time` milliseconds.
+pub fn count_ways_to_win(race_time: i64, record: i64) -> i64 {
+    match exact_win_region(race_time, record) {
+        Some((lower, upper)) => 1 + upper - lower,
+        None => 0,
+    }
+}
+
+#[test]
+fn test_count_ways_to_win() {
+    assert_eq!(count_ways_to_win(7, 9), 4);
+    assert_eq!(count_ways_to_win(15, 40), 8);
+    assert_eq!(count_ways_to_win(30, 200), 9);
+    assert_eq!(count_ways_to_win(7, 12), 0);
+    assert_eq!(count_ways_to_win(-1, 0), 0);
+    assert_eq!(count_ways_to_win(4, -1), 5);
+}
+
+fn parse_number(s: &str) -> Result<i64, Fail> {
+    s.parse()
+        .map_err(|e| fail!("{s} should be a valid number: {e}"))
+}
+
+fn parse_numbers_part1(s: &str) -> Result<Vec<i64>, Fail> {
+    s.split_whitespace().map(parse_number).collect()
+}
+
+fn parse_numbers_part2(s: &str) -> Result<Vec<i64>, Fail> {
+    let s: String = s.chars().filter(|ch| !ch.is_ascii_whitespace()).collect();
+    Ok(vec![parse_number(&s)?])
+}
+
+/// Parses the numbers which follow the "Time:" or "Distance:" label
+/// on a line of the input.
+pub fn parse_numbers(s: &str, part: &Part) -> Result<Vec<i64>, Fail> {
+    match part {
+        Part::One => parse_numbers_part1(s),
+        Part::Two => parse_numbers_part2(s),
+    }
+}
+
+/// Parses the puzzle input into (race time, record distance) pairs.
+/// The input should consist of a "Time:" line and a "Distance:"
+/// line; blank lines after those are ignored, but anything else is an
+/// error.
+pub fn parse_input(s: &str, part: &Part) -> Result<Vec<(i64, i64)>, Fail> {
+    let mut lines = s.lines();
+    let (Some(time_line), Some(distance_line)) = (lines.next(), lines.next()) else {
+        return Err(Fail(
+            "expected 2 lines (the times and the distances)".to_string(),
+        ));
+    };
+    if let Some(extra) = lines.find(|line| !line.trim().is_empty()) {
+        return Err(ParseError::at(
+            s,
+            extra,
+            "unexpected extra line; the input should have only a Time: line and a Distance: line",
+        )
+        .into());
+    }
+    let times_str = time_line
+        .strip_prefix("Time:")
+        .ok_or_else(|| ParseError::at(s, time_line, "expected Time: prefix"))?;
+    let dist_str = distance_line
+        .strip_prefix("Distance:")
+        .ok_or_else(|| ParseError::at(s, distance_line, "expected Distance: prefix"))?;
+    let times = parse_numbers(times_str, part)?;
+    let distances = parse_numbers(dist_str, part)?;
+    if times.len() != distances.len() {
+        return Err(fail!(
+            "there are {} times but {} distances",
+            times.len(),
+            distances.len()
+        ));
+    }
+    Ok(times.into_iter().zip(distances).collect())
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(6).expect("there should be an example")
+}
+
+#[test]
+fn test_parse_input() {
+    assert_eq!(
+        parse_input(get_example(), &Part::One),
+        Ok(vec![(7, 9), (15, 40), (30, 200)])
+    );
+    assert_eq!(
+        parse_input(get_example(), &Part::Two),
+        Ok(vec![(71530, 940200)])
+    );
+}
+
+#[test]
+fn test_parse_invalid_input() {
+    assert!(parse_input("Time: 7\n", &Part::One).is_err());
+    assert!(parse_input("Time: 7 x\nDistance: 9 40\n", &Part::One).is_err());
+    assert!(parse_input("Time: 7 15\nDistance: 9\n", &Part::One).is_err());
+    assert!(parse_input("Race: 7\nDistance: 9\n", &Part::Two).is_err());
+    assert!(parse_input("", &Part::One).is_err());
+}
+
+#[test]
+fn test_parse_extra_lines() {
+    assert_eq!(
+        parse_input("Time: 7 15 30 1\nDistance: 9 40 200 0\n\n\n", &Part::One),
+        Ok(vec![(7, 9), (15, 40), (30, 200), (1, 0)])
+    );
+    assert_eq!(
+        parse_input("Time: 7\r\nDistance: 9\r\n", &Part::Two),
+        Ok(vec![(7, 9)])
+    );
+    assert_eq!(
+        parse_input("Time: 7\nDistance: 9\n\nTime: 8\n", &Part::One),
+        Err(Fail(
+            concat!(
+                "line 4, column 1: unexpected extra line; ",
+                "the input should have only a Time: line and a Distance: line\n",
+                "  Time: 8\n",
+                "  ^"
+            )
+            .to_string()
+        ))
+    );
+}
+
+pub fn solve(input: &[(i64, i64)]) -> i64 {
+    input
+        .iter()
+        .map(|(time, record)| count_ways_to_win(*time, *record))
+        .product()
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day06::part1;
+/// let example = lib::examples::examples(6)[0];
+/// assert_eq!(part1(example), Ok(288));
+/// ```
+pub fn part1(input: &str) -> Result<i64, Fail> {
+    Ok(solve(&parse_input(input, &Part::One)?))
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(get_example()), Ok(288));
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day06::part2;
+/// let example = lib::examples::examples(6)[0];
+/// assert_eq!(part2(example), Ok(71503));
+/// ```
+pub fn part2(input: &str) -> Result<i64, Fail> {
+    Ok(solve(&parse_input(input, &Part::Two)?))
+}
+
+#[test]
+fn test_part2() {
+    assert_eq!(part2(get_example()), Ok(71503));
+}
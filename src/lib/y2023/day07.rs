@@ -0,0 +1,608 @@
+use std::fmt::Debug;
+use std::str;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::iterplus::counts_by_index;
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Camel Cards",
+    algorithm: "Each hand is classified by counting its cards (treating jokers as the most common other card in part 2), and the hands are sorted by type and then by card.",
+    complexity: "O(h log h) for h hands",
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Label {
+    Number(u8),
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl TryFrom<char> for Label {
+    type Error = Fail;
+
+    fn try_from(ch: char) -> Result<Label, Self::Error> {
+        match ch {
+            '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => Ok(Label::Number(
+                ch.to_digit(10).expect("valid decimal digit") as u8,
+            )),
+            'T' => Ok(Label::Number(10_u8)),
+            'J' => Ok(Label::Jack),
+            'Q' => Ok(Label::Queen),
+            'K' => Ok(Label::King),
+            'A' => Ok(Label::Ace),
+            other => Err(fail!("card {other} is not valid")),
+        }
+    }
+}
+
+#[test]
+fn test_label_parsing() {
+    assert_eq!(Label::try_from('2'), Ok(Label::Number(2)));
+    assert_eq!(Label::try_from('3'), Ok(Label::Number(3)));
+    assert_eq!(Label::try_from('4'), Ok(Label::Number(4)));
+    assert_eq!(Label::try_from('5'), Ok(Label::Number(5)));
+    assert_eq!(Label::try_from('6'), Ok(Label::Number(6)));
+    assert_eq!(Label::try_from('7'), Ok(Label::Number(7)));
+    assert_eq!(Label::try_from('8'), Ok(Label::Number(8)));
+    assert_eq!(Label::try_from('9'), Ok(Label::Number(9)));
+    assert_eq!(Label::try_from('T'), Ok(Label::Number(10)));
+
+    assert_eq!(Label::try_from('J'), Ok(Label::Jack));
+    assert_eq!(Label::try_from('Q'), Ok(Label::Queen));
+    assert_eq!(Label::try_from('K'), Ok(Label::King));
+    assert_eq!(Label::try_from('A'), Ok(Label::Ace));
+
+    assert!(Label::try_from('0').is_err());
+    assert!(Label::try_from('1').is_err());
+    assert!(Label::try_from('Z').is_err());
+}
+
+/// Something which can be used as a card.  The puzzle's cards are
+/// `Label`s, but any type which can be compared and printed will do.
+pub trait Card: Eq + Debug {}
+
+impl<T: Eq + Debug> Card for T {}
+
+/// A hand of `N` cards.  The puzzle itself uses hands of five
+/// `Label`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hand<C = Label, const N: usize = 5> {
+    pub cards: [C; N],
+}
+
+/// The most kinds of card a deck may have.  We count the cards of
+/// each kind in an array this size, which is enough for the puzzle's
+/// deck.
+pub const MAX_DECK_SIZE: usize = 13;
+
+/// Returns the sizes of the groups of identical cards in a hand whose
+/// cards have the given ranks, largest first and padded with zeros.
+/// Cards of rank `joker` join the largest group, since that always
+/// makes the strongest hand.
+///
+/// Rather than hashing the cards, we count them in a fixed array
+/// indexed by rank, and sort the counts.  That takes O(N) time for a
+/// hand of N cards (the sort is of `MAX_DECK_SIZE` counts), and no
+/// memory is allocated.  The price is that larger decks are rejected.
+fn shape_of_ranks<const N: usize>(
+    ranks: &[usize; N],
+    joker: Option<usize>,
+) -> Result<[usize; N], Fail> {
+    let is_joker = |rank: &usize| joker == Some(*rank);
+    let jokers = ranks.iter().filter(|rank| is_joker(rank)).count();
+    let mut counts: [u8; MAX_DECK_SIZE] =
+        counts_by_index(ranks.iter().copied().filter(|rank| !is_joker(rank))).ok_or_else(|| {
+            fail!(
+                "a deck may have at most {MAX_DECK_SIZE} kinds of card, and a hand at most {} cards of each kind",
+                u8::MAX
+            )
+        })?;
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    let mut shape = [0; N];
+    for (size, count) in shape.iter_mut().zip(counts.iter()) {
+        *size = usize::from(*count);
+    }
+    if let Some(largest) = shape.first_mut() {
+        *largest += jokers;
+    }
+    Ok(shape)
+}
+
+/// How the cards are valued in a game of Camel Cards.
+#[derive(Debug, Clone, Copy)]
+pub struct Rules<C: 'static = Label> {
+    /// Every card, from the weakest to the strongest.
+    pub order: &'static [C],
+    /// The card (if any) which stands in for whichever card makes the
+    /// hand strongest.
+    pub joker: Option<C>,
+}
+
+impl<C: PartialEq + Debug> Rules<C> {
+    fn rank(&self, card: &C) -> Result<usize, Fail> {
+        self.order
+            .iter()
+            .position(|c| c == card)
+            .ok_or_else(|| fail!("card {card:?} is not in the deck"))
+    }
+
+    fn ranks<const N: usize>(&self, hand: &Hand<C, N>) -> Result<[usize; N], Fail> {
+        let mut ranks = [0; N];
+        for (rank, card) in ranks.iter_mut().zip(hand.cards.iter()) {
+            *rank = self.rank(card)?;
+        }
+        Ok(ranks)
+    }
+
+    /// Returns the sizes of the groups of identical cards in `hand`,
+    /// largest first and padded with zeros.  Jokers join the largest
+    /// group.  Comparing shapes orders hands by type, whatever the
+    /// size of the hand: for five cards, five of a kind is
+    /// `[5,0,0,0,0]`, a full house `[3,2,0,0,0]`, two pair
+    /// `[2,2,1,0,0]` and so on.
+    pub fn shape<const N: usize>(&self, hand: &Hand<C, N>) -> Result<[usize; N], Fail> {
+        shape_of_ranks(&self.ranks(hand)?, self.joker_rank())
+    }
+
+    fn joker_rank(&self) -> Option<usize> {
+        self.joker.as_ref().and_then(|joker| self.rank(joker).ok())
+    }
+}
+
+pub const PART1_RULES: Rules = Rules {
+    order: &[
+        Label::Number(2),
+        Label::Number(3),
+        Label::Number(4),
+        Label::Number(5),
+        Label::Number(6),
+        Label::Number(7),
+        Label::Number(8),
+        Label::Number(9),
+        Label::Number(10),
+        Label::Jack,
+        Label::Queen,
+        Label::King,
+        Label::Ace,
+    ],
+    joker: None,
+};
+
+/// In part 2, J is a joker, and is the weakest card.
+pub const PART2_RULES: Rules = Rules {
+    order: &[
+        Label::Jack,
+        Label::Number(2),
+        Label::Number(3),
+        Label::Number(4),
+        Label::Number(5),
+        Label::Number(6),
+        Label::Number(7),
+        Label::Number(8),
+        Label::Number(9),
+        Label::Number(10),
+        Label::Queen,
+        Label::King,
+        Label::Ace,
+    ],
+    joker: Some(Label::Jack),
+};
+
+fn parse_hand<const N: usize>(s: &str) -> Result<Hand<Label, N>, Fail> {
+    let v: Vec<Label> = s
+        .chars()
+        .map(Label::try_from)
+        .collect::<Result<Vec<Label>, Fail>>()?;
+    let count = v.len();
+    match <[Label; N]>::try_from(v) {
+        Ok(cards) => Ok(Hand { cards }),
+        Err(_) => Err(fail!("hand contains {count} cards, expected {N}")),
+    }
+}
+
+pub type ParsedLine<C = Label, const N: usize = 5> = (Hand<C, N>, u32);
+
+fn parse_line<const N: usize>(s: &str) -> Result<ParsedLine<Label, N>, Fail> {
+    match s.split_once(' ') {
+        Some((hand, bid)) => Ok((
+            parse_hand(hand)?,
+            bid.parse::<u32>()
+                .map_err(|e| fail!("{bid} is not a valid bid: {e}"))?,
+        )),
+        None => Err(fail!("expected to find a space in {s}")),
+    }
+}
+
+/// Parses hands of `N` cards, each followed by a bid.
+pub fn parse_hands<const N: usize>(s: &str) -> Result<Vec<ParsedLine<Label, N>>, Fail> {
+    s.split_terminator('\n')
+        .map(parse_line)
+        .collect::<Result<Vec<ParsedLine<Label, N>>, Fail>>()
+}
+
+pub fn parse_input(s: &str) -> Result<Vec<ParsedLine>, Fail> {
+    parse_hands::<5>(s)
+}
+
+#[test]
+fn test_parse_line() {
+    use Label::*;
+    assert_eq!(
+        parse_line("KTJJT 220").expect("valid"),
+        (
+            Hand {
+                cards: [King, Number(10), Jack, Jack, Number(10)]
+            },
+            220
+        )
+    );
+}
+
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+#[test]
+fn test_hand_type_ordering() {
+    use HandType::*;
+    assert!(OnePair > HighCard);
+    assert!(TwoPair > OnePair);
+}
+
+impl HandType {
+    /// Names the shape (see `Rules::shape`) of a five-card hand.
+    fn from_shape(shape: &[usize; 5]) -> Result<HandType, Fail> {
+        match shape {
+            [5, 0, 0, 0, 0] => Ok(HandType::FiveOfAKind),
+            [4, 1, 0, 0, 0] => Ok(HandType::FourOfAKind),
+            [3, 2, 0, 0, 0] => Ok(HandType::FullHouse),
+            [3, 1, 1, 0, 0] => Ok(HandType::ThreeOfAKind),
+            [2, 2, 1, 0, 0] => Ok(HandType::TwoPair),
+            [2, 1, 1, 1, 0] => Ok(HandType::OnePair),
+            [1, 1, 1, 1, 1] => Ok(HandType::HighCard),
+            _ => Err(fail!("{shape:?} is not the shape of a hand")),
+        }
+    }
+}
+
+pub fn hand_type(hand: &Hand, rules: &Rules) -> Result<HandType, Fail> {
+    HandType::from_shape(&rules.shape(hand)?)
+}
+
+pub fn get_part1_hand_type(hand: &Hand) -> Result<HandType, Fail> {
+    hand_type(hand, &PART1_RULES)
+}
+
+#[test]
+fn test_part1_hand_type() {
+    fn get_type(s: &str) -> HandType {
+        let hand = parse_hand(s).expect("test input should be valid");
+        get_part1_hand_type(&hand).expect("test input should be valid")
+    }
+    assert_eq!(get_type("32T3K"), HandType::OnePair);
+    assert_eq!(get_type("KK677"), HandType::TwoPair);
+    assert_eq!(get_type("T55J5"), HandType::ThreeOfAKind);
+    assert_eq!(get_type("KTJJT"), HandType::TwoPair);
+    assert_eq!(get_type("QQQJA"), HandType::ThreeOfAKind);
+}
+
+pub fn get_part2_hand_type(hand: &Hand) -> Result<HandType, Fail> {
+    hand_type(hand, &PART2_RULES)
+}
+
+#[test]
+fn test_part2_hand_type() {
+    fn get_type(s: &str) -> HandType {
+        let hand = parse_hand(s).expect("test input should be valid");
+        get_part2_hand_type(&hand).expect("test input should be valid")
+    }
+    assert_eq!(get_type("32T3K"), HandType::OnePair);
+    assert_eq!(get_type("KK677"), HandType::TwoPair);
+    assert_eq!(get_type("T55J5"), HandType::FourOfAKind);
+    assert_eq!(get_type("KTJJT"), HandType::FourOfAKind);
+    assert_eq!(get_type("QQQJA"), HandType::FourOfAKind);
+    assert_eq!(get_type("JJJJJ"), HandType::FiveOfAKind);
+    assert_eq!(get_type("2345J"), HandType::OnePair);
+}
+
+/// Hands sort in the same order as their keys.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub struct SortKey<const N: usize = 5> {
+    shape: [usize; N],
+    ranks: [usize; N],
+}
+
+pub fn sort_key<C, const N: usize>(hand: &Hand<C, N>, rules: &Rules<C>) -> Result<SortKey<N>, Fail>
+where
+    C: Card,
+{
+    let ranks = rules.ranks(hand)?;
+    Ok(SortKey {
+        shape: shape_of_ranks(&ranks, rules.joker_rank())?,
+        ranks,
+    })
+}
+
+/// A hand's place in the ranking.  Hands are ordered by their sort
+/// keys and then, if two hands are identical, by their position in
+/// the input.  The puzzle doesn't say how to rank identical hands
+/// (and real inputs don't have any), but this way the ranking is at
+/// least well defined and doesn't depend on the bids.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub struct RankedHand<const N: usize = 5> {
+    key: SortKey<N>,
+    index: usize,
+}
+
+impl<const N: usize> RankedHand<N> {
+    /// Returns the position of the hand in the input.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Returns the hands in order of rank, weakest first.
+pub fn ranked_hands<C, const N: usize>(
+    hands: &[ParsedLine<C, N>],
+    rules: &Rules<C>,
+) -> Result<Vec<RankedHand<N>>, Fail>
+where
+    C: Card,
+{
+    let mut ranked: Vec<RankedHand<N>> = hands
+        .iter()
+        .enumerate()
+        .map(|(index, (hand, _bid))| sort_key(hand, rules).map(|key| RankedHand { key, index }))
+        .collect::<Result<Vec<RankedHand<N>>, Fail>>()?;
+    // No two entries are equal, so an unstable sort gives the same
+    // result as a stable one.
+    ranked.sort_unstable();
+    Ok(ranked)
+}
+
+fn rank_hands<C, const N: usize>(
+    hands: &[ParsedLine<C, N>],
+    rules: &Rules<C>,
+) -> Result<Vec<(usize, u32)>, Fail>
+where
+    C: Card,
+{
+    Ok(ranked_hands(hands, rules)?
+        .iter()
+        .enumerate()
+        .map(|(i, ranked)| (i + 1, hands[ranked.index].1))
+        .collect())
+}
+
+#[test]
+fn test_identical_hands() {
+    // Identical hands are ranked in the order they appear, whatever
+    // their bids.
+    let hands = parse_input("32T3K 10\nKK677 5\n32T3K 1\n").expect("test input should be valid");
+    let order: Vec<usize> = ranked_hands(&hands, &PART1_RULES)
+        .expect("cards are in the deck")
+        .iter()
+        .map(|ranked| ranked.index)
+        .collect();
+    assert_eq!(order, vec![0, 2, 1]);
+    assert_eq!(solve(&hands, &PART1_RULES), Ok(10 + 2 + 3 * 5));
+
+    let reversed = parse_input("32T3K 1\nKK677 5\n32T3K 10\n").expect("test input should be valid");
+    assert_eq!(solve(&reversed, &PART1_RULES), Ok(1 + 2 * 10 + 3 * 5));
+}
+
+#[test]
+fn test_part1_card_ordering() {
+    use Label::*;
+    let rank = |label| {
+        PART1_RULES
+            .rank(&label)
+            .expect("every label is in the deck")
+    };
+    assert!(rank(Number(3)) > rank(Number(2)));
+    assert!(rank(Number(4)) > rank(Number(3)));
+    assert!(rank(Number(5)) > rank(Number(4)));
+    assert!(rank(Number(6)) > rank(Number(5)));
+    assert!(rank(Number(7)) > rank(Number(6)));
+    assert!(rank(Number(8)) > rank(Number(7)));
+    assert!(rank(Number(9)) > rank(Number(8)));
+    assert!(rank(Number(10)) > rank(Number(9)));
+    assert!(rank(Jack) > rank(Number(10)));
+    assert!(rank(Queen) > rank(Jack));
+    assert!(rank(King) > rank(Queen));
+    assert!(rank(Ace) > rank(King));
+    assert!(PART1_RULES.rank(&Number(1)).is_err());
+}
+
+#[cfg(test)]
+fn get_sort_key(s: &str, rules: &Rules) -> SortKey {
+    match parse_hand(s) {
+        Ok(hand) => match sort_key(&hand, rules) {
+            Ok(result) => result,
+            Err(e) => {
+                panic!("invalid test input {hand:?}: {e}");
+            }
+        },
+        Err(e) => {
+            panic!("invalid test input {s}: {e}");
+        }
+    }
+}
+
+#[test]
+fn test_part1_hand_comparison() {
+    let get_sort_key = |s| get_sort_key(s, &PART1_RULES);
+    assert!(get_sort_key("32T3K") < get_sort_key("KTJJT"));
+    assert!(get_sort_key("KTJJT") < get_sort_key("KK677"));
+    assert!(get_sort_key("KK677") < get_sort_key("T55J5"));
+    assert!(get_sort_key("T55J5") < get_sort_key("QQQJA"));
+
+    assert_eq!(get_sort_key("T55J5"), get_sort_key("T55J5"));
+}
+
+#[test]
+fn test_part2_hand_comparison() {
+    let get_sort_key = |s| get_sort_key(s, &PART2_RULES);
+    assert!(get_sort_key("32T3K") < get_sort_key("KK677"));
+    assert!(get_sort_key("KK677") < get_sort_key("T55J5"));
+    assert!(get_sort_key("T55J5") < get_sort_key("QQQJA"));
+    assert!(get_sort_key("QQQJA") < get_sort_key("KTJJT"));
+    assert!(get_sort_key("JKKK2") < get_sort_key("QQQQ2"));
+    assert!(get_sort_key("JJJJJ") < get_sort_key("22222"));
+
+    assert!(get_sort_key("AAKKJ") < get_sort_key("AAKKK"));
+    assert!(get_sort_key("AAKKJ") > get_sort_key("AAKKQ"));
+}
+
+#[test]
+fn test_get_part1_hand_type_valid() {
+    use HandType::*;
+    fn get_hand_type(s: &str) -> HandType {
+        match parse_hand(s) {
+            Ok(hand) => match get_part1_hand_type(&hand) {
+                Ok(hand_type) => hand_type,
+                Err(e) => {
+                    panic!("invalid test input {hand:?}: {e}");
+                }
+            },
+            Err(e) => {
+                panic!("invalid test input {s}: {e}");
+            }
+        }
+    }
+
+    assert_eq!(get_hand_type("AAAAA"), FiveOfAKind);
+    assert_eq!(get_hand_type("AA8AA"), FourOfAKind);
+    assert_eq!(get_hand_type("23332"), FullHouse);
+    assert_eq!(get_hand_type("TTT98"), ThreeOfAKind);
+    assert_eq!(get_hand_type("23432"), TwoPair);
+    assert_eq!(get_hand_type("A23A4"), OnePair);
+    assert_eq!(get_hand_type("23456"), HighCard);
+}
+
+#[test]
+fn test_get_part1_hand_type_invalid_count() {
+    fn get_hand_type(s: &str) -> Result<HandType, Fail> {
+        parse_hand(s).and_then(|hand| get_part1_hand_type(&hand))
+    }
+
+    assert!(get_hand_type("").is_err());
+    assert!(get_hand_type("2").is_err());
+    assert!(get_hand_type("22").is_err());
+    assert!(get_hand_type("333").is_err());
+    assert!(get_hand_type("4444").is_err());
+    assert!(get_hand_type("666666").is_err());
+}
+
+#[test]
+fn test_other_hand_sizes() {
+    let shape = |s: &str, rules: &Rules| -> Vec<usize> {
+        let hand: Hand<Label, 7> = parse_hand(s).expect("test input should be valid");
+        rules.shape(&hand).expect("cards are in the deck").to_vec()
+    };
+    assert_eq!(shape("2233344", &PART1_RULES), vec![3, 2, 2, 0, 0, 0, 0]);
+    assert_eq!(shape("JJ23456", &PART1_RULES), vec![2, 1, 1, 1, 1, 1, 0]);
+    assert_eq!(shape("JJ23456", &PART2_RULES), vec![3, 1, 1, 1, 1, 0, 0]);
+    assert_eq!(shape("JJJJJJJ", &PART2_RULES), vec![7, 0, 0, 0, 0, 0, 0]);
+
+    let hands =
+        parse_hands::<3>("AAK 1\nKKK 10\n2A2 100\nKKA 1000\n").expect("test input should be valid");
+    // Ranked 2A2, KKA, AAK, KKK.
+    assert_eq!(solve(&hands, &PART1_RULES), Ok(100 + 2 * 1000 + 3 + 4 * 10));
+}
+
+#[test]
+fn test_other_decks() {
+    // A deck of three cards, where 'w' is wild.
+    const RULES: Rules<char> = Rules {
+        order: &['w', 'x', 'y'],
+        joker: Some('w'),
+    };
+    let hand = |s: &str| -> Hand<char, 4> {
+        Hand {
+            cards: s
+                .chars()
+                .collect::<Vec<char>>()
+                .try_into()
+                .expect("4 cards"),
+        }
+    };
+    let key = |s: &str| sort_key(&hand(s), &RULES).expect("cards are in the deck");
+    assert!(key("xyxy") < key("wxyy"));
+    assert!(key("wxyy") < key("yyyx"));
+    assert!(key("wyyy") < key("xxxx"));
+    assert!(sort_key(&hand("xyzx"), &RULES).is_err());
+
+    // The cards of each kind are counted in an array which only has
+    // room for the 13 kinds of the puzzle's deck.
+    const BIG_DECK: Rules<char> = Rules {
+        order: &[
+            'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n',
+        ],
+        joker: None,
+    };
+    assert_eq!(BIG_DECK.shape(&Hand { cards: ['a', 'm'] }), Ok([1, 1]));
+    assert!(BIG_DECK.shape(&Hand { cards: ['a', 'n'] }).is_err());
+}
+
+pub fn solve<C, const N: usize>(lines: &[ParsedLine<C, N>], rules: &Rules<C>) -> Result<u64, Fail>
+where
+    C: Card,
+{
+    Ok(rank_hands(lines, rules)?
+        .into_iter()
+        .map(|(rank, bid)| (rank as u64) * (bid as u64))
+        .sum())
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(7).expect("there should be an example")
+}
+
+#[test]
+fn test_solve() {
+    let input = parse_input(get_example()).expect("example input should be valid");
+    assert_eq!(solve(&input, &PART1_RULES), Ok(6440));
+    assert_eq!(solve(&input, &PART2_RULES), Ok(5905));
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day07::part1;
+/// let example = lib::examples::examples(7)[0];
+/// assert_eq!(part1(example), Ok(6440));
+/// ```
+pub fn part1(input: &str) -> Result<u64, Fail> {
+    solve(&parse_input(input)?, &PART1_RULES)
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day07::part2;
+/// let example = lib::examples::examples(7)[0];
+/// assert_eq!(part2(example), Ok(5905));
+/// ```
+pub fn part2(input: &str) -> Result<u64, Fail> {
+    solve(&parse_input(input)?, &PART2_RULES)
+}
+
+#[test]
+fn test_parts() {
+    assert_eq!(part1(get_example()), Ok(6440));
+    assert_eq!(part2(get_example()), Ok(5905));
+    assert!(part1("32T3X 765\n").is_err());
+}
@@ -0,0 +1,741 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str;
+
+use regex::Regex;
+
+use crate::cycle::CycleInfo;
+use crate::error::Fail;
+use crate::fail;
+use crate::intern::Interner;
+use crate::numeric::lcm_all;
+use crate::parse::ParseError;
+use crate::solver::{Backend, Notes};
+
+pub const NOTES: Notes = Notes {
+    title: "Haunted Wasteland",
+    algorithm: "Part 1 follows the instructions from AAA to ZZZ.  In part 2, each ghost's path is followed until it repeats, and the answer is the least common multiple of the cycle lengths.",
+    complexity: "O(i·v) for i instructions and v nodes",
+};
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+struct Node {
+    left: String,
+    right: String,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Network {
+    nodes: HashMap<String, Node>,
+}
+
+impl Network {
+    fn step(&self, here: &String, step: char) -> Result<&String, Fail> {
+        let go_left = match step {
+            'L' => Ok(true),
+            'R' => Ok(false),
+            other => Err(fail!("invalid step {other}")),
+        }?;
+        match self.nodes.get(here) {
+            Some(Node { left, right }) => Ok({
+                if go_left {
+                    left
+                } else {
+                    right
+                }
+            }),
+            None => Err(fail!("{here} is not a known location")),
+        }
+    }
+}
+
+impl Network {
+    /// Returns the start nodes (those whose names end in 'A'), in
+    /// order, so that we report problems in the same order every
+    /// time.
+    fn start_nodes(&self) -> Vec<&String> {
+        let mut starts: Vec<&String> = self
+            .nodes
+            .keys()
+            .filter(|node| node.ends_with('A'))
+            .collect();
+        starts.sort();
+        starts
+    }
+
+    /// Describes the network as a Graphviz directed graph, with an
+    /// edge labelled L and one labelled R leaving each node.  Start
+    /// nodes (ending in 'A') are green and target nodes (ending in
+    /// 'Z') are red, which shows up the separate cycle each ghost
+    /// follows.
+    pub fn to_dot(&self) -> String {
+        let mut names: Vec<&String> = self.nodes.keys().collect();
+        names.sort();
+        let mut result = String::from("digraph network {\n");
+        for name in names.iter() {
+            if name.ends_with('A') {
+                result.push_str(&format!("  \"{name}\" [style=filled, fillcolor=green];\n"));
+            } else if name.ends_with('Z') {
+                result.push_str(&format!("  \"{name}\" [style=filled, fillcolor=red];\n"));
+            }
+        }
+        for name in names {
+            let Node { left, right } = &self.nodes[name];
+            result.push_str(&format!("  \"{name}\" -> \"{left}\" [label=\"L\"];\n"));
+            result.push_str(&format!("  \"{name}\" -> \"{right}\" [label=\"R\"];\n"));
+        }
+        result.push_str("}\n");
+        result
+    }
+}
+
+pub fn parse_input(s: &str) -> Result<(String, Network), Fail> {
+    let line_re = Regex::new(r"^([A-Z0-9]{3}) = \(([A-Z0-9]{3}), ([A-Z0-9]{3})\)$")?;
+    match s.split_once("\n\n") {
+        Some((instructions, mappings)) => {
+            if let Some((i, ch)) = instructions
+                .char_indices()
+                .find(|(_, ch)| !matches!(ch, 'L' | 'R'))
+            {
+                return Err(ParseError::at(
+                    s,
+                    &instructions[i..],
+                    format!("instruction {ch:?} should be L or R"),
+                )
+                .into());
+            }
+            Ok((
+                instructions.to_string(),
+                Network {
+                    nodes: mappings
+                        .split_terminator('\n')
+                        .map(|line| match line_re.captures(line) {
+                            Some(caps) => Ok((
+                                String::from(&caps[1]),
+                                Node {
+                                    left: String::from(&caps[2]),
+                                    right: String::from(&caps[3]),
+                                },
+                            )),
+                            None => Err(ParseError::at(
+                                s,
+                                line,
+                                "expected a node, such as \"AAA = (BBB, CCC)\"",
+                            )
+                            .into()),
+                        })
+                        .collect::<Result<HashMap<String, Node>, Fail>>()?,
+                },
+            ))
+        }
+        None => Err(ParseError::at(s, s, "expected a blank line after the instructions").into()),
+    }
+}
+
+#[cfg(test)]
+fn build_network(nw: &[(&str, (&str, &str))]) -> Network {
+    Network {
+        nodes: nw
+            .iter()
+            .map(|&(name, (l, r))| {
+                (
+                    String::from(name),
+                    Node {
+                        left: String::from(l),
+                        right: String::from(r),
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+fn get_example_1() -> (String, Network) {
+    parse_input(crate::examples::examples(8)[0]).expect("example 1 should be valid")
+}
+
+#[test]
+fn test_parser() {
+    let expected = [
+        ("AAA", ("BBB", "CCC")),
+        ("BBB", ("DDD", "EEE")),
+        ("CCC", ("ZZZ", "GGG")),
+        ("DDD", ("DDD", "DDD")),
+        ("EEE", ("EEE", "EEE")),
+        ("GGG", ("GGG", "GGG")),
+        ("ZZZ", ("ZZZ", "ZZZ")),
+    ];
+    let expected_network = build_network(&expected);
+    assert_eq!(get_example_1(), ("RL".to_string(), expected_network,));
+}
+
+#[test]
+fn test_to_dot() {
+    let network = build_network(&[("11A", ("11Z", "XXX")), ("11Z", ("11A", "11A"))]);
+    assert_eq!(
+        network.to_dot(),
+        concat!(
+            "digraph network {\n",
+            "  \"11A\" [style=filled, fillcolor=green];\n",
+            "  \"11Z\" [style=filled, fillcolor=red];\n",
+            "  \"11A\" -> \"11Z\" [label=\"L\"];\n",
+            "  \"11A\" -> \"XXX\" [label=\"R\"];\n",
+            "  \"11Z\" -> \"11A\" [label=\"L\"];\n",
+            "  \"11Z\" -> \"11A\" [label=\"R\"];\n",
+            "}\n",
+        )
+    );
+}
+
+#[test]
+fn test_parse_error_location() {
+    assert_eq!(
+        parse_input("LR\n\nAAA = (BBB, CCC)\nBBB = BBB, BBB\n"),
+        Err(Fail(
+            "line 4, column 1: expected a node, such as \"AAA = (BBB, CCC)\"\n  BBB = BBB, BBB\n  ^"
+                .to_string()
+        ))
+    );
+    assert_eq!(
+        parse_input("LRX\n\nAAA = (AAA, AAA)\n"),
+        Err(Fail(
+            "line 1, column 3: instruction 'X' should be L or R\n  LRX\n    ^".to_string()
+        ))
+    );
+    assert!(parse_input("LR\nAAA = (AAA, AAA)\n")
+        .expect_err("there is no blank line")
+        .to_string()
+        .starts_with("line 1, column 1: expected a blank line after the instructions\n"));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_parse_arbitrary_bytes_does_not_panic(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..200)) {
+        let _ = parse_input(String::from_utf8_lossy(&bytes).as_ref());
+    }
+
+    #[test]
+    fn test_parse_almost_valid_does_not_panic(s in "[LRX]{0,5}\n\n([A-Z0-9]{2,4} = \\([A-Z0-9]{2,4}, [A-Z0-9]{2,4}\\)?\n){0,4}") {
+        let _ = parse_input(s.as_str());
+    }
+}
+
+/// Describes the loop a path is stuck in, having failed to reach
+/// `target`.  `here` is a node on the loop, which is reached just
+/// before turn number `phase`, and `step` follows a turn.
+fn describe_loop<N, S, D>(
+    start: &str,
+    target: &str,
+    here: N,
+    phase: usize,
+    turns: usize,
+    mut step: S,
+    name: D,
+) -> Fail
+where
+    N: Copy + PartialEq,
+    S: FnMut(N, usize) -> Result<N, Fail>,
+    D: Fn(N) -> String,
+{
+    const MAX_NAMES: usize = 10;
+    let (mut node, mut p) = (here, phase);
+    let mut period = 0;
+    let mut names: Vec<String> = Vec::new();
+    loop {
+        let n = name(node);
+        if !names.contains(&n) {
+            names.push(n);
+        }
+        node = match step(node, p) {
+            Ok(node) => node,
+            Err(e) => return e,
+        };
+        p = (p + 1) % turns;
+        period += 1;
+        if node == here && p == phase {
+            break;
+        }
+    }
+    names.sort();
+    let count = names.len();
+    if count > MAX_NAMES {
+        names.truncate(MAX_NAMES);
+        names.push(format!("and {} more", count - MAX_NAMES));
+    }
+    fail!(
+        "there is no way from {start} to {target}: the path goes round a loop of {period} steps through {count} nodes ({})",
+        names.join(", ")
+    )
+}
+
+/// Counts the steps from `start` to a node for which `is_target` is
+/// true.  `target` describes such a node, for the error we return if
+/// there isn't one on the path.
+fn count_steps<F>(
+    instructions: &str,
+    network: &Network,
+    start: &str,
+    target: &str,
+    is_target: F,
+) -> Result<usize, Fail>
+where
+    F: Fn(&str) -> bool,
+{
+    if instructions.is_empty() {
+        return Err(Fail("there are no instructions".to_string()));
+    }
+    let turns: Vec<char> = instructions.chars().collect();
+    // There are only this many states (a node and a position in the
+    // instructions), so if we take more steps than this, we are
+    // going round in circles.
+    let limit = network.nodes.len() * turns.len();
+    let start = String::from(start);
+    let mut here = &start;
+    for (steps_taken, instruction) in turns.iter().cycle().enumerate() {
+        if steps_taken > limit {
+            let phase = steps_taken % turns.len();
+            return Err(describe_loop(
+                &start,
+                target,
+                here,
+                phase,
+                turns.len(),
+                |node, phase| network.step(node, turns[phase]),
+                String::clone,
+            ));
+        }
+        here = network.step(here, *instruction)?;
+        if is_target(here) {
+            return Ok(steps_taken + 1);
+        }
+    }
+    unreachable!("cycling a non-empty iterator never ends")
+}
+
+pub fn steps_to_zzz(instructions: &str, network: &Network) -> Result<usize, Fail> {
+    let done = |name: &str| name == "ZZZ";
+    count_steps(instructions, network, "AAA", "ZZZ", done)
+}
+
+#[test]
+fn test_part1_example1() {
+    let (instructions, network) = get_example_1();
+    assert_eq!(steps_to_zzz(&instructions, &network), Ok(2));
+}
+
+#[test]
+fn test_part1_example2() {
+    let (instructions, network) =
+        parse_input(crate::examples::examples(8)[1]).expect("example should be valid");
+    assert_eq!(steps_to_zzz(&instructions, &network), Ok(6));
+}
+
+#[test]
+fn test_steps_to_zzz_errors() {
+    let (_, network) = get_example_1();
+    assert!(steps_to_zzz("", &network).is_err());
+    assert!(steps_to_zzz("LX", &network).is_err());
+    let (instructions, network) = parse_input("L\n\nBBB = (BBB, BBB)\n").expect("input is valid");
+    assert!(steps_to_zzz(&instructions, &network).is_err());
+}
+
+#[test]
+fn test_unreachable_target() {
+    let input = concat!(
+        "LR\n",
+        "\n",
+        "AAA = (BBB, BBB)\n",
+        "BBB = (AAA, AAA)\n",
+        "CCC = (CCC, ZZZ)\n",
+        "ZZZ = (ZZZ, ZZZ)\n",
+        "11A = (11B, 11B)\n",
+        "11B = (11A, 11A)\n",
+    );
+    let (instructions, network) = parse_input(input).expect("input is valid");
+    let lost = Err(Fail(
+        "there is no way from AAA to ZZZ: the path goes round a loop of 2 steps through 2 nodes (AAA, BBB)"
+            .to_string(),
+    ));
+    assert_eq!(steps_to_zzz(&instructions, &network), lost);
+    assert_eq!(network.compile().steps_to_zzz(&instructions), lost);
+    assert_eq!(part1(input), lost);
+    let lost_ghost = Err(Fail(
+        "there is no way from 11A to a node ending in Z: the path goes round a loop of 2 steps through 2 nodes (11A, 11B)"
+            .to_string(),
+    ));
+    assert_eq!(part2(input), lost_ghost);
+    assert_eq!(ghost_steps(&instructions, &network), lost_ghost);
+    // ZZZ isn't defined at all.
+    let (instructions, network) = parse_input("L\n\nAAA = (AAA, AAA)\n").expect("input is valid");
+    assert!(steps_to_zzz(&instructions, &network)
+        .expect_err("ZZZ is unreachable")
+        .to_string()
+        .starts_with("there is no way from AAA to ZZZ: the path goes round a loop of 1 steps"));
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day08::part1;
+/// let example = lib::examples::examples(8)[1];
+/// assert_eq!(part1(example), Ok(6));
+/// ```
+pub fn part1(input: &str) -> Result<usize, Fail> {
+    let (instructions, network) = parse_input(input)?;
+    network.compile().steps_to_zzz(&instructions)
+}
+
+pub fn ghost_steps(instructions: &str, network: &Network) -> Result<usize, Fail> {
+    fn is_target(name: &str) -> bool {
+        name.ends_with('Z')
+    }
+
+    let cycle_lengths = network
+        .start_nodes()
+        .into_iter()
+        // Measure the length of the cycle starting at each start node.
+        .map(|start| {
+            count_steps(
+                instructions,
+                network,
+                start,
+                "a node ending in Z",
+                is_target,
+            )
+        })
+        .collect::<Result<Vec<usize>, Fail>>()?;
+    // Find the lowest common multiple of all the cycle lengths.
+    lcm_all(cycle_lengths).ok_or_else(|| Fail("there are no start nodes".to_string()))
+}
+
+#[test]
+fn test_ghost_steps_error_is_deterministic() {
+    // Both ghosts get lost, but we always report the first.
+    let input = concat!(
+        "L\n",
+        "\n",
+        "11A = (11X, 11X)\n",
+        "22A = (22X, 22X)\n",
+        "33A = (33X, 33X)\n",
+    );
+    for _ in 0..10 {
+        let (instructions, network) = parse_input(input).expect("input is valid");
+        assert_eq!(
+            ghost_steps(&instructions, &network),
+            Err(Fail("11X is not a known location".to_string()))
+        );
+    }
+}
+
+#[test]
+fn test_part2_example3() {
+    let input = crate::examples::examples(8)[2];
+    let (instructions, network) = parse_input(input).expect("example input should be valid");
+    assert_eq!(ghost_steps(&instructions, &network), Ok(6));
+    assert_eq!(part2(input), Ok(6));
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day08::part2;
+/// let example = lib::examples::examples(8)[2];
+/// assert_eq!(part2(example), Ok(6));
+/// ```
+pub fn part2(input: &str) -> Result<usize, Fail> {
+    let (instructions, network) = parse_input(input)?;
+    network.compile().ghost_steps(&instructions)
+}
+
+/// The network with each node's name replaced by a number, so that
+/// following the instructions involves neither looking names up nor
+/// cloning them.  The names are needed only to read the network and
+/// to report problems.
+#[derive(Debug, Clone)]
+pub struct CompiledNetwork {
+    /// The nodes the network defines are numbered first, in order of
+    /// name; after them come names which are mentioned but not
+    /// defined.
+    names: Interner,
+    /// The left and right successors of each node the network
+    /// defines.
+    next: Vec<(u32, u32)>,
+}
+
+impl Network {
+    pub fn compile(&self) -> CompiledNetwork {
+        let mut defined: Vec<&String> = self.nodes.keys().collect();
+        defined.sort();
+        let mut names = Interner::new();
+        for name in defined.iter() {
+            names.intern(name);
+        }
+        let mut id = |name: &str| names.intern(name) as u32;
+        let next = defined
+            .iter()
+            .map(|name| {
+                let Node { left, right } = &self.nodes[*name];
+                (id(left), id(right))
+            })
+            .collect();
+        CompiledNetwork { names, next }
+    }
+}
+
+/// Converts the instructions into a list of turns, true meaning left.
+fn compile_instructions(instructions: &str) -> Result<Vec<bool>, Fail> {
+    if instructions.is_empty() {
+        return Err(Fail("there are no instructions".to_string()));
+    }
+    instructions
+        .chars()
+        .map(|step| match step {
+            'L' => Ok(true),
+            'R' => Ok(false),
+            other => Err(fail!("invalid step {other}")),
+        })
+        .collect()
+}
+
+impl CompiledNetwork {
+    fn step(&self, here: u32, go_left: bool) -> Result<u32, Fail> {
+        match self.next.get(here as usize) {
+            Some((left, _)) if go_left => Ok(*left),
+            Some((_, right)) => Ok(*right),
+            None => Err(fail!(
+                "{} is not a known location",
+                self.names.name(here as usize)
+            )),
+        }
+    }
+
+    /// Counts the steps from `start` to a node for which `is_target`
+    /// is true, as `count_steps` does.
+    fn count_steps<F>(
+        &self,
+        turns: &[bool],
+        start: &str,
+        target: &str,
+        is_target: F,
+    ) -> Result<usize, Fail>
+    where
+        F: Fn(u32) -> bool,
+    {
+        let mut here = self
+            .names
+            .get(start)
+            .ok_or_else(|| fail!("{start} is not a known location"))? as u32;
+        let limit = self.next.len() * turns.len();
+        for (steps_taken, go_left) in turns.iter().cycle().enumerate() {
+            if steps_taken > limit {
+                return Err(describe_loop(
+                    start,
+                    target,
+                    here,
+                    steps_taken % turns.len(),
+                    turns.len(),
+                    |node, phase| self.step(node, turns[phase]),
+                    |node| self.names.name(node as usize).to_string(),
+                ));
+            }
+            here = self.step(here, *go_left)?;
+            if is_target(here) {
+                return Ok(steps_taken + 1);
+            }
+        }
+        unreachable!("cycling a non-empty iterator never ends")
+    }
+
+    pub fn steps_to_zzz(&self, instructions: &str) -> Result<usize, Fail> {
+        let turns = compile_instructions(instructions)?;
+        let zzz = self.names.get("ZZZ").map(|id| id as u32);
+        self.count_steps(&turns, "AAA", "ZZZ", |node| Some(node) == zzz)
+    }
+
+    pub fn ghost_steps(&self, instructions: &str) -> Result<usize, Fail> {
+        let turns = compile_instructions(instructions)?;
+        let is_target: Vec<bool> = (0..self.names.len())
+            .map(|id| self.names.name(id).ends_with('Z'))
+            .collect();
+        // The defined nodes are numbered in order of name, so the
+        // start nodes come out in the same order as `start_nodes`.
+        let cycle_lengths = (0..self.next.len())
+            .map(|id| self.names.name(id))
+            .filter(|name| name.ends_with('A'))
+            .map(|start| {
+                self.count_steps(&turns, start, "a node ending in Z", |node| {
+                    is_target[node as usize]
+                })
+            })
+            .collect::<Result<Vec<usize>, Fail>>()?;
+        lcm_all(cycle_lengths).ok_or_else(|| Fail("there are no start nodes".to_string()))
+    }
+}
+
+#[test]
+fn test_compiled_network() {
+    let examples = crate::examples::examples(8);
+    for (example, expected) in [(examples[0], 2), (examples[1], 6)] {
+        let (instructions, network) = parse_input(example).expect("example should be valid");
+        assert_eq!(network.compile().steps_to_zzz(&instructions), Ok(expected));
+    }
+    let (instructions, network) = parse_input(examples[2]).expect("example should be valid");
+    assert_eq!(network.compile().ghost_steps(&instructions), Ok(6));
+
+    let (_, network) = get_example_1();
+    let compiled = network.compile();
+    assert!(compiled.steps_to_zzz("").is_err());
+    assert!(compiled.steps_to_zzz("LX").is_err());
+    let (instructions, network) = parse_input(concat!(
+        "L\n",
+        "\n",
+        "11A = (11X, 11X)\n",
+        "22A = (22X, 22X)\n",
+    ))
+    .expect("input is valid");
+    assert_eq!(
+        network.compile().ghost_steps(&instructions),
+        Err(Fail("11X is not a known location".to_string()))
+    );
+}
+
+pub const BACKENDS: [Backend; 4] = [
+    Backend {
+        part: 1,
+        name: "indexed",
+        description: "Number the nodes, and follow the instructions through a table of successors",
+        slow: false,
+        solve: |input| Ok(part1(input)?.into()),
+    },
+    Backend {
+        part: 1,
+        name: "names",
+        description: "Follow the instructions by looking up each node by name",
+        slow: false,
+        solve: |input| {
+            let (instructions, network) = parse_input(input)?;
+            Ok(steps_to_zzz(&instructions, &network)?.into())
+        },
+    },
+    Backend {
+        part: 2,
+        name: "indexed",
+        description: "Number the nodes, and follow the instructions through a table of successors",
+        slow: false,
+        solve: |input| Ok(part2(input)?.into()),
+    },
+    Backend {
+        part: 2,
+        name: "names",
+        description: "Follow the instructions by looking up each node by name",
+        slow: false,
+        solve: |input| {
+            let (instructions, network) = parse_input(input)?;
+            Ok(ghost_steps(&instructions, &network)?.into())
+        },
+    },
+];
+
+/// The route taken by a ghost from one start node.  The ghost's state
+/// is its node together with its position in the instructions, so it
+/// must eventually repeat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhostPath {
+    pub start: String,
+    /// After `cycle.prefix_len` steps the ghost is in the state it
+    /// will return to every `cycle.period` steps.
+    pub cycle: CycleInfo,
+    /// The step numbers at which the ghost is on a node ending in 'Z',
+    /// up to the end of the first repetition of the cycle.
+    pub targets: Vec<(usize, String)>,
+}
+
+impl Display for GhostPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: cycle starts after {} steps, period {}",
+            self.start, self.cycle.prefix_len, self.cycle.period
+        )?;
+        for (step, node) in self.targets.iter() {
+            writeln!(f, "  step {step}: {node}")?;
+        }
+        Ok(())
+    }
+}
+
+fn trace_ghost(
+    instructions: &[char],
+    network: &Network,
+    start: &String,
+) -> Result<GhostPath, Fail> {
+    if instructions.is_empty() {
+        return Err(Fail("there are no instructions".to_string()));
+    }
+    let mut seen: HashMap<(&String, usize), usize> = HashMap::new();
+    let mut targets = Vec::new();
+    let mut here = start;
+    for step in 0.. {
+        let phase = step % instructions.len();
+        if let Some(first) = seen.insert((here, phase), step) {
+            return Ok(GhostPath {
+                start: start.clone(),
+                cycle: CycleInfo {
+                    prefix_len: first,
+                    period: step - first,
+                },
+                targets,
+            });
+        }
+        if here.ends_with('Z') {
+            targets.push((step, here.clone()));
+        }
+        here = network.step(here, instructions[phase])?;
+    }
+    unreachable!("the number of states is finite")
+}
+
+/// Follows the ghost from each start node until its state repeats.
+pub fn trace_ghosts(instructions: &str, network: &Network) -> Result<Vec<GhostPath>, Fail> {
+    let instructions: Vec<char> = instructions.chars().collect();
+    network
+        .start_nodes()
+        .into_iter()
+        .map(|start| trace_ghost(&instructions, network, start))
+        .collect()
+}
+
+#[test]
+fn test_trace_ghosts() {
+    let (instructions, network) =
+        parse_input(crate::examples::examples(8)[2]).expect("example input should be valid");
+    let paths = trace_ghosts(&instructions, &network).expect("example should be traceable");
+    assert_eq!(
+        paths,
+        vec![
+            GhostPath {
+                start: "11A".to_string(),
+                cycle: CycleInfo {
+                    prefix_len: 1,
+                    period: 2
+                },
+                targets: vec![(2, "11Z".to_string())],
+            },
+            GhostPath {
+                start: "22A".to_string(),
+                cycle: CycleInfo {
+                    prefix_len: 1,
+                    period: 6
+                },
+                targets: vec![(3, "22Z".to_string()), (6, "22Z".to_string())],
+            },
+        ]
+    );
+    assert_eq!(
+        paths[1].to_string(),
+        "22A: cycle starts after 1 steps, period 6\n  step 3: 22Z\n  step 6: 22Z\n"
+    );
+    assert!(trace_ghosts("", &network).is_err());
+}
@@ -0,0 +1,328 @@
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::iterplus::windows_exact;
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Mirage Maintenance",
+    algorithm: "Each sequence is repeatedly replaced by its differences until they are all zero, and the next value is built back up from the last difference at each level.  Part 2 does the same with each sequence reversed.",
+    complexity: "O(k²) time and O(k) space for each sequence of length k",
+};
+
+/// Which end of a sequence to extrapolate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Predict the value after the last one.
+    Forwards,
+    /// Predict the value before the first one.
+    Backwards,
+}
+
+/// Why a sequence could not be extrapolated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtrapolationError {
+    /// The sequence has fewer than two values, so there are no
+    /// differences to go on.
+    TooShort,
+    /// After taking differences `depth` times, they were still not
+    /// all zero.  This happens when the sequence is not generated by
+    /// a polynomial of low degree.
+    MaxDepthExceeded { depth: usize },
+    /// The differences ran out (each level being one shorter than the
+    /// one above it) before they were all zero, so the sequence is
+    /// not generated by a polynomial of low enough degree for its
+    /// length.
+    NeverReachesZero,
+    /// The limit on the number of levels of differences is larger
+    /// than `DEFAULT_MAX_DEPTH`, so the differences could overflow.
+    DepthLimitTooLarge { max_depth: usize },
+}
+
+impl Display for ExtrapolationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtrapolationError::TooShort => {
+                f.write_str("a sequence needs at least two values to extrapolate")
+            }
+            ExtrapolationError::MaxDepthExceeded { depth } => {
+                write!(f, "the differences were not all zero after {depth} levels")
+            }
+            ExtrapolationError::NeverReachesZero => {
+                f.write_str("the differences ran out before they were all zero")
+            }
+            ExtrapolationError::DepthLimitTooLarge { max_depth } => write!(
+                f,
+                "the differences could overflow after {max_depth} levels (the limit is {DEFAULT_MAX_DEPTH})"
+            ),
+        }
+    }
+}
+
+impl Error for ExtrapolationError {}
+
+impl From<ExtrapolationError> for Fail {
+    fn from(e: ExtrapolationError) -> Fail {
+        Fail(e.to_string())
+    }
+}
+
+/// The number of levels of differences we take before giving up.
+/// Each level at most doubles the size of the values, so starting
+/// from `i32` values, neither the differences nor their total can
+/// overflow an `i64` within this many levels, and no larger limit is
+/// accepted.
+pub const DEFAULT_MAX_DEPTH: usize = 32;
+
+/// Predicts the next value of the sequence in `values`, overwriting
+/// it in the process.  Rather than building the whole table of
+/// differences, we replace each level with the one below it, and
+/// keep a running total of the values at the right-hand end (which
+/// is what the extrapolated value is built up from).
+fn extrapolate_in_place(values: &mut [i64], max_depth: usize) -> Result<i64, ExtrapolationError> {
+    if max_depth > DEFAULT_MAX_DEPTH {
+        return Err(ExtrapolationError::DepthLimitTooLarge { max_depth });
+    }
+    if values.len() < 2 {
+        return Err(ExtrapolationError::TooShort);
+    }
+    let mut total = 0;
+    let mut len = values.len();
+    let mut depth = 0;
+    while values[..len].iter().any(|&n| n != 0) {
+        if len == 1 {
+            // The next level would be empty.
+            return Err(ExtrapolationError::NeverReachesZero);
+        }
+        if depth == max_depth {
+            return Err(ExtrapolationError::MaxDepthExceeded { depth });
+        }
+        total += values[len - 1];
+        // Each value is replaced before the one after it, so `b` is
+        // still the value from the level above.
+        let level = Cell::from_mut(&mut values[..len]).as_slice_of_cells();
+        for [a, b] in windows_exact(level) {
+            a.set(b.get() - a.get());
+        }
+        len -= 1;
+        depth += 1;
+    }
+    Ok(total)
+}
+
+/// Predicts the value which comes after (or, going backwards, before)
+/// `seq`.  A sequence run backwards has the same differences, only
+/// negated, so predicting the value before the first is the same as
+/// predicting the next value of the reversed sequence.
+///
+/// `scratch` is working space; passing the same vector for each
+/// sequence avoids allocating memory each time.
+fn extrapolate_with(
+    seq: &[i32],
+    direction: Direction,
+    max_depth: usize,
+    scratch: &mut Vec<i64>,
+) -> Result<i64, ExtrapolationError> {
+    scratch.clear();
+    match direction {
+        Direction::Forwards => scratch.extend(seq.iter().copied().map(i64::from)),
+        Direction::Backwards => scratch.extend(seq.iter().rev().copied().map(i64::from)),
+    }
+    extrapolate_in_place(scratch, max_depth)
+}
+
+/// Predicts the value which comes after (or, going backwards, before)
+/// `seq`, taking differences at most `DEFAULT_MAX_DEPTH` times.
+pub fn extrapolate(seq: &[i32], direction: Direction) -> Result<i64, ExtrapolationError> {
+    extrapolate_with(
+        seq,
+        direction,
+        DEFAULT_MAX_DEPTH,
+        &mut Vec::with_capacity(seq.len()),
+    )
+}
+
+#[test]
+fn test_extrapolate() {
+    use Direction::*;
+    assert_eq!(extrapolate(&[10, 13, 16, 21, 30, 45], Forwards), Ok(68));
+    assert_eq!(extrapolate(&[10, 13, 16, 21, 30, 45], Backwards), Ok(5));
+    assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15], Forwards), Ok(18));
+    assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15], Backwards), Ok(-3));
+    assert_eq!(extrapolate(&[7, 7], Forwards), Ok(7));
+    assert_eq!(extrapolate(&[1, 2, 3], Backwards), Ok(0));
+    assert_eq!(extrapolate(&[0, 0, 0], Forwards), Ok(0));
+}
+
+#[test]
+fn test_extrapolate_degenerate() {
+    use Direction::*;
+    use ExtrapolationError::*;
+    assert_eq!(extrapolate(&[7], Forwards), Err(TooShort));
+    assert_eq!(extrapolate(&[7], Backwards), Err(TooShort));
+    assert_eq!(extrapolate(&[], Forwards), Err(TooShort));
+    // These have no level of differences which is all zero.
+    assert_eq!(extrapolate(&[1, 2, 4], Forwards), Err(NeverReachesZero));
+    assert_eq!(extrapolate(&[7, 8], Forwards), Err(NeverReachesZero));
+    assert_eq!(extrapolate(&[7, 8], Backwards), Err(NeverReachesZero));
+    // Powers of two are their own differences, so they never reach
+    // zero.
+    let powers: Vec<i32> = (0..31).map(|n| 1 << n).collect();
+    assert_eq!(
+        extrapolate_with(&powers, Forwards, 5, &mut Vec::new()),
+        Err(MaxDepthExceeded { depth: 5 })
+    );
+    // The values alternate in sign, and double in size at each level;
+    // this is the worst case for overflow.
+    let alternating: Vec<i32> = (0..40)
+        .map(|n| if n % 2 == 0 { i32::MAX } else { i32::MIN })
+        .collect();
+    assert_eq!(
+        extrapolate(&alternating, Forwards),
+        Err(MaxDepthExceeded {
+            depth: DEFAULT_MAX_DEPTH
+        })
+    );
+    assert_eq!(
+        extrapolate(&alternating[..DEFAULT_MAX_DEPTH], Backwards),
+        Err(NeverReachesZero)
+    );
+}
+
+/// Sums the predicted values for each sequence in `sequences`,
+/// taking differences at most `max_depth` times for each.
+pub fn sum_extrapolated(
+    sequences: &[Vec<i32>],
+    direction: Direction,
+    max_depth: usize,
+) -> Result<i64, ExtrapolationError> {
+    let mut scratch = Vec::new();
+    sequences
+        .iter()
+        .map(|seq| extrapolate_with(seq, direction, max_depth, &mut scratch))
+        .sum()
+}
+
+#[test]
+fn test_sum_extrapolated() {
+    let sequences = vec![
+        vec![0, 3, 6, 9, 12, 15],
+        vec![1, 3, 6, 10, 15, 21],
+        vec![10, 13, 16, 21, 30, 45],
+    ];
+    assert_eq!(
+        sum_extrapolated(&sequences, Direction::Forwards, DEFAULT_MAX_DEPTH),
+        Ok(114)
+    );
+    assert_eq!(
+        sum_extrapolated(&sequences, Direction::Backwards, DEFAULT_MAX_DEPTH),
+        Ok(2)
+    );
+    assert_eq!(
+        sum_extrapolated(&sequences, Direction::Forwards, 2),
+        Err(ExtrapolationError::MaxDepthExceeded { depth: 2 })
+    );
+}
+
+pub fn number_seq(s: &str) -> Result<Vec<i32>, Fail> {
+    s.split_whitespace()
+        .map(|num| {
+            num.parse()
+                .map_err(|e| fail!("{num} should be a valid number: {e}"))
+        })
+        .collect()
+}
+
+#[test]
+fn test_number_seq() {
+    assert_eq!(
+        number_seq("10 13 16 21 30 45"),
+        Ok(vec![10, 13, 16, 21, 30, 45])
+    );
+    assert!(number_seq("10 x").is_err());
+}
+
+pub fn parse_input(s: &str) -> Result<Vec<Vec<i32>>, Fail> {
+    s.split_terminator('\n').map(number_seq).collect()
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(9).expect("there should be an example")
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day09::part1;
+/// let example = lib::examples::examples(9)[0];
+/// assert_eq!(part1(example), Ok(114));
+/// ```
+pub fn part1(input: &str) -> Result<i64, Fail> {
+    part1_with(input, DEFAULT_MAX_DEPTH)
+}
+
+/// Computes the answer to part 1, taking differences at most
+/// `max_depth` times for each sequence.
+pub fn part1_with(input: &str, max_depth: usize) -> Result<i64, Fail> {
+    Ok(sum_extrapolated(
+        &parse_input(input)?,
+        Direction::Forwards,
+        max_depth,
+    )?)
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(get_example()), Ok(114));
+    assert_eq!(
+        part1("1 2 3\n4\n"),
+        Err(Fail(
+            "a sequence needs at least two values to extrapolate".to_string()
+        ))
+    );
+    assert!(part1_with(get_example(), 2).is_err());
+    assert_eq!(
+        part1_with(get_example(), DEFAULT_MAX_DEPTH + 1),
+        Err(Fail(
+            "the differences could overflow after 33 levels (the limit is 32)".to_string()
+        ))
+    );
+    assert_eq!(
+        part1("1 2 4 8 16\n"),
+        Err(Fail(
+            "the differences ran out before they were all zero".to_string()
+        ))
+    );
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day09::part2;
+/// let example = lib::examples::examples(9)[0];
+/// assert_eq!(part2(example), Ok(2));
+/// ```
+pub fn part2(input: &str) -> Result<i64, Fail> {
+    part2_with(input, DEFAULT_MAX_DEPTH)
+}
+
+/// Computes the answer to part 2, taking differences at most
+/// `max_depth` times for each sequence.
+pub fn part2_with(input: &str, max_depth: usize) -> Result<i64, Fail> {
+    Ok(sum_extrapolated(
+        &parse_input(input)?,
+        Direction::Backwards,
+        max_depth,
+    )?)
+}
+
+#[test]
+fn test_part2() {
+    assert_eq!(part2(get_example()), Ok(2));
+    assert!(part2_with(get_example(), usize::MAX).is_err());
+}
@@ -0,0 +1,707 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter, Write};
+use std::str::{self, FromStr};
+
+use crate::error::Fail;
+use crate::fail;
+use crate::graph::bfs_with_distances;
+use crate::grid::{bounds, CompassDirection, Position};
+use crate::solver::Notes;
+use crate::{debug, info, trace};
+
+pub const NOTES: Notes = Notes {
+    title: "Pipe Maze",
+    algorithm: "A breadth-first search from the start finds the loop and the farthest point on it.  For part 2, a scan along each row counts the crossings of the loop to decide which cells are inside it.",
+    complexity: "O(n) in the number of cells",
+};
+
+/// A piece of pipe, named after the character which shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pipe {
+    PipeF,
+    PipeJ,
+    PipeL,
+    Pipe7,
+    PipeH,
+    PipeV,
+}
+
+impl TryFrom<char> for Pipe {
+    type Error = Fail;
+    fn try_from(ch: char) -> Result<Pipe, Self::Error> {
+        match ch {
+            '-' => Ok(Pipe::PipeH),
+            '|' => Ok(Pipe::PipeV),
+            'F' => Ok(Pipe::PipeF),
+            'J' => Ok(Pipe::PipeJ),
+            'L' => Ok(Pipe::PipeL),
+            '7' => Ok(Pipe::Pipe7),
+            _ => Err(fail!("not a pipe character: {ch}")),
+        }
+    }
+}
+
+/// Every kind of pipe, in the order in which `StartMode::Search`
+/// prefers them.
+const ALL_PIPES: [Pipe; 6] = [
+    Pipe::PipeF,
+    Pipe::PipeJ,
+    Pipe::PipeL,
+    Pipe::Pipe7,
+    Pipe::PipeH,
+    Pipe::PipeV,
+];
+
+impl Pipe {
+    /// Returns the directions in which the pipe leads.
+    fn exits(&self) -> [CompassDirection; 2] {
+        use CompassDirection::*;
+        match self {
+            Pipe::PipeJ => [North, West],
+            Pipe::PipeF => [East, South],
+            Pipe::Pipe7 => [West, South],
+            Pipe::PipeL => [East, North],
+            Pipe::PipeH => [East, West],
+            Pipe::PipeV => [North, South],
+        }
+    }
+
+    /// Returns true if the pipe connects to the cell to its north.
+    fn goes_north(&self) -> bool {
+        matches!(self, Pipe::PipeJ | Pipe::PipeL | Pipe::PipeV)
+    }
+
+    /// Returns the box-drawing character which looks like this pipe.
+    fn box_char(&self) -> char {
+        match self {
+            Pipe::PipeF => '┌',
+            Pipe::PipeJ => '┘',
+            Pipe::PipeL => '└',
+            Pipe::Pipe7 => '┐',
+            Pipe::PipeH => '─',
+            Pipe::PipeV => '│',
+        }
+    }
+}
+
+impl Display for Pipe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_char(match self {
+            Pipe::PipeF => 'F',
+            Pipe::PipeJ => 'J',
+            Pipe::PipeL => 'L',
+            Pipe::Pipe7 => '7',
+            Pipe::PipeH => '-',
+            Pipe::PipeV => '|',
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Grid {
+    cells: HashMap<Position, Pipe>,
+    start: Position,
+}
+
+impl Grid {
+    fn neighbours(&self, pos: &Position) -> Vec<Position> {
+        trace!("finding neighbours of {pos}");
+        let mut neighbours = match self.cells.get(pos) {
+            None => vec![],
+            Some(pipe) => pipe.exits().iter().map(|d| pos.move_direction(d)).collect(),
+        };
+        neighbours.retain(|pos| self.cells.contains_key(pos));
+        neighbours
+    }
+
+    fn identify_start_pos_pipe(&mut self) -> Result<(), Fail> {
+        // Decide if the start pipe has an exit in each of the cardinal
+        // directions: it does if the pipe next to it in that direction
+        // leads back to it.
+        let [north, east, south, west] = [
+            CompassDirection::North,
+            CompassDirection::East,
+            CompassDirection::South,
+            CompassDirection::West,
+        ]
+        .map(|d| self.leads_back(&self.start, &d));
+        let insufficient =
+            || Fail("cannot determine start pipe type: insufficient exits".to_string());
+        let toomany = || Fail("cannot determine start pipe type: too many exits".to_string());
+        const F: bool = false;
+        const T: bool = true;
+        let pipe: Pipe = match (north, east, south, west) {
+            (F, F, F, F) => Err(insufficient()),
+            (F, F, F, T) => Err(insufficient()),
+            (F, F, T, F) => Err(insufficient()),
+            (F, F, T, T) => Ok(Pipe::Pipe7),
+            (F, T, F, F) => Err(insufficient()),
+            (F, T, F, T) => Ok(Pipe::PipeH),
+            (F, T, T, F) => Ok(Pipe::PipeF),
+            (F, T, T, T) => Err(toomany()),
+            (T, F, F, F) => Err(insufficient()),
+            (T, F, F, T) => Ok(Pipe::PipeJ),
+            (T, F, T, F) => Ok(Pipe::PipeV),
+            (T, F, T, T) => Err(toomany()),
+            (T, T, F, F) => Ok(Pipe::PipeL),
+            (T, T, F, T) => Err(toomany()),
+            (T, T, T, F) => Err(toomany()),
+            (T, T, T, T) => Err(toomany()),
+        }?;
+        self.cells.insert(self.start, pipe);
+        Ok(())
+    }
+
+    /// Returns true if the pipe next to `pos` in direction `d` leads
+    /// back to `pos`.
+    fn leads_back(&self, pos: &Position, d: &CompassDirection) -> bool {
+        self.cells
+            .get(&pos.move_direction(d))
+            .is_some_and(|pipe| pipe.exits().contains(&d.reversed()))
+    }
+
+    /// Follows the pipes from the start until we get back there,
+    /// returning the number of pipes in the loop, or `None` if the
+    /// pipes don't lead back to the start.
+    fn loop_length(&self) -> Option<usize> {
+        let exits = self
+            .cells
+            .get(&self.start)?
+            .exits()
+            .map(|d| self.start.move_direction(&d));
+        let mut prev = self.start;
+        let mut pos = exits[0];
+        let mut length = 1;
+        while pos != self.start {
+            if length > self.cells.len() {
+                return None;
+            }
+            let [a, b] = self
+                .cells
+                .get(&pos)?
+                .exits()
+                .map(|d| pos.move_direction(&d));
+            let next = if a == prev {
+                b
+            } else if b == prev {
+                a
+            } else {
+                return None;
+            };
+            (prev, pos) = (pos, next);
+            length += 1;
+        }
+        exits.contains(&prev).then_some(length)
+    }
+
+    /// Tries each kind of pipe which fits between the start's
+    /// neighbours, keeping those which close a loop, and puts the one
+    /// making the longest loop at the start.
+    fn search_start_pos_pipe(&mut self) -> Result<StartResolution, Fail> {
+        let mut candidates: Vec<(Pipe, usize)> = Vec::new();
+        for pipe in ALL_PIPES {
+            if !pipe.exits().iter().all(|d| self.leads_back(&self.start, d)) {
+                continue;
+            }
+            self.cells.insert(self.start, pipe.clone());
+            if let Some(length) = self.loop_length() {
+                candidates.push((pipe, length));
+            }
+        }
+        // Among loops of equal length, prefer the pipe which comes
+        // first in ALL_PIPES.
+        let Some((pipe, _)) = candidates
+            .iter()
+            .rev()
+            .max_by_key(|(_, length)| *length)
+            .cloned()
+        else {
+            self.cells.remove(&self.start);
+            return Err(fail!(
+                "cannot determine start pipe type: no pipe at {} closes a loop",
+                self.start
+            ));
+        };
+        self.cells.insert(self.start, pipe.clone());
+        Ok(StartResolution {
+            pipe,
+            candidates: candidates.into_iter().map(|(pipe, _)| pipe).collect(),
+        })
+    }
+}
+
+/// How to decide which kind of pipe is hidden under the 'S'.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StartMode {
+    /// Deduce it from which neighbours lead back to the start, failing
+    /// unless exactly two do.
+    #[default]
+    Strict,
+    /// Try each kind of pipe which fits, and keep one which closes a
+    /// loop.  This copes with inputs where pipes which are not part of
+    /// the loop also lead to the start.
+    Search,
+}
+
+pub const ALL_START_MODES: [StartMode; 2] = [StartMode::Strict, StartMode::Search];
+
+impl Display for StartMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StartMode::Strict => "strict",
+            StartMode::Search => "search",
+        })
+    }
+}
+
+impl FromStr for StartMode {
+    type Err = Fail;
+
+    fn from_str(s: &str) -> Result<StartMode, Fail> {
+        ALL_START_MODES
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| fail!("unknown start mode {s}"))
+    }
+}
+
+/// Which kind of pipe was chosen for the start, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartResolution {
+    /// The pipe placed at the start.
+    pub pipe: Pipe,
+    /// Every pipe which would have closed a loop through the start.
+    /// If there are several, `pipe` is the one making the longest
+    /// loop.
+    pub candidates: Vec<Pipe>,
+}
+
+impl Display for StartResolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "the start is {}", self.pipe)?;
+        if self.candidates.len() > 1 {
+            f.write_str(" (of")?;
+            for pipe in self.candidates.iter() {
+                write!(f, " {pipe}")?;
+            }
+            f.write_str(", which would all close a loop)")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(bounds) = bounds(self.cells.keys()) {
+            for y in (bounds.top_left.y)..=(bounds.bottom_right.y) {
+                for x in (bounds.top_left.x)..=(bounds.bottom_right.x) {
+                    let pos = Position { x, y };
+                    if pos == self.start {
+                        f.write_char('S')?;
+                    } else {
+                        match self.cells.get(&Position { x, y }) {
+                            Some(pipe) => {
+                                write!(f, "{pipe}")?;
+                            }
+                            None => {
+                                f.write_char('.')?;
+                            }
+                        }
+                    }
+                }
+                f.write_char('\n')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_input(s: &str) -> Result<Grid, Fail> {
+    parse_input_with(s, StartMode::Strict).map(|(grid, _)| grid)
+}
+
+/// Parses the input, deciding what is under the 'S' according to
+/// `mode`.
+pub fn parse_input_with(s: &str, mode: StartMode) -> Result<(Grid, StartResolution), Fail> {
+    let mut cells = HashMap::new();
+    let mut start: Option<Position> = None;
+    for (y, line) in s.split_terminator('\n').enumerate() {
+        for (x, ch) in line.chars().enumerate() {
+            match ch {
+                '.' => {
+                    continue;
+                }
+                'S' => {
+                    start = Some(Position {
+                        x: x as i64,
+                        y: y as i64,
+                    });
+                }
+                'J' | 'L' | 'F' | '7' | '-' | '|' => {
+                    let pipe = Pipe::try_from(ch)?;
+                    cells.insert(
+                        Position {
+                            x: x as i64,
+                            y: y as i64,
+                        },
+                        pipe,
+                    );
+                }
+                _ => return Err(fail!("unrecognised character {ch}")),
+            }
+        }
+    }
+    if let Some(start) = start {
+        let mut grid = Grid { cells, start };
+        let resolution = match mode {
+            StartMode::Strict => {
+                grid.identify_start_pos_pipe()?;
+                let pipe = grid.cells[&start].clone();
+                StartResolution {
+                    candidates: vec![pipe.clone()],
+                    pipe,
+                }
+            }
+            StartMode::Search => grid.search_start_pos_pipe()?,
+        };
+        Ok((grid, resolution))
+    } else {
+        Err(Fail("no known start position".to_string()))
+    }
+}
+
+#[test]
+fn test_parse_input() {
+    let grid = parse_input(concat!(
+        "7-F7-\n", ".FJ|7\n", "SJLL7\n", "|F--J\n", "LJ.LJ\n",
+    ))
+    .expect("test input is valid");
+    let expected_start = Position { x: 0, y: 2 };
+    assert_eq!(grid.start, expected_start);
+    assert_eq!(grid.cells.get(&expected_start), Some(&Pipe::PipeF))
+}
+
+#[test]
+fn test_start_mode() {
+    for mode in ALL_START_MODES {
+        assert_eq!(mode.to_string().parse::<StartMode>(), Ok(mode));
+    }
+    assert!("lenient".parse::<StartMode>().is_err());
+
+    // The pipes to the north, south and west all lead to the start,
+    // but only the north and south ones are part of a loop.
+    let input = concat!(".....\n", ".F-7.\n", "-S.|.\n", ".L-J.\n", ".....\n",);
+    assert!(parse_input(input).is_err());
+    let (grid, resolution) =
+        parse_input_with(input, StartMode::Search).expect("test input is valid");
+    assert_eq!(
+        resolution,
+        StartResolution {
+            pipe: Pipe::PipeV,
+            candidates: vec![Pipe::PipeV],
+        }
+    );
+    assert_eq!(grid.analyse_loop().length, 8);
+    assert_eq!(resolution.to_string(), "the start is |");
+
+    // Two loops of the same length meet at the start.
+    let input = concat!("F-7..\n", "|.|..\n", "L-S-7\n", "..|.|\n", "..L-J\n",);
+    let (grid, resolution) =
+        parse_input_with(input, StartMode::Search).expect("test input is valid");
+    assert_eq!(resolution.pipe, Pipe::PipeF);
+    assert_eq!(resolution.candidates, vec![Pipe::PipeF, Pipe::PipeJ]);
+    assert_eq!(
+        resolution.to_string(),
+        "the start is F (of F J, which would all close a loop)"
+    );
+    assert_eq!(grid.analyse_loop().length, 8);
+    assert_eq!(part1_with(input, StartMode::Search), Ok(4));
+
+    // A search fails if no pipe closes a loop.
+    assert!(parse_input_with("-S-\n", StartMode::Search).is_err());
+    // But it agrees with the strict mode where that succeeds.
+    let example = crate::examples::examples(10)[0];
+    assert_eq!(
+        parse_input_with(example, StartMode::Search).map(|(grid, _)| grid),
+        parse_input(example)
+    );
+}
+
+fn measure_distances(grid: &Grid) -> HashMap<Position, usize> {
+    bfs_with_distances(grid.start, |pos| grid.neighbours(pos))
+}
+
+/// What we learn by walking both ways round the loop from the start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopAnalysis {
+    /// The number of pipes in the loop.
+    pub length: usize,
+    /// The pipe farthest along the loop from the start.  A loop in a
+    /// grid always has an even length, so there is only one.
+    pub farthest: Position,
+    /// How far along the loop each of its pipes is from the start.
+    pub distances: HashMap<Position, usize>,
+}
+
+impl LoopAnalysis {
+    /// Returns the number of steps from the start to `farthest`.
+    pub fn max_distance(&self) -> usize {
+        self.distances.get(&self.farthest).copied().unwrap_or(0)
+    }
+}
+
+impl Grid {
+    pub fn analyse_loop(&self) -> LoopAnalysis {
+        let distances = measure_distances(self);
+        // The distances always include the start position.
+        let farthest = positions_by_distance(&distances)
+            .pop_last()
+            .and_then(|(_, positions)| positions.first().copied())
+            .unwrap_or(self.start);
+        LoopAnalysis {
+            length: distances.len(),
+            farthest,
+            distances,
+        }
+    }
+}
+
+#[test]
+fn test_analyse_loop() {
+    let grid = parse_input(crate::examples::examples(10)[0]).expect("test input is valid");
+    let analysis = grid.analyse_loop();
+    assert_eq!(analysis.length, 16);
+    assert_eq!(analysis.farthest, Position { x: 4, y: 2 });
+    assert_eq!(analysis.max_distance(), 8);
+    assert_eq!(analysis.distances.get(&grid.start), Some(&0));
+
+    let grid = parse_input(concat!("S-7\n", "|.|\n", "L-J\n")).expect("test input is valid");
+    let analysis = grid.analyse_loop();
+    assert_eq!(analysis.length, 8);
+    assert_eq!(analysis.farthest, Position { x: 2, y: 2 });
+    assert_eq!(analysis.max_distance(), 4);
+}
+
+/// Groups the positions by their distance from the start.  Within
+/// each group the positions are in order, so the result doesn't
+/// depend on the order in which `distances` gives them to us.
+fn positions_by_distance(distances: &HashMap<Position, usize>) -> BTreeMap<usize, Vec<Position>> {
+    let mut inverted: BTreeMap<usize, Vec<Position>> = BTreeMap::new();
+    for (pos, steps) in distances.iter() {
+        inverted.entry(*steps).or_default().push(*pos);
+    }
+    for positions in inverted.values_mut() {
+        positions.sort();
+    }
+    inverted
+}
+
+#[test]
+fn test_positions_by_distance() {
+    let grid = parse_input(concat!(
+        "-L|F7\n", "7S-7|\n", "L|7||\n", "-L-J|\n", "L|-JF\n",
+    ))
+    .expect("test input is valid");
+    let expected = format!("{:?}", positions_by_distance(&measure_distances(&grid)));
+    for _ in 0..10 {
+        assert_eq!(
+            format!("{:?}", positions_by_distance(&measure_distances(&grid))),
+            expected
+        );
+    }
+    let by_distance = positions_by_distance(&measure_distances(&grid));
+    assert_eq!(
+        by_distance.get(&1),
+        Some(&vec![Position { x: 1, y: 2 }, Position { x: 2, y: 1 }])
+    );
+    assert_eq!(by_distance.get(&4), Some(&vec![Position { x: 3, y: 3 }]));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Classification {
+    Loop,
+    Inside,
+    Outside,
+}
+
+impl Grid {
+    /// Returns a copy of the grid without the junk pipes, that is,
+    /// keeping only the pipes which are part of the loop through the
+    /// start position.
+    pub fn clean(&self) -> Grid {
+        let on_loop = measure_distances(self);
+        Grid {
+            cells: self
+                .cells
+                .iter()
+                .filter(|(pos, _)| on_loop.contains_key(pos))
+                .map(|(pos, pipe)| (*pos, pipe.clone()))
+                .collect(),
+            start: self.start,
+        }
+    }
+
+    /// Classifies every cell within the bounds of the grid as being
+    /// part of the loop, enclosed by it, or outside it.
+    fn classify(&self) -> HashMap<Position, Classification> {
+        let main_loop = self.clean();
+        let mut result = HashMap::new();
+        if let Some(bounds) = bounds(self.cells.keys()) {
+            for y in bounds.rows() {
+                // Scanning along the row, we cross the loop each time
+                // we meet a loop pipe which has an exit to the north.
+                // Considering only northward exits means that a run
+                // like "L--7" counts as one crossing, but "L--J" as
+                // none.
+                let mut inside = false;
+                for x in bounds.columns() {
+                    let pos = Position { x, y };
+                    let classification = if let Some(pipe) = main_loop.cells.get(&pos) {
+                        if pipe.goes_north() {
+                            inside = !inside;
+                        }
+                        Classification::Loop
+                    } else if inside {
+                        Classification::Inside
+                    } else {
+                        Classification::Outside
+                    };
+                    result.insert(pos, classification);
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns a picture of the loop drawn with box-drawing
+    /// characters, with the enclosed cells marked 'I' and the cells
+    /// outside the loop marked 'O'.
+    pub fn overlay(&self) -> Overlay<'_> {
+        Overlay {
+            grid: self,
+            classification: self.classify(),
+        }
+    }
+}
+
+pub struct Overlay<'a> {
+    grid: &'a Grid,
+    classification: HashMap<Position, Classification>,
+}
+
+impl Display for Overlay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(bounds) = bounds(self.classification.keys()) {
+            for y in bounds.rows() {
+                for x in bounds.columns() {
+                    let pos = Position { x, y };
+                    let ch = match (self.classification.get(&pos), self.grid.cells.get(&pos)) {
+                        (Some(Classification::Loop), Some(pipe)) => pipe.box_char(),
+                        (Some(Classification::Inside), _) => 'I',
+                        _ => 'O',
+                    };
+                    f.write_char(ch)?;
+                }
+                f.write_char('\n')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_overlay() {
+    let grid = parse_input(concat!(
+        ".....\n", ".S-7.\n", ".|.|.\n", ".L-J.\n", ".....\n",
+    ))
+    .expect("test input is valid");
+    assert_eq!(grid.overlay().to_string(), "┌─┐\n│I│\n└─┘\n");
+}
+
+#[test]
+fn test_overlay_ignores_junk_pipes() {
+    let grid = parse_input(concat!(
+        "-L|F7\n", "7S-7|\n", "L|7||\n", "-L-J|\n", "L|-JF\n",
+    ))
+    .expect("test input is valid");
+    assert_eq!(
+        grid.overlay().to_string(),
+        concat!("OOOOO\n", "O┌─┐O\n", "O│I│O\n", "O└─┘O\n", "OOOOO\n")
+    );
+}
+
+#[test]
+fn test_clean() {
+    let grid = parse_input(concat!(
+        "-L|F7\n", "7S-7|\n", "L|7||\n", "-L-J|\n", "L|-JF\n",
+    ))
+    .expect("test input is valid");
+    let clean = grid.clean();
+    assert_eq!(clean.cells.len(), 8);
+    assert_eq!(clean.to_string(), concat!("S-7\n", "|.|\n", "L-J\n"));
+    // Cleaning a clean grid changes nothing.
+    assert_eq!(clean.clean(), clean);
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day10::part1;
+/// let example = lib::examples::examples(10)[0];
+/// assert_eq!(part1(example), Ok(8));
+/// ```
+pub fn part1(s: &str) -> Result<usize, Fail> {
+    part1_with(s, StartMode::Strict)
+}
+
+/// Computes the answer to part 1, deciding what is under the 'S'
+/// according to `mode`.
+pub fn part1_with(s: &str, mode: StartMode) -> Result<usize, Fail> {
+    let (grid, resolution) = parse_input_with(s, mode)?;
+    debug!("{resolution}");
+    debug!("grid:\n{grid}");
+    let analysis = grid.analyse_loop();
+    info!(
+        "the farthest point from the start is {}, {} steps away round a loop of {} pipes",
+        analysis.farthest,
+        analysis.max_distance(),
+        analysis.length
+    );
+    Ok(analysis.max_distance())
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(crate::examples::examples(10)[0]), Ok(8));
+    assert!(part1("F-7\n|.|\nL-J\n").is_err());
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day10::part2;
+/// let example = lib::examples::examples(10)[2];
+/// assert_eq!(part2(example), Ok(8));
+/// ```
+pub fn part2(s: &str) -> Result<usize, Fail> {
+    part2_with(s, StartMode::Strict)
+}
+
+/// Computes the answer to part 2, deciding what is under the 'S'
+/// according to `mode`.
+pub fn part2_with(s: &str, mode: StartMode) -> Result<usize, Fail> {
+    let (grid, _) = parse_input_with(s, mode)?;
+    Ok(grid
+        .classify()
+        .values()
+        .filter(|c| **c == Classification::Inside)
+        .count())
+}
+
+#[test]
+fn test_part2() {
+    let examples = crate::examples::examples(10);
+    assert_eq!(part2(examples[1]), Ok(4));
+    assert_eq!(part2(examples[2]), Ok(8));
+}
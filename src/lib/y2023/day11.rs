@@ -0,0 +1,585 @@
+use std::cmp::max;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter, Write};
+use std::str;
+
+use bimap::BiMap;
+use itertools::Itertools;
+
+use crate::error::Fail;
+use crate::grid::{BoundingBox, Metric, Position};
+use crate::solver::{Backend, Notes};
+
+pub const NOTES: Notes = Notes {
+    title: "Cosmic Expansion",
+    algorithm: "Empty rows and columns are found, each galaxy's coordinates are adjusted for the expansion, and the Manhattan distances between every pair of galaxies are summed.",
+    complexity: "O(n + g²) for n cells and g galaxies",
+};
+
+#[derive(Debug)]
+pub struct Image {
+    occupied_rows: BTreeMap<i64, BTreeSet<i64>>,
+    occupied_cols: BTreeSet<i64>,
+    bounds: BoundingBox,
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for y in (self.bounds.top_left.y)..=(self.bounds.bottom_right.y) {
+            match self.occupied_rows.get(&y) {
+                Some(row) => {
+                    for x in (self.bounds.top_left.x)..=(self.bounds.bottom_right.x) {
+                        if row.contains(&x) {
+                            f.write_char('#')?;
+                        } else {
+                            f.write_char('.')?;
+                        }
+                    }
+                }
+                None => {
+                    for _ in (self.bounds.top_left.x)..=(self.bounds.bottom_right.x) {
+                        f.write_char('.')?;
+                    }
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl Image {
+    /// Returns the number of galaxies in the image.
+    pub fn popcount(&self) -> usize {
+        self.occupied_rows.values().map(|row| row.len()).sum()
+    }
+
+    /// Returns the columns which contain no galaxy, in order.  These
+    /// are the columns which get wider when the universe expands.
+    pub fn unoccupied_cols(&self) -> Vec<i64> {
+        self.bounds
+            .columns()
+            .filter(|col| !self.occupied_cols.contains(col))
+            .collect()
+    }
+
+    /// Returns the rows which contain no galaxy, in order.  These are
+    /// the rows which get taller when the universe expands.
+    pub fn unoccupied_rows(&self) -> Vec<i64> {
+        self.bounds
+            .rows()
+            .filter(|y| !self.occupied_rows.contains_key(y))
+            .collect()
+    }
+
+    /// Returns some statistics about the image.
+    pub fn stats(&self) -> ImageStats {
+        ImageStats {
+            galaxies: self.popcount(),
+            width: self.bounds.width(),
+            height: self.bounds.height(),
+            empty_rows: self.unoccupied_rows(),
+            empty_cols: self.unoccupied_cols(),
+        }
+    }
+}
+
+/// Some statistics about an `Image`, mostly useful for understanding
+/// how much the universe will expand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageStats {
+    /// The number of galaxies.
+    pub galaxies: usize,
+    /// The width of the image (before expansion).
+    pub width: i64,
+    /// The height of the image (before expansion).
+    pub height: i64,
+    /// The rows which contain no galaxy.
+    pub empty_rows: Vec<i64>,
+    /// The columns which contain no galaxy.
+    pub empty_cols: Vec<i64>,
+}
+
+impl ImageStats {
+    /// Returns the fraction of the image's cells which hold a galaxy.
+    pub fn density(&self) -> f64 {
+        match self.width * self.height {
+            0 => 0.0,
+            area => self.galaxies as f64 / area as f64,
+        }
+    }
+
+    /// Returns how much wider and taller the image becomes when each
+    /// empty row and column is made `expandby` times larger.
+    pub fn expansion_delta(&self, expandby: i64) -> (i64, i64) {
+        let extra = expandby - 1;
+        (
+            extra * self.empty_cols.len() as i64,
+            extra * self.empty_rows.len() as i64,
+        )
+    }
+}
+
+impl Display for ImageStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{} galaxies in {}x{} ({:.2}% density), {} empty rows, {} empty columns",
+            self.galaxies,
+            self.width,
+            self.height,
+            self.density() * 100.0,
+            self.empty_rows.len(),
+            self.empty_cols.len()
+        )
+    }
+}
+
+pub fn parse_input(s: &str) -> Result<Image, Fail> {
+    let mut bbox: Option<BoundingBox> = None;
+    let mut occupied_rows: BTreeMap<i64, BTreeSet<i64>> = BTreeMap::new();
+    let mut occupied_cols: BTreeSet<i64> = BTreeSet::new();
+    for (y, line) in s.split_terminator('\n').enumerate() {
+        for (x, ch) in line.chars().enumerate() {
+            let pos = Position {
+                x: x as i64,
+                y: y as i64,
+            };
+            match bbox.as_mut() {
+                None => {
+                    bbox = Some(BoundingBox {
+                        top_left: pos,
+                        bottom_right: pos,
+                    });
+                }
+                Some(b) => {
+                    b.update(&pos);
+                }
+            }
+            fn just(z: i64) -> BTreeSet<i64> {
+                let mut result = BTreeSet::new();
+                result.insert(z);
+                result
+            }
+            if ch == '#' {
+                occupied_cols.insert(pos.x);
+                occupied_rows
+                    .entry(pos.y)
+                    .and_modify(|row| {
+                        row.insert(pos.x);
+                    })
+                    .or_insert_with(|| just(pos.x));
+            }
+        }
+    }
+    match bbox {
+        Some(bounds) => Ok(Image {
+            occupied_rows,
+            occupied_cols,
+            bounds,
+        }),
+        None => Err(Fail("empty input".to_string())),
+    }
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(11).expect("there should be an example")
+}
+
+#[cfg(test)]
+fn get_example_image() -> Image {
+    parse_input(get_example()).expect("example should be valid")
+}
+
+#[test]
+fn test_parse() {
+    let image =
+        parse_input(concat!("....\n", ".##.\n", "..#.\n")).expect("test input should be valid");
+    assert_eq!(image.popcount(), 3);
+    assert_eq!(
+        image.bounds,
+        BoundingBox {
+            top_left: Position { x: 0, y: 0 },
+            bottom_right: Position { x: 3, y: 2 },
+        }
+    );
+}
+
+#[test]
+fn test_image_display() {
+    let representation = get_example();
+    let img = parse_input(representation).expect("example should be valid");
+    assert_eq!(&img.to_string(), representation);
+}
+
+#[test]
+fn test_col_occupation() {
+    let img = get_example_image();
+    assert_eq!(img.unoccupied_cols(), vec![2, 5, 8]);
+}
+
+#[test]
+fn test_row_occupation() {
+    let img = get_example_image();
+    assert_eq!(img.unoccupied_rows(), vec![3, 7]);
+}
+
+#[test]
+fn test_stats() {
+    let stats = get_example_image().stats();
+    assert_eq!(
+        stats,
+        ImageStats {
+            galaxies: 9,
+            width: 10,
+            height: 10,
+            empty_rows: vec![3, 7],
+            empty_cols: vec![2, 5, 8],
+        }
+    );
+    assert_eq!(stats.density(), 0.09);
+    assert_eq!(stats.expansion_delta(PART1_EXPANSION), (3, 2));
+    assert_eq!(stats.expansion_delta(10), (27, 18));
+    assert_eq!(
+        stats.to_string(),
+        "9 galaxies in 10x10 (9.00% density), 2 empty rows, 3 empty columns"
+    );
+}
+
+/// The image as it is after the universe has expanded.
+pub struct ExpandedImage<'a> {
+    original: &'a Image,
+    // x_map maps x values from the expanded coordinate system to the
+    // original coordinate system.
+    x_map: BiMap<i64, i64>,
+    // y_map maps y values from the expanded coordinate system to the
+    // original coordinate system.
+    y_map: BiMap<i64, i64>,
+    bounds: BoundingBox,
+}
+
+/// How many times larger each empty row or column becomes in part 1.
+pub const PART1_EXPANSION: i64 = 2;
+/// How many times larger each empty row or column becomes in part 2.
+pub const PART2_EXPANSION: i64 = 1_000_000;
+
+/// Expands the universe, making each empty row and column of `img`
+/// `expandby` times larger.
+pub fn expand(img: &Image, expandby: i64) -> ExpandedImage<'_> {
+    let extra_rows_or_columns = expandby - 1;
+    let (x_map, max_x) = {
+        let mut empty_col_count: i64 = 0;
+        let mut x_map: BiMap<i64, i64> = Default::default();
+        let mut max_x = img.bounds.top_left.x;
+        for orig_x in (img.bounds.top_left.x)..=(img.bounds.bottom_right.x) {
+            let expanded_x = empty_col_count + orig_x;
+            max_x = max(max_x, expanded_x);
+            if img.occupied_cols.contains(&orig_x) {
+                x_map.insert(expanded_x, orig_x);
+            } else {
+                empty_col_count += extra_rows_or_columns;
+            }
+        }
+        (x_map, max_x)
+    };
+
+    let (y_map, max_y) = {
+        let mut empty_row_count: i64 = 0;
+        let mut y_map: BiMap<i64, i64> = Default::default();
+        let mut max_y = img.bounds.top_left.y;
+
+        for orig_y in (img.bounds.top_left.y)..=(img.bounds.bottom_right.y) {
+            let expanded_y = empty_row_count + orig_y;
+            max_y = max(max_y, expanded_y);
+            if img.occupied_rows.contains_key(&orig_y) {
+                y_map.insert(expanded_y, orig_y);
+            } else {
+                empty_row_count += extra_rows_or_columns;
+            }
+        }
+        (y_map, max_y)
+    };
+
+    ExpandedImage {
+        original: img,
+        x_map,
+        y_map,
+        bounds: BoundingBox {
+            top_left: Position {
+                x: img.bounds.top_left.x,
+                y: img.bounds.top_left.y,
+            },
+            bottom_right: Position { x: max_x, y: max_y },
+        },
+    }
+}
+
+fn print_empty_row(f: &mut Formatter<'_>, len: i64) -> Result<(), std::fmt::Error> {
+    for _ in 0..len {
+        f.write_char('.')?;
+    }
+    Ok(())
+}
+
+impl<'a> Display for ExpandedImage<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for y in (self.bounds.top_left.y)..=(self.bounds.bottom_right.y) {
+            match self
+                .y_map
+                .get_by_left(&y)
+                .and_then(|orig_y| self.original.occupied_rows.get(orig_y))
+            {
+                Some(row) => {
+                    for x in (self.bounds.top_left.x)..=(self.bounds.bottom_right.x) {
+                        match self.x_map.get_by_left(&x) {
+                            Some(orig_x) if row.contains(orig_x) => {
+                                f.write_char('#')?;
+                            }
+                            _ => {
+                                f.write_char('.')?;
+                            }
+                        }
+                    }
+                }
+                None => {
+                    let len = 1 + self.bounds.bottom_right.x - self.bounds.top_left.x;
+                    print_empty_row(f, len)?;
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+/// A galaxy in the expanded image.  The galaxies are numbered from 1
+/// in reading order, as in the puzzle description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Galaxy {
+    pub number: usize,
+    pub pos: Position,
+}
+
+impl ExpandedImage<'_> {
+    /// Returns the galaxies in reading order.
+    pub fn galaxies(&self) -> impl Iterator<Item = Galaxy> + Clone + '_ {
+        self.original
+            .occupied_rows
+            .iter()
+            .flat_map(move |(y, xs)| {
+                let expanded_y = *self
+                    .y_map
+                    .get_by_right(y)
+                    .expect("y_map entry should exist");
+                xs.iter().map(move |x| Position {
+                    x: *self
+                        .x_map
+                        .get_by_right(x)
+                        .expect("x_map entry should exist"),
+                    y: expanded_y,
+                })
+            })
+            .enumerate()
+            .map(|(i, pos)| Galaxy { number: i + 1, pos })
+    }
+
+    /// Returns each pair of galaxies once, with the lower-numbered
+    /// galaxy first.
+    pub fn galaxy_pairs(&self) -> impl Iterator<Item = (Galaxy, Galaxy)> + '_ {
+        self.galaxies().tuple_combinations()
+    }
+
+    /// Returns the position of each galaxy as comma-separated values,
+    /// with a header line.
+    pub fn galaxies_csv(&self) -> String {
+        let mut result = String::from("galaxy,x,y\n");
+        for Galaxy { number, pos } in self.galaxies() {
+            result.push_str(&format!("{number},{},{}\n", pos.x, pos.y));
+        }
+        result
+    }
+
+    /// Returns the distance between each pair of galaxies, measured
+    /// with `metric`, as comma-separated values with a header line.
+    pub fn pairs_csv(&self, metric: Metric) -> String {
+        let mut result = String::from("first,second,first_x,first_y,second_x,second_y,distance\n");
+        for (first, second) in self.galaxy_pairs() {
+            result.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                first.number,
+                second.number,
+                first.pos.x,
+                first.pos.y,
+                second.pos.x,
+                second.pos.y,
+                metric.distance(&first.pos, &second.pos)
+            ));
+        }
+        result
+    }
+}
+
+#[test]
+fn test_galaxies() {
+    let img = get_example_image();
+    let expanded = expand(&img, PART1_EXPANSION);
+    let galaxies: Vec<Galaxy> = expanded.galaxies().collect();
+    assert_eq!(galaxies.len(), 9);
+    assert_eq!(
+        galaxies[0],
+        Galaxy {
+            number: 1,
+            pos: Position { x: 4, y: 0 }
+        }
+    );
+    assert_eq!(galaxies[8].pos, Position { x: 5, y: 11 });
+    assert_eq!(expanded.galaxy_pairs().count(), 36);
+    // The examples from the puzzle description.
+    let distance = |a: usize, b: usize| galaxies[a - 1].pos.manhattan(&galaxies[b - 1].pos);
+    assert_eq!(distance(5, 9), 9);
+    assert_eq!(distance(1, 7), 15);
+    assert_eq!(distance(3, 6), 17);
+    assert_eq!(distance(8, 9), 5);
+
+    let csv = expanded.galaxies_csv();
+    assert_eq!(csv.lines().count(), 10);
+    assert!(csv.starts_with("galaxy,x,y\n1,4,0\n"));
+    let csv = expanded.pairs_csv(Metric::Manhattan);
+    assert_eq!(csv.lines().count(), 37);
+    assert_eq!(csv.lines().nth(1), Some("1,2,4,0,9,1,6"));
+    let total: i64 = csv
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.rsplit(',').next())
+        .map(|d| d.parse::<i64>().expect("distance should be a number"))
+        .sum();
+    assert_eq!(total, 374);
+}
+
+#[test]
+fn test_expand() {
+    let img = get_example_image();
+    let expanded = expand(&img, 2);
+    crate::assert_grid_snapshot!(expanded, "tests/snapshots/day11_expanded.txt");
+}
+
+/// Adds up the distances between each pair of galaxies, measured
+/// with `metric`.  The puzzle uses `Metric::Manhattan`.
+pub fn sum_distances(expanded: &ExpandedImage<'_>, metric: Metric) -> i64 {
+    expanded
+        .galaxy_pairs()
+        .map(|(first, second)| metric.distance(&first.pos, &second.pos))
+        .sum()
+}
+
+/// Like `sum_distances` with `Metric::Manhattan`, but without looking
+/// at each pair of galaxies.  The Manhattan distance is the sum of the
+/// distances in x and in y, which we can add up separately.  Once the
+/// x coordinates are sorted, each is at least as large as all those
+/// before it, so the i-th (counting from 0) adds i times itself, less
+/// the sum of those before it.  This takes O(g log g) time for g
+/// galaxies, rather than O(g²).
+pub fn sum_manhattan_distances_sorted(expanded: &ExpandedImage<'_>) -> i64 {
+    fn sum_of_differences(mut coords: Vec<i64>) -> i64 {
+        coords.sort_unstable();
+        let mut before = 0;
+        let mut total = 0;
+        for (i, z) in coords.iter().enumerate() {
+            total += i as i64 * z - before;
+            before += z;
+        }
+        total
+    }
+    let (xs, ys) = expanded.galaxies().map(|g| (g.pos.x, g.pos.y)).unzip();
+    sum_of_differences(xs) + sum_of_differences(ys)
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day11::part1;
+/// let example = lib::examples::examples(11)[0];
+/// assert_eq!(part1(example), Ok(374));
+/// ```
+pub fn part1(input: &str) -> Result<i64, Fail> {
+    Ok(sum_distances(
+        &expand(&parse_input(input)?, PART1_EXPANSION),
+        Metric::Manhattan,
+    ))
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(get_example()), Ok(374));
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day11::part2;
+/// let example = lib::examples::examples(11)[0];
+/// assert_eq!(part2(example), Ok(82000210));
+/// ```
+pub fn part2(input: &str) -> Result<i64, Fail> {
+    Ok(sum_distances(
+        &expand(&parse_input(input)?, PART2_EXPANSION),
+        Metric::Manhattan,
+    ))
+}
+
+#[test]
+fn test_expand_10_100() {
+    let img = get_example_image();
+    assert_eq!(sum_distances(&expand(&img, 10), Metric::Manhattan), 1030);
+    assert_eq!(sum_distances(&expand(&img, 100), Metric::Manhattan), 8410);
+    assert_eq!(sum_distances(&expand(&img, 2), Metric::Chebyshev), 266);
+    for expandby in [1, 2, 10, 100] {
+        let expanded = expand(&img, expandby);
+        assert_eq!(
+            sum_manhattan_distances_sorted(&expanded),
+            sum_distances(&expanded, Metric::Manhattan)
+        );
+    }
+}
+
+pub const BACKENDS: [Backend; 4] = [
+    Backend {
+        part: 1,
+        name: "pairwise",
+        description: "Add up the distance between each pair of galaxies",
+        slow: false,
+        solve: |input| Ok(part1(input)?.into()),
+    },
+    Backend {
+        part: 1,
+        name: "sorted",
+        description: "Add up the distances in x and in y separately, using sorted coordinates",
+        slow: false,
+        solve: |input| {
+            Ok(
+                sum_manhattan_distances_sorted(&expand(&parse_input(input)?, PART1_EXPANSION))
+                    .into(),
+            )
+        },
+    },
+    Backend {
+        part: 2,
+        name: "pairwise",
+        description: "Add up the distance between each pair of galaxies",
+        slow: false,
+        solve: |input| Ok(part2(input)?.into()),
+    },
+    Backend {
+        part: 2,
+        name: "sorted",
+        description: "Add up the distances in x and in y separately, using sorted coordinates",
+        slow: false,
+        solve: |input| {
+            Ok(
+                sum_manhattan_distances_sorted(&expand(&parse_input(input)?, PART2_EXPANSION))
+                    .into(),
+            )
+        },
+    },
+];
@@ -0,0 +1,922 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::str;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::grid::{BoundingBox, Position, Transform};
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Point of Incidence",
+    algorithm: "Every possible line of reflection is checked by comparing cells with their mirror images; part 2 looks for a line with exactly one mismatch.",
+    complexity: "O(n·(w + h)) for a pattern of n cells which is w wide and h high",
+};
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    rocks: BTreeSet<Position>,
+    bbox: BoundingBox,
+}
+
+/// Reads a grid of characters, using `is_solid` to decide whether each
+/// one is a rock (true) or ash (false).
+fn parse_cells<F>(s: &str, is_solid: F) -> Result<Pattern, Fail>
+where
+    F: Fn(char) -> Result<bool, Fail>,
+{
+    let mut rocks = BTreeSet::new();
+    let mut bbox: Option<BoundingBox> = None;
+    for (y, line) in s.split_terminator('\n').enumerate() {
+        for (x, ch) in line.chars().enumerate() {
+            let here = Position {
+                x: x as i64,
+                y: y as i64,
+            };
+            match bbox.as_mut() {
+                None => {
+                    bbox = Some(BoundingBox::new(&here));
+                }
+                Some(b) => {
+                    b.update(&here);
+                }
+            }
+            if is_solid(ch)? {
+                rocks.insert(here);
+            }
+        }
+    }
+    if let Some(bbox) = bbox {
+        Ok(Pattern { rocks, bbox })
+    } else {
+        Err(Fail("empty patterns are not allowed".to_string()))
+    }
+}
+
+fn parse_pattern(s: &str) -> Result<Pattern, Fail> {
+    parse_cells(s, |ch| match ch {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        other => Err(fail!("unexpected input char {other}")),
+    })
+}
+
+/// Reads any grid of characters as a pattern, so that it can be
+/// checked for lines of reflection.  The characters in `solid` are
+/// treated as rocks, and all others as ash.
+pub fn parse_grid(s: &str, solid: &str) -> Result<Pattern, Fail> {
+    parse_cells(s, |ch| Ok(solid.contains(ch)))
+}
+
+pub fn parse_input(s: &str) -> Result<Vec<Pattern>, Fail> {
+    s.split("\n\n")
+        .map(parse_pattern)
+        .collect::<Result<Vec<Pattern>, Fail>>()
+}
+
+#[cfg(test)]
+fn get_examples() -> Vec<Pattern> {
+    let input = crate::examples::example(13).expect("there should be an example");
+    parse_input(input).expect("example input should be valid")
+}
+
+#[test]
+fn test_parse_input() {
+    let examples = get_examples();
+    match examples.as_slice() {
+        [a, b] => {
+            assert_eq!(a.columns(), 0..=8);
+            assert_eq!(a.rows(), 0..=6);
+
+            assert_eq!(b.columns(), 0..=8);
+            assert_eq!(b.rows(), 0..=6);
+        }
+        _ => {
+            panic!("expected 2 patterns, got {}", examples.len());
+        }
+    }
+}
+
+/// A line of reflection.  `Horizontal(x)` lies between columns `x`
+/// and `x+1`, and `Vertical(y)` lies between rows `y` and `y+1`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Symmetry {
+    Horizontal(i64),
+    Vertical(i64),
+}
+
+impl Debug for Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Symmetry::Horizontal(i) => write!(f, "Horizontal({i})"),
+            Symmetry::Vertical(i) => write!(f, "Vertical({i})"),
+        }
+    }
+}
+
+impl Symmetry {
+    /// Returns the score of this line of reflection when summarizing
+    /// the patterns.
+    pub fn score(&self) -> i64 {
+        match self {
+            Symmetry::Horizontal(x) => 1 + *x,
+            Symmetry::Vertical(y) => 100 * (1 + *y),
+        }
+    }
+
+    /// Returns the same line in the transposed pattern.
+    fn transposed(&self) -> Symmetry {
+        match self {
+            Symmetry::Horizontal(i) => Symmetry::Vertical(*i),
+            Symmetry::Vertical(i) => Symmetry::Horizontal(*i),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+enum SmudgeFix {
+    Single(Position),
+    Multiple,
+}
+
+impl TryFrom<Vec<Position>> for SmudgeFix {
+    type Error = Fail;
+    fn try_from(v: Vec<Position>) -> Result<SmudgeFix, Fail> {
+        match v.as_slice() {
+            [single] => Ok(SmudgeFix::Single(*single)),
+            [_first, ..] => Ok(SmudgeFix::Multiple),
+            [] => Err(Fail("mismatch vector should not be empty".to_string())),
+        }
+    }
+}
+
+impl From<Position> for SmudgeFix {
+    fn from(fix: Position) -> SmudgeFix {
+        SmudgeFix::Single(fix)
+    }
+}
+
+impl SmudgeFix {
+    fn merge(&mut self, _edits: SmudgeFix) {
+        *self = SmudgeFix::Multiple;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+struct SmudgeFixesNeeded {
+    changes_needed: BTreeMap<Symmetry, SmudgeFix>,
+}
+
+impl SmudgeFixesNeeded {
+    fn score_if_fixed(&self) -> i64 {
+        self.changes_needed
+            .iter()
+            .map(|(sym, fix)| match fix {
+                SmudgeFix::Single(_) => sym.score(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn mismatches_at(axis: Symmetry, locations: Vec<Position>) -> SmudgeFixesNeeded {
+        let changes_needed: BTreeMap<Symmetry, SmudgeFix> = [(
+            axis,
+            SmudgeFix::try_from(locations).expect("locations should not be empty"),
+        )]
+        .into_iter()
+        .collect();
+        SmudgeFixesNeeded { changes_needed }
+    }
+
+    fn new() -> SmudgeFixesNeeded {
+        SmudgeFixesNeeded {
+            changes_needed: Default::default(),
+        }
+    }
+
+    fn union(mut self, other: SmudgeFixesNeeded) -> SmudgeFixesNeeded {
+        for (axis, edits) in other.changes_needed.into_iter() {
+            self.changes_needed
+                .entry(axis)
+                .and_modify(|fix| fix.merge(edits.clone()))
+                .or_insert_with(|| edits.clone());
+        }
+        self
+    }
+}
+
+fn zero_area() -> Fail {
+    Fail("it looks like your input pattern has zero area".to_string())
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum SymmetryAssessment {
+    Mismatch(SmudgeFixesNeeded),
+    Symmetrical(BTreeSet<Symmetry>, SmudgeFixesNeeded),
+    AllAxes,
+}
+
+impl SymmetryAssessment {
+    fn symmetrical_about(axis: Symmetry) -> SymmetryAssessment {
+        let symmetries = {
+            let mut v = BTreeSet::new();
+            v.insert(axis);
+            v
+        };
+        SymmetryAssessment::Symmetrical(symmetries, SmudgeFixesNeeded::new())
+    }
+
+    fn empty_mismatch() -> SymmetryAssessment {
+        SymmetryAssessment::Mismatch(SmudgeFixesNeeded::new())
+    }
+
+    fn smudge_summary_score(&self) -> Result<i64, Fail> {
+        match self {
+            SymmetryAssessment::AllAxes => Err(zero_area()),
+            SymmetryAssessment::Mismatch(smudge_fixes) => Ok(smudge_fixes.score_if_fixed()),
+            SymmetryAssessment::Symmetrical(_, fixes) => Ok(fixes.score_if_fixed()),
+        }
+    }
+
+    fn summary_score(&self) -> Result<i64, Fail> {
+        match self {
+            SymmetryAssessment::AllAxes => Err(zero_area()),
+            SymmetryAssessment::Mismatch(_) => Ok(0),
+            SymmetryAssessment::Symmetrical(symmetries, _) => {
+                Ok(symmetries.iter().map(|sym| sym.score()).sum())
+            }
+        }
+    }
+
+    fn union(self, other: SymmetryAssessment) -> SymmetryAssessment {
+        use SymmetryAssessment::*;
+        match (self, other) {
+            (AllAxes, _) | (_, AllAxes) => AllAxes,
+            (Mismatch(smudges1), Mismatch(smudges2)) => Mismatch(smudges1.union(smudges2)),
+            (Mismatch(smudges1), Symmetrical(v, smudges2))
+            | (Symmetrical(v, smudges1), Mismatch(smudges2)) => {
+                Symmetrical(v, smudges1.union(smudges2))
+            }
+            (Symmetrical(mut v1, smudges1), Symmetrical(mut v2, smudges2)) => {
+                v1.append(&mut v2);
+                Symmetrical(v1, smudges1.union(smudges2))
+            }
+        }
+    }
+}
+
+fn consistently_pick_one<'a>(p1: &'a Position, p2: &'a Position) -> &'a Position {
+    match p1.x.cmp(&p2.x).then_with(|| p1.y.cmp(&p2.y)) {
+        Ordering::Less => p1,
+        Ordering::Greater => p2,
+        Ordering::Equal => {
+            // In this case it doesn't matter which we pick.
+            p2
+        }
+    }
+}
+
+impl Pattern {
+    fn reflection_area_would_be_empty(&self, axis: &Symmetry) -> bool {
+        match axis {
+            Symmetry::Horizontal(x) => *x < self.bbox.top_left.x || *x >= self.bbox.bottom_right.x,
+            Symmetry::Vertical(y) => *y < self.bbox.top_left.y || *y >= self.bbox.bottom_right.y,
+        }
+    }
+
+    /// Returns the pattern transformed by `t`.
+    fn transform(&self, t: Transform) -> Pattern {
+        let (width, height) = (self.bbox.width(), self.bbox.height());
+        let rocks = self
+            .rocks
+            .iter()
+            .map(|pos| t.apply(pos, width, height))
+            .collect();
+        let (width, height) = if t.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        Pattern {
+            rocks,
+            bbox: BoundingBox {
+                top_left: Position { x: 0, y: 0 },
+                bottom_right: Position {
+                    x: width - 1,
+                    y: height - 1,
+                },
+            },
+        }
+    }
+
+    fn get_marker(&self, pos: &Position) -> Option<char> {
+        if self.bbox.contains(pos) {
+            if self.rocks.contains(pos) {
+                Some('#')
+            } else {
+                Some('.')
+            }
+        } else {
+            None
+        }
+    }
+
+    fn columns(&self) -> RangeInclusive<i64> {
+        (self.bbox.top_left.x)..=(self.bbox.bottom_right.x)
+    }
+
+    fn rows(&self) -> RangeInclusive<i64> {
+        (self.bbox.top_left.y)..=(self.bbox.bottom_right.y)
+    }
+
+    fn symmetries(&self) -> SymmetryAssessment {
+        self.horizontal_symmetries()
+            .union(self.vertical_symmetries())
+    }
+
+    fn horizontal_symmetries(&self) -> SymmetryAssessment {
+        self.columns()
+            .rev()
+            .skip(1)
+            .map(|x| {
+                let axis = Symmetry::Horizontal(x);
+                let edits = self.horizontal_symmetry_mismatches(&axis);
+                if edits.is_empty() {
+                    SymmetryAssessment::symmetrical_about(axis)
+                } else {
+                    SymmetryAssessment::Mismatch(SmudgeFixesNeeded::mismatches_at(axis, edits))
+                }
+            })
+            .fold(
+                SymmetryAssessment::empty_mismatch(),
+                SymmetryAssessment::union,
+            )
+    }
+
+    fn vertical_symmetries(&self) -> SymmetryAssessment {
+        let transposed = self.transposed();
+        self.rows()
+            .rev()
+            .skip(1)
+            .map(|y| {
+                let axis = Symmetry::Vertical(y);
+                let edits = self.vertical_symmetry_mismatches(&transposed, &axis);
+                if edits.is_empty() {
+                    SymmetryAssessment::symmetrical_about(axis)
+                } else {
+                    SymmetryAssessment::Mismatch(SmudgeFixesNeeded::mismatches_at(axis, edits))
+                }
+            })
+            .fold(
+                SymmetryAssessment::empty_mismatch(),
+                SymmetryAssessment::union,
+            )
+    }
+
+    fn check_point_pair_match(&self, p1: &Position, p2: &Position) -> bool {
+        let marker1 = self.get_marker(p1);
+        let marker2 = self.get_marker(p2);
+        match (marker1, marker2) {
+            (None, _) | (_, None) => true,
+            (Some(m1), Some(m2)) => m1 == m2,
+        }
+    }
+
+    fn row_symmetry_mismatches_for_axis(&self, axis: &Symmetry, y: i64) -> Vec<Position> {
+        // axis is the possible reflection axis, y identifies the row
+        // to check.
+        let x_about = match axis {
+            Symmetry::Horizontal(x) => *x,
+            Symmetry::Vertical(_) => {
+                panic!("is_row_symmetrical_about_axis: should only be called to check horizontal symmetry: {axis:?}");
+            }
+        };
+
+        (0..=x_about)
+            .map(|distance| (x_about - distance, 1 + x_about + distance))
+            .map(|(x1, x2)| (Position { x: x1, y }, Position { x: x2, y }))
+            .filter(|(p1, p2)| self.bbox.contains(p1) && self.bbox.contains(p2))
+            .filter(|(p1, p2)| !self.check_point_pair_match(p1, p2))
+            .map(|(p1, p2)| *consistently_pick_one(&p1, &p2))
+            .collect()
+    }
+
+    fn horizontal_symmetry_mismatches(&self, axis: &Symmetry) -> Vec<Position> {
+        assert!(!self.reflection_area_would_be_empty(axis));
+        self.rows()
+            .flat_map(|y| self.row_symmetry_mismatches_for_axis(axis, y))
+            .collect()
+    }
+
+    /// A line between two rows is a line between two columns of the
+    /// transposed pattern, so we check for that instead, and transpose
+    /// the mismatches back.  `transposed` is `self.transposed()`,
+    /// which callers checking several lines need only make once.
+    fn vertical_symmetry_mismatches(&self, transposed: &Pattern, axis: &Symmetry) -> Vec<Position> {
+        assert!(!self.reflection_area_would_be_empty(axis));
+        let t = Transform::Transpose;
+        let (width, height) = (transposed.bbox.width(), transposed.bbox.height());
+        transposed
+            .horizontal_symmetry_mismatches(&axis.transposed())
+            .iter()
+            .map(|pos| t.inverse().apply(pos, width, height))
+            .collect()
+    }
+
+    fn possible_axes(&self) -> impl Iterator<Item = Symmetry> + '_ {
+        let columns = self.columns().rev().skip(1).map(Symmetry::Horizontal);
+        let rows = self.rows().rev().skip(1).map(Symmetry::Vertical);
+        columns.chain(rows)
+    }
+
+    fn transposed(&self) -> Pattern {
+        self.transform(Transform::Transpose)
+    }
+
+    /// Returns each line the pattern might be symmetrical about,
+    /// along with the cells which don't match their reflections in it.
+    fn symmetry_mismatches(&self) -> impl Iterator<Item = (Symmetry, Vec<Position>)> + '_ {
+        let transposed = self.transposed();
+        self.possible_axes().map(move |axis| {
+            let mismatches = match axis {
+                Symmetry::Horizontal(_) => self.horizontal_symmetry_mismatches(&axis),
+                Symmetry::Vertical(_) => self.vertical_symmetry_mismatches(&transposed, &axis),
+            };
+            (axis, mismatches)
+        })
+    }
+
+    /// Returns every line about which the pattern is symmetrical.
+    pub fn all_symmetries(&self) -> Vec<Symmetry> {
+        self.symmetry_mismatches()
+            .filter(|(_, mismatches)| mismatches.is_empty())
+            .map(|(axis, _)| axis)
+            .collect()
+    }
+
+    /// Finds the smudge: that is, the single cell which, if changed,
+    /// gives the pattern a line of reflection it doesn't already have.
+    /// Returns the position of the smudge and that line.
+    pub fn find_smudge(&self) -> Result<(Position, Symmetry), Fail> {
+        let fixes: Vec<(Symmetry, Position)> = self
+            .symmetry_mismatches()
+            .filter_map(|(axis, mismatches)| match mismatches.as_slice() {
+                [single] => Some((axis, *single)),
+                _ => None,
+            })
+            .collect();
+        match fixes.as_slice() {
+            [(axis, pos)] => Ok((*pos, *axis)),
+            [] => Err(Fail(
+                "no single change gives the pattern a new line of reflection".to_string(),
+            )),
+            _ => Err(fail!(
+                "the smudge could be in any of {} places",
+                fixes.len()
+            )),
+        }
+    }
+
+    /// Fixes the smudge (see `find_smudge`).  Returns the repaired
+    /// pattern and the new line of reflection.
+    pub fn with_smudge_fixed(&self) -> Result<(Pattern, Symmetry), Fail> {
+        let (pos, axis) = self.find_smudge()?;
+        let mut repaired = self.clone();
+        if !repaired.rocks.remove(&pos) {
+            repaired.rocks.insert(pos);
+        }
+        Ok((repaired, axis))
+    }
+
+    /// Draws the pattern, marking the line of reflection `axis` (if
+    /// any) in the same way as the puzzle description: `><` above and
+    /// below the pattern for a line between two columns, and `v` and
+    /// `^` either side of the pattern for a line between two rows.
+    /// The cell at `smudge` (if any) is shown as `X`.
+    pub fn render(&self, axis: Option<&Symmetry>, smudge: Option<&Position>) -> String {
+        let left = self.bbox.top_left.x;
+        let column_marker = match axis {
+            Some(Symmetry::Horizontal(x)) => {
+                let indent = (x - left) as usize;
+                Some(format!("{:indent$}><\n", ""))
+            }
+            _ => None,
+        };
+        let row_marker = |y: i64| match axis {
+            Some(Symmetry::Vertical(axis_y)) if y == *axis_y => "v",
+            Some(Symmetry::Vertical(axis_y)) if y == axis_y + 1 => "^",
+            Some(Symmetry::Vertical(_)) => " ",
+            _ => "",
+        };
+        let mut result = String::new();
+        result.extend(column_marker.clone());
+        for y in self.rows() {
+            let row: String = self
+                .columns()
+                .map(|x| {
+                    let here = Position { x, y };
+                    if smudge == Some(&here) {
+                        'X'
+                    } else {
+                        self.get_marker(&here).unwrap_or('?')
+                    }
+                })
+                .collect();
+            let marker = row_marker(y);
+            result.push_str(&format!("{marker}{row}{marker}\n"));
+        }
+        result.extend(column_marker);
+        result
+    }
+
+    /// Draws the pattern with its smudge and the new line of
+    /// reflection the smudge hides (see `find_smudge` and `render`).
+    pub fn render_smudge(&self) -> Result<String, Fail> {
+        let (smudge, axis) = self.find_smudge()?;
+        Ok(self.render(Some(&axis), Some(&smudge)))
+    }
+}
+
+#[test]
+fn test_horizontal_symmetry_first_pattern() {
+    let first_pattern = get_examples()[0].clone();
+
+    assert_eq!(
+        first_pattern.horizontal_symmetry_mismatches(&Symmetry::Horizontal(4)),
+        vec![]
+    );
+
+    assert!(!first_pattern
+        .horizontal_symmetry_mismatches(&Symmetry::Horizontal(0))
+        .is_empty());
+
+    match first_pattern.horizontal_symmetries() {
+        SymmetryAssessment::Symmetrical(syms, _fixes) => {
+            assert!(syms.contains(&Symmetry::Horizontal(4)));
+            assert_eq!(syms.len(), 1);
+        }
+        other => {
+            panic!("expected first_pattern to be symmetrical about 4, but instead got: {other:?}");
+        }
+    }
+}
+
+#[test]
+fn test_horizontal_reflection_second_pattern() {
+    let second_pattern = get_examples()[1].clone();
+    let axis_second_pattern_is_not_symmetrical_about = Symmetry::Horizontal(4);
+    println!(
+        "Second pattern should not be symmetrical about column 4:\n{}",
+        second_pattern.render(Some(&axis_second_pattern_is_not_symmetrical_about), None)
+    );
+
+    assert!(!second_pattern
+        .horizontal_symmetry_mismatches(&axis_second_pattern_is_not_symmetrical_about)
+        .is_empty());
+
+    assert!(matches!(
+        second_pattern.horizontal_symmetries(),
+        SymmetryAssessment::Mismatch(_)
+    ));
+}
+
+#[test]
+fn test_vertical_symmetry_first_pattern() {
+    let first_pattern = get_examples()[0].clone();
+    // The first pattern cannot be symmetrical about row
+    // 2 because Position{x:0,y:0) (#) should be reflected
+    // at Position{x:0,y:5} (.) but it isn't.
+    assert!(first_pattern
+        .vertical_symmetry_mismatches(&first_pattern.transposed(), &Symmetry::Vertical(2))
+        .contains(&Position { x: 0, y: 0 }));
+
+    assert!(matches!(
+        first_pattern.vertical_symmetries(),
+        SymmetryAssessment::Mismatch(_)
+    ));
+}
+
+#[test]
+fn test_transpose() {
+    for pattern in get_examples() {
+        let transposed = pattern.transform(Transform::Transpose);
+        assert_eq!(transposed.bbox.width(), pattern.bbox.height());
+        assert_eq!(
+            transposed.transform(Transform::Transpose).rocks,
+            pattern.rocks
+        );
+        // Lines between rows become lines between columns, and vice
+        // versa.
+        let mut expected: Vec<Symmetry> = pattern
+            .all_symmetries()
+            .iter()
+            .map(Symmetry::transposed)
+            .collect();
+        expected.sort();
+        let mut found = transposed.all_symmetries();
+        found.sort();
+        assert_eq!(found, expected);
+    }
+}
+
+#[test]
+fn test_first_pattern_all_reflections() {
+    let examples = get_examples();
+    let first_pattern = examples[0].clone();
+    match first_pattern.horizontal_symmetries() {
+        SymmetryAssessment::Symmetrical(symmetries, _) => {
+            assert_eq!(symmetries.len(), 1);
+            assert!(symmetries.contains(&Symmetry::Horizontal(4)));
+        }
+        _ => {
+            panic!("expected to see horizontal symmetry");
+        }
+    };
+
+    assert!(matches!(
+        first_pattern.vertical_symmetries(),
+        SymmetryAssessment::Mismatch(_)
+    ));
+
+    match first_pattern.symmetries() {
+        SymmetryAssessment::Symmetrical(syms, _) => {
+            assert_eq!(syms.len(), 1);
+            assert!(syms.contains(&Symmetry::Horizontal(4)));
+        }
+        _ => {
+            panic!("expected to see horizontal symmetry");
+        }
+    }
+}
+
+#[test]
+fn test_second_pattern_all_reflections() {
+    let examples = get_examples();
+    let second_pattern = examples[1].clone();
+    match second_pattern.symmetries() {
+        SymmetryAssessment::Symmetrical(syms, _) => {
+            assert_eq!(syms.len(), 1);
+            assert!(syms.contains(&Symmetry::Vertical(3)));
+        }
+        _ => {
+            panic!("expected to see vertical symmetry");
+        }
+    }
+}
+
+/// Returns every line about which `grid` would be symmetrical if
+/// exactly `allowed_mismatches` cells were changed.  With no
+/// mismatches these are the lines of reflection of part 1, and with
+/// one they are the lines revealed by fixing the smudge in part 2.
+pub fn find_reflections(grid: &Pattern, allowed_mismatches: usize) -> Vec<Symmetry> {
+    grid.symmetry_mismatches()
+        .filter(|(_, mismatches)| mismatches.len() == allowed_mismatches)
+        .map(|(axis, _)| axis)
+        .collect()
+}
+
+/// Sums the scores of the lines of reflection found by
+/// `find_reflections`.
+pub fn summarize_with_mismatches(patterns: &[Pattern], allowed_mismatches: usize) -> i64 {
+    patterns
+        .iter()
+        .flat_map(|pat| find_reflections(pat, allowed_mismatches))
+        .map(|axis| axis.score())
+        .sum()
+}
+
+#[test]
+fn test_find_reflections() {
+    let examples = get_examples();
+    assert_eq!(
+        find_reflections(&examples[0], 0),
+        vec![Symmetry::Horizontal(4)]
+    );
+    assert_eq!(
+        find_reflections(&examples[0], 1),
+        vec![Symmetry::Vertical(2)]
+    );
+    assert_eq!(summarize_with_mismatches(&examples, 0), 405);
+    assert_eq!(summarize_with_mismatches(&examples, 1), 400);
+
+    // Any two kinds of character will do.
+    let grid = parse_grid("xo.o\nooxo\n", "x").expect("grid is valid");
+    assert_eq!(find_reflections(&grid, 0), vec![]);
+    assert_eq!(
+        find_reflections(&grid, 1),
+        vec![Symmetry::Horizontal(2), Symmetry::Horizontal(0)]
+    );
+    assert_eq!(
+        find_reflections(&grid, 2),
+        vec![Symmetry::Horizontal(1), Symmetry::Vertical(0)]
+    );
+    assert!(parse_grid("", "x").is_err());
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_find_reflections_matches_summaries(seed in 0_u64..1000) {
+        use crate::gen::{reflected_pattern, Rng};
+        let mut rng = Rng::new(seed);
+        let input = reflected_pattern(&mut rng, 9, 7, 0.5);
+        let patterns = parse_input(&input).expect("generated pattern is valid");
+        proptest::prop_assert_eq!(Ok(summarize_with_mismatches(&patterns, 0)), summarize(&patterns));
+        proptest::prop_assert_eq!(
+            Ok(summarize_with_mismatches(&patterns, 1)),
+            summarize_with_smudges(&patterns)
+        );
+    }
+}
+
+/// Sums the scores of the lines of reflection of `patterns`.
+pub fn summarize(patterns: &[Pattern]) -> Result<i64, Fail> {
+    patterns
+        .iter()
+        .map(|pat| pat.symmetries().summary_score())
+        .sum()
+}
+
+/// Sums the scores of the lines of reflection of `patterns`, once
+/// their smudges are fixed.
+pub fn summarize_with_smudges(patterns: &[Pattern]) -> Result<i64, Fail> {
+    patterns
+        .iter()
+        .map(|pat| pat.symmetries().smudge_summary_score())
+        .sum()
+}
+
+/// Like `summarize`, but examines the patterns in parallel.
+#[cfg(feature = "parallel")]
+pub fn par_summarize(patterns: &[Pattern]) -> Result<i64, Fail> {
+    use rayon::prelude::*;
+    // Collecting the scores in order before adding them up means that
+    // if several patterns fail, we always report the first.
+    patterns
+        .par_iter()
+        .map(|pat| pat.symmetries().summary_score())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .sum()
+}
+
+/// Like `summarize_with_smudges`, but examines the patterns in
+/// parallel.
+#[cfg(feature = "parallel")]
+pub fn par_summarize_with_smudges(patterns: &[Pattern]) -> Result<i64, Fail> {
+    use rayon::prelude::*;
+    patterns
+        .par_iter()
+        .map(|pat| pat.symmetries().smudge_summary_score())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .sum()
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_summaries() {
+    let examples = get_examples();
+    assert_eq!(par_summarize(&examples), summarize(&examples));
+    assert_eq!(
+        par_summarize_with_smudges(&examples),
+        summarize_with_smudges(&examples)
+    );
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day13::part1;
+/// let example = lib::examples::examples(13)[0];
+/// assert_eq!(part1(example), Ok(405));
+/// ```
+pub fn part1(input: &str) -> Result<i64, Fail> {
+    let patterns = parse_input(input)?;
+    #[cfg(feature = "parallel")]
+    return par_summarize(&patterns);
+    #[cfg(not(feature = "parallel"))]
+    return summarize(&patterns);
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day13::part2;
+/// let example = lib::examples::examples(13)[0];
+/// assert_eq!(part2(example), Ok(400));
+/// ```
+pub fn part2(input: &str) -> Result<i64, Fail> {
+    let patterns = parse_input(input)?;
+    #[cfg(feature = "parallel")]
+    return par_summarize_with_smudges(&patterns);
+    #[cfg(not(feature = "parallel"))]
+    return summarize_with_smudges(&patterns);
+}
+
+#[test]
+fn test_part1() {
+    let examples = get_examples();
+
+    let first_pattern = examples[0].clone();
+    assert_eq!(summarize(&[first_pattern]), Ok(5));
+
+    let second_pattern = examples[1].clone();
+    assert_eq!(summarize(&[second_pattern]), Ok(400));
+
+    assert_eq!(summarize(&examples), Ok(405));
+}
+
+#[test]
+fn test_part2() {
+    let examples = get_examples();
+    assert_eq!(summarize_with_smudges(&examples), Ok(400));
+}
+
+#[test]
+fn test_part2_first_pattern_all_reflections() {
+    let examples = get_examples();
+    let first_pattern = examples[0].clone();
+
+    assert_eq!(
+        first_pattern
+            .vertical_symmetry_mismatches(&first_pattern.transposed(), &Symmetry::Vertical(2)),
+        vec![Position { x: 0, y: 0 }]
+    );
+
+    match first_pattern.vertical_symmetries() {
+        SymmetryAssessment::Mismatch(SmudgeFixesNeeded { changes_needed }) => {
+            assert_eq!(
+                changes_needed.get(&Symmetry::Vertical(2)),
+                Some(&SmudgeFix::Single(Position { x: 0, y: 0 }))
+            );
+        }
+        _ => {
+            panic!("expected to see no vertical symmetries");
+        }
+    }
+}
+
+#[test]
+fn test_all_symmetries() {
+    let examples = get_examples();
+    assert_eq!(examples[0].all_symmetries(), vec![Symmetry::Horizontal(4)]);
+    assert_eq!(examples[1].all_symmetries(), vec![Symmetry::Vertical(3)]);
+}
+
+#[test]
+fn test_with_smudge_fixed() {
+    for (pattern, expected_axis) in get_examples()
+        .iter()
+        .zip([Symmetry::Vertical(2), Symmetry::Vertical(0)])
+    {
+        let (repaired, axis) = pattern.with_smudge_fixed().expect("example has a smudge");
+        assert_eq!(axis, expected_axis);
+        assert_eq!(
+            repaired.rocks.symmetric_difference(&pattern.rocks).count(),
+            1
+        );
+        assert!(repaired.all_symmetries().contains(&axis));
+        assert!(repaired
+            .symmetry_mismatches()
+            .any(|(line, mismatches)| line == axis && mismatches.is_empty()));
+    }
+    // Every row already matches, so no single change makes a new line.
+    let plain = parse_pattern("##\n##\n").expect("pattern is valid");
+    assert!(plain.with_smudge_fixed().is_err());
+}
+
+#[test]
+fn test_render() {
+    let examples = get_examples();
+    assert_eq!(
+        examples[0].render(Some(&Symmetry::Horizontal(4)), None),
+        concat!(
+            "    ><\n",
+            "#.##..##.\n",
+            "..#.##.#.\n",
+            "##......#\n",
+            "##......#\n",
+            "..#.##.#.\n",
+            "..##..##.\n",
+            "#.#.##.#.\n",
+            "    ><\n",
+        )
+    );
+    assert_eq!(
+        examples[1].render_smudge(),
+        Ok(concat!(
+            "v#...X#..#v\n",
+            "^#....#..#^\n",
+            " ..##..### \n",
+            " #####.##. \n",
+            " #####.##. \n",
+            " ..##..### \n",
+            " #....#..# \n",
+        )
+        .to_string())
+    );
+    assert_eq!(examples[1].render(None, None).lines().count(), 7);
+}
@@ -0,0 +1,512 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+use std::str;
+
+use crate::cycle::{self, CycleInfo};
+use crate::error::Fail;
+
+use crate::fail;
+use crate::grid::{BoundingBox, CompassDirection, Position, Transform};
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Parabolic Reflector Dish",
+    algorithm: "Tilting moves each round rock as far as the cube rocks allow.  For part 2, spin cycles are run until the platform repeats a position, and the cycle is used to skip ahead to a billion spins.",
+    complexity: "O(n·c) for n cells and c spin cycles before the first repeat",
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Rock {
+    Round,
+    Cube,
+}
+
+impl Rock {
+    fn as_char(&self) -> char {
+        match self {
+            Rock::Round => 'O',
+            Rock::Cube => '#',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Platform {
+    rocks: BTreeMap<Position, Rock>,
+    bbox: BoundingBox,
+}
+
+impl Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in self.bbox.top_left.y..=self.bbox.bottom_right.y {
+            for x in self.bbox.top_left.x..=self.bbox.bottom_right.x {
+                let here = Position { x, y };
+                let ch = self
+                    .rocks
+                    .get(&here)
+                    .map(|rock| rock.as_char())
+                    .unwrap_or('.');
+                f.write_char(ch)?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_input(s: &str) -> Result<Platform, Fail> {
+    let mut rocks = BTreeMap::new();
+    let mut bbox: Option<BoundingBox> = None;
+    for (y, line) in s.split_terminator('\n').enumerate() {
+        for (x, ch) in line.chars().enumerate() {
+            let here = Position {
+                x: x as i64,
+                y: y as i64,
+            };
+            match bbox.as_mut() {
+                None => {
+                    bbox = Some(BoundingBox::new(&here));
+                }
+                Some(b) => {
+                    b.update(&here);
+                }
+            }
+            match ch {
+                '#' => {
+                    rocks.insert(here, Rock::Cube);
+                }
+                'O' => {
+                    rocks.insert(here, Rock::Round);
+                }
+                '.' => (),
+                other => {
+                    return Err(fail!("unexpected input char {other}"));
+                }
+            }
+        }
+    }
+    if let Some(bbox) = bbox {
+        Ok(Platform { rocks, bbox })
+    } else {
+        Err(Fail("empty patterns are not allowed".to_string()))
+    }
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(14).expect("there should be an example")
+}
+
+#[cfg(test)]
+fn get_parsed_example() -> Platform {
+    parse_input(get_example()).expect("example should be valid")
+}
+
+#[test]
+fn test_parse() {
+    get_parsed_example();
+}
+
+#[test]
+fn test_transform_round_trip() {
+    let platform = get_parsed_example();
+    for t in crate::grid::ALL_TRANSFORMS.iter() {
+        assert_eq!(platform.transform(*t).transform(t.inverse()), platform);
+    }
+}
+
+#[test]
+fn test_tilt() {
+    let tilted = get_parsed_example().tilt(CompassDirection::North);
+    crate::assert_grid_snapshot!(tilted, "tests/snapshots/day14_tilted_north.txt");
+    assert_eq!(tilted, tilted_north_example());
+}
+
+/// Returns the position at which a round rock at `pos` comes to rest
+/// when the platform is tilted north.
+fn compute_final_position(
+    mut pos: Position,
+    occupied: &BTreeMap<Position, Rock>,
+    bounds: &BoundingBox,
+) -> Position {
+    loop {
+        let newpos = pos.move_direction(&CompassDirection::North);
+        if (!bounds.contains(&newpos)) || occupied.contains_key(&newpos) {
+            return pos;
+        } else {
+            pos = newpos;
+        }
+    }
+}
+
+impl Platform {
+    fn popcount(&self, rock: &Rock) -> usize {
+        self.rocks.values().filter(|r| *r == rock).count()
+    }
+
+    /// Rotates or reflects the platform.  The top-left corner of the
+    /// platform must be at the origin.
+    fn transform(&self, t: Transform) -> Platform {
+        let (width, height) = (self.bbox.width(), self.bbox.height());
+        let rocks = self
+            .rocks
+            .iter()
+            .map(|(pos, rock)| (t.apply(pos, width, height), *rock))
+            .collect();
+        let (width, height) = if t.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        Platform {
+            rocks,
+            bbox: BoundingBox {
+                top_left: Position { x: 0, y: 0 },
+                bottom_right: Position {
+                    x: width - 1,
+                    y: height - 1,
+                },
+            },
+        }
+    }
+
+    fn tilt_north(&self) -> Platform {
+        // Deal with the round rocks nearest the north edge first, so
+        // that they are out of the way of the ones behind them.
+        let round_rocks_by_original_pos: BTreeMap<i64, Vec<Position>> = self
+            .rocks
+            .iter()
+            .filter_map(|(pos, rock)| {
+                if *rock == Rock::Round {
+                    Some((pos.y, *pos))
+                } else {
+                    None
+                }
+            })
+            .fold(BTreeMap::new(), |mut acc, (rank, pos)| {
+                acc.entry(rank)
+                    .and_modify(|v| v.push(pos))
+                    .or_insert_with(|| vec![pos]);
+                acc
+            });
+        assert_eq!(
+            self.popcount(&Rock::Round),
+            round_rocks_by_original_pos
+                .values()
+                .map(|v| v.len())
+                .sum::<usize>(),
+            "We lost or gained some round rocks"
+        );
+
+        // Preserve the existing position of the cube rocks.
+        let mut new_positions: BTreeMap<Position, Rock> = self
+            .rocks
+            .iter()
+            .filter(|(_, rock)| **rock == Rock::Cube)
+            .map(|(p, r)| (*p, *r))
+            .collect();
+
+        // Move the rounded rocks northward.
+        for pos in round_rocks_by_original_pos.values().flat_map(|v| v.iter()) {
+            let newpos = compute_final_position(*pos, &new_positions, &self.bbox);
+            new_positions.insert(newpos, Rock::Round);
+        }
+
+        Platform {
+            rocks: new_positions,
+            bbox: self.bbox,
+        }
+    }
+
+    fn tilt(&self, direction: CompassDirection) -> Platform {
+        // Turn the platform so that we're tilting it north, then turn
+        // it back.
+        let t = Transform::to_north(direction);
+        self.transform(t).tilt_north().transform(t.inverse())
+    }
+
+    fn rock_load(&self, pos: &Position, rock: &Rock, tilt_direction: CompassDirection) -> i64 {
+        match rock {
+            Rock::Cube => 0,
+            Rock::Round => match tilt_direction {
+                CompassDirection::North => 1 + self.bbox.bottom_right.y - pos.y,
+                CompassDirection::South => 1 + pos.y - self.bbox.top_left.y,
+                CompassDirection::East => 1 + pos.x - self.bbox.top_left.x,
+                CompassDirection::West => 1 + self.bbox.bottom_right.x - pos.x,
+            },
+        }
+    }
+
+    fn loading(&self, direction: CompassDirection) -> i64 {
+        self.rocks
+            .iter()
+            .map(|(pos, rock)| self.rock_load(pos, rock, direction))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+fn tilted_north_example() -> Platform {
+    parse_input(concat!(
+        "OOOO.#.O..\n", //  10
+        "OO..#....#\n", //   9
+        "OO..O##..O\n", //   8
+        "O..#.OO...\n", //   7
+        "........#.\n", //   6
+        "..#....#.#\n", //   5
+        "..O..#.O.O\n", //   4
+        "..O.......\n", //   3
+        "#....###..\n", //   2
+        "#....#....\n", //   1
+    ))
+    .expect("tilted example should be valid")
+}
+
+#[test]
+fn test_rock_load_cube() {
+    let tilted_platform = tilted_north_example();
+    for y in (tilted_platform.bbox.top_left.y)..=(tilted_platform.bbox.bottom_right.y) {
+        for x in (tilted_platform.bbox.top_left.x)..=(tilted_platform.bbox.bottom_right.x) {
+            assert_eq!(
+                tilted_platform.rock_load(&Position { x, y }, &Rock::Cube, CompassDirection::North),
+                0
+            );
+        }
+    }
+}
+
+#[test]
+fn test_rock_load_round() {
+    let tilted_platform = tilted_north_example();
+    assert_eq!(
+        tilted_platform.rock_load(
+            &Position { x: 0, y: 0 },
+            &Rock::Round,
+            CompassDirection::North
+        ),
+        10
+    );
+    assert_eq!(
+        tilted_platform.rock_load(
+            &Position { x: 0, y: 1 },
+            &Rock::Round,
+            CompassDirection::North
+        ),
+        9
+    );
+}
+
+#[test]
+fn test_loading() {
+    let tilted_platform = tilted_north_example();
+    assert_eq!(tilted_platform.loading(CompassDirection::North), 136);
+}
+
+/// Computes the load on the north support beams after tilting the
+/// platform north.
+pub fn north_load_after_tilt(platform: &Platform) -> i64 {
+    platform
+        .tilt(CompassDirection::North)
+        .loading(CompassDirection::North)
+}
+
+#[test]
+fn test_north_load_after_tilt() {
+    let platform = get_parsed_example();
+    assert_eq!(north_load_after_tilt(&platform), 136);
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day14::part1;
+/// let example = lib::examples::examples(14)[0];
+/// assert_eq!(part1(example), Ok(136));
+/// ```
+pub fn part1(input: &str) -> Result<i64, Fail> {
+    Ok(north_load_after_tilt(&parse_input(input)?))
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(get_example()), Ok(136));
+}
+
+/// The order in which the platform is tilted in each spin cycle of
+/// the puzzle.
+pub const SPIN_ORDER: &str = "NWSE";
+
+/// Reads a sequence of tilts such as "NWSE".
+pub fn parse_sequence(s: &str) -> Result<Vec<CompassDirection>, Fail> {
+    s.chars()
+        .map(|ch| ch.to_string().parse::<CompassDirection>())
+        .collect()
+}
+
+#[test]
+fn test_parse_sequence() {
+    use CompassDirection::*;
+    assert_eq!(
+        parse_sequence(SPIN_ORDER),
+        Ok(vec![North, West, South, East])
+    );
+    assert_eq!(parse_sequence(""), Ok(vec![]));
+    assert!(parse_sequence("NX").is_err());
+}
+
+/// Tilts the platform in each of `sequence` in turn.
+fn spin(platform: &Platform, sequence: &[CompassDirection]) -> Platform {
+    sequence
+        .iter()
+        .fold(platform.clone(), |platform, direction| {
+            platform.tilt(*direction)
+        })
+}
+
+impl Platform {
+    /// Tilts the platform in each of the directions in `sequence` (for
+    /// example "NWSE"), `cycles` times over.  Once the platform
+    /// returns to a position it has been in before, we know where it
+    /// will end up without doing the rest.
+    pub fn apply_sequence(&self, sequence: &str, cycles: usize) -> Result<Platform, Fail> {
+        let sequence = parse_sequence(sequence)?;
+        let history = cycle::detect(self.clone(), |platform| spin(platform, &sequence));
+        Ok(history.nth_state_after(cycles).clone())
+    }
+
+    /// Returns the load on the north support beams.
+    pub fn north_load(&self) -> i64 {
+        self.loading(CompassDirection::North)
+    }
+}
+
+#[test]
+fn test_apply_sequence() {
+    let platform = get_parsed_example();
+    assert_eq!(
+        platform.apply_sequence("N", 1),
+        Ok(platform.tilt(CompassDirection::North))
+    );
+    assert_eq!(platform.apply_sequence("NWSE", 0), Ok(platform.clone()));
+    let spun = platform
+        .apply_sequence(SPIN_ORDER, 3)
+        .expect("sequence is valid");
+    assert_eq!(spun.north_load(), 69);
+    crate::assert_grid_snapshot!(spun, "tests/snapshots/day14_three_spin_cycles.txt");
+    assert_eq!(
+        platform
+            .apply_sequence(SPIN_ORDER, 1000000000)
+            .map(|p| p.north_load()),
+        Ok(64)
+    );
+    // Tilting west then east is the same as just tilting east.
+    assert_eq!(
+        platform.apply_sequence("WE", 1000),
+        platform.apply_sequence("E", 1)
+    );
+    assert!(platform.apply_sequence("NWXE", 1).is_err());
+}
+
+/// The load on the north support beams after each spin cycle, up to
+/// the point where the platform returns to a position it has been in
+/// before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadHistory {
+    /// `loads[n]` is the load after `n` spin cycles.
+    pub loads: Vec<i64>,
+    pub info: CycleInfo,
+}
+
+impl LoadHistory {
+    /// Returns the load after `n` spin cycles.
+    pub fn load_after(&self, n: usize) -> i64 {
+        self.loads[self.info.reduce(n)]
+    }
+
+    /// Returns the loads as comma-separated values, with a header
+    /// line.  The "phase" column shows whether the platform is in the
+    /// repeating part of the sequence yet.
+    pub fn to_csv(&self) -> String {
+        let mut result = String::from("spin_cycles,north_load,phase\n");
+        for (n, load) in self.loads.iter().enumerate() {
+            let phase = if n < self.info.prefix_len {
+                "prefix"
+            } else {
+                "cycle"
+            };
+            result.push_str(&format!("{n},{load},{phase}\n"));
+        }
+        result
+    }
+}
+
+/// Spins the platform until it repeats a position, recording the
+/// north load along the way.
+pub fn spin_cycle_history(platform: &Platform) -> LoadHistory {
+    spin_history(platform, SPIN_ORDER).expect("SPIN_ORDER is a valid sequence")
+}
+
+/// Like `spin_cycle_history`, but tilts the platform in the directions
+/// given by `sequence` in each spin cycle.
+pub fn spin_history(platform: &Platform, sequence: &str) -> Result<LoadHistory, Fail> {
+    let sequence = parse_sequence(sequence)?;
+    let cycle = cycle::detect(platform.clone(), |platform| spin(platform, &sequence));
+    Ok(LoadHistory {
+        loads: cycle
+            .states()
+            .iter()
+            .map(|p| p.loading(CompassDirection::North))
+            .collect(),
+        info: cycle.info,
+    })
+}
+
+#[test]
+fn test_spin_cycle_history() {
+    let history = spin_cycle_history(&get_parsed_example());
+    assert_eq!(
+        history.info,
+        CycleInfo {
+            prefix_len: 3,
+            period: 7
+        }
+    );
+    assert_eq!(history.loads, vec![136, 87, 69, 69, 69, 65, 64, 65, 63, 68]);
+    assert_eq!(history.load_after(10), 69);
+    let csv = history.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("spin_cycles,north_load,phase"));
+    assert_eq!(lines.next(), Some("0,136,prefix"));
+    assert_eq!(lines.nth(2), Some("3,69,cycle"));
+}
+
+/// Computes the load on the north support beams after a billion spin
+/// cycles.
+pub fn north_load_after_spin_cycles(platform: &Platform) -> i64 {
+    spin_cycle_history(platform).load_after(SPIN_CYCLES)
+}
+
+/// The number of spin cycles in part 2.
+pub const SPIN_CYCLES: usize = 1000000000;
+
+#[test]
+fn test_north_load_after_spin_cycles() {
+    let platform = get_parsed_example();
+    assert_eq!(north_load_after_spin_cycles(&platform), 64);
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day14::part2;
+/// let example = lib::examples::examples(14)[0];
+/// assert_eq!(part2(example), Ok(64));
+/// ```
+pub fn part2(input: &str) -> Result<i64, Fail> {
+    Ok(north_load_after_spin_cycles(&parse_input(input)?))
+}
+
+#[test]
+fn test_part2() {
+    assert_eq!(part2(get_example()), Ok(64));
+}
@@ -0,0 +1,1125 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter, Write};
+use std::ops::{Add, Mul, Rem};
+use std::str::FromStr;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::report::json_quote;
+use crate::solver::{Backend, Notes};
+use crate::trace;
+
+pub const NOTES: Notes = Notes {
+    title: "Lens Library",
+    algorithm: "The HASH algorithm is applied to each step; in part 2 the steps are carried out on an array of 256 ordered boxes of lenses.",
+    complexity: "O(n) in the size of the input",
+};
+
+fn update_hash_value<W>(h: W, codepoint: W) -> W
+where
+    W: From<u8> + From<u16> + Add<Output = W> + Rem<Output = W> + Mul<Output = W>,
+{
+    let sum: W = h + codepoint;
+    let modulus: W = W::from(256_u16);
+    (sum * W::from(17_u8)) % modulus
+}
+
+fn convert_ascii_char<W: From<u8>>(ch: char) -> W {
+    let codepoint = u8::try_from(ch).expect("input should be all-ASCII");
+    W::from(codepoint)
+}
+
+#[inline]
+pub fn hash_generic<W>(s: &str) -> u8 {
+    s.chars()
+        .map(convert_ascii_char)
+        .fold(0, update_hash_value)
+        .try_into()
+        .expect("there should be no way for the hash accumulator to get out of range")
+}
+
+/// Computes the HASH of a byte string.
+///
+/// Since the HASH is reduced modulo 256 at every step, wrapping `u8`
+/// arithmetic gives the same result as `hash_generic` without
+/// widening.  Unlike `hash_generic`, this accepts non-ASCII bytes.
+#[inline]
+pub fn hash_bytes(s: &[u8]) -> u8 {
+    s.iter()
+        .fold(0_u8, |h, &b| h.wrapping_add(b).wrapping_mul(17))
+}
+
+/// Computes the HASH of `s` using the accumulator width the solver uses.
+///
+/// ```
+/// use lib::y2023::day15::hash;
+/// assert_eq!(hash("HASH"), 52);
+/// ```
+#[inline]
+pub fn hash(s: &str) -> u8 {
+    hash_generic::<u32>(s)
+}
+
+/// The width of the accumulator used to compute a HASH.  They all
+/// give the same answer, and differ only in speed, except that `U8`
+/// (which is `hash_bytes`) also accepts non-ASCII input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashWidth {
+    U8,
+    U16,
+    #[default]
+    U32,
+    U64,
+}
+
+pub const ALL_HASH_WIDTHS: [HashWidth; 4] = [
+    HashWidth::U8,
+    HashWidth::U16,
+    HashWidth::U32,
+    HashWidth::U64,
+];
+
+impl HashWidth {
+    /// Computes the HASH of `s` with an accumulator of this width.
+    pub fn hash(self, s: &str) -> u8 {
+        match self {
+            HashWidth::U8 => hash_bytes(s.as_bytes()),
+            HashWidth::U16 => hash_generic::<u16>(s),
+            HashWidth::U32 => hash_generic::<u32>(s),
+            HashWidth::U64 => hash_generic::<u64>(s),
+        }
+    }
+}
+
+impl Display for HashWidth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.write_str(match self {
+            HashWidth::U8 => "u8",
+            HashWidth::U16 => "u16",
+            HashWidth::U32 => "u32",
+            HashWidth::U64 => "u64",
+        })
+    }
+}
+
+impl FromStr for HashWidth {
+    type Err = Fail;
+
+    fn from_str(s: &str) -> Result<HashWidth, Fail> {
+        ALL_HASH_WIDTHS
+            .into_iter()
+            .find(|width| width.to_string() == s)
+            .ok_or_else(|| fail!("unknown hash width {s}"))
+    }
+}
+
+#[test]
+fn test_hash_width() {
+    for width in ALL_HASH_WIDTHS {
+        assert_eq!(width.to_string().parse::<HashWidth>(), Ok(width));
+        assert_eq!(width.hash("HASH"), 52);
+        assert_eq!(width.hash("qp=3"), 97);
+    }
+    assert_eq!(HashWidth::default().hash("rn=1"), hash("rn=1"));
+    assert!("u128".parse::<HashWidth>().is_err());
+}
+
+/// Computes the HASH of a byte string without a chain of
+/// multiplications.
+///
+/// Modulo 256, 17^j is 1 + 16j, so the HASH of bytes b(0) to b(n-1)
+/// is the sum of b(k)(1 + 16(n - k)), which is S + 16(nS - W) where S
+/// is the sum of the bytes and W is the sum of k b(k).  Both sums can
+/// be computed a byte at a time without waiting for the previous
+/// multiplication, and the compiler can vectorise them.
+#[inline]
+pub fn hash_sums(s: &[u8]) -> u8 {
+    let (sum, weighted) = s
+        .iter()
+        .enumerate()
+        .fold((0_u8, 0_u8), |(sum, weighted), (k, &b)| {
+            (
+                sum.wrapping_add(b),
+                weighted.wrapping_add(b.wrapping_mul(k as u8)),
+            )
+        });
+    let n = s.len() as u8;
+    sum.wrapping_add(16_u8.wrapping_mul(n.wrapping_mul(sum).wrapping_sub(weighted)))
+}
+
+/// Labels up to this long are hashed by `hash_short`.
+const SHORT: usize = 8;
+
+/// Computes the HASH of a byte string of between 1 and `SHORT` bytes
+/// in the same way as `hash_sums`, but without any branches which
+/// depend on its length.  The bytes past the end of `s` count as
+/// zeros, which make no difference to the sums.
+#[inline]
+fn hash_short(s: &[u8]) -> u8 {
+    let n = s.len();
+    let (mut sum, mut weighted) = (0_u8, 0_u8);
+    for k in 0..SHORT {
+        let b = s[k.min(n - 1)] & 0_u8.wrapping_sub(u8::from(k < n));
+        sum = sum.wrapping_add(b);
+        weighted = weighted.wrapping_add(b.wrapping_mul(k as u8));
+    }
+    let n = n as u8;
+    sum.wrapping_add(16_u8.wrapping_mul(n.wrapping_mul(sum).wrapping_sub(weighted)))
+}
+
+/// Computes the HASH of each of `labels`, which is faster than
+/// hashing them one at a time with `hash_bytes`.  Like `hash_bytes`,
+/// this accepts non-ASCII labels.
+///
+/// ```
+/// use lib::y2023::day15::hash_many;
+/// assert_eq!(hash_many(&["HASH", "rn=1", "cm-"]), vec![52, 30, 253]);
+/// ```
+pub fn hash_many(labels: &[&str]) -> Vec<u8> {
+    labels
+        .iter()
+        .map(|label| match label.len() {
+            1..=SHORT => hash_short(label.as_bytes()),
+            _ => hash_sums(label.as_bytes()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_hash_many_matches_hash_bytes(labels in proptest::collection::vec("[ -~]{0,12}", 0..30)) {
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let expected: Vec<u8> = labels.iter().map(|label| hash_bytes(label.as_bytes())).collect();
+        proptest::prop_assert_eq!(hash_many(&labels), expected);
+    }
+
+    #[test]
+    fn test_hash_sums_matches_hash_bytes(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..300)) {
+        proptest::prop_assert_eq!(hash_sums(&bytes), hash_bytes(&bytes));
+    }
+}
+
+#[test]
+fn test_hash_ascii() {
+    fn hash(s: &str) -> u8 {
+        hash_generic::<u16>(s)
+    }
+    assert_eq!(hash("HASH"), 52);
+    assert_eq!(hash("rn=1"), 30);
+    assert_eq!(hash("cm-"), 253);
+    assert_eq!(hash("qp=3"), 97);
+}
+
+#[test]
+fn test_hash_bytes() {
+    assert_eq!(hash_bytes(b"HASH"), 52);
+    assert_eq!(hash_bytes(b"rn=1"), 30);
+    assert_eq!(hash_bytes(b"cm-"), 253);
+    assert_eq!(hash_bytes(b"qp=3"), 97);
+    assert_eq!(hash_bytes(b""), 0);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_hash_bytes_matches_generic(s in "[ -~]*") {
+        proptest::prop_assert_eq!(hash_bytes(s.as_bytes()), hash_generic::<u16>(&s));
+        proptest::prop_assert_eq!(hash_bytes(s.as_bytes()), hash_generic::<u32>(&s));
+        proptest::prop_assert_eq!(hash_bytes(s.as_bytes()), hash_generic::<u64>(&s));
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_hash_nonascii() {
+    fn hash(s: &str) -> u8 {
+        hash_generic::<u16>(s)
+    }
+    hash("😊");
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LabeledLens {
+    pub label: String,
+    pub focal_length: u8,
+}
+
+impl Display for LabeledLens {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "[{0} {1}]", self.label, self.focal_length)
+    }
+}
+
+impl LabeledLens {
+    fn power(&self, pos: usize) -> u64 {
+        (pos as u64) * u64::from(self.focal_length)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    Remove(String),
+    Insert(String, u8),
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Instruction::Remove(label) => write!(f, "{label}-"),
+            Instruction::Insert(label, focal_length) => write!(f, "{label}={focal_length}"),
+        }
+    }
+}
+
+impl Instruction {
+    pub fn label(&self) -> &str {
+        match self {
+            Instruction::Remove(label) | Instruction::Insert(label, _) => label,
+        }
+    }
+
+    /// Returns the index of the box this instruction operates on.
+    pub fn target(&self) -> usize {
+        usize::from(hash(self.label()))
+    }
+}
+
+impl TryFrom<&str> for Instruction {
+    type Error = Fail;
+    fn try_from(instruction: &str) -> Result<Instruction, Self::Error> {
+        match instruction.split_once('=') {
+            Some((label, fl)) => match fl.parse() {
+                Ok(focal_length) => Ok(Instruction::Insert(label.to_string(), focal_length)),
+                Err(e) => Err(fail!("{fl} is not a valid focal length: {e}")),
+            },
+            None => match instruction.strip_suffix('-') {
+                Some(label) => Ok(Instruction::Remove(label.to_string())),
+                None => Err(fail!(
+                    "don't know how to interpret instruction {instruction}"
+                )),
+            },
+        }
+    }
+}
+
+pub fn parse_instructions(s: &str) -> Result<Vec<Instruction>, Fail> {
+    s.split(',').map(Instruction::try_from).collect()
+}
+
+#[test]
+fn test_instruction_from_str() {
+    assert_eq!(
+        Instruction::try_from("rn=1"),
+        Ok(Instruction::Insert("rn".to_string(), 1))
+    );
+    assert_eq!(
+        Instruction::try_from("cm=2"),
+        Ok(Instruction::Insert("cm".to_string(), 2))
+    );
+    assert_eq!(
+        Instruction::try_from("qp-"),
+        Ok(Instruction::Remove("qp".to_string()))
+    );
+}
+
+#[test]
+fn test_instruction_target() {
+    assert_eq!(Instruction::try_from("rn=1").map(|ins| ins.target()), Ok(0));
+    assert_eq!(Instruction::try_from("cm-").map(|ins| ins.target()), Ok(0));
+    assert_eq!(Instruction::try_from("pc=4").map(|ins| ins.target()), Ok(3));
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LensBox {
+    // In the context of the instructions, "behind" means "at a
+    // greater index".
+    lenses: Vec<LabeledLens>,
+}
+
+impl Display for LensBox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let mut first = true;
+        for lens in self.lenses.iter() {
+            if first {
+                first = false;
+            } else {
+                f.write_char(' ')?;
+            }
+            write!(f, "{lens}")?;
+        }
+        Ok(())
+    }
+}
+
+impl LensBox {
+    fn with_capacity(capacity: usize) -> LensBox {
+        LensBox {
+            lenses: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lenses.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lenses.len()
+    }
+
+    /// Iterates over the lenses in the box, front to back.
+    pub fn iter(&self) -> impl Iterator<Item = &LabeledLens> {
+        self.lenses.iter()
+    }
+
+    pub fn get(&self, label: &str) -> Option<&LabeledLens> {
+        self.lenses.iter().find(|lens| lens.label == label)
+    }
+
+    /// Removes the lens with the given label, returning it if it
+    /// was present.  The lenses behind it move forward.
+    pub fn remove(&mut self, label_to_remove: &str) -> Option<LabeledLens> {
+        self.lenses
+            .iter()
+            .position(|lens| lens.label == label_to_remove)
+            .map(|pos| self.lenses.remove(pos))
+    }
+
+    /// Inserts a lens, replacing any existing lens with the same
+    /// label in place.  Returns the focal length of the replaced
+    /// lens, if there was one.
+    pub fn insert(&mut self, label_to_add: &str, focal_length: u8) -> Option<u8> {
+        if let Some(existing_lens) = self
+            .lenses
+            .iter_mut()
+            .find(|lens| lens.label == label_to_add)
+        {
+            Some(std::mem::replace(
+                &mut existing_lens.focal_length,
+                focal_length,
+            ))
+        } else {
+            self.lenses.push(LabeledLens {
+                label: label_to_add.to_string(),
+                focal_length,
+            });
+            None
+        }
+    }
+
+    pub fn perform(&mut self, instruction: &Instruction) {
+        match instruction {
+            Instruction::Remove(label) => {
+                self.remove(label);
+            }
+            Instruction::Insert(label, focal_length) => {
+                self.insert(label, *focal_length);
+            }
+        }
+    }
+
+    /// Computes the focusing power of the lenses in this box, where
+    /// `boxnum` is the 1-based number of the box.
+    pub fn power(&self, boxnum: usize) -> u64 {
+        self.lenses
+            .iter()
+            .enumerate()
+            .map(|(i, lens)| (boxnum as u64) * lens.power(i + 1))
+            .sum()
+    }
+}
+
+#[test]
+fn test_lens_box_perform() {
+    let mut b = LensBox::default();
+    b.perform(&Instruction::Insert("rn".to_string(), 1));
+    assert_eq!(
+        b,
+        LensBox {
+            lenses: vec![LabeledLens {
+                label: "rn".to_string(),
+                focal_length: 1,
+            }]
+        }
+    );
+    b.perform(&Instruction::Insert("cm".to_string(), 2));
+    assert_eq!(
+        b,
+        LensBox {
+            lenses: vec![
+                LabeledLens {
+                    label: "rn".to_string(),
+                    focal_length: 1,
+                },
+                LabeledLens {
+                    label: "cm".to_string(),
+                    focal_length: 2,
+                }
+            ]
+        }
+    );
+    b.perform(&Instruction::Remove("cm".to_string()));
+    assert_eq!(
+        b,
+        LensBox {
+            lenses: vec![LabeledLens {
+                label: "rn".to_string(),
+                focal_length: 1,
+            },]
+        }
+    );
+}
+
+#[test]
+fn test_lens_box_insert_replaces_in_place() {
+    let mut b = LensBox::default();
+    assert_eq!(b.insert("rn", 1), None);
+    assert_eq!(b.insert("cm", 2), None);
+    assert_eq!(b.insert("rn", 7), Some(1));
+    let labels: Vec<&str> = b.iter().map(|lens| lens.label.as_str()).collect();
+    assert_eq!(labels, vec!["rn", "cm"]);
+    assert_eq!(b.get("rn").map(|lens| lens.focal_length), Some(7));
+    assert_eq!(b.remove("xx"), None);
+    assert_eq!(b.remove("rn").map(|lens| lens.focal_length), Some(7));
+    assert_eq!(b.len(), 1);
+}
+
+/// The HASHMAP: 256 lens boxes, each lens being stored in the box
+/// selected by the HASH of its label.
+#[derive(Debug, Clone)]
+pub struct LensArray {
+    lens_boxes: Vec<LensBox>,
+}
+
+impl Default for LensArray {
+    fn default() -> LensArray {
+        LensArray::new()
+    }
+}
+
+impl LensArray {
+    /// The number of boxes; one for each possible HASH value.
+    pub const BOX_COUNT: usize = 256;
+
+    pub fn new() -> LensArray {
+        LensArray::with_capacity(0)
+    }
+
+    /// Creates an empty array in which each box has room for
+    /// `lenses_per_box` lenses without reallocating.
+    pub fn with_capacity(lenses_per_box: usize) -> LensArray {
+        let mut lens_boxes = Vec::with_capacity(Self::BOX_COUNT);
+        lens_boxes.resize_with(Self::BOX_COUNT, || LensBox::with_capacity(lenses_per_box));
+        LensArray { lens_boxes }
+    }
+
+    fn box_for(&self, label: &str) -> &LensBox {
+        &self.lens_boxes[usize::from(hash(label))]
+    }
+
+    fn box_for_mut(&mut self, label: &str) -> &mut LensBox {
+        &mut self.lens_boxes[usize::from(hash(label))]
+    }
+
+    /// Returns the box with the given (0-based) index.
+    pub fn get_box(&self, index: usize) -> Option<&LensBox> {
+        self.lens_boxes.get(index)
+    }
+
+    /// Inserts a lens, returning the focal length of the lens it
+    /// replaced (if any).
+    pub fn insert(&mut self, label: &str, focal_length: u8) -> Option<u8> {
+        self.box_for_mut(label).insert(label, focal_length)
+    }
+
+    /// Removes a lens, returning its focal length (if it was present).
+    pub fn remove(&mut self, label: &str) -> Option<u8> {
+        self.box_for_mut(label)
+            .remove(label)
+            .map(|lens| lens.focal_length)
+    }
+
+    /// Returns the focal length of the lens with the given label.
+    pub fn get(&self, label: &str) -> Option<u8> {
+        self.box_for(label).get(label).map(|lens| lens.focal_length)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lens_boxes.iter().map(|b| b.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lens_boxes.iter().all(|b| b.is_empty())
+    }
+
+    /// Iterates over all lenses in box order, yielding the (0-based)
+    /// index of the containing box alongside each lens.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &LabeledLens)> {
+        self.lens_boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, lens_box)| lens_box.iter().map(move |lens| (index, lens)))
+    }
+
+    /// Carries out `instruction`, returning a description of what
+    /// happened.
+    pub fn perform<'a>(&'a mut self, instruction: &'a Instruction) -> TraceEvent<'a> {
+        let box_index = instruction.target();
+        if let Some(target) = self.lens_boxes.get_mut(box_index) {
+            target.perform(instruction);
+            TraceEvent {
+                instruction,
+                box_index,
+                box_state: target,
+            }
+        } else {
+            panic!("we don't have a box {box_index}");
+        }
+    }
+
+    /// Carries out each of `instructions` in turn, passing a
+    /// description of what happened to `observer` after each one.
+    pub fn perform_sequence_with<F>(&mut self, instructions: &[Instruction], mut observer: F)
+    where
+        F: FnMut(&TraceEvent<'_>),
+    {
+        for instruction in instructions.iter() {
+            observer(&self.perform(instruction));
+        }
+    }
+
+    pub fn perform_sequence(&mut self, instructions: &[Instruction]) {
+        self.perform_sequence_with(instructions, |event| trace!("{event}"));
+    }
+
+    pub fn focusing_power(&self) -> u64 {
+        self.lens_boxes
+            .iter()
+            .enumerate()
+            .map(|(i, lens_box)| lens_box.power(i + 1))
+            .sum()
+    }
+}
+
+/// What happened when one instruction was carried out: which box it
+/// changed, and what that box contains afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent<'a> {
+    pub instruction: &'a Instruction,
+    pub box_index: usize,
+    pub box_state: &'a LensBox,
+}
+
+impl TraceEvent<'_> {
+    /// Describes the event as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        let lenses: Vec<String> = self
+            .box_state
+            .iter()
+            .map(|lens| {
+                format!(
+                    "{{\"label\":{},\"focal_length\":{}}}",
+                    json_quote(&lens.label),
+                    lens.focal_length
+                )
+            })
+            .collect();
+        format!(
+            "{{\"instruction\":{},\"box\":{},\"lenses\":[{}]}}",
+            json_quote(&self.instruction.to_string()),
+            self.box_index,
+            lenses.join(",")
+        )
+    }
+}
+
+impl Display for TraceEvent<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "After \"{}\": Box {}: {}",
+            self.instruction, self.box_index, self.box_state
+        )
+    }
+}
+
+impl Display for LensArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for (index, lens_box) in self.lens_boxes.iter().enumerate() {
+            if !lens_box.is_empty() {
+                writeln!(f, "Box {index}: {lens_box}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A way of storing the contents of the HASHMAP.  `LensArray` is the
+/// obvious one, but most of its 256 boxes are usually empty, and
+/// computing the focusing power (or displaying the array) looks at
+/// every one of them.  The other implementations exist to measure
+/// what that costs; they all behave identically.
+pub trait LensStore: Default + Display {
+    /// Inserts a lens, returning the focal length of the lens it
+    /// replaced (if any).
+    fn insert(&mut self, label: &str, focal_length: u8) -> Option<u8>;
+
+    /// Removes a lens, returning its focal length (if it was present).
+    fn remove(&mut self, label: &str) -> Option<u8>;
+
+    /// Returns the focal length of the lens with the given label.
+    fn get(&self, label: &str) -> Option<u8>;
+
+    fn focusing_power(&self) -> u64;
+
+    fn perform_all(&mut self, instructions: &[Instruction]) {
+        for instruction in instructions.iter() {
+            match instruction {
+                Instruction::Remove(label) => {
+                    self.remove(label);
+                }
+                Instruction::Insert(label, focal_length) => {
+                    self.insert(label, *focal_length);
+                }
+            }
+        }
+    }
+}
+
+impl LensStore for LensArray {
+    fn insert(&mut self, label: &str, focal_length: u8) -> Option<u8> {
+        LensArray::insert(self, label, focal_length)
+    }
+
+    fn remove(&mut self, label: &str) -> Option<u8> {
+        LensArray::remove(self, label)
+    }
+
+    fn get(&self, label: &str) -> Option<u8> {
+        LensArray::get(self, label)
+    }
+
+    fn focusing_power(&self) -> u64 {
+        LensArray::focusing_power(self)
+    }
+}
+
+/// The HASHMAP, keeping only the boxes which have a lens in them,
+/// and a list of which those are.
+#[derive(Debug, Clone)]
+pub struct SparseLensArray {
+    /// Box `i` is `None` unless it has a lens in it.
+    boxes: Vec<Option<LensBox>>,
+    /// The indexes of the boxes which are not `None`, in order.
+    occupied: Vec<usize>,
+}
+
+impl Default for SparseLensArray {
+    fn default() -> SparseLensArray {
+        SparseLensArray {
+            boxes: vec![None; LensArray::BOX_COUNT],
+            occupied: Vec::new(),
+        }
+    }
+}
+
+impl SparseLensArray {
+    fn occupied_boxes(&self) -> impl Iterator<Item = (usize, &LensBox)> {
+        self.occupied
+            .iter()
+            .filter_map(|&index| self.boxes[index].as_ref().map(|b| (index, b)))
+    }
+}
+
+impl LensStore for SparseLensArray {
+    fn insert(&mut self, label: &str, focal_length: u8) -> Option<u8> {
+        let index = usize::from(hash(label));
+        match &mut self.boxes[index] {
+            Some(lens_box) => lens_box.insert(label, focal_length),
+            empty => {
+                let mut lens_box = LensBox::default();
+                lens_box.insert(label, focal_length);
+                *empty = Some(lens_box);
+                if let Err(pos) = self.occupied.binary_search(&index) {
+                    self.occupied.insert(pos, index);
+                }
+                None
+            }
+        }
+    }
+
+    fn remove(&mut self, label: &str) -> Option<u8> {
+        let index = usize::from(hash(label));
+        let lens_box = self.boxes[index].as_mut()?;
+        let removed = lens_box.remove(label);
+        if lens_box.is_empty() {
+            self.boxes[index] = None;
+            if let Ok(pos) = self.occupied.binary_search(&index) {
+                self.occupied.remove(pos);
+            }
+        }
+        removed.map(|lens| lens.focal_length)
+    }
+
+    fn get(&self, label: &str) -> Option<u8> {
+        self.boxes[usize::from(hash(label))]
+            .as_ref()
+            .and_then(|lens_box| lens_box.get(label))
+            .map(|lens| lens.focal_length)
+    }
+
+    fn focusing_power(&self) -> u64 {
+        self.occupied_boxes()
+            .map(|(index, lens_box)| lens_box.power(index + 1))
+            .sum()
+    }
+}
+
+impl Display for SparseLensArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for (index, lens_box) in self.occupied_boxes() {
+            writeln!(f, "Box {index}: {lens_box}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The HASHMAP as a single list of lenses, in the order in which they
+/// were inserted, with an index from label to position (as in the
+/// `indexmap` crate).  The lenses in each box are in the same order
+/// as they are in this list, so the boxes are never stored at all.
+#[derive(Debug, Clone, Default)]
+pub struct IndexedLensArray {
+    /// Each lens, with its box number, in the order in which they
+    /// were inserted.  A lens which is removed leaves a gap.
+    entries: Vec<Option<(usize, LabeledLens)>>,
+    /// The position in `entries` of each lens.
+    index: HashMap<String, usize>,
+}
+
+impl IndexedLensArray {
+    fn lenses(&self) -> impl Iterator<Item = &(usize, LabeledLens)> {
+        self.entries.iter().flatten()
+    }
+}
+
+impl LensStore for IndexedLensArray {
+    fn insert(&mut self, label: &str, focal_length: u8) -> Option<u8> {
+        match self.index.get(label) {
+            Some(&pos) => self.entries[pos]
+                .as_mut()
+                .map(|(_, lens)| std::mem::replace(&mut lens.focal_length, focal_length)),
+            None => {
+                self.index.insert(label.to_string(), self.entries.len());
+                self.entries.push(Some((
+                    usize::from(hash(label)),
+                    LabeledLens {
+                        label: label.to_string(),
+                        focal_length,
+                    },
+                )));
+                None
+            }
+        }
+    }
+
+    fn remove(&mut self, label: &str) -> Option<u8> {
+        let pos = self.index.remove(label)?;
+        self.entries[pos].take().map(|(_, lens)| lens.focal_length)
+    }
+
+    fn get(&self, label: &str) -> Option<u8> {
+        let pos = self.index.get(label)?;
+        self.entries[*pos]
+            .as_ref()
+            .map(|(_, lens)| lens.focal_length)
+    }
+
+    fn focusing_power(&self) -> u64 {
+        let mut slots = [0_usize; LensArray::BOX_COUNT];
+        self.lenses()
+            .map(|(index, lens)| {
+                slots[*index] += 1;
+                (*index as u64 + 1) * lens.power(slots[*index])
+            })
+            .sum()
+    }
+}
+
+impl Display for IndexedLensArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let mut boxes: BTreeMap<usize, Vec<&LabeledLens>> = BTreeMap::new();
+        for (index, lens) in self.lenses() {
+            boxes.entry(*index).or_default().push(lens);
+        }
+        for (index, lenses) in boxes {
+            write!(f, "Box {index}:")?;
+            for lens in lenses {
+                write!(f, " {lens}")?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_lens_array_power() {
+    let mut array = LensArray::default();
+    array.lens_boxes[0].lenses = vec![
+        LabeledLens {
+            label: "rn".to_string(),
+            focal_length: 1,
+        },
+        LabeledLens {
+            label: "cm".to_string(),
+            focal_length: 2,
+        },
+    ];
+    array.lens_boxes[3].lenses = vec![
+        LabeledLens {
+            label: "ot".to_string(),
+            focal_length: 7,
+        },
+        LabeledLens {
+            label: "ab".to_string(),
+            focal_length: 5,
+        },
+        LabeledLens {
+            label: "pc".to_string(),
+            focal_length: 6,
+        },
+    ];
+    assert_eq!(array.lens_boxes[0].power(1), 1 + 4);
+    assert_eq!(array.lens_boxes[3].power(4), 28 + 40 + 72);
+    assert_eq!(array.focusing_power(), 145);
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(15)
+        .expect("there should be an example")
+        .trim_end()
+}
+
+#[test]
+fn test_lens_array_perform_sequence() {
+    let instructions: Vec<Instruction> =
+        parse_instructions(get_example()).expect("example instructions should be valid");
+    let mut array = LensArray::default();
+    array.perform_sequence(&instructions);
+    assert_eq!(
+        array.lens_boxes[0],
+        LensBox {
+            lenses: vec![
+                LabeledLens {
+                    label: "rn".to_string(),
+                    focal_length: 1,
+                },
+                LabeledLens {
+                    label: "cm".to_string(),
+                    focal_length: 2,
+                },
+            ]
+        }
+    );
+    assert_eq!(array.focusing_power(), 145);
+}
+
+#[test]
+fn test_trace_events() {
+    let instructions: Vec<Instruction> =
+        parse_instructions(get_example()).expect("example instructions should be valid");
+    let mut array = LensArray::new();
+    let mut events: Vec<String> = Vec::new();
+    array.perform_sequence_with(&instructions, |event| events.push(event.to_string()));
+    assert_eq!(events.len(), instructions.len());
+    assert_eq!(events[0], "After \"rn=1\": Box 0: [rn 1]");
+    assert_eq!(events[2], "After \"qp=3\": Box 1: [qp 3]");
+    assert_eq!(events[3], "After \"cm=2\": Box 0: [rn 1] [cm 2]");
+    assert_eq!(events[4], "After \"qp-\": Box 1: ");
+
+    let remove = Instruction::Remove("rn".to_string());
+    let event = array.perform(&remove);
+    assert_eq!(event.box_index, 0);
+    assert_eq!(event.box_state.len(), 1);
+    assert_eq!(
+        event.to_json(),
+        r#"{"instruction":"rn-","box":0,"lenses":[{"label":"cm","focal_length":2}]}"#
+    );
+}
+
+#[test]
+fn test_lens_array_map_operations() {
+    let mut array = LensArray::with_capacity(4);
+    assert!(array.is_empty());
+    assert_eq!(array.insert("rn", 1), None);
+    assert_eq!(array.insert("cm", 2), None);
+    assert_eq!(array.insert("pc", 4), None);
+    assert_eq!(array.insert("rn", 3), Some(1));
+    assert_eq!(array.len(), 3);
+    assert_eq!(array.get("rn"), Some(3));
+    assert_eq!(array.get("qp"), None);
+    assert_eq!(array.remove("cm"), Some(2));
+    assert_eq!(array.remove("cm"), None);
+    assert_eq!(array.get_box(3).map(|b| b.len()), Some(1));
+    assert_eq!(array.get_box(LensArray::BOX_COUNT), None);
+}
+
+#[test]
+fn test_lens_array_iter() {
+    let instructions: Vec<Instruction> =
+        parse_instructions(get_example()).expect("example instructions should be valid");
+    let mut array = LensArray::new();
+    array.perform_sequence(&instructions);
+    let contents: Vec<(usize, &str, u8)> = array
+        .iter()
+        .map(|(index, lens)| (index, lens.label.as_str(), lens.focal_length))
+        .collect();
+    assert_eq!(
+        contents,
+        vec![
+            (0, "rn", 1),
+            (0, "cm", 2),
+            (3, "ot", 7),
+            (3, "ab", 5),
+            (3, "pc", 6),
+        ]
+    );
+}
+
+#[test]
+fn test_lens_stores_agree() {
+    fn run<S: LensStore>(instructions: &[Instruction]) -> (String, u64, Vec<Option<u8>>) {
+        let mut store = S::default();
+        store.perform_all(instructions);
+        let lengths = ["rn", "cm", "ot", "ab", "pc", "qp", "zz"]
+            .iter()
+            .map(|label| store.get(label))
+            .collect();
+        (store.to_string(), store.focusing_power(), lengths)
+    }
+    fn check(instructions: &[Instruction]) {
+        let expected = run::<LensArray>(instructions);
+        assert_eq!(run::<SparseLensArray>(instructions), expected);
+        assert_eq!(run::<IndexedLensArray>(instructions), expected);
+    }
+    let example = parse_instructions(get_example()).expect("example instructions should be valid");
+    check(&example);
+    assert_eq!(run::<IndexedLensArray>(&example).1, 145);
+    let generated = crate::gen::initialization_sequence(&mut crate::gen::Rng::new(15), 2000);
+    check(&parse_instructions(&generated).expect("generated instructions should be valid"));
+}
+
+#[test]
+fn test_lens_store_operations() {
+    fn check<S: LensStore>() {
+        let mut store = S::default();
+        assert_eq!(store.insert("rn", 1), None);
+        assert_eq!(store.insert("cm", 2), None);
+        assert_eq!(store.insert("rn", 3), Some(1));
+        assert_eq!(store.remove("rn"), Some(3));
+        assert_eq!(store.remove("rn"), None);
+        assert_eq!(store.get("cm"), Some(2));
+        assert_eq!(store.remove("cm"), Some(2));
+        assert_eq!(store.to_string(), "");
+        assert_eq!(store.focusing_power(), 0);
+        // A lens which is put back goes behind the others.
+        store.insert("rn", 1);
+        store.insert("cm", 2);
+        store.remove("rn");
+        store.insert("rn", 4);
+        assert_eq!(store.to_string(), "Box 0: [cm 2] [rn 4]\n");
+    }
+    check::<LensArray>();
+    check::<SparseLensArray>();
+    check::<IndexedLensArray>();
+}
+
+/// Returns the initialization sequence without its trailing newline,
+/// checking that it can be hashed.
+fn initialization_sequence(s: &str) -> Result<&str, Fail> {
+    let s = s.trim();
+    if s.is_ascii() {
+        Ok(s)
+    } else {
+        Err(Fail(
+            "the initialization sequence should be ASCII".to_string(),
+        ))
+    }
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day15::part1;
+/// let example = lib::examples::examples(15)[0];
+/// assert_eq!(part1(example), Ok(1320));
+/// ```
+pub fn part1(s: &str) -> Result<u64, Fail> {
+    let s = initialization_sequence(s)?;
+    Ok(s.split(',').map(|s| u64::from(hash(s))).sum())
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(get_example()), Ok(1320));
+    assert_eq!(part1(&format!("{}\n", get_example())), Ok(1320));
+    assert!(part1("rn=1,cm\u{e9}-").is_err());
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day15::part2;
+/// let example = lib::examples::examples(15)[0];
+/// assert_eq!(part2(example), Ok(145));
+/// ```
+pub fn part2(s: &str) -> Result<u64, Fail> {
+    let instructions = parse_instructions(initialization_sequence(s)?)?;
+    let mut array = LensArray::default();
+    array.perform_sequence(&instructions);
+    Ok(array.focusing_power())
+}
+
+/// Computes the answer to part 2, keeping the lenses in an `S`.
+pub fn part2_with<S: LensStore>(s: &str) -> Result<u64, Fail> {
+    let instructions = parse_instructions(initialization_sequence(s)?)?;
+    let mut store = S::default();
+    store.perform_all(&instructions);
+    Ok(store.focusing_power())
+}
+
+pub const BACKENDS: [Backend; 3] = [
+    Backend {
+        part: 2,
+        name: "array",
+        description: "Keep all 256 boxes, whether or not they have lenses in them",
+        slow: false,
+        solve: |input| Ok(part2(input)?.into()),
+    },
+    Backend {
+        part: 2,
+        name: "sparse",
+        description: "Keep only the boxes which have lenses in them, and a list of which those are",
+        slow: false,
+        solve: |input| Ok(part2_with::<SparseLensArray>(input)?.into()),
+    },
+    Backend {
+        part: 2,
+        name: "indexed",
+        description:
+            "Keep the lenses in one list in the order they were inserted, indexed by label",
+        slow: false,
+        solve: |input| Ok(part2_with::<IndexedLensArray>(input)?.into()),
+    },
+];
+
+#[test]
+fn test_part2() {
+    assert_eq!(part2(get_example()), Ok(145));
+    for backend in BACKENDS {
+        assert_eq!((backend.solve)(get_example()), Ok(145.into()));
+    }
+    assert!(part2("\u{e9}=1").is_err());
+}
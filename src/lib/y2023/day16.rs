@@ -0,0 +1,566 @@
+use std::collections::HashMap;
+#[cfg(test)]
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter, Write};
+use std::str;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::grid::{BoundingBox, CompassDirection, Position};
+use crate::memo::Cache;
+use crate::solver::Notes;
+use crate::{debug, trace};
+
+pub const NOTES: Notes = Notes {
+    title: "The Floor Will Be Lava",
+    algorithm: "Beams are traced through the grid a straight run at a time, remembering each (position, direction) state already visited so that loops terminate.  The runs are cached, since beams from different starting points share them.  Part 2 tries every starting point on the edge.",
+    complexity: "O(n) for one starting point, O(n·(w + h)) in all, for n cells",
+};
+
+/// A kind of tile which a beam can pass through.  The puzzle's
+/// mirrors and splitters are `Tile`s, but a `Grid` can be made of
+/// anything which implements this, so other kinds of tile can be
+/// tried out without changing the simulator.
+pub trait Optic: Copy + TryFrom<char, Error = Fail> {
+    /// Returns the directions in which a beam which enters the tile
+    /// heading in `direction` leaves it.  A beam which carries on
+    /// unchanged leaves in `[Some(direction), None]`, and one which
+    /// is absorbed in `[None, None]`.
+    fn deflect(&self, direction: CompassDirection) -> [Option<CompassDirection>; 2];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tile {
+    Empty,
+    DashSplitter,    // -
+    PipeSplitter,    // |
+    BackslashMirror, // \
+    SlashMirror,     // /
+}
+
+impl TryFrom<char> for Tile {
+    type Error = Fail;
+    fn try_from(ch: char) -> Result<Tile, Self::Error> {
+        use Tile::*;
+        match ch {
+            '-' => Ok(DashSplitter),
+            '|' => Ok(PipeSplitter),
+            '/' => Ok(SlashMirror),
+            '\\' => Ok(BackslashMirror),
+            '.' => Ok(Empty),
+            other => Err(fail!("unexpected character {other}")),
+        }
+    }
+}
+
+impl Optic for Tile {
+    fn deflect(&self, direction: CompassDirection) -> [Option<CompassDirection>; 2] {
+        use CompassDirection::*;
+        let (updated_direction, new_beam_direction) = match self {
+            Tile::Empty => (direction, None),
+            Tile::DashSplitter => match direction {
+                East | West => (direction, None),
+                North | South => (East, Some(West)),
+            },
+            Tile::PipeSplitter => match direction {
+                North | South => (direction, None),
+                East | West => (North, Some(South)),
+            },
+            Tile::SlashMirror => (
+                match direction {
+                    North => East,
+                    East => North,
+                    South => West,
+                    West => South,
+                },
+                None,
+            ),
+            Tile::BackslashMirror => (
+                match direction {
+                    North => West,
+                    East => South,
+                    South => East,
+                    West => North,
+                },
+                None,
+            ),
+        };
+        [Some(updated_direction), new_beam_direction]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Grid<T: Optic = Tile> {
+    cells: HashMap<Position, T>,
+    bbox: BoundingBox,
+}
+
+impl<T: Optic> Grid<T> {
+    /// Returns each way a beam can enter the grid from outside it.
+    pub fn possible_start_points(&self) -> impl Iterator<Item = Beam> + '_ {
+        self.bbox
+            .edge_positions_with_inward_direction()
+            .map(|(pos, direction)| Beam { pos, direction })
+    }
+}
+
+pub fn parse_grid(s: &str) -> Result<Grid, Fail> {
+    parse_grid_of(s)
+}
+
+/// Reads a grid made of tiles of type `T`.
+pub fn parse_grid_of<T: Optic>(s: &str) -> Result<Grid<T>, Fail> {
+    let mut here = Position { x: 0, y: 0 };
+    let mut cells = HashMap::new();
+    let mut bbox = BoundingBox::new(&here);
+    for ch in s.chars() {
+        if ch == '\n' {
+            if here.y == 0 && here.x == 0 {
+                // Ignore so that the bounding box stays correct.
+                continue;
+            }
+            here.x = 0;
+            here.y += 1;
+        } else {
+            cells.insert(here, T::try_from(ch)?);
+            bbox.update(&here);
+            here.x += 1;
+        }
+    }
+    Ok(Grid { cells, bbox })
+}
+
+/// A beam of light in a tile of the grid, heading in some direction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Beam {
+    pub pos: Position,
+    pub direction: CompassDirection,
+}
+
+impl Beam {
+    /// Returns the beams which leave the tile this beam is in, if the
+    /// tile is `tile`.
+    fn next<T: Optic>(self, tile: &T) -> [Option<Beam>; 2] {
+        tile.deflect(self.direction).map(|direction| {
+            direction.map(|direction| Beam {
+                pos: self.pos.move_direction(&direction),
+                direction,
+            })
+        })
+    }
+}
+
+/// The beam states along a straight run of a beam, and the beams
+/// which carry on from the end of it.
+#[derive(Debug, Clone, Default)]
+struct Run {
+    beams: Vec<Beam>,
+    next: Vec<Beam>,
+}
+
+/// Follows the beam `start` in a straight line until it leaves the
+/// grid or reaches a tile which changes its direction.
+fn straight_run<T: Optic>(start: &Beam, grid: &Grid<T>) -> Run {
+    let direction = start.direction;
+    let mut run = Run::default();
+    for pos in std::iter::once(start.pos).chain(start.pos.ray(&direction)) {
+        let Some(tile) = grid.cells.get(&pos) else {
+            // The beam has left the grid, so we are done with it.
+            break;
+        };
+        let beam = Beam { pos, direction };
+        let step = beam.clone().next(tile);
+        run.beams.push(beam);
+        match step {
+            [Some(b), None] if b.direction == direction => (),
+            beams => {
+                run.next.extend(beams.into_iter().flatten());
+                break;
+            }
+        }
+    }
+    run
+}
+
+/// Caches the straight runs of beams.  In part 2, beams from many
+/// starting points follow the same runs.
+type RunCache = Cache<Beam, Run>;
+
+/// A set of beam states, with a flag for each direction at each
+/// tile of the grid.  This is much smaller and faster than a
+/// `HashSet<Beam>`.
+struct BeamStates {
+    bbox: BoundingBox,
+    seen: Vec<[bool; 4]>,
+}
+
+impl BeamStates {
+    fn new(bbox: BoundingBox) -> BeamStates {
+        BeamStates {
+            bbox,
+            seen: vec![[false; 4]; bbox.area() as usize],
+        }
+    }
+
+    fn index(&self, pos: &Position) -> usize {
+        assert!(self.bbox.contains(pos), "{pos} is outside the grid");
+        let x = pos.x - self.bbox.top_left.x;
+        let y = pos.y - self.bbox.top_left.y;
+        (y * self.bbox.width() + x) as usize
+    }
+
+    /// Adds `beam`, returning false if it was already present.
+    fn insert(&mut self, beam: &Beam) -> bool {
+        let index = self.index(&beam.pos);
+        let seen = &mut self.seen[index][beam.direction as usize];
+        !std::mem::replace(seen, true)
+    }
+
+    /// Returns each tile which a beam passes through, with the number
+    /// of directions in which beams pass through it.
+    fn tiles(&self) -> impl Iterator<Item = (Position, usize)> + '_ {
+        self.bbox
+            .surface()
+            .zip(self.seen.iter())
+            .map(|(pos, directions)| (pos, directions.iter().filter(|d| **d).count()))
+            .filter(|(_, count)| *count > 0)
+    }
+}
+
+/// Returns every beam state (a position and a direction) which occurs
+/// inside the grid when `initial` enters it.
+fn trace_beams<T: Optic>(initial: Beam, grid: &Grid<T>, runs: &mut RunCache) -> BeamStates {
+    let mut cycle_detector = BeamStates::new(grid.bbox);
+    let mut todo = vec![initial];
+    while let Some(start) = todo.pop() {
+        let run = runs.get_or_insert_with(start, |start| straight_run(start, grid));
+        trace!("beam run of {} tiles", run.beams.len());
+        // If the beam reaches a state we have already seen, then we
+        // also know where it goes from there.
+        let mut repeated = false;
+        for beam in run.beams.iter() {
+            if !cycle_detector.insert(beam) {
+                repeated = true;
+                break;
+            }
+        }
+        if !repeated {
+            todo.extend(run.next.iter().cloned());
+        }
+    }
+    cycle_detector
+}
+
+/// Follows the beams one tile at a time, keeping every state in a
+/// `HashSet`, as a check on `trace_beams`.
+#[cfg(test)]
+fn trace_beams_slowly(initial: Beam, grid: &Grid) -> HashMap<Position, usize> {
+    let mut seen: HashSet<Beam> = HashSet::new();
+    let mut todo = vec![initial];
+    while let Some(beam) = todo.pop() {
+        let Some(tile) = grid.cells.get(&beam.pos) else {
+            continue;
+        };
+        if seen.insert(beam.clone()) {
+            todo.extend(beam.next(tile).into_iter().flatten());
+        }
+    }
+    let mut visits = HashMap::new();
+    for beam in seen {
+        *visits.entry(beam.pos).or_insert(0) += 1;
+    }
+    visits
+}
+
+#[test]
+fn test_trace_beams() {
+    let mut rng = crate::gen::Rng::new(16);
+    let mut inputs = vec![get_example().to_string()];
+    inputs.extend((0..5).map(|_| crate::gen::mirror_grid(&mut rng, 30, 20, 0.2)));
+    for input in inputs {
+        let grid = parse_grid(&input).expect("input should be valid");
+        let mut runs = RunCache::new();
+        for start in grid.possible_start_points() {
+            let visits: HashMap<Position, usize> = trace_beams(start.clone(), &grid, &mut runs)
+                .tiles()
+                .collect();
+            assert_eq!(visits, trace_beams_slowly(start, &grid), "{input}");
+        }
+    }
+}
+
+/// For each tile, the number of beam states (that is, the number of
+/// directions in which a beam passes through it) for a single initial
+/// beam.  Tiles with a non-zero count are energised.
+#[derive(Debug, Clone)]
+pub struct Heatmap {
+    visits: HashMap<Position, usize>,
+    bbox: BoundingBox,
+}
+
+impl Heatmap {
+    fn new<T: Optic>(initial: Beam, grid: &Grid<T>, runs: &mut RunCache) -> Heatmap {
+        Heatmap {
+            visits: trace_beams(initial, grid, runs).tiles().collect(),
+            bbox: grid.bbox,
+        }
+    }
+
+    pub fn energised(&self) -> usize {
+        self.visits.len()
+    }
+
+    pub fn visits(&self, pos: &Position) -> usize {
+        self.visits.get(pos).copied().unwrap_or(0)
+    }
+
+    /// Returns the visit counts as comma-separated values, one line
+    /// per row of the grid.
+    pub fn to_csv(&self) -> String {
+        let mut result = String::new();
+        for y in self.bbox.rows() {
+            let row: Vec<String> = self
+                .bbox
+                .columns()
+                .map(|x| self.visits(&Position { x, y }).to_string())
+                .collect();
+            result.push_str(&row.join(","));
+            result.push('\n');
+        }
+        result
+    }
+}
+
+/// Shows the visit count of each tile, with '.' for tiles which are
+/// not energised.
+impl Display for Heatmap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for y in self.bbox.rows() {
+            for x in self.bbox.columns() {
+                match self.visits(&Position { x, y }) {
+                    0 => f.write_char('.')?,
+                    n => write!(f, "{n}")?,
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+/// Follows the beam `start` through the grid, and returns the tiles
+/// it energises.
+///
+/// ```
+/// use lib::grid::{CompassDirection, Position};
+/// use lib::y2023::day16::{parse_grid, simulate, Beam};
+/// let grid = parse_grid(".|.\n...\n").unwrap();
+/// let start = Beam { pos: Position { x: 0, y: 0 }, direction: CompassDirection::East };
+/// assert_eq!(simulate(&grid, start).energised(), 3);
+/// ```
+pub fn simulate<T: Optic>(grid: &Grid<T>, start: Beam) -> Heatmap {
+    Heatmap::new(start, grid, &mut RunCache::new())
+}
+
+fn count_energised_squares<T: Optic>(initial: Beam, grid: &Grid<T>, runs: &mut RunCache) -> usize {
+    Heatmap::new(initial, grid, runs).energised()
+}
+
+fn top_left_start<T: Optic>(grid: &Grid<T>) -> Beam {
+    Beam {
+        direction: CompassDirection::East,
+        pos: grid.bbox.top_left,
+    }
+}
+
+/// Counts the tiles energised by a beam entering the top-left tile
+/// heading east.
+pub fn energised_from_top_left<T: Optic>(grid: &Grid<T>) -> usize {
+    count_energised_squares(top_left_start(grid), grid, &mut RunCache::new())
+}
+
+/// Returns the heatmap for a beam entering the top-left tile heading
+/// east.
+pub fn heatmap_from_top_left<T: Optic>(grid: &Grid<T>) -> Heatmap {
+    simulate(grid, top_left_start(grid))
+}
+
+/// Returns the heatmap for the beam entering from the edge of the
+/// grid which energises the most tiles.
+pub fn heatmap_for_best_start<T: Optic>(grid: &Grid<T>) -> Option<Heatmap> {
+    let mut runs = RunCache::new();
+    let best = grid
+        .possible_start_points()
+        .map(|start| Heatmap::new(start, grid, &mut runs))
+        .max_by_key(Heatmap::energised);
+    debug!("beam run cache: {}", runs.stats());
+    best
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(16).expect("there should be an example")
+}
+
+#[test]
+fn test_energised_from_top_left() {
+    let grid = parse_grid(get_example()).expect("example should be valid");
+    assert_eq!(energised_from_top_left(&grid), 46);
+}
+
+#[test]
+fn test_possible_start_points() {
+    let grid = parse_grid(get_example()).expect("example should be valid");
+    let starts: HashSet<Beam> = grid.possible_start_points().collect();
+    assert_eq!(starts.len(), 40);
+    // Every tile on the edge (including the last row and column) is a
+    // starting point.
+    for pos in grid.bbox.surface() {
+        let on_edge = pos.x == 0 || pos.x == 9 || pos.y == 0 || pos.y == 9;
+        assert_eq!(starts.iter().any(|beam| beam.pos == pos), on_edge, "{pos}");
+    }
+    // A beam can enter a corner tile from either side.
+    for (pos, direction) in [
+        ((9, 9), CompassDirection::West),
+        ((9, 9), CompassDirection::North),
+        ((9, 0), CompassDirection::South),
+        ((0, 9), CompassDirection::East),
+    ] {
+        let (x, y) = pos;
+        let beam = Beam {
+            pos: Position { x, y },
+            direction,
+        };
+        assert!(starts.contains(&beam), "{beam:?}");
+    }
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day16::part1;
+/// let example = lib::examples::examples(16)[0];
+/// assert_eq!(part1(example), Ok(46));
+/// ```
+pub fn part1(input: &str) -> Result<usize, Fail> {
+    Ok(energised_from_top_left(&parse_grid(input)?))
+}
+
+/// Finds the largest number of tiles which can be energised by a
+/// beam entering from the edge of the grid.
+pub fn max_energised<T: Optic>(grid: &Grid<T>) -> usize {
+    let mut runs = RunCache::new();
+    let best = grid
+        .possible_start_points()
+        .map(|start| count_energised_squares(start, grid, &mut runs))
+        .max()
+        .unwrap_or(0);
+    debug!("beam run cache: {}", runs.stats());
+    best
+}
+
+#[test]
+fn test_max_energised() {
+    let grid = parse_grid(get_example()).expect("example should be valid");
+    assert_eq!(max_energised(&grid), 51);
+}
+
+#[test]
+fn test_heatmap() {
+    let grid = parse_grid(get_example()).expect("example should be valid");
+    let heatmap = heatmap_from_top_left(&grid);
+    assert_eq!(heatmap.energised(), 46);
+    let energised: String = heatmap
+        .to_string()
+        .chars()
+        .map(|ch| if ch.is_ascii_digit() { '#' } else { ch })
+        .collect();
+    assert_eq!(
+        energised,
+        concat!(
+            "######....\n",
+            ".#...#....\n",
+            ".#...#####\n",
+            ".#...##...\n",
+            ".#...##...\n",
+            ".#...##...\n",
+            ".#..####..\n",
+            "########..\n",
+            ".#######..\n",
+            ".#...#.#..\n",
+        )
+    );
+    // The beam passes through the top-left tile only once, heading
+    // east.
+    assert_eq!(heatmap.visits(&Position { x: 0, y: 0 }), 1);
+    let csv = heatmap.to_csv();
+    assert_eq!(csv.lines().count(), 10);
+    assert!(csv.starts_with("1,"));
+    assert!(csv.lines().all(|line| line.split(',').count() == 10));
+
+    let best = heatmap_for_best_start(&grid).expect("grid is not empty");
+    assert_eq!(best.energised(), 51);
+}
+
+/// A tile set with a block, '#', which absorbs beams, as well as the
+/// puzzle's tiles.
+#[cfg(test)]
+#[derive(Debug, Clone, Copy)]
+struct Absorbing(Option<Tile>);
+
+#[cfg(test)]
+impl TryFrom<char> for Absorbing {
+    type Error = Fail;
+    fn try_from(ch: char) -> Result<Absorbing, Fail> {
+        match ch {
+            '#' => Ok(Absorbing(None)),
+            ch => Tile::try_from(ch).map(|tile| Absorbing(Some(tile))),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Optic for Absorbing {
+    fn deflect(&self, direction: CompassDirection) -> [Option<CompassDirection>; 2] {
+        match self.0 {
+            Some(tile) => tile.deflect(direction),
+            None => [None, None],
+        }
+    }
+}
+
+#[test]
+fn test_other_tiles() {
+    let east = |x, y| Beam {
+        pos: Position { x, y },
+        direction: CompassDirection::East,
+    };
+    let grid: Grid<Absorbing> = parse_grid_of("..#..\n").expect("test input should be valid");
+    assert_eq!(simulate(&grid, east(0, 0)).energised(), 3);
+    assert_eq!(simulate(&grid, east(3, 0)).energised(), 2);
+    let grid: Grid<Absorbing> = parse_grid_of("\\..\n#..\n").expect("test input should be valid");
+    let heatmap = simulate(&grid, east(0, 0));
+    assert_eq!(heatmap.to_string(), "1..\n1..\n");
+    assert_eq!(max_energised(&grid), 3);
+    // The puzzle's own tiles behave as they do in a plain grid.
+    let grid: Grid<Absorbing> = parse_grid_of(get_example()).expect("example should be valid");
+    assert_eq!(energised_from_top_left(&grid), 46);
+    assert!(parse_grid_of::<Absorbing>("..x\n").is_err());
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day16::part2;
+/// let example = lib::examples::examples(16)[0];
+/// assert_eq!(part2(example), Ok(51));
+/// ```
+pub fn part2(input: &str) -> Result<usize, Fail> {
+    Ok(max_energised(&parse_grid(input)?))
+}
+
+#[test]
+fn test_parts() {
+    assert_eq!(part1(get_example()), Ok(46));
+    assert_eq!(part2(get_example()), Ok(51));
+}
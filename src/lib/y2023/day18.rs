@@ -0,0 +1,558 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::graph::flood_fill;
+use crate::grid::{BoundingBox, CompassDirection, Grid2D, Position};
+use crate::solver::{Backend, Notes};
+
+pub const NOTES: Notes = Notes {
+    title: "Lavaduct Lagoon",
+    algorithm: "The trench is dug out on a grid and the interior found with a flood fill.  The shoelace formula together with Pick's theorem gives the same answer without a grid.",
+    complexity: "O(a) in the area of the lagoon, or O(i) with the shoelace formula for i instructions",
+};
+
+/// The colour of the trench dug by an instruction, written in the
+/// dig plan as a hex triplet such as "(#70c710)".
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Rgb(u32);
+
+impl Rgb {
+    pub fn new(red: u8, green: u8, blue: u8) -> Rgb {
+        Rgb(u32::from_be_bytes([0, red, green, blue]))
+    }
+
+    pub fn red(&self) -> u8 {
+        self.0.to_be_bytes()[1]
+    }
+
+    pub fn green(&self) -> u8 {
+        self.0.to_be_bytes()[2]
+    }
+
+    pub fn blue(&self) -> u8 {
+        self.0.to_be_bytes()[3]
+    }
+
+    /// Returns the colour as a 24-bit number, 0xRRGGBB.
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "#{:06x}", self.0)
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = Fail;
+
+    /// Parses a colour in the form it has in the dig plan, such as
+    /// "(#70c710)".
+    fn from_str(s: &str) -> Result<Rgb, Fail> {
+        let bad = || fail!("colour {s} should have the form (#rrggbb)");
+        let hex = s
+            .strip_prefix("(#")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .filter(|hex| hex.len() == 6 && hex.chars().all(|ch| ch.is_ascii_hexdigit()))
+            .ok_or_else(bad)?;
+        u32::from_str_radix(hex, 16).map(Rgb).map_err(|_| bad())
+    }
+}
+
+#[test]
+fn test_rgb() {
+    let colour: Rgb = "(#70c710)".parse().expect("colour is valid");
+    assert_eq!(colour, Rgb::new(0x70, 0xc7, 0x10));
+    assert_eq!(
+        (colour.red(), colour.green(), colour.blue()),
+        (0x70, 0xc7, 0x10)
+    );
+    assert_eq!(colour.value(), 0x70c710);
+    assert_eq!(colour.to_string(), "#70c710");
+    assert_eq!("(#0A0B0C)".parse::<Rgb>(), Ok(Rgb::new(10, 11, 12)));
+    for bad in [
+        "#70c710",
+        "(70c710)",
+        "(#70c71)",
+        "(#70c7100)",
+        "(#70c71g)",
+        "(#+0c710)",
+        "",
+    ] {
+        assert!(bad.parse::<Rgb>().is_err(), "{bad}");
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Instruction {
+    pub direction: CompassDirection,
+    pub distance: i64,
+    pub colour: Rgb,
+}
+
+fn parse_integer(s: &str) -> Result<i64, Fail> {
+    match s.parse() {
+        Ok(n) => Ok(n),
+        Err(e) => Err(Fail(e.to_string())),
+    }
+}
+
+fn parse_line(s: &str) -> Result<Instruction, Fail> {
+    match s.split_once(' ') {
+        Some((dir, dist_and_colour)) => match dist_and_colour.split_once(' ') {
+            Some((dist, colour)) => Ok(Instruction {
+                direction: dir.parse()?,
+                distance: parse_integer(dist)?,
+                colour: colour.parse()?,
+            }),
+            None => Err(Fail("colour field is missing".to_string())),
+        },
+        None => Err(Fail("line should contain spaces".to_string())),
+    }
+}
+
+/// Parses a dig plan, one instruction (such as "R 6 (#70c710)") per
+/// line.
+pub fn parse_plan(s: &str) -> Result<Vec<Instruction>, Fail> {
+    s.split_terminator('\n')
+        .map(parse_line)
+        .collect::<Result<Vec<Instruction>, Fail>>()
+}
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(18).expect("there should be an example")
+}
+
+#[test]
+fn test_parse_example() {
+    let plan = parse_plan(get_example()).expect("example should be valid");
+    assert_eq!(plan.len(), 14);
+    assert_eq!(
+        plan[0],
+        Instruction {
+            direction: CompassDirection::East,
+            distance: 6,
+            colour: Rgb::new(0x70, 0xc7, 0x10),
+        }
+    );
+    assert_eq!(plan[13].colour.to_string(), "#7a21e3");
+    assert!(parse_plan("R 6 (#70c710\n").is_err());
+    assert!(parse_plan("R 6 red\n").is_err());
+}
+
+/// The cubes of ground dug out by following a dig plan.  This
+/// represents each cube individually, so it is only practical for
+/// small plans; `measure` works for plans of any size.
+#[derive(Debug, Hash, Eq, PartialEq)]
+pub struct Grid {
+    pos: Position,
+    cubes: BTreeSet<Position>,
+    bbox: BoundingBox,
+}
+
+impl Grid {
+    pub fn new(start: Position) -> Grid {
+        let mut cubes = BTreeSet::new();
+        cubes.insert(start);
+        Grid {
+            bbox: BoundingBox::new(&start),
+            pos: start,
+            cubes,
+        }
+    }
+
+    pub fn capacity(&self) -> i64 {
+        self.cubes.len() as i64
+    }
+
+    fn dig_at(&mut self, pos: Position) {
+        self.bbox.update(&pos);
+        self.cubes.insert(pos);
+        self.pos = pos;
+    }
+
+    pub fn dig(&mut self, direction: CompassDirection, dist: i64) {
+        for pos in self.pos.ray(&direction).take(dist.max(0) as usize) {
+            self.dig_at(pos)
+        }
+    }
+
+    fn find_interior(&self) -> Result<BTreeSet<Position>, Fail> {
+        // Leave a margin around the trench, so that the exterior is
+        // connected and includes the top-left corner.
+        let origin = Position {
+            x: self.bbox.top_left.x - 1,
+            y: self.bbox.top_left.y - 1,
+        };
+        let width = self.bbox.width() as usize + 2;
+        let height = self.bbox.height() as usize + 2;
+        let mut trench = Grid2D::new(width, height, false);
+        for pos in self.cubes.iter() {
+            if let Some(cell) = trench.get_mut(&(*pos - origin)) {
+                *cell = true;
+            }
+        }
+        let exterior = flood_fill(&trench, &Position { x: 0, y: 0 }, |dug| !dug)?;
+        Ok(self
+            .bbox
+            .surface()
+            .filter(|pos| exterior.get(&(*pos - origin)) == Some(&false))
+            .collect())
+    }
+
+    pub fn excavate_interior(&mut self) -> Result<(), Fail> {
+        // changes to the interior will not affect the bounding box.
+        self.cubes.extend(self.find_interior()?);
+        Ok(())
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        for y in self.bbox.top_left.y..=self.bbox.bottom_right.y {
+            for x in self.bbox.top_left.x..=self.bbox.bottom_right.x {
+                let here = Position { x, y };
+                f.write_char(if self.cubes.contains(&here) { '#' } else { '.' })?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+pub fn dig_trenches(plan: &[Instruction]) -> Grid {
+    let mut grid: Grid = Grid::new(Position { x: 0, y: 0 });
+    for instruction in plan.iter() {
+        grid.dig(instruction.direction, instruction.distance);
+    }
+    grid
+}
+
+#[test]
+fn test_example_dig() {
+    let plan = parse_plan(get_example()).expect("example should be valid");
+    let grid = dig_trenches(&plan);
+    crate::assert_grid_snapshot!(grid, "tests/snapshots/day18_dig.txt");
+}
+
+#[test]
+fn test_example_excavate_interior() {
+    let plan = parse_plan(get_example()).expect("example should be valid");
+    let mut grid = dig_trenches(&plan);
+    grid.excavate_interior()
+        .expect("the interior should be found");
+    crate::assert_grid_snapshot!(grid, "tests/snapshots/day18_excavated.txt");
+}
+
+/// The geometry of the polygon traced out by a closed dig plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metrics {
+    /// The number of corners in the trench.
+    pub vertices: usize,
+    /// The length of the trench.
+    pub perimeter: i64,
+    /// The area enclosed by the centre line of the trench (by the
+    /// shoelace formula).  This is positive if the path runs clockwise
+    /// as seen with y increasing downwards (i.e. when "D" means
+    /// south).
+    pub signed_area: i64,
+}
+
+impl Metrics {
+    /// Returns the number of cubes in the lagoon (the trench plus its
+    /// interior).  By Pick's theorem, the lattice points strictly
+    /// inside the polygon number `area - perimeter/2 + 1`, to which we
+    /// add the `perimeter` points on the trench itself.
+    pub fn capacity(&self) -> i64 {
+        self.signed_area.abs() + self.perimeter / 2 + 1
+    }
+}
+
+impl Display for Metrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        writeln!(f, "vertices: {}", self.vertices)?;
+        writeln!(f, "perimeter: {}", self.perimeter)?;
+        writeln!(f, "signed area: {}", self.signed_area)?;
+        writeln!(f, "capacity: {}", self.capacity())
+    }
+}
+
+/// Simplifies a dig plan without changing the path it traces, except
+/// that a trench which is dug and then immediately retraced is left
+/// out.  Consecutive instructions in the same direction are merged,
+/// an instruction followed by one in the opposite direction is
+/// replaced by their difference (or by nothing, if they cancel out),
+/// and instructions to dig nowhere are dropped.
+///
+/// In the result, no two consecutive instructions are in the same or
+/// opposite directions.  A merged instruction has the colour of the
+/// first of the instructions it replaces.  The distances in `plan`
+/// should not be negative.
+pub fn optimize(plan: &[Instruction]) -> Vec<Instruction> {
+    let mut result: Vec<Instruction> = Vec::with_capacity(plan.len());
+    for instruction in plan.iter().filter(|ins| ins.distance != 0) {
+        match result.last_mut() {
+            Some(last) if last.direction == instruction.direction => {
+                last.distance += instruction.distance;
+            }
+            Some(last) if last.direction == instruction.direction.reversed() => {
+                if last.distance > instruction.distance {
+                    last.distance -= instruction.distance;
+                } else if last.distance < instruction.distance {
+                    last.direction = instruction.direction;
+                    last.distance = instruction.distance - last.distance;
+                } else {
+                    // Popping keeps the invariant: the instruction
+                    // before `last` was not collinear with it.
+                    result.pop();
+                }
+            }
+            _ => result.push(*instruction),
+        }
+    }
+    result
+}
+
+#[test]
+fn test_optimize() {
+    let plan = parse_plan(concat!(
+        "R 2 (#000001)\n",
+        "R 3 (#000002)\n",
+        "L 3 (#000003)\n",
+        "D 0 (#000004)\n",
+        "D 2 (#000005)\n",
+        "L 2 (#000006)\n",
+        "U 1 (#000007)\n",
+        "D 1 (#000008)\n",
+        "U 3 (#000009)\n",
+        "D 1 (#00000a)\n",
+    ))
+    .expect("plan should be valid");
+    assert_eq!(
+        optimize(&plan),
+        parse_plan(concat!(
+            "R 2 (#000001)\n",
+            "D 2 (#000005)\n",
+            "L 2 (#000006)\n",
+            "U 2 (#000009)\n",
+        ))
+        .expect("plan should be valid")
+    );
+    // The example has nothing to simplify.
+    let example = parse_plan(get_example()).expect("example should be valid");
+    assert_eq!(optimize(&example), example);
+    assert_eq!(optimize(&[]), vec![]);
+}
+
+/// Returns twice the signed area enclosed by the path traced by
+/// `plan`, by the shoelace formula, and the point where the path
+/// ends.
+fn shoelace(plan: &[Instruction]) -> (i64, Position) {
+    let mut pos = Position { x: 0, y: 0 };
+    let mut twice_area = 0;
+    for instruction in plan.iter() {
+        let next = pos.step_n(&instruction.direction, instruction.distance);
+        twice_area += pos.x * next.y - next.x * pos.y;
+        pos = next;
+    }
+    (twice_area, pos)
+}
+
+/// Computes the geometry of the path traced by `plan`, which must
+/// return to its starting point.
+pub fn measure(plan: &[Instruction]) -> Result<Metrics, Fail> {
+    let mut perimeter = 0;
+    for instruction in plan.iter() {
+        if instruction.distance < 0 {
+            return Err(fail!(
+                "instruction {:?} has a negative distance",
+                instruction
+            ));
+        }
+        perimeter += instruction.distance;
+    }
+    // Each edge of the simplified plan is a side of the polygon, but
+    // the perimeter must include any trench which is dug twice.
+    let edges = optimize(plan);
+    let (twice_area, end) = shoelace(&edges);
+    if end != (Position { x: 0, y: 0 }) {
+        return Err(fail!(
+            "the dig plan does not return to its starting point (it finishes at {end})"
+        ));
+    }
+    let mut vertices = edges.len();
+    if vertices > 1 && edges.first().map(|e| e.direction) == edges.last().map(|e| e.direction) {
+        // The first and last edges join up.
+        vertices -= 1;
+    }
+    Ok(Metrics {
+        vertices,
+        perimeter,
+        signed_area: twice_area / 2,
+    })
+}
+
+#[test]
+fn test_measure_example() {
+    let plan = parse_plan(get_example()).expect("example should be valid");
+    let metrics = measure(&plan).expect("example should be closed");
+    assert_eq!(
+        metrics,
+        Metrics {
+            vertices: 14,
+            perimeter: 38,
+            signed_area: 42,
+        }
+    );
+    assert_eq!(metrics.capacity(), 62);
+    let mut grid = dig_trenches(&plan);
+    grid.excavate_interior()
+        .expect("the interior should be found");
+    assert_eq!(metrics.capacity(), grid.capacity());
+}
+
+#[test]
+fn test_measure_anticlockwise() {
+    // A 3x3 square, traced anticlockwise from a point in the middle of
+    // one side, so that the first and last edges are collinear.
+    let plan = parse_plan(concat!(
+        "D 1 (#000000)\n",
+        "R 2 (#000000)\n",
+        "U 2 (#000000)\n",
+        "L 2 (#000000)\n",
+        "D 1 (#000000)\n",
+    ))
+    .expect("plan should be valid");
+    let metrics = measure(&plan).expect("plan should be closed");
+    assert_eq!(
+        metrics,
+        Metrics {
+            vertices: 4,
+            perimeter: 8,
+            signed_area: -4,
+        }
+    );
+    assert_eq!(metrics.capacity(), 9);
+}
+
+#[test]
+fn test_measure_retraced_trench() {
+    // A 3x3 square with a spur of three cubes sticking out of it.
+    let plan = parse_plan(concat!(
+        "R 2 (#000000)\n",
+        "R 3 (#000000)\n",
+        "L 3 (#000000)\n",
+        "D 2 (#000000)\n",
+        "L 2 (#000000)\n",
+        "U 2 (#000000)\n",
+    ))
+    .expect("plan should be valid");
+    let metrics = measure(&plan).expect("plan should be closed");
+    assert_eq!(
+        metrics,
+        Metrics {
+            vertices: 4,
+            perimeter: 14,
+            signed_area: 4,
+        }
+    );
+    assert_eq!(Ok(metrics.capacity()), dig_capacity(&plan));
+    assert_eq!(metrics.capacity(), 12);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_optimize_preserves_area(moves in proptest::collection::vec((0..4_usize, 0..6_i64), 0..40)) {
+        use CompassDirection::*;
+        let mut plan: Vec<Instruction> = moves
+            .into_iter()
+            .map(|(d, distance)| Instruction {
+                direction: [North, East, South, West][d],
+                distance,
+                colour: Rgb::new(0, 0, 0),
+            })
+            .collect();
+        // Close the path, so that it encloses an area.
+        let (_, end) = shoelace(&plan);
+        for (direction, distance) in [(West, end.x), (North, end.y)] {
+            let (direction, distance) = if distance < 0 {
+                (direction.reversed(), -distance)
+            } else {
+                (direction, distance)
+            };
+            plan.push(Instruction { direction, distance, colour: Rgb::new(0, 0, 0) });
+        }
+        let optimized = optimize(&plan);
+        proptest::prop_assert_eq!(shoelace(&optimized), shoelace(&plan));
+        proptest::prop_assert_eq!(shoelace(&optimized).1, Position { x: 0, y: 0 });
+        proptest::prop_assert_eq!(&optimize(&optimized), &optimized);
+        for pair in optimized.windows(2) {
+            proptest::prop_assert!(pair[0].direction != pair[1].direction);
+            proptest::prop_assert!(pair[0].direction != pair[1].direction.reversed());
+        }
+        let metrics = measure(&plan).expect("the path should be closed");
+        let perimeter: i64 = plan.iter().map(|ins| ins.distance).sum();
+        proptest::prop_assert_eq!(metrics.perimeter, perimeter);
+    }
+}
+
+#[test]
+fn test_measure_open_path() {
+    let plan = parse_plan("R 2 (#000000)\nD 2 (#000000)\n").expect("plan should be valid");
+    assert!(measure(&plan).is_err());
+}
+
+/// Counts the cubes in the lagoon by digging it out one cube at a
+/// time.
+pub fn dig_capacity(plan: &[Instruction]) -> Result<i64, Fail> {
+    let mut grid = dig_trenches(plan);
+    grid.excavate_interior()?;
+    Ok(grid.capacity())
+}
+
+#[test]
+fn test_example_dig_capacity() {
+    let plan = parse_plan(get_example()).expect("example should be valid");
+    assert_eq!(dig_capacity(&plan), Ok(62));
+}
+
+pub const BACKENDS: [Backend; 2] = [
+    Backend {
+        part: 1,
+        name: "flood",
+        description: "Dig out the trench on a grid, and flood-fill the ground outside it",
+        slow: false,
+        solve: |input| Ok(dig_capacity(&parse_plan(input)?)?.into()),
+    },
+    Backend {
+        part: 1,
+        name: "shoelace",
+        description: "Find the area inside the trench with the shoelace formula and Pick's theorem",
+        slow: false,
+        solve: |input| Ok(measure(&parse_plan(input)?)?.capacity().into()),
+    },
+];
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day18::part1;
+/// let example = lib::examples::examples(18)[0];
+/// assert_eq!(part1(example), Ok(62));
+/// ```
+pub fn part1(input: &str) -> Result<i64, Fail> {
+    dig_capacity(&parse_plan(input)?)
+}
+
+#[test]
+fn test_part1() {
+    assert_eq!(part1(get_example()), Ok(62));
+    assert!(part1("R 6\n").is_err());
+}
@@ -0,0 +1,1091 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+use std::str;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::error::Fail;
+use crate::fail;
+use crate::intern::Interner;
+use crate::interval::Interval;
+use crate::parse::{BadInput, ParseError};
+use crate::solver::Notes;
+
+pub const NOTES: Notes = Notes {
+    title: "Aplenty",
+    algorithm: "Part 1 runs each part through the workflows.  Part 2 runs ranges of ratings through them instead, splitting each range at every condition.",
+    complexity: "O(p·r) for p parts and rules r long; O(c) for the c conditions in part 2",
+};
+
+#[cfg(test)]
+fn get_example() -> &'static str {
+    crate::examples::example(19).expect("there should be an example")
+}
+
+/// The categories in which each part is rated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Attr {
+    X,
+    M,
+    A,
+    S,
+}
+
+const ALL_ATTRS: [Attr; 4] = [Attr::X, Attr::M, Attr::A, Attr::S];
+
+impl Attr {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Display for Attr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Attr::X => "x",
+            Attr::M => "m",
+            Attr::A => "a",
+            Attr::S => "s",
+        })
+    }
+}
+
+fn parse_attr(s: &str) -> Result<Attr, BadInput<'_>> {
+    match s {
+        "x" => Ok(Attr::X),
+        "m" => Ok(Attr::M),
+        "a" => Ok(Attr::A),
+        "s" => Ok(Attr::S),
+        _ => Err(BadInput::new(
+            s,
+            "unknown attribute (it should be x, m, a or s)",
+        )),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Item {
+    /// The rating for each attribute, indexed by `Attr::index`.
+    ratings: [i64; 4],
+}
+
+impl Item {
+    fn rating(&self, attribute: Attr) -> i64 {
+        self.ratings[attribute.index()]
+    }
+
+    fn total_rating(&self) -> i64 {
+        self.ratings.iter().sum()
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let [x, m, a, s] = self.ratings;
+        write!(f, "{{x={x},m={m},a={a},s={s}}}")
+    }
+}
+
+fn parse_integer(s: &str) -> Result<i64, BadInput<'_>> {
+    match s.parse() {
+        Err(e) => Err(BadInput::new(s, format!("not a valid integer: {e}"))),
+        Ok(n) => Ok(n),
+    }
+}
+
+fn parse_item(s: &str) -> Result<Item, BadInput<'_>> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new("([a-zA-Z0-9]+)=([0-9]+)").unwrap();
+    }
+    let mut ratings: [Option<i64>; 4] = [None; 4];
+    for c in RE.captures_iter(s) {
+        let name = c.get(1).unwrap().as_str();
+        let attribute = parse_attr(name)?;
+        let value = parse_integer(c.get(2).unwrap().as_str())?;
+        if ratings[attribute.index()].replace(value).is_some() {
+            return Err(BadInput::new(
+                name,
+                format!("there is more than one rating for {attribute}"),
+            ));
+        }
+    }
+    let mut result = [0; 4];
+    for attribute in ALL_ATTRS {
+        result[attribute.index()] = ratings[attribute.index()]
+            .ok_or_else(|| BadInput::new(s, format!("there is no rating for {attribute}")))?;
+    }
+    Ok(Item { ratings: result })
+}
+
+#[test]
+fn test_parse_item() {
+    let item = parse_item("{x=2461,m=1339,a=466,s=291}").expect("test input is valid");
+    assert_eq!(item.rating(Attr::M), 1339);
+    assert_eq!(item.total_rating(), 4557);
+    assert!(parse_item("{x=2461,m=1339,a=466}").is_err());
+    assert!(parse_item("{x=2461,m=1339,a=466,s=291,x=1}").is_err());
+    assert!(parse_item("{x=2461,m=1339,a=466,y=291}").is_err());
+}
+
+/// A rule's number, as given to its name by an `Interner`.
+type RuleId = usize;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Next {
+    Stop(bool),
+    Goto(RuleId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    Greater,
+}
+
+impl Comparison {
+    fn holds(&self, value: i64, boundary: i64) -> bool {
+        match self {
+            Comparison::Less => value < boundary,
+            Comparison::Greater => value > boundary,
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparison::Less => "<",
+            Comparison::Greater => ">",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Check {
+    Condition {
+        attribute: Attr,
+        comparison: Comparison,
+        boundary: i64,
+        next_if_met: Next,
+    },
+    Always(Next),
+}
+
+fn parse_check<'a>(s: &'a str, names: &mut Interner) -> Result<Check, BadInput<'a>> {
+    let mut parse_next = |s: &str| -> Next {
+        match s {
+            "A" => Next::Stop(true),
+            "R" => Next::Stop(false),
+            target => Next::Goto(names.intern(target)),
+        }
+    };
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new("^([a-zA-Z0-9]+)([<>])([0-9]+):([a-zA-Z]+)$").unwrap();
+    }
+    match RE.captures(s) {
+        Some(caps) => {
+            let attribute = parse_attr(caps.get(1).unwrap().as_str())?;
+            let comparison = caps.get(2).unwrap();
+            let comparison = match comparison.as_str() {
+                "<" => Comparison::Less,
+                ">" => Comparison::Greater,
+                other => {
+                    return Err(BadInput::new(
+                        comparison.as_str(),
+                        format!("unknown comparison {other}"),
+                    ));
+                }
+            };
+            let boundary = parse_integer(caps.get(3).unwrap().as_str())?;
+            let next_if_met = parse_next(caps.get(4).unwrap().as_str());
+            Ok(Check::Condition {
+                attribute,
+                comparison,
+                boundary,
+                next_if_met,
+            })
+        }
+        None => Ok(Check::Always(parse_next(s))),
+    }
+}
+
+#[test]
+fn test_parse_check() {
+    let mut names = Interner::new();
+    let check = parse_check("a<2006:qkq", &mut names).expect("test input should be valid");
+    match check {
+        Check::Condition {
+            attribute,
+            comparison,
+            boundary,
+            next_if_met,
+        } => {
+            assert_eq!(attribute, Attr::A);
+            assert_eq!(comparison, Comparison::Less);
+            assert_eq!(boundary, 2006);
+            assert_eq!(next_if_met, Next::Goto(names.intern("qkq")));
+        }
+        _ => {
+            panic!("expected conditinal check");
+        }
+    }
+    assert!(parse_check("q<2006:qkq", &mut names).is_err());
+}
+
+impl Check {
+    fn next(&self) -> &Next {
+        match self {
+            Check::Condition { next_if_met, .. } => next_if_met,
+            Check::Always(next) => next,
+        }
+    }
+
+    /// Returns the next step for `item` if this check applies to it.
+    fn next_step_for_item(&self, item: &Item) -> Option<&Next> {
+        match self {
+            Check::Always(decision) => Some(decision),
+            Check::Condition {
+                attribute,
+                comparison,
+                boundary,
+                next_if_met,
+            } => comparison
+                .holds(item.rating(*attribute), *boundary)
+                .then_some(next_if_met),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rule {
+    checks: Vec<Check>,
+    default_next: Next,
+}
+
+fn parse_rule<'a>(s: &'a str, names: &mut Interner) -> Result<(RuleId, Rule), BadInput<'a>> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(concat!(
+            "^",
+        "([^{]+)",		// rule name
+            "[{]",		// opening delimiter for checks
+            "([^}]+)",		// the checks
+            "[}]",		// closing delimiter for checks
+        "$",
+        )).expect("regex should be valid");
+    }
+    let (name, checks_text) = match RE.captures(s) {
+        Some(caps) => (
+            names.intern(caps.get(1).expect("name").as_str()),
+            caps.get(2).expect("checks").as_str(),
+        ),
+        None => {
+            return Err(BadInput::new(s, "expected to see a rule name and checks"));
+        }
+    };
+    let mut checks = checks_text
+        .split(',')
+        .map(|check| parse_check(check, names))
+        .collect::<Result<Vec<Check>, BadInput>>()?;
+    match checks.pop() {
+        Some(Check::Always(default_next)) => Ok((
+            name,
+            Rule {
+                checks,
+                default_next,
+            },
+        )),
+        Some(Check::Condition { .. }) => Err(BadInput::new(
+            checks_text.rsplit(',').next().unwrap_or(checks_text),
+            "final check should not be conditional",
+        )),
+        None => Err(BadInput::new(
+            checks_text,
+            "there should be at least one check in every rule",
+        )),
+    }
+}
+
+impl Rule {
+    /// Returns the next step for `item`, and the index of the check
+    /// which sent it there (or `None` if none of them applied).
+    fn examine(&self, item: &Item) -> (Option<usize>, &Next) {
+        self.checks
+            .iter()
+            .enumerate()
+            .find_map(|(index, check)| {
+                check
+                    .next_step_for_item(item)
+                    .map(|next| (Some(index), next))
+            })
+            .unwrap_or((None, &self.default_next))
+    }
+
+    /// Returns the places the rule can send an item.
+    fn targets(&self) -> impl Iterator<Item = &Next> {
+        self.checks
+            .iter()
+            .map(Check::next)
+            .chain(std::iter::once(&self.default_next))
+    }
+}
+
+#[test]
+fn test_parse_rule() {
+    let mut names = Interner::new();
+    let (id, rule) =
+        parse_rule("qqz{s>2770:qs,m<1801:hdj,R}", &mut names).expect("test input is valid");
+    assert_eq!(names.name(id), "qqz");
+    assert_eq!(rule.checks.len(), 2);
+    assert_eq!(
+        rule.checks[0],
+        Check::Condition {
+            attribute: Attr::S,
+            comparison: Comparison::Greater,
+            boundary: 2770,
+            next_if_met: Next::Goto(names.intern("qs")),
+        }
+    );
+    assert_eq!(
+        rule.checks[1],
+        Check::Condition {
+            attribute: Attr::M,
+            comparison: Comparison::Less,
+            boundary: 1801,
+            next_if_met: Next::Goto(names.intern("hdj")),
+        }
+    );
+    assert_eq!(rule.default_next, Next::Stop(false));
+}
+
+/// The rules, indexed by the numbers given to their names.
+#[derive(Debug)]
+pub struct Workflows {
+    names: Interner,
+    /// The rule for each name, or `None` for names which are used but
+    /// which have no rule.
+    rules: Vec<Option<Rule>>,
+    /// The rule every item starts at.
+    start: RuleId,
+}
+
+impl Workflows {
+    fn name(&self, id: RuleId) -> &str {
+        self.names.name(id)
+    }
+
+    fn rule(&self, id: RuleId) -> Result<&Rule, Fail> {
+        self.rules
+            .get(id)
+            .and_then(Option::as_ref)
+            .ok_or_else(|| fail!("cannot find rule {}", self.name(id)))
+    }
+
+    #[cfg(test)]
+    fn find(&self, name: &str) -> Option<&Rule> {
+        self.names
+            .get(name)
+            .and_then(|id| self.rules.get(id))
+            .and_then(Option::as_ref)
+    }
+
+    /// Returns the rules which exist, in order of their names.
+    fn sorted(&self) -> Vec<(RuleId, &Rule)> {
+        let mut result: Vec<(RuleId, &Rule)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter_map(|(id, rule)| rule.as_ref().map(|rule| (id, rule)))
+            .collect();
+        result.sort_by_key(|(id, _)| self.name(*id));
+        result
+    }
+
+    /// Returns the name of `next` as it appears in the input.
+    fn next_name(&self, next: &Next) -> &str {
+        match next {
+            Next::Stop(true) => "A",
+            Next::Stop(false) => "R",
+            Next::Goto(id) => self.name(*id),
+        }
+    }
+
+    /// Returns `check` as it appears in the input.
+    fn describe_check(&self, check: &Check) -> String {
+        match check {
+            Check::Condition {
+                attribute,
+                comparison,
+                boundary,
+                next_if_met,
+            } => format!(
+                "{attribute}{comparison}{boundary}:{}",
+                self.next_name(next_if_met)
+            ),
+            Check::Always(next) => self.next_name(next).to_string(),
+        }
+    }
+}
+
+pub fn parse_input(s: &str) -> Result<(Workflows, Vec<Item>), Fail> {
+    let Some((first, second)) = s.split_once("\n\n") else {
+        return Err(
+            ParseError::at(s, s, "expected blank line between the rules and the items").into(),
+        );
+    };
+    let mut names = Interner::new();
+    let mut rules: Vec<Option<Rule>> = Vec::new();
+    for line in first.split_terminator('\n') {
+        let (id, rule) = parse_rule(line, &mut names).map_err(|e| e.locate(s))?;
+        rules.resize_with(names.len(), || None);
+        if rules[id].replace(rule).is_some() {
+            return Err(ParseError::at(
+                s,
+                line,
+                format!("there is more than one rule named {}", names.name(id)),
+            )
+            .into());
+        }
+    }
+    let start = names.intern("in");
+    rules.resize_with(names.len(), || None);
+    let items = second
+        .split_terminator('\n')
+        .map(parse_item)
+        .collect::<Result<Vec<Item>, BadInput>>()
+        .map_err(|e| e.locate(s))?;
+    Ok((
+        Workflows {
+            names,
+            rules,
+            start,
+        },
+        items,
+    ))
+}
+
+#[test]
+fn test_parse_input() {
+    let example = get_example();
+    let (rules, items) = parse_input(example).expect("input is valid");
+    assert_eq!(rules.sorted().len(), 11);
+    assert_eq!(
+        rules.find("pv"),
+        Some(&Rule {
+            checks: vec![Check::Condition {
+                attribute: Attr::A,
+                comparison: Comparison::Greater,
+                boundary: 1716,
+                next_if_met: Next::Stop(false),
+            },],
+            default_next: Next::Stop(true),
+        })
+    );
+    assert_eq!(items.len(), 5);
+}
+
+#[test]
+fn test_parse_error_location() {
+    let error_for = |input: &str| -> String {
+        parse_input(input)
+            .expect_err("input should not be valid")
+            .to_string()
+    };
+    assert_eq!(
+        error_for("in{x<10:A,R}\nqq{m>99999999999999999999:A,R}\n\n{x=1}\n"),
+        "line 2, column 6: not a valid integer: number too large to fit in target type\n  qq{m>99999999999999999999:A,R}\n       ^"
+    );
+    assert_eq!(
+        error_for("in{x<10:A,x>4:R}\n\n{x=1}\n"),
+        "line 1, column 11: final check should not be conditional\n  in{x<10:A,x>4:R}\n            ^"
+    );
+    assert_eq!(
+        error_for("in{x<10:A,R}\n\n{x=1,m=1,a=1,s=1}\n{x=99999999999999999999}\n"),
+        "line 4, column 4: not a valid integer: number too large to fit in target type\n  {x=99999999999999999999}\n     ^"
+    );
+    assert!(error_for("in{x<10:A,R}\n{x=1}\n").starts_with("line 1, column 1: expected blank line"));
+    assert!(error_for("in{y<10:A,R}\n\n{x=1,m=1,a=1,s=1}\n")
+        .starts_with("line 1, column 4: unknown attribute"));
+    assert!(error_for("in{x<10:A,R}\n\n{x=1,m=1,a=1}\n")
+        .starts_with("line 3, column 1: there is no rating for s"));
+    assert!(error_for("in{x<10:A,R}\nin{A}\n\n{x=1,m=1,a=1,s=1}\n")
+        .starts_with("line 2, column 1: there is more than one rule named in"));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_parse_arbitrary_bytes_does_not_panic(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..200)) {
+        let _ = parse_input(String::from_utf8_lossy(&bytes).as_ref());
+    }
+
+    #[test]
+    fn test_parse_almost_valid_does_not_panic(s in "([a-z]{1,3}\\{([xmas][<>=][0-9]{0,20}:[a-zAR]{0,3},){0,3}[a-zAR]{0,3}\\}\n){0,3}\n(\\{([xmas]=[0-9]{0,20},?){0,4}\\}\n){0,3}") {
+        let _ = parse_input(s.as_str());
+    }
+}
+
+/// The path an item takes through the workflows.
+#[derive(Debug)]
+pub struct Route<'a> {
+    workflows: &'a Workflows,
+    /// The rules the item visits, each with the index of the check
+    /// which sent it on (or `None` for the rule's default).
+    steps: Vec<(RuleId, Option<usize>)>,
+    accepted: bool,
+}
+
+impl Route<'_> {
+    pub fn accepted(&self) -> bool {
+        self.accepted
+    }
+
+    /// Describes the route with the check which sent the item on
+    /// from each rule, for example `in [s<1351:px] -> px [A]`.
+    pub fn explain(&self) -> String {
+        self.describe(true)
+    }
+
+    fn describe(&self, with_checks: bool) -> String {
+        let mut result = String::new();
+        for (id, check_index) in self.steps.iter() {
+            result.push_str(self.workflows.name(*id));
+            if with_checks {
+                let check = self
+                    .workflows
+                    .rule(*id)
+                    .ok()
+                    .map(|rule| match check_index {
+                        Some(i) => self.workflows.describe_check(&rule.checks[*i]),
+                        None => self.workflows.next_name(&rule.default_next).to_string(),
+                    })
+                    .unwrap_or_default();
+                result.push_str(&format!(" [{check}]"));
+            }
+            result.push_str(" -> ");
+        }
+        result.push(if self.accepted { 'A' } else { 'R' });
+        result
+    }
+}
+
+/// Shows the route the way the puzzle does, for example `in -> px -> A`.
+impl Display for Route<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.describe(false))
+    }
+}
+
+impl Workflows {
+    /// Follows `item` through the workflows, from `in` until it is
+    /// accepted or rejected.
+    pub fn route(&self, item: &Item) -> Result<Route<'_>, Fail> {
+        let mut visited = vec![false; self.rules.len()];
+        let mut steps = Vec::new();
+        let mut id = self.start;
+        loop {
+            if std::mem::replace(&mut visited[id], true) {
+                return Err(fail!(
+                    "{item} goes round in a loop at rule {}",
+                    self.name(id)
+                ));
+            }
+            let (check_index, next) = self.rule(id)?.examine(item);
+            steps.push((id, check_index));
+            match next {
+                Next::Stop(accepted) => {
+                    return Ok(Route {
+                        workflows: self,
+                        steps,
+                        accepted: *accepted,
+                    });
+                }
+                Next::Goto(next) => {
+                    id = *next;
+                }
+            }
+        }
+    }
+}
+
+fn accept(item: &Item, workflows: &Workflows) -> Result<bool, Fail> {
+    workflows.route(item).map(|route| route.accepted())
+}
+
+#[test]
+fn test_route() {
+    let (rules, items) = parse_input(get_example()).expect("example input is valid");
+    let routes: Vec<String> = items
+        .iter()
+        .map(|item| {
+            rules
+                .route(item)
+                .map(|route| format!("{item}: {route}"))
+                .expect("example has no loops")
+        })
+        .collect();
+    // These are the routes given in the puzzle.
+    assert_eq!(
+        routes,
+        vec![
+            "{x=787,m=2655,a=1222,s=2876}: in -> qqz -> qs -> lnx -> A",
+            "{x=1679,m=44,a=2067,s=496}: in -> px -> rfg -> gd -> R",
+            "{x=2036,m=264,a=79,s=2244}: in -> qqz -> hdj -> pv -> A",
+            "{x=2461,m=1339,a=466,s=291}: in -> px -> qkq -> crn -> R",
+            "{x=2127,m=1623,a=2188,s=1013}: in -> px -> rfg -> A",
+        ]
+    );
+    let route = rules.route(&items[0]).expect("example has no loops");
+    assert!(route.accepted());
+    assert_eq!(
+        route.explain(),
+        "in [qqz] -> qqz [s>2770:qs] -> qs [lnx] -> lnx [m>1548:A] -> A"
+    );
+}
+
+#[test]
+fn test_route_loop() {
+    let (rules, items) =
+        parse_input("in{x<10:ab,A}\nab{in}\n\n{x=1,m=1,a=1,s=1}\n").expect("input is valid");
+    assert_eq!(
+        rules.route(&items[0]).map(|route| route.to_string()),
+        Err(Fail(
+            "{x=1,m=1,a=1,s=1} goes round in a loop at rule in".to_string()
+        ))
+    );
+}
+
+/// Sums the ratings of the items which the rules accept.
+pub fn total_accepted_rating(workflows: &Workflows, items: &[Item]) -> Result<i64, Fail> {
+    let mut total = 0;
+    for item in items.iter() {
+        if accept(item, workflows)? {
+            total += item.total_rating();
+        }
+    }
+    Ok(total)
+}
+
+#[test]
+fn test_total_accepted_rating() {
+    let (rules, items) = parse_input(get_example()).expect("example input is valid");
+    assert_eq!(total_accepted_rating(&rules, &items), Ok(19114));
+}
+
+#[test]
+fn test_missing_rule() {
+    let (rules, items) =
+        parse_input("in{x<10:nx,A}\n\n{x=1,m=1,a=1,s=1}\n").expect("input is valid");
+    assert_eq!(
+        total_accepted_rating(&rules, &items),
+        Err(Fail("cannot find rule nx".to_string()))
+    );
+    assert!(count_acceptable_combinations(&rules).is_err());
+    let (rules, items) = parse_input("px{A}\n\n{x=1,m=1,a=1,s=1}\n").expect("input is valid");
+    assert_eq!(
+        total_accepted_rating(&rules, &items),
+        Err(Fail("cannot find rule in".to_string()))
+    );
+}
+
+/// The range of values an attribute can take.
+const RATING_RANGE: Interval = Interval { lo: 1, hi: 4001 };
+
+/// The ranges of values which the attributes of an item can have,
+/// indexed by `Attr::index`.  Together they describe a hypercube of
+/// items.
+type AttributeRanges = [Interval; 4];
+
+/// Splits `range` into the values which meet the condition
+/// `comparison boundary` and those which don't.
+fn split_interval(
+    comparison: Comparison,
+    boundary: i64,
+    range: &Interval,
+) -> (Option<Interval>, Option<Interval>) {
+    match comparison {
+        Comparison::Less => range.split_at(boundary),
+        Comparison::Greater => {
+            let (unmet, met) = range.split_at(boundary + 1);
+            (met, unmet)
+        }
+    }
+}
+
+impl Check {
+    /// Splits the items in `ranges` into those which meet this check
+    /// and those which don't.  Either part is `None` if it would have
+    /// no items in it.
+    fn split_range(
+        &self,
+        ranges: &AttributeRanges,
+    ) -> (Option<AttributeRanges>, Option<AttributeRanges>) {
+        match self {
+            Check::Always(_) => (Some(*ranges), None),
+            Check::Condition {
+                attribute,
+                comparison,
+                boundary,
+                ..
+            } => {
+                let index = attribute.index();
+                let (met, unmet) = split_interval(*comparison, *boundary, &ranges[index]);
+                let with = |interval: Interval| {
+                    let mut result = *ranges;
+                    result[index] = interval;
+                    result
+                };
+                (met.map(with), unmet.map(with))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_split_range() {
+    let mut names = Interner::new();
+    let mut split = |check: &str, ranges: &AttributeRanges| {
+        parse_check(check, &mut names)
+            .expect("test input should be valid")
+            .split_range(ranges)
+    };
+    let all = [RATING_RANGE; 4];
+    let with_x = |lo, hi| {
+        let mut result = all;
+        result[Attr::X.index()] = Interval::new(lo, hi);
+        result
+    };
+
+    assert_eq!(
+        split("x<5:A", &all),
+        (Some(with_x(1, 5)), Some(with_x(5, 4001)))
+    );
+    assert_eq!(
+        split("x>5:A", &all),
+        (Some(with_x(6, 4001)), Some(with_x(1, 6)))
+    );
+    // The other attributes are left alone.
+    let (met, _) = split("m<100:A", &with_x(10, 20));
+    let met = met.expect("some items should meet the check");
+    assert_eq!(met[Attr::X.index()], Interval::new(10, 20));
+    assert_eq!(met[Attr::M.index()], Interval::new(1, 100));
+
+    // At the edges of the range, one part is empty.
+    assert_eq!(split("x<1:A", &all), (None, Some(all)));
+    assert_eq!(
+        split("x<2:A", &all),
+        (Some(with_x(1, 2)), Some(with_x(2, 4001)))
+    );
+    assert_eq!(split("x<4001:A", &all), (Some(all), None));
+    assert_eq!(split("x>4000:A", &all), (None, Some(all)));
+    assert_eq!(
+        split("x>3999:A", &all),
+        (Some(with_x(4000, 4001)), Some(with_x(1, 4000)))
+    );
+    assert_eq!(split("x>0:A", &all), (Some(all), None));
+    assert_eq!(split("A", &all), (Some(all), None));
+}
+
+/// A finding about the rules which doesn't stop them working, but
+/// which suggests that they are not as simple as they could be.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Lint {
+    /// The check has no effect because the outcome is the same
+    /// whether or not it is met.
+    Redundant { rule: String, check: String },
+    /// Given the checks before it, this check can never be met.
+    NeverMet { rule: String, check: String },
+    /// Given the checks before it, this check is always met, so later
+    /// checks in the rule are never used.
+    AlwaysMet { rule: String, check: String },
+    /// The rule cannot be reached from the "in" rule.
+    Unreachable { rule: String },
+    /// The rule refers to a rule which doesn't exist.
+    Undefined { rule: String, target: String },
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::Redundant { rule, check } => {
+                write!(
+                    f,
+                    "{rule}: check {check} makes no difference to the outcome"
+                )
+            }
+            Lint::NeverMet { rule, check } => write!(f, "{rule}: check {check} can never be met"),
+            Lint::AlwaysMet { rule, check } => write!(
+                f,
+                "{rule}: check {check} is always met, so the checks after it are unreachable"
+            ),
+            Lint::Unreachable { rule } => write!(f, "{rule}: rule is never used"),
+            Lint::Undefined { rule, target } => {
+                write!(f, "{rule}: refers to rule {target}, which does not exist")
+            }
+        }
+    }
+}
+
+/// Finds checks in `rule` which are redundant or contradictory.
+fn lint_rule(workflows: &Workflows, name: &str, rule: &Rule, findings: &mut Vec<Lint>) {
+    // The range of values each attribute can have, given that none of
+    // the earlier checks were met.
+    let mut ranges: AttributeRanges = [RATING_RANGE; 4];
+    for (index, check) in rule.checks.iter().enumerate() {
+        let Check::Condition { next_if_met, .. } = check else {
+            continue;
+        };
+        let (met, unmet) = check.split_range(&ranges);
+        let finding = |make: fn(String, String) -> Lint| {
+            make(name.to_string(), workflows.describe_check(check))
+        };
+        let Some(unmet) = unmet else {
+            findings.push(finding(|rule, check| Lint::AlwaysMet { rule, check }));
+            return;
+        };
+        if met.is_none() {
+            findings.push(finding(|rule, check| Lint::NeverMet { rule, check }));
+        } else if rule
+            .targets()
+            .skip(index + 1)
+            .all(|next| next == next_if_met)
+        {
+            findings.push(finding(|rule, check| Lint::Redundant { rule, check }));
+        }
+        ranges = unmet;
+    }
+}
+
+/// Analyses the rules, returning the findings in a consistent order.
+pub fn lint(workflows: &Workflows) -> Vec<Lint> {
+    let mut findings = Vec::new();
+    let sorted = workflows.sorted();
+    for (id, rule) in sorted.iter() {
+        let name = workflows.name(*id);
+        lint_rule(workflows, name, rule, &mut findings);
+        for target in rule.targets() {
+            if let Next::Goto(target) = target {
+                if workflows.rule(*target).is_err() {
+                    findings.push(Lint::Undefined {
+                        rule: name.to_string(),
+                        target: workflows.name(*target).to_string(),
+                    });
+                }
+            }
+        }
+    }
+    let mut reachable: Vec<bool> = vec![false; workflows.rules.len()];
+    let mut todo: VecDeque<RuleId> = VecDeque::from([workflows.start]);
+    while let Some(id) = todo.pop_front() {
+        if let Ok(rule) = workflows.rule(id) {
+            if !reachable[id] {
+                reachable[id] = true;
+                for target in rule.targets() {
+                    if let Next::Goto(target) = target {
+                        todo.push_back(*target);
+                    }
+                }
+            }
+        }
+    }
+    for (id, _) in sorted.iter() {
+        if !reachable[*id] {
+            findings.push(Lint::Unreachable {
+                rule: workflows.name(*id).to_string(),
+            });
+        }
+    }
+    findings
+}
+
+#[test]
+fn test_lint_example() {
+    let (rules, _) = parse_input(get_example()).expect("example input is valid");
+    let findings: Vec<String> = lint(&rules).iter().map(|l| l.to_string()).collect();
+    assert_eq!(
+        findings,
+        vec![
+            "gd: check a>3333:R makes no difference to the outcome",
+            "lnx: check m>1548:A makes no difference to the outcome",
+        ]
+    );
+}
+
+#[test]
+fn test_lint_contradictions() {
+    let rules = concat!(
+        "in{x<100:A,x<50:R,x>10:two,R}\n",
+        "two{m>0:A,a<5:R,R}\n",
+        "orphan{A}\n",
+        "three{s<2:nowhere,A}\n",
+        "\n",
+    );
+    let (rules, _) = parse_input(rules).expect("test input is valid");
+    assert_eq!(
+        lint(&rules),
+        vec![
+            Lint::NeverMet {
+                rule: "in".to_string(),
+                check: "x<50:R".to_string(),
+            },
+            Lint::AlwaysMet {
+                rule: "in".to_string(),
+                check: "x>10:two".to_string(),
+            },
+            Lint::Undefined {
+                rule: "three".to_string(),
+                target: "nowhere".to_string(),
+            },
+            Lint::AlwaysMet {
+                rule: "two".to_string(),
+                check: "m>0:A".to_string(),
+            },
+            Lint::Unreachable {
+                rule: "orphan".to_string(),
+            },
+            Lint::Unreachable {
+                rule: "three".to_string(),
+            },
+        ]
+    );
+}
+
+/// Quotes `s` for use as an identifier or label in a Graphviz file.
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Describes the rules as a Graphviz directed graph.  There is a node
+/// for each rule and for the accept and reject outcomes, and an edge
+/// for each check, labelled with its condition.  The checks of a rule
+/// are numbered, since each one applies only to items which failed
+/// the ones before it.
+pub fn to_dot(workflows: &Workflows) -> String {
+    let mut result = String::new();
+    result.push_str("digraph workflows {\n");
+    result.push_str("  \"A\" [shape=box, label=\"accept\"];\n");
+    result.push_str("  \"R\" [shape=box, label=\"reject\"];\n");
+    for (id, rule) in workflows.sorted() {
+        let from = dot_quote(workflows.name(id));
+        if id == workflows.start {
+            result.push_str(&format!("  {from} [shape=doublecircle];\n"));
+        }
+        for (index, check) in rule.checks.iter().enumerate() {
+            let condition = match check {
+                Check::Condition {
+                    attribute,
+                    comparison,
+                    boundary,
+                    ..
+                } => format!("{attribute}{comparison}{boundary}"),
+                Check::Always(_) => "always".to_string(),
+            };
+            let to = dot_quote(workflows.next_name(check.next()));
+            let label = dot_quote(&format!("{}: {condition}", index + 1));
+            result.push_str(&format!("  {from} -> {to} [label={label}];\n"));
+        }
+        let to = dot_quote(workflows.next_name(&rule.default_next));
+        result.push_str(&format!("  {from} -> {to} [label=\"otherwise\"];\n"));
+    }
+    result.push_str("}\n");
+    result
+}
+
+#[test]
+fn test_to_dot() {
+    let (rules, _) = parse_input("in{x<100:two,R}\ntwo{m>5:A,a<3:R,A}\n\n").expect("valid");
+    assert_eq!(
+        to_dot(&rules),
+        concat!(
+            "digraph workflows {\n",
+            "  \"A\" [shape=box, label=\"accept\"];\n",
+            "  \"R\" [shape=box, label=\"reject\"];\n",
+            "  \"in\" [shape=doublecircle];\n",
+            "  \"in\" -> \"two\" [label=\"1: x<100\"];\n",
+            "  \"in\" -> \"R\" [label=\"otherwise\"];\n",
+            "  \"two\" -> \"A\" [label=\"1: m>5\"];\n",
+            "  \"two\" -> \"R\" [label=\"2: a<3\"];\n",
+            "  \"two\" -> \"A\" [label=\"otherwise\"];\n",
+            "}\n",
+        )
+    );
+    assert_eq!(dot_quote("a\"b\\c"), "\"a\\\"b\\\\c\"");
+}
+
+/// Counts the items with attributes in `ranges` which are accepted by
+/// the rules, given that they have got as far as `next`.
+fn count_accepted(
+    workflows: &Workflows,
+    next: &Next,
+    mut ranges: AttributeRanges,
+) -> Result<i64, Fail> {
+    let id = match next {
+        Next::Stop(true) => {
+            return Ok(ranges.iter().map(Interval::len).product());
+        }
+        Next::Stop(false) => {
+            return Ok(0);
+        }
+        Next::Goto(id) => *id,
+    };
+    let rule = workflows.rule(id)?;
+    let mut total = 0;
+    for check in rule.checks.iter() {
+        let (met, unmet) = check.split_range(&ranges);
+        if let Some(met) = met {
+            total += count_accepted(workflows, check.next(), met)?;
+        }
+        match unmet {
+            Some(unmet) => {
+                ranges = unmet;
+            }
+            None => {
+                return Ok(total);
+            }
+        }
+    }
+    Ok(total + count_accepted(workflows, &rule.default_next, ranges)?)
+}
+
+/// Counts the combinations of ratings (each between 1 and 4000) which
+/// the rules accept.
+pub fn count_acceptable_combinations(workflows: &Workflows) -> Result<i64, Fail> {
+    count_accepted(workflows, &Next::Goto(workflows.start), [RATING_RANGE; 4])
+}
+
+#[test]
+fn test_count_acceptable_combinations() {
+    let (rules, _) = parse_input(get_example()).expect("example input is valid");
+    assert_eq!(count_acceptable_combinations(&rules), Ok(167409079868000));
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day19::part1;
+/// let example = lib::examples::examples(19)[0];
+/// assert_eq!(part1(example), Ok(19114));
+/// ```
+pub fn part1(input: &str) -> Result<i64, Fail> {
+    let (rules, items) = parse_input(input)?;
+    total_accepted_rating(&rules, &items)
+}
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day19::part2;
+/// let example = lib::examples::examples(19)[0];
+/// assert_eq!(part2(example), Ok(167409079868000));
+/// ```
+pub fn part2(input: &str) -> Result<i64, Fail> {
+    let (rules, _) = parse_input(input)?;
+    count_acceptable_combinations(&rules)
+}
+
+#[test]
+fn test_parts() {
+    assert_eq!(part1(get_example()), Ok(19114));
+    assert_eq!(part2(get_example()), Ok(167409079868000));
+}
@@ -0,0 +1,1177 @@
+use std::cmp::{max, min, Ordering};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Debug, Display};
+use std::str;
+
+use crate::debug;
+use crate::error::Fail;
+use crate::fail;
+use crate::gen::Rng;
+use crate::grid::{BoundingBox, Position};
+use crate::parse::BadInput;
+use crate::solver::{Backend, Notes};
+
+pub const NOTES: Notes = Notes {
+    title: "Sand Slabs",
+    algorithm: "Bricks are dropped in order of their lowest point onto a height map of the settled surface.  Part 2 settles the pile again without each brick in turn.",
+    complexity: "O(b·a) to settle b bricks of area a; O(b²·a) for part 2",
+};
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct Position3 {
+    x: i64,
+    y: i64,
+    z: i64,
+}
+
+/// Position3 values sort by z first, so that we can order them by height-above-ground.
+impl Ord for Position3 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.z
+            .cmp(&other.z)
+            .then(self.x.cmp(&other.x))
+            .then(self.y.cmp(&other.y))
+    }
+}
+
+impl PartialOrd for Position3 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Position3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+impl Debug for Position3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (x, y, z) = (self.x, self.y, self.z);
+        write!(f, "Position3{{x:{x},y:{y},z:{z}}}")
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Position3 {
+    type Error = BadInput<'a>;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        if let [x, y, z] = s
+            .split(',')
+            .map(|s| {
+                s.parse::<i64>()
+                    .map_err(|e| BadInput::new(s, format!("not a valid coordinate: {e}")))
+            })
+            .collect::<Result<Vec<i64>, BadInput>>()?
+            .as_slice()
+        {
+            Ok(Position3 {
+                x: *x,
+                y: *y,
+                z: *z,
+            })
+        } else {
+            Err(BadInput::new(s, "a 3D point should have 3 coordinates"))
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct Brick {
+    lower: Position3,
+    upper: Position3,
+    label: Option<String>,
+}
+
+impl Display for Brick {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.label.as_ref() {
+            Some(label) => write!(f, "{}~{} <- {label}", self.lower, self.upper),
+            None => write!(f, "{}~{}", self.lower, self.upper),
+        }
+    }
+}
+
+impl Debug for Brick {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Brick {
+    type Error = BadInput<'a>;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        if let Some((left, right)) = s.split_once('~') {
+            let (right, label) = match right.split_once("<-") {
+                Some((r, s)) => (r.trim(), Some(s.trim().to_string())),
+                None => (right, None),
+            };
+            let left = Position3::try_from(left)?;
+            let right = Position3::try_from(right)?;
+            if left.z <= right.z {
+                Ok(Brick {
+                    lower: left,
+                    upper: right,
+                    label,
+                })
+            } else {
+                Ok(Brick {
+                    lower: right,
+                    upper: left,
+                    label,
+                })
+            }
+        } else {
+            Err(BadInput::new(s, "expected '~'"))
+        }
+    }
+}
+
+impl Ord for Brick {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.lower
+            .cmp(&other.lower)
+            .then(self.upper.cmp(&other.upper))
+    }
+}
+
+impl PartialOrd for Brick {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[test]
+#[allow(clippy::nonminimal_bool)]
+fn brick_comarison() {
+    // From the example:
+    //
+    // ```
+    // 1,0,1~1,2,1   <- A
+    // 0,0,2~2,0,2   <- B
+    // 0,2,3~2,2,3   <- C
+    // 0,0,4~0,2,4   <- D
+    // 2,0,5~2,2,5   <- E
+    // 0,1,6~2,1,6   <- F
+    // 1,1,8~1,1,9   <- G
+    // ```
+    //
+    // Looking along the y axis, these are arranged:
+    //
+    // ```
+    //  x
+    // 012
+    // .G. 9
+    // .G. 8
+    // ... 7
+    // FFF 6
+    // ..E 5 z
+    // D.. 4
+    // CCC 3
+    // BBB 2
+    // .A. 1
+    // --- 0
+    // ```
+    let e = Brick::try_from("2,0,5~2,2,5").expect("Brick E should be valid");
+    let f = Brick::try_from("0,1,6~2,1,6").expect("Brick F should be valid");
+    assert!(e < f);
+    assert!(e <= f);
+    assert!(f > e);
+    assert!(f >= e);
+
+    assert!(!(e > f));
+    assert!(!(e >= f));
+
+    assert!(!(f < e));
+    assert!(!(f <= e));
+}
+
+#[test]
+#[allow(clippy::nonminimal_bool)]
+fn brick_equality() {
+    let e = Brick::try_from("2,0,5~2,2,5").expect("Brick E should be valid");
+    let f = Brick::try_from("0,1,6~2,1,6").expect("Brick F should be valid");
+    let f_twin = Brick::try_from("0,1,6~2,1,6").expect("Brick F (twin) should be valid");
+    assert_eq!(e, e);
+    assert_eq!(f, f);
+
+    assert_eq!(f, f_twin);
+    assert_eq!(f_twin, f);
+
+    assert!(!(e == f));
+    assert!(!(f == e));
+}
+
+impl Brick {
+    fn fall(&mut self, dz: i64) {
+        self.lower.z -= dz;
+        self.upper.z -= dz;
+    }
+
+    fn plan(&self) -> BoundingBox {
+        BoundingBox {
+            top_left: Position {
+                x: min(self.lower.x, self.upper.x),
+                y: min(self.lower.y, self.upper.y),
+            },
+            bottom_right: Position {
+                x: max(self.lower.x, self.upper.x),
+                y: max(self.lower.y, self.upper.y),
+            },
+        }
+    }
+}
+
+#[test]
+fn brick_plan() {
+    let brick = Brick::try_from("2,0,5~2,2,5").expect("Brick E should be valid");
+    assert_eq!(
+        brick.plan(),
+        BoundingBox {
+            top_left: Position { x: 2, y: 0 },
+            bottom_right: Position { x: 2, y: 2 }
+        }
+    );
+}
+
+#[cfg(test)]
+fn get_unlabeled_example() -> &'static str {
+    crate::examples::example(22).expect("there should be an example")
+}
+
+#[cfg(test)]
+fn get_labeled_example() -> &'static str {
+    concat!(
+        "1,0,1~1,2,1   <- A\n",
+        "0,0,2~2,0,2   <- B\n",
+        "0,2,3~2,2,3   <- C\n",
+        "0,0,4~0,2,4   <- D\n",
+        "2,0,5~2,2,5   <- E\n",
+        "0,1,6~2,1,6   <- F\n",
+        "1,1,8~1,1,9   <- G\n",
+    )
+}
+
+pub fn parse_input(s: &str) -> Result<Vec<Brick>, Fail> {
+    s.split_terminator('\n')
+        .map(|line| Brick::try_from(line).map_err(|e| Fail::from(e.locate(s))))
+        .collect::<Result<Vec<Brick>, Fail>>()
+}
+
+#[test]
+fn test_parse_unlabeled_example() {
+    let bricks = parse_input(get_unlabeled_example()).expect("example should be valid");
+    assert_eq!(bricks.len(), 7);
+    assert_eq!(
+        &bricks[0],
+        &Brick {
+            lower: Position3 { x: 1, y: 0, z: 1 },
+            upper: Position3 { x: 1, y: 2, z: 1 },
+            label: None,
+        }
+    );
+}
+
+#[test]
+fn test_parse_labeled_example() {
+    let bricks = parse_input(get_labeled_example()).expect("labeled example should be valid");
+    assert_eq!(bricks.len(), 7);
+    assert_eq!(
+        &bricks[0],
+        &Brick {
+            lower: Position3 { x: 1, y: 0, z: 1 },
+            upper: Position3 { x: 1, y: 2, z: 1 },
+            label: Some("A".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_parse_error_location() {
+    assert_eq!(
+        parse_input("1,0,1~1,2,1\n0,0,2~2,x,2\n"),
+        Err(Fail(
+            "line 2, column 9: not a valid coordinate: invalid digit found in string\n  0,0,2~2,x,2\n          ^"
+                .to_string()
+        ))
+    );
+    assert_eq!(
+        parse_input("1,0,1~1,2,1\n0,0,2 2,0,2\n"),
+        Err(Fail(
+            "line 2, column 1: expected '~'\n  0,0,2 2,0,2\n  ^".to_string()
+        ))
+    );
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_parse_arbitrary_bytes_does_not_panic(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..200)) {
+        let _ = parse_input(String::from_utf8_lossy(&bytes).as_ref());
+    }
+
+    #[test]
+    fn test_parse_almost_valid_does_not_panic(s in "(-?[0-9]{1,20},-?[0-9]{1,20},-?[0-9]{1,20}~-?[0-9]{1,20},[0-9]{0,3},?[0-9]{0,20}( *<- *[A-Z]?)?\n){0,4}") {
+        let _ = parse_input(s.as_str());
+    }
+}
+
+/// The height of the settled bricks at each point of the plan view,
+/// and which brick is on top there.
+pub trait Surface {
+    /// Creates an empty surface (with height 0 everywhere) covering
+    /// `plan`.
+    fn new(plan: &BoundingBox) -> Self;
+
+    fn get(&self, pos: &Position) -> (i64, Option<usize>);
+
+    /// Records that the top of brick `index` is at height `z` over
+    /// all of `bbox`.  Fails if the surface there is already at least
+    /// that high (so the brick would have fallen into another).
+    fn set_height(&mut self, bbox: &BoundingBox, z: i64, index: usize) -> Result<(), Fail>;
+}
+
+fn fell_too_far(z: i64, pos: &Position) -> Fail {
+    fail!("shape with bottom at {z} fell too far at {pos}")
+}
+
+/// A `Surface` which stores only the points something has landed on.
+/// This was our original implementation, and is kept so that the
+/// benchmarks can compare it with `FlatSurface`.
+#[derive(Debug, Default)]
+pub struct TreeSurface {
+    heightmap: BTreeMap<Position, (i64, usize)>,
+}
+
+impl Surface for TreeSurface {
+    fn new(_plan: &BoundingBox) -> Self {
+        TreeSurface::default()
+    }
+
+    fn get(&self, pos: &Position) -> (i64, Option<usize>) {
+        match self.heightmap.get(pos) {
+            Some((h, index)) => (*h, Some(*index)),
+            None => (0, None),
+        }
+    }
+
+    fn set_height(&mut self, bbox: &BoundingBox, z: i64, index: usize) -> Result<(), Fail> {
+        for pos in bbox.surface() {
+            match self.heightmap.get_mut(&pos) {
+                Some((existing_height, _)) if *existing_height >= z => {
+                    return Err(fell_too_far(z, &pos));
+                }
+                Some(existing) => *existing = (z, index),
+                None => {
+                    self.heightmap.insert(pos, (z, index));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `Surface` which stores every point of the plan view in a `Vec`.
+/// The puzzle input covers only a small area in the plan view, so
+/// this is small, and much faster than `TreeSurface`.
+#[derive(Debug)]
+pub struct FlatSurface {
+    plan: BoundingBox,
+    cells: Vec<(i64, Option<usize>)>,
+}
+
+impl FlatSurface {
+    fn offset(&self, pos: &Position) -> Option<usize> {
+        self.plan.contains(pos).then(|| {
+            let dx = pos.x - self.plan.top_left.x;
+            let dy = pos.y - self.plan.top_left.y;
+            (dy * self.plan.width() + dx) as usize
+        })
+    }
+}
+
+impl Surface for FlatSurface {
+    fn new(plan: &BoundingBox) -> Self {
+        FlatSurface {
+            plan: *plan,
+            cells: vec![(0, None); plan.area() as usize],
+        }
+    }
+
+    fn get(&self, pos: &Position) -> (i64, Option<usize>) {
+        match self.offset(pos) {
+            Some(offset) => self.cells[offset],
+            None => (0, None),
+        }
+    }
+
+    fn set_height(&mut self, bbox: &BoundingBox, z: i64, index: usize) -> Result<(), Fail> {
+        for pos in bbox.surface() {
+            let offset = self
+                .offset(&pos)
+                .ok_or_else(|| fail!("{pos} is outside the plan view {:?}", self.plan))?;
+            let cell = &mut self.cells[offset];
+            if cell.0 >= z {
+                return Err(fell_too_far(z, &pos));
+            }
+            *cell = (z, Some(index));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn check_surface_set_height<S: Surface>() {
+    let brick = Brick::try_from("2,0,5~2,2,5").expect("brick should be valid");
+    let plan = BoundingBox {
+        top_left: Position { x: 0, y: 0 },
+        bottom_right: Position { x: 2, y: 3 },
+    };
+    let mut surface = S::new(&plan);
+    assert_eq!(surface.get(&Position { x: 1000, y: 22 }), (0, None));
+    assert_eq!(surface.get(&Position { x: 2, y: 0 }), (0, None));
+    // The brick would fall from z=5 to z=1.  The brick itself, once
+    // fallen, has height 1, extending from z=1 to z=1.
+    assert_eq!(surface.set_height(&brick.plan(), 1, 200), Ok(()));
+    assert_eq!(surface.get(&Position { x: 1000, y: 22 }), (0, None));
+    assert_eq!(surface.get(&Position { x: 2, y: 0 }), (1, Some(200)));
+    assert_eq!(surface.get(&Position { x: 2, y: 1 }), (1, Some(200)));
+    assert_eq!(surface.get(&Position { x: 2, y: 2 }), (1, Some(200)));
+    assert_eq!(surface.get(&Position { x: 2, y: 3 }), (0, None));
+    assert_eq!(surface.get(&Position { x: 1, y: 1 }), (0, None));
+    // Nothing can come to rest at or below the top of that brick.
+    assert_eq!(
+        surface.set_height(&brick.plan(), 1, 201),
+        Err(Fail(
+            "shape with bottom at 1 fell too far at 2,0".to_string()
+        ))
+    );
+    assert_eq!(surface.get(&Position { x: 2, y: 0 }), (1, Some(200)));
+}
+
+#[test]
+fn test_surface_set_height() {
+    check_surface_set_height::<TreeSurface>();
+    check_surface_set_height::<FlatSurface>();
+}
+
+fn just(ix: Option<usize>) -> HashSet<usize> {
+    let mut result = HashSet::new();
+    if let Some(i) = ix {
+        result.insert(i);
+    }
+    result
+}
+
+fn identify_supporting_bricks(
+    acc: Option<(i64, HashSet<usize>)>,
+    h: i64,
+    maybe_index: Option<usize>,
+) -> Option<(i64, HashSet<usize>)> {
+    match acc {
+        None => Some((h, just(maybe_index))),
+        Some((existing_height, mut bricks)) => match existing_height.cmp(&h) {
+            Ordering::Less => Some((h, just(maybe_index))),
+            Ordering::Equal => {
+                if let Some(i) = maybe_index {
+                    bricks.insert(i);
+                }
+                Some((h, bricks))
+            }
+            Ordering::Greater => Some((existing_height, bricks)),
+        },
+    }
+}
+
+/// Returns the plan view of the space occupied by `bricks`.
+fn plan_of(bricks: &[Brick]) -> BoundingBox {
+    let mut plans = bricks.iter().map(Brick::plan);
+    match plans.next() {
+        Some(first) => plans.fold(first, |mut plan, brick| {
+            plan.update(&brick.top_left);
+            plan.update(&brick.bottom_right);
+            plan
+        }),
+        None => BoundingBox::new(&Position { x: 0, y: 0 }),
+    }
+}
+
+/// Returns the indexes of `bricks` in the order in which they settle:
+/// by their lower ends (comparing z, then x, then y), then by their
+/// upper ends, then by their position in the input.  The last key
+/// matters only for bricks which are in the same place, but it means
+/// that the order never depends on how the sort treats equal keys.
+fn settling_order(bricks: &[Brick]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..bricks.len()).collect();
+    order.sort_unstable_by(|&a, &b| bricks[a].cmp(&bricks[b]).then(a.cmp(&b)));
+    order
+}
+
+#[test]
+fn test_settling_order() {
+    // Bricks at the same height settle in order of x and then y,
+    // whatever order they are given in.
+    let bricks = parse_input(concat!(
+        "0,0,4~2,0,4 <- top\n",
+        "2,0,3~2,0,3 <- right\n",
+        "0,1,3~0,1,3 <- back\n",
+        "0,0,3~0,0,3 <- left\n",
+        "1,0,3~1,0,3 <- middle\n",
+    ))
+    .expect("test input should be valid");
+    assert_eq!(settling_order(&bricks), vec![3, 2, 4, 1, 0]);
+    let mut reversed = bricks.clone();
+    reversed.reverse();
+    assert_eq!(settling_order(&reversed), vec![1, 2, 0, 3, 4]);
+    let mut settled = settle(&bricks).expect("bricks should settle");
+    let mut settled_reversed = settle(&reversed).expect("bricks should settle");
+    settled.sort_by(|a, b| a.label.cmp(&b.label));
+    settled_reversed.sort_by(|a, b| a.label.cmp(&b.label));
+    assert_eq!(settled, settled_reversed);
+
+    // Bricks which are in the same place are taken in input order, so
+    // the first one lands on the ground and the second lands on it.
+    let bricks =
+        parse_input("0,0,5~0,0,5 <- P\n0,0,5~0,0,5 <- Q\n").expect("test input should be valid");
+    assert_eq!(settling_order(&bricks), vec![0, 1]);
+    let heights = |settled: Vec<Brick>| -> Vec<(String, i64)> {
+        settled
+            .into_iter()
+            .map(|brick| (brick.label.unwrap_or_default(), brick.lower.z))
+            .collect()
+    };
+    assert_eq!(
+        heights(settle(&bricks).expect("bricks should settle")),
+        vec![("P".to_string(), 1), ("Q".to_string(), 2)]
+    );
+    let swapped = [bricks[1].clone(), bricks[0].clone()];
+    assert_eq!(
+        heights(settle(&swapped).expect("bricks should settle")),
+        vec![("Q".to_string(), 1), ("P".to_string(), 2)]
+    );
+}
+
+fn compute_fallen_brick_positions<S, IgnorePredicate>(
+    bricks: &[Brick],
+    ignore: IgnorePredicate,
+) -> Result<(usize, Vec<Brick>, HashSet<usize>), Fail>
+where
+    S: Surface,
+    IgnorePredicate: Fn(usize) -> bool,
+{
+    let mut bricks_with_changed_z = 0;
+    // The bricks stay in their original order, so that callers can
+    // use the same indexes for the fallen bricks.
+    let mut fallen_bricks: Vec<Brick> = bricks.to_vec();
+    let mut can_disintegrate: HashSet<usize> = HashSet::new();
+    let mut heightmap = S::new(&plan_of(bricks));
+    for index in settling_order(bricks)
+        .into_iter()
+        .filter(|index| !ignore(*index))
+    {
+        let brick = &mut fallen_bricks[index];
+        can_disintegrate.insert(index);
+        let brick_xy_bbox = brick.plan();
+        if let Some((highest_ground, supporting_bricks)) =
+            brick_xy_bbox.surface().fold(None, |acc, pos| {
+                let (h, maybe_index) = heightmap.get(&pos);
+                identify_supporting_bricks(acc, h, maybe_index)
+            })
+        {
+            // Suppose the "ground" at this point has z=1.  Then,
+            // the bottom of this brick will come to rest at z=2.
+            let fell_by = brick.lower.z - (highest_ground + 1);
+            if fell_by > 0 {
+                bricks_with_changed_z += 1;
+            }
+
+            // If the brick is 2 units high then the top of the
+            // brick will be at z=3 (the brick occupying the
+            // levels z=2 and z=3).
+            brick.fall(fell_by);
+            heightmap.set_height(&brick_xy_bbox, brick.upper.z, index)?;
+
+            if supporting_bricks.len() == 1 {
+                for supporting_brick_index in supporting_bricks.into_iter() {
+                    can_disintegrate.remove(&supporting_brick_index);
+                }
+            }
+        } else {
+            return Err(fail!("brick {brick} has zero area in the xy plane"));
+        }
+    }
+    Ok((bricks_with_changed_z, fallen_bricks, can_disintegrate))
+}
+
+fn ignore_none(_: usize) -> bool {
+    false
+}
+
+#[test]
+fn example_compute_fallen_brick_positions() {
+    let (_, bricks, can_disintegrate) = compute_fallen_brick_positions::<FlatSurface, _>(
+        &parse_input(get_labeled_example()).expect("example should be valid"),
+        ignore_none,
+    )
+    .expect("example should settle");
+    assert!(bricks.contains(&Brick {
+        // A (which didn't move)
+        lower: Position3 { x: 1, y: 0, z: 1 },
+        upper: Position3 { x: 1, y: 2, z: 1 },
+        label: Some("A".to_string()),
+    }));
+    assert!(bricks.contains(&Brick {
+        // B (which didn't move), resting on A.
+        lower: Position3 { x: 0, y: 0, z: 2 },
+        upper: Position3 { x: 2, y: 0, z: 2 },
+        label: Some("B".to_string()),
+    }));
+    assert!(bricks.contains(&Brick {
+        // C (which did move), resting on A and B with z=2.
+        lower: Position3 { x: 0, y: 2, z: 2 },
+        upper: Position3 { x: 2, y: 2, z: 2 },
+        label: Some("C".to_string()),
+    }));
+    assert!(bricks.contains(&Brick {
+        // D fell from z=4 to z=3.
+        lower: Position3 { x: 0, y: 0, z: 3 },
+        upper: Position3 { x: 0, y: 2, z: 3 },
+        label: Some("D".to_string()),
+    }));
+    assert!(bricks.contains(&Brick {
+        // E fell from z=5 to z=3.
+        lower: Position3 { x: 2, y: 0, z: 3 },
+        upper: Position3 { x: 2, y: 2, z: 3 },
+        label: Some("E".to_string()),
+    }));
+    assert!(bricks.contains(&Brick {
+        // F fell from z=6 to z=4.
+        lower: Position3 { x: 0, y: 1, z: 4 },
+        upper: Position3 { x: 2, y: 1, z: 4 },
+        label: Some("F".to_string()),
+    }));
+
+    assert!(bricks.contains(&Brick {
+        // G fell from z=8 (top being z=9) to z=5
+        lower: Position3 { x: 1, y: 1, z: 5 },
+        upper: Position3 { x: 1, y: 1, z: 6 },
+        label: Some("G".to_string()),
+    }));
+
+    assert_eq!(can_disintegrate.len(), 5);
+}
+
+/// What happens when a single brick is disintegrated.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FallReport {
+    /// The brick which was disintegrated (in its settled position).
+    removed: Brick,
+    /// The bricks which fall as a result (in their settled positions
+    /// before the removal), with the distance each one falls.
+    moved: Vec<(Brick, i64)>,
+}
+
+impl Display for FallReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "disintegrating {} makes {} brick(s) fall",
+            self.removed,
+            self.moved.len()
+        )?;
+        for (brick, distance) in self.moved.iter() {
+            writeln!(f, "{brick} falls by {distance}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lets the bricks settle, then works out which of them fall (and how
+/// far) when the brick at `target_index` is disintegrated.
+pub fn simulate_disintegration(bricks: &[Brick], target_index: usize) -> Result<FallReport, Fail> {
+    let (_, settled, _) = compute_fallen_brick_positions::<FlatSurface, _>(bricks, ignore_none)?;
+    let (_, after, _) =
+        compute_fallen_brick_positions::<FlatSurface, _>(&settled, |ix| ix == target_index)?;
+    let moved: Vec<(Brick, i64)> = settled
+        .iter()
+        .zip(after.iter())
+        .enumerate()
+        .filter(|(index, _)| *index != target_index)
+        .filter_map(|(_, (before, after))| {
+            let distance = before.lower.z - after.lower.z;
+            (distance > 0).then(|| (before.clone(), distance))
+        })
+        .collect();
+    Ok(FallReport {
+        removed: settled[target_index].clone(),
+        moved,
+    })
+}
+
+#[test]
+fn test_simulate_disintegration() {
+    let bricks = parse_input(get_labeled_example()).expect("example should be valid");
+    let labels = |report: &FallReport| -> Vec<(String, i64)> {
+        report
+            .moved
+            .iter()
+            .map(|(brick, distance)| (brick.label.clone().unwrap_or_default(), *distance))
+            .collect()
+    };
+    // B and C rest only on A, and everything else rests on them.
+    let report = simulate_disintegration(&bricks, 0).expect("example should settle");
+    assert_eq!(report.removed.label.as_deref(), Some("A"));
+    assert_eq!(
+        labels(&report),
+        ["B", "C", "D", "E", "F", "G"].map(|label| (label.to_string(), 1))
+    );
+    // G rests only on F, and falls all the way down to A.
+    let report = simulate_disintegration(&bricks, 5).expect("example should settle");
+    assert_eq!(labels(&report), vec![("G".to_string(), 3)]);
+    assert_eq!(
+        report.to_string(),
+        concat!(
+            "disintegrating 0,1,4~2,1,4 <- F makes 1 brick(s) fall\n",
+            "1,1,5~1,1,6 <- G falls by 3\n",
+        )
+    );
+    // C also supports D and E.
+    let report = simulate_disintegration(&bricks, 1).expect("example should settle");
+    assert!(report.moved.is_empty());
+}
+
+/// Finds the brick with the given label.  Unlabeled bricks can be
+/// identified by their (1-based) line number in the input.
+pub fn find_brick(bricks: &[Brick], label: &str) -> Option<usize> {
+    bricks
+        .iter()
+        .position(|brick| brick.label.as_deref() == Some(label))
+        .or_else(|| match label.parse::<usize>() {
+            Ok(line) if (1..=bricks.len()).contains(&line) => Some(line - 1),
+            _ => None,
+        })
+}
+
+#[test]
+fn test_find_brick() {
+    let bricks = parse_input(get_labeled_example()).expect("example should be valid");
+    assert_eq!(find_brick(&bricks, "C"), Some(2));
+    assert_eq!(find_brick(&bricks, "7"), Some(6));
+    assert_eq!(find_brick(&bricks, "8"), None);
+    assert_eq!(find_brick(&bricks, "Z"), None);
+}
+
+/// Returns the bricks (in their original order) in the positions they
+/// come to rest in.
+pub fn settle(bricks: &[Brick]) -> Result<Vec<Brick>, Fail> {
+    let (_, settled, _) = compute_fallen_brick_positions::<FlatSurface, _>(bricks, ignore_none)?;
+    Ok(settled)
+}
+
+/// Which of the settled bricks rest directly on which others.
+///
+/// The bricks settle one at a time, in the order given by
+/// `settling_order`: lowest first, with bricks whose ends are in the
+/// same places taken in input order.  So the graph depends only on
+/// the bricks, never on how a sort happens to break ties.  Bricks are
+/// identified by their index in the input, and each list of
+/// neighbours is in increasing order of index.
+#[derive(Debug)]
+pub struct SupportGraph {
+    settled: Vec<Brick>,
+    order: Vec<usize>,
+    below: Vec<Vec<usize>>,
+    above: Vec<Vec<usize>>,
+}
+
+impl SupportGraph {
+    /// Lets `bricks` settle, and works out what rests on what.
+    pub fn new(bricks: &[Brick]) -> Result<SupportGraph, Fail> {
+        let settled = settle(bricks)?;
+        let order = settling_order(bricks);
+        let mut below: Vec<Vec<usize>> = vec![Vec::new(); bricks.len()];
+        let mut above: Vec<Vec<usize>> = vec![Vec::new(); bricks.len()];
+        let mut heightmap = FlatSurface::new(&plan_of(&settled));
+        for &index in order.iter() {
+            let brick = &settled[index];
+            let plan = brick.plan();
+            let mut supporters: Vec<usize> = plan
+                .surface()
+                .filter_map(|pos| match heightmap.get(&pos) {
+                    (z, Some(other)) if z + 1 == brick.lower.z => Some(other),
+                    _ => None,
+                })
+                .collect();
+            supporters.sort_unstable();
+            supporters.dedup();
+            for &supporter in supporters.iter() {
+                above[supporter].push(index);
+            }
+            below[index] = supporters;
+            heightmap.set_height(&plan, brick.upper.z, index)?;
+        }
+        for supported in above.iter_mut() {
+            supported.sort_unstable();
+        }
+        Ok(SupportGraph {
+            settled,
+            order,
+            below,
+            above,
+        })
+    }
+
+    /// The bricks in the positions they come to rest in, in input
+    /// order.
+    pub fn settled(&self) -> &[Brick] {
+        &self.settled
+    }
+
+    /// The indexes of the bricks, in the order in which they settled.
+    pub fn settling_order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// The bricks which brick `index` rests on.
+    pub fn supporters(&self, index: usize) -> &[usize] {
+        &self.below[index]
+    }
+
+    /// The bricks which rest on brick `index`.
+    pub fn supported(&self, index: usize) -> &[usize] {
+        &self.above[index]
+    }
+}
+
+#[test]
+fn test_support_graph() {
+    let bricks = parse_input(get_labeled_example()).expect("example should be valid");
+    let graph = SupportGraph::new(&bricks).expect("bricks should settle");
+    assert_eq!(
+        graph.settled(),
+        settle(&bricks).expect("example should settle").as_slice()
+    );
+    assert_eq!(graph.settling_order(), &[0, 1, 2, 3, 4, 5, 6]);
+    // A supports B and C, which both support D and E, which both
+    // support F, which supports G.
+    assert_eq!(graph.supporters(0), &[] as &[usize]);
+    assert_eq!(graph.supported(0), &[1, 2]);
+    assert_eq!(graph.supporters(3), &[1, 2]);
+    assert_eq!(graph.supported(1), &[3, 4]);
+    assert_eq!(graph.supporters(5), &[3, 4]);
+    assert_eq!(graph.supported(5), &[6]);
+    assert_eq!(graph.supporters(6), &[5]);
+    assert_eq!(graph.supported(6), &[] as &[usize]);
+
+    // Of two bricks in the same place, the one given first is
+    // underneath.
+    let bricks = parse_input("0,0,5~1,0,5\n0,0,5~1,0,5\n").expect("test input should be valid");
+    let graph = SupportGraph::new(&bricks).expect("bricks should settle");
+    assert_eq!(graph.supporters(1), &[0]);
+    assert_eq!(graph.supported(0), &[1]);
+}
+
+/// The faces of a cube whose corners are numbered so that bit 0 is
+/// set for the larger x, bit 1 for the larger y and bit 2 for the
+/// larger z.  Each face lists its corners anticlockwise as seen from
+/// outside the cube.
+const CUBE_FACES: [[usize; 4]; 6] = [
+    [0, 2, 3, 1], // bottom
+    [4, 5, 7, 6], // top
+    [0, 1, 5, 4], // low y
+    [2, 6, 7, 3], // high y
+    [0, 4, 6, 2], // low x
+    [1, 3, 7, 5], // high x
+];
+
+/// Describes the bricks as a Wavefront OBJ scene, with each brick as
+/// a box named after its label (or, for unlabeled bricks, its line
+/// number in the input).  Each brick fills the unit cubes it
+/// occupies, so a brick "1,0,1~1,2,1" spans x from 1 to 2.
+pub fn to_obj(bricks: &[Brick]) -> String {
+    let mut result = String::new();
+    for (index, brick) in bricks.iter().enumerate() {
+        match brick.label.as_ref() {
+            Some(label) => result.push_str(&format!("o {label}\n")),
+            None => result.push_str(&format!("o brick{}\n", index + 1)),
+        }
+        let xs = [
+            min(brick.lower.x, brick.upper.x),
+            max(brick.lower.x, brick.upper.x) + 1,
+        ];
+        let ys = [
+            min(brick.lower.y, brick.upper.y),
+            max(brick.lower.y, brick.upper.y) + 1,
+        ];
+        let zs = [brick.lower.z, brick.upper.z + 1];
+        for corner in 0..8 {
+            result.push_str(&format!(
+                "v {} {} {}\n",
+                xs[corner & 1],
+                ys[(corner >> 1) & 1],
+                zs[corner >> 2]
+            ));
+        }
+        // Vertex numbers start at 1 and continue across objects.
+        let first_vertex = index * 8 + 1;
+        for face in CUBE_FACES.iter() {
+            let corners: Vec<String> = face
+                .iter()
+                .map(|corner| (first_vertex + corner).to_string())
+                .collect();
+            result.push_str(&format!("f {}\n", corners.join(" ")));
+        }
+    }
+    result
+}
+
+#[test]
+fn test_to_obj() {
+    let bricks = parse_input("1,1,8~1,1,9   <- G\n0,1,2~2,1,2\n").expect("valid");
+    let obj = to_obj(&settle(&bricks).expect("bricks should settle"));
+    let lines: Vec<&str> = obj.lines().collect();
+    assert_eq!(lines.len(), 2 * (1 + 8 + 6));
+    assert_eq!(lines[0], "o G");
+    // G falls onto the other brick, which falls to the ground.
+    assert_eq!(lines[1], "v 1 1 2");
+    assert_eq!(lines[8], "v 2 2 4");
+    assert_eq!(lines[9], "f 1 3 4 2");
+    assert_eq!(lines[15], "o brick2");
+    assert_eq!(lines[16], "v 0 1 1");
+    assert_eq!(lines[23], "v 3 2 2");
+    assert_eq!(lines[29], "f 10 12 16 14");
+}
+
+/// The size and density of a randomly generated snapshot of falling
+/// bricks (see `random_bricks`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrickParams {
+    /// The extent of the snapshot in x and y.
+    pub width: i64,
+    pub depth: i64,
+    /// The highest z at which a brick may start.
+    pub height: i64,
+    /// How many bricks to make.  If the space is too crowded to fit
+    /// them all in, there will be fewer.
+    pub count: usize,
+    /// The longest a brick may be.
+    pub max_length: i64,
+}
+
+impl Default for BrickParams {
+    /// Returns parameters which give a snapshot like the real puzzle
+    /// inputs.
+    fn default() -> BrickParams {
+        BrickParams {
+            width: 10,
+            depth: 10,
+            height: 350,
+            count: 1200,
+            max_length: 5,
+        }
+    }
+}
+
+impl Brick {
+    /// Returns the unit cubes the brick occupies.
+    fn cubes(&self) -> impl Iterator<Item = Position3> + '_ {
+        let (x1, x2) = (
+            min(self.lower.x, self.upper.x),
+            max(self.lower.x, self.upper.x),
+        );
+        let (y1, y2) = (
+            min(self.lower.y, self.upper.y),
+            max(self.lower.y, self.upper.y),
+        );
+        (self.lower.z..=self.upper.z).flat_map(move |z| {
+            (x1..=x2).flat_map(move |x| (y1..=y2).map(move |y| Position3 { x, y, z }))
+        })
+    }
+}
+
+/// Makes up a valid snapshot of falling bricks: each brick is a
+/// straight line of cubes at or above z=1, and no two bricks overlap.
+/// The bricks have not settled.
+pub fn random_bricks(rng: &mut Rng, params: &BrickParams) -> Vec<Brick> {
+    let mut occupied: HashSet<Position3> = HashSet::new();
+    let mut bricks = Vec::with_capacity(params.count);
+    // Give up eventually if the space is too full.
+    let mut attempts_left = params.count.saturating_mul(20);
+    while bricks.len() < params.count && attempts_left > 0 {
+        attempts_left -= 1;
+        let length = rng.in_range(1..=params.max_length.max(1));
+        let lower = Position3 {
+            x: rng.in_range(0..=params.width - 1),
+            y: rng.in_range(0..=params.depth - 1),
+            z: rng.in_range(1..=params.height),
+        };
+        let mut upper = lower.clone();
+        match rng.index(3) {
+            0 => upper.x = min(lower.x + length - 1, params.width - 1),
+            1 => upper.y = min(lower.y + length - 1, params.depth - 1),
+            _ => upper.z += length - 1,
+        }
+        let brick = Brick {
+            lower,
+            upper,
+            label: None,
+        };
+        if brick.cubes().any(|cube| occupied.contains(&cube)) {
+            continue;
+        }
+        occupied.extend(brick.cubes());
+        bricks.push(brick);
+    }
+    bricks
+}
+
+/// Returns the bricks as puzzle input.
+pub fn format_bricks(bricks: &[Brick]) -> String {
+    bricks.iter().map(|brick| format!("{brick}\n")).collect()
+}
+
+#[cfg(test)]
+fn overlapping_cube(bricks: &[Brick]) -> Option<Position3> {
+    let mut occupied: HashSet<Position3> = HashSet::new();
+    bricks
+        .iter()
+        .flat_map(|brick| brick.cubes())
+        .find(|cube| !occupied.insert(cube.clone()))
+}
+
+#[test]
+fn test_random_bricks() {
+    let params = BrickParams {
+        width: 4,
+        depth: 3,
+        height: 20,
+        count: 30,
+        max_length: 3,
+    };
+    let bricks = random_bricks(&mut Rng::new(1), &params);
+    assert_eq!(bricks.len(), 30);
+    assert_eq!(overlapping_cube(&bricks), None);
+    assert!(bricks.iter().all(|brick| brick.lower.z >= 1));
+    assert_eq!(parse_input(&format_bricks(&bricks)), Ok(bricks.clone()));
+    assert_eq!(random_bricks(&mut Rng::new(1), &params), bricks);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_settled_bricks_are_supported(seed in proptest::num::u64::ANY, count in 1usize..60) {
+        let params = BrickParams {
+            width: 5,
+            depth: 5,
+            height: 40,
+            count,
+            max_length: 4,
+        };
+        let bricks = random_bricks(&mut Rng::new(seed), &params);
+        proptest::prop_assert_eq!(overlapping_cube(&bricks), None);
+        let settled = settle(&bricks).expect("generated bricks should settle");
+        proptest::prop_assert_eq!(overlapping_cube(&settled), None);
+        let occupied: HashSet<Position3> = settled.iter().flat_map(|b| b.cubes()).collect();
+        for brick in settled.iter() {
+            let on_ground = brick.lower.z == 1;
+            let supported = brick.cubes().filter(|cube| cube.z == brick.lower.z).any(|cube| {
+                occupied.contains(&Position3 { z: cube.z - 1, ..cube })
+            });
+            proptest::prop_assert!(on_ground || supported, "{brick} is floating");
+        }
+    }
+}
+
+/// Counts the bricks which could be disintegrated without making any
+/// other brick fall.
+pub fn count_safe_to_disintegrate(bricks: &[Brick]) -> Result<usize, Fail> {
+    let (_, _, can_disintegrate) =
+        compute_fallen_brick_positions::<FlatSurface, _>(bricks, ignore_none)?;
+    debug!(
+        "{} of {} bricks can be safely disintegrated",
+        can_disintegrate.len(),
+        bricks.len()
+    );
+    Ok(can_disintegrate.len())
+}
+
+/// Sums, over every brick, the number of other bricks which would fall
+/// if that brick were disintegrated.
+pub fn count_chain_reaction_falls(bricks: &[Brick]) -> Result<usize, Fail> {
+    count_chain_reaction_falls_with::<FlatSurface>(bricks)
+}
+
+/// Like `count_chain_reaction_falls`, but keeps track of the settled
+/// bricks with a surface of type `S`.
+pub fn count_chain_reaction_falls_with<S: Surface>(bricks: &[Brick]) -> Result<usize, Fail> {
+    let (_, fallen_bricks, _) = compute_fallen_brick_positions::<S, _>(bricks, ignore_none)?;
+    let mut additional_fallers = 0;
+    for ignore_index in 0..bricks.len() {
+        let ignore = |ix| ix == ignore_index;
+        let (fallcount, _, _) = compute_fallen_brick_positions::<S, _>(&fallen_bricks, ignore)?;
+        debug!("disintegrating brick {ignore_index} makes {fallcount} others fall");
+        additional_fallers += fallcount;
+    }
+    Ok(additional_fallers)
+}
+
+/// Computes the answer to part 1.
+///
+/// ```
+/// use lib::y2023::day22::part1;
+/// let example = lib::examples::examples(22)[0];
+/// assert_eq!(part1(example), Ok(5));
+/// ```
+pub fn part1(input: &str) -> Result<usize, Fail> {
+    count_safe_to_disintegrate(&parse_input(input)?)
+}
+
+#[test]
+fn test_part1() {
+    let bricks = parse_input(get_labeled_example()).expect("example should be valid");
+    assert_eq!(count_safe_to_disintegrate(&bricks), Ok(5));
+    assert_eq!(part1(get_unlabeled_example()), Ok(5));
+}
+
+pub const BACKENDS: [Backend; 2] = [
+    Backend {
+        part: 2,
+        name: "flat",
+        description: "Keep the height of the settled bricks in a grid over the whole plan",
+        slow: false,
+        solve: |input| Ok(count_chain_reaction_falls(&parse_input(input)?)?.into()),
+    },
+    Backend {
+        part: 2,
+        name: "tree",
+        description: "Keep the height of the settled bricks in a map of the points they cover",
+        slow: false,
+        solve: |input| {
+            Ok(count_chain_reaction_falls_with::<TreeSurface>(&parse_input(input)?)?.into())
+        },
+    },
+];
+
+/// Computes the answer to part 2.
+///
+/// ```
+/// use lib::y2023::day22::part2;
+/// let example = lib::examples::examples(22)[0];
+/// assert_eq!(part2(example), Ok(7));
+/// ```
+pub fn part2(input: &str) -> Result<usize, Fail> {
+    count_chain_reaction_falls(&parse_input(input)?)
+}
+
+#[test]
+fn test_part2() {
+    let bricks = parse_input(get_labeled_example()).expect("example should be valid");
+    assert_eq!(count_chain_reaction_falls(&bricks), Ok(7));
+    assert_eq!(
+        count_chain_reaction_falls_with::<TreeSurface>(&bricks),
+        Ok(7)
+    );
+    assert_eq!(part2(get_unlabeled_example()), Ok(7));
+    assert!(part2("1,0,1~1,2\n").is_err());
+}
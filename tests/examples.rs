@@ -0,0 +1,59 @@
+//! Checks each day's solution against the answers given for the
+//! examples in the puzzle description.
+use lib::answer::Answer;
+use lib::examples::examples;
+use lib::solver::solve;
+
+/// (day, part, which example, expected answer)
+const ANSWERS: &[(u8, u8, usize, &str)] = &[
+    (1, 1, 0, "142"),
+    (1, 2, 1, "281"),
+    (2, 1, 0, "8"),
+    (2, 2, 0, "2286"),
+    (3, 1, 0, "4361"),
+    (3, 2, 0, "467835"),
+    (4, 1, 0, "13"),
+    (4, 2, 0, "30"),
+    (5, 1, 0, "35"),
+    (5, 2, 0, "46"),
+    (6, 1, 0, "288"),
+    (6, 2, 0, "71503"),
+    (7, 1, 0, "6440"),
+    (7, 2, 0, "5905"),
+    (8, 1, 0, "2"),
+    (8, 1, 1, "6"),
+    (8, 2, 2, "6"),
+    (9, 1, 0, "114"),
+    (9, 2, 0, "2"),
+    (10, 1, 0, "8"),
+    (10, 2, 1, "4"),
+    (10, 2, 2, "8"),
+    (11, 1, 0, "374"),
+    (11, 2, 0, "82000210"),
+    (13, 1, 0, "405"),
+    (13, 2, 0, "400"),
+    (14, 1, 0, "136"),
+    (14, 2, 0, "64"),
+    (15, 1, 0, "1320"),
+    (15, 2, 0, "145"),
+    (16, 1, 0, "46"),
+    (16, 2, 0, "51"),
+    (18, 1, 0, "62"),
+    (19, 1, 0, "19114"),
+    (19, 2, 0, "167409079868000"),
+    (22, 1, 0, "5"),
+    (22, 2, 0, "7"),
+];
+
+#[test]
+fn test_example_answers() {
+    for (day, part, index, expected) in ANSWERS.iter().copied() {
+        let input = examples(day)[index];
+        assert_eq!(
+            solve(day, part, input),
+            Ok(Answer::from(expected)),
+            "day {day} part {part} example {}",
+            index + 1
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Checks that each day's solution runs within a time budget on the
+//! real puzzle input, so that a change which makes one much slower is
+//! noticed before it lands.
+//!
+//! This needs the puzzle inputs, and timings from a debug build mean
+//! little, so it only runs when `AOC_TIME_BUDGET` is set to the
+//! budget for each day, in seconds:
+//!
+//! ```sh
+//! AOC_TIME_BUDGET=2 cargo test --release --test timing
+//! ```
+//!
+//! Days whose input we don't have are skipped (the input is not
+//! downloaded).
+use std::time::{Duration, Instant};
+
+use lib::input::{input_dir, input_path, normalize, read_file_as_string};
+use lib::solver::{implemented_days, solve, YEARS};
+
+const BUDGET_VAR: &str = "AOC_TIME_BUDGET";
+
+#[test]
+fn test_time_budget() {
+    let Ok(budget) = std::env::var(BUDGET_VAR) else {
+        return;
+    };
+    let budget = budget
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .unwrap_or_else(|| panic!("{BUDGET_VAR} should be a number of seconds, not {budget}"));
+    let mut offenders = Vec::new();
+    for year in YEARS {
+        for day in implemented_days(*year).iter().copied() {
+            let input = match read_file_as_string(&input_path(&input_dir(), day)) {
+                Ok(input) => normalize(&input),
+                Err(e) => {
+                    eprintln!("day {day:02}: skipped: {e}");
+                    continue;
+                }
+            };
+            let start = Instant::now();
+            for part in [1, 2] {
+                // Only the time matters here; the answers are checked
+                // elsewhere.
+                let _ = solve(day, part, &input);
+            }
+            let elapsed = start.elapsed();
+            eprintln!("day {day:02}: {:.3}s", elapsed.as_secs_f64());
+            if elapsed > budget {
+                offenders.push(format!("day {day:02} ({:.3}s)", elapsed.as_secs_f64()));
+            }
+        }
+    }
+    assert!(
+        offenders.is_empty(),
+        "over the budget of {:.3}s: {}",
+        budget.as_secs_f64(),
+        offenders.join(", ")
+    );
+}